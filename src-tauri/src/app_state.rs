@@ -2,26 +2,60 @@
 //
 // SOLID: Dependency Injection Container
 // 全てのサービスとその依存関係をここで構築・管理する
+//
+// 保管庫（Vault）ごとのサービスは `VaultServices` にまとめ、保管庫の切り替え時に
+// 新しい保存ディレクトリとインデックスで作り直して差し替える（再起動は不要）。
 
+use crate::domain::Vault;
+use crate::infrastructure::sqlite_index::IndexError;
 use crate::infrastructure::{
-    EventBusImpl, FileSettingsRepository, FileStorage, HeadingFilenameStrategy,
-    HybridRepository, SqliteIndex,
+    ConfiguredFilenameStrategy, DailyFilenameStrategy, DraftJournal, EventBusImpl, FileSettingsRepository, FileStorage, HistoryStore, TemplateStore,
+    ConfiguredUidGenerator, HybridRepository, SqliteIndex, SyncStateStore, VaultRoot,
+    load_ignore_rules,
+};
+use crate::services::{
+    AutosaveService, BacklinkService, DraftService, DuplicateService, EncryptionService, HistoryService,
+    BackupService, NoteService, RelocationService, SearchService, SettingsService, SyncService,
+    TemplateService, TrashService, VersioningService,
 };
-use crate::services::{BacklinkService, NoteService, SearchService, SettingsService};
+use crate::traits::{Clipboard, FilenameStrategy};
 use log::info;
+use parking_lot::{Mutex, RwLock};
+use std::path::Path;
 use std::sync::Arc;
 
+/// 保管庫ごとのサービス
+pub struct VaultServices {
+    pub note_service: Arc<NoteService>,
+    pub autosave_service: Arc<AutosaveService>,
+    pub draft_service: Arc<DraftService>,
+    pub search_service: Arc<SearchService>,
+    pub backlink_service: Arc<BacklinkService>,
+    pub history_service: Arc<HistoryService>,
+    pub relocation_service: Arc<RelocationService>,
+    pub encryption_service: Arc<EncryptionService>,
+    pub versioning_service: Arc<VersioningService>,
+    pub backup_service: Arc<BackupService>,
+    pub sync_service: Arc<SyncService>,
+    pub duplicate_service: Arc<DuplicateService>,
+    pub trash_service: Arc<TrashService>,
+}
+
 /// アプリケーション状態（Dependency Injection Container）
 pub struct AppState {
-    pub note_service: NoteService,
-    pub search_service: SearchService,
-    pub backlink_service: Arc<BacklinkService>,
+    /// 使用中の保管庫のサービス（切り替え時に差し替える）
+    vault: RwLock<Arc<VaultServices>>,
+    /// 保管庫の切り替えを直列化
+    switch_lock: Mutex<()>,
+    factory: VaultFactory,
+    pub template_service: Arc<TemplateService>,
     pub settings_service: Arc<SettingsService>,
     pub event_bus: Arc<EventBusImpl>,
 }
 
 impl AppState {
-    pub fn new() -> Self {
+    /// `clipboard` はテンプレートの `{{clipboard}}` 変数の読み取りに使う
+    pub fn new(clipboard: Arc<dyn Clipboard>) -> Self {
         // EventBus
         let event_bus = Arc::new(EventBusImpl::new());
 
@@ -33,24 +67,191 @@ impl AppState {
             event_bus.clone(),
         ));
 
-        // Storage & Repository (HybridRepository with SQLite index)
+        // Storage
         let storage = Arc::new(FileStorage::new());
-        let filename_strategy = Arc::new(HeadingFilenameStrategy::new());
 
-        // SQLiteインデックスを初期化（設定ディレクトリにDBファイルを配置）
-        let db_path = settings_service.config_directory().join("index.db");
-        let sqlite_index = Arc::new(
-            SqliteIndex::open(db_path.clone())
-                .expect("Failed to open SQLite index"),
-        );
-        info!("[AppState] SQLite index opened at {:?}", db_path);
+        // 前回の書き込み途中で残った一時ファイルを削除（保管庫のデータディレクトリは設定ディレクトリ配下）
+        for dir in [
+            settings_service.storage_directory(),
+            settings_service.config_directory(),
+        ] {
+            cleanup_temp_files(&storage, &dir);
+        }
+        // デイリーノートは日付パターン、それ以外は設定（または front matter）の方式でファイル名を生成
+        let filename_strategy: Arc<dyn FilenameStrategy> = Arc::new(DailyFilenameStrategy::new(
+            Arc::new(ConfiguredFilenameStrategy::new(settings_service.clone())),
+            settings_service.clone(),
+        ));
+
+        // Template Service（テンプレートは設定ディレクトリ配下、全保管庫で共有）
+        let template_service = Arc::new(TemplateService::new(
+            TemplateStore::new(
+                settings_service.config_directory().join("templates"),
+                storage.clone(),
+            ),
+            settings_service.clone(),
+        )
+        .with_clipboard(clipboard));
+
+        let factory = VaultFactory {
+            storage,
+            filename_strategy,
+            template_service: template_service.clone(),
+            settings_service: settings_service.clone(),
+            event_bus: event_bus.clone(),
+        };
+
+        // 使用中の保管庫のサービスを構築
+        let active = active_vault(&settings_service);
+        let data_dir = active.data_dir(&settings_service.config_directory());
+        let sqlite_index = open_index(&data_dir).expect("Failed to open SQLite index");
+        let vault = factory.build(sqlite_index, &active, &data_dir);
+
+        Self {
+            vault: RwLock::new(Arc::new(vault)),
+            switch_lock: Mutex::new(()),
+            factory,
+            template_service,
+            settings_service,
+            event_bus,
+        }
+    }
+
+    /// 使用中の保管庫
+    pub fn active_vault(&self) -> Vault {
+        active_vault(&self.settings_service)
+    }
+
+    /// 保管庫を切り替える（保存待ちの変更を書き出してからサービスを差し替える）
+    ///
+    /// インデックスを開けない場合や設定を保存できない場合は切り替えずにエラーを返す。
+    pub fn switch_vault(&self, id: &str) -> Result<Vault, String> {
+        let _guard = self.switch_lock.lock();
+
+        let settings = self.settings_service.get_arc();
+        let vault = settings
+            .vaults
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("Vault not found: '{}'", id))?;
+        if settings.vaults.active == vault.id {
+            return Ok(vault);
+        }
+
+        // 先にインデックスを開き、失敗した場合は現在の保管庫のまま
+        let data_dir = vault.data_dir(&self.settings_service.config_directory());
+        let sqlite_index = open_index(&data_dir).map_err(|e| e.to_string())?;
+
+        // サービスを止める前に設定を確定する（失敗した場合は現在の保管庫のサービスのまま）
+        // 各サービスは保管庫の保存ディレクトリを `VaultRoot` で持つため、設定の変更では移らない
+        self.settings_service
+            .update(|s| {
+                s.vaults.active = vault.id.clone();
+                s.storage_directory = vault.storage_directory.clone();
+                s.last_note_uid = None;
+            })
+            .map_err(|e| e.to_string())?;
+
+        // 保存待ちの変更は切り替え前の保管庫に書き出し、その保管庫のスレッドを止める
+        let previous = self.vault.read().clone();
+        previous.autosave_service.flush();
+        previous.autosave_service.shutdown();
+        previous.versioning_service.shutdown();
+        previous.backup_service.shutdown();
+        previous.sync_service.shutdown();
+        previous.encryption_service.shutdown();
+        previous.trash_service.shutdown();
+
+        // 新しい保管庫のサービスは保管庫の保存ディレクトリで構築して差し替える
+        cleanup_temp_files(&self.factory.storage, &vault.storage_directory);
+        let services = self.factory.build(sqlite_index, &vault, &data_dir);
+        *self.vault.write() = Arc::new(services);
+
+        info!("[AppState] Switched to vault '{}' ({:?})", vault.id, vault.storage_directory);
+        Ok(vault)
+    }
+
+    /// 使用中の保管庫のサービス
+    pub fn vault_services(&self) -> Arc<VaultServices> {
+        self.vault.read().clone()
+    }
+
+    pub fn note_service(&self) -> Arc<NoteService> {
+        self.vault.read().note_service.clone()
+    }
+
+    pub fn autosave_service(&self) -> Arc<AutosaveService> {
+        self.vault.read().autosave_service.clone()
+    }
+
+    pub fn draft_service(&self) -> Arc<DraftService> {
+        self.vault.read().draft_service.clone()
+    }
+
+    pub fn search_service(&self) -> Arc<SearchService> {
+        self.vault.read().search_service.clone()
+    }
+
+    pub fn backlink_service(&self) -> Arc<BacklinkService> {
+        self.vault.read().backlink_service.clone()
+    }
+
+    pub fn history_service(&self) -> Arc<HistoryService> {
+        self.vault.read().history_service.clone()
+    }
+
+    pub fn relocation_service(&self) -> Arc<RelocationService> {
+        self.vault.read().relocation_service.clone()
+    }
+
+    pub fn encryption_service(&self) -> Arc<EncryptionService> {
+        self.vault.read().encryption_service.clone()
+    }
+
+    pub fn versioning_service(&self) -> Arc<VersioningService> {
+        self.vault.read().versioning_service.clone()
+    }
+
+    pub fn backup_service(&self) -> Arc<BackupService> {
+        self.vault.read().backup_service.clone()
+    }
+
+    pub fn sync_service(&self) -> Arc<SyncService> {
+        self.vault.read().sync_service.clone()
+    }
+
+    pub fn duplicate_service(&self) -> Arc<DuplicateService> {
+        self.vault.read().duplicate_service.clone()
+    }
+
+    pub fn trash_service(&self) -> Arc<TrashService> {
+        self.vault.read().trash_service.clone()
+    }
+}
+
+/// 保管庫ごとのサービスを構築する（保管庫をまたいで共有する依存を保持）
+struct VaultFactory {
+    storage: Arc<FileStorage>,
+    filename_strategy: Arc<dyn FilenameStrategy>,
+    template_service: Arc<TemplateService>,
+    settings_service: Arc<SettingsService>,
+    event_bus: Arc<EventBusImpl>,
+}
+
+impl VaultFactory {
+    /// 保管庫ごとのサービスを構築（保存ディレクトリは `vault` のものに固定し、移転時のみ変わる）
+    fn build(&self, sqlite_index: Arc<SqliteIndex>, vault: &Vault, data_dir: &Path) -> VaultServices {
+        let settings_service = &self.settings_service;
+        let event_bus = &self.event_bus;
+        let root = Arc::new(VaultRoot::new(vault.id.clone(), vault.storage_directory.clone()));
 
         // HybridRepositoryを作成
         let note_repository = Arc::new(HybridRepository::new(
             sqlite_index,
-            storage,
-            filename_strategy,
+            self.storage.clone(),
+            self.filename_strategy.clone(),
             settings_service.clone(),
+            root.clone(),
         ));
 
         // インデックスを初期化（必要に応じてファイルをスキャン）
@@ -58,32 +259,164 @@ impl AppState {
             eprintln!("[AppState] Failed to initialize index: {}", e);
         }
 
+        // History Service（リビジョンは保管庫のデータディレクトリ配下に保存）
+        let history_store = HistoryStore::new(data_dir.join("history"), self.storage.clone());
+        let history_service = Arc::new(HistoryService::new(
+            history_store,
+            settings_service.clone(),
+        ));
+
+        // Encryption Service（パスフレーズと鍵はメモリ上のみ、保管庫の切り替えでロックされる）
+        // 自動ロックのスレッドを起動
+        let encryption_service = Arc::new(EncryptionService::new(
+            note_repository.clone(),
+            settings_service.clone(),
+            event_bus.clone(),
+        ));
+        encryption_service.start();
+
         // Note Service
-        let note_service = NoteService::new(note_repository.clone(), event_bus.clone());
+        let note_service = Arc::new(
+            NoteService::new(note_repository.clone(), event_bus.clone())
+                .with_history(history_service.clone())
+                .with_templates(self.template_service.clone())
+                .with_encryption(encryption_service.clone())
+                .with_uid_generator(Arc::new(ConfiguredUidGenerator::new(settings_service.clone()))),
+        );
+
+        // Trash Service（保持期間を過ぎたゴミ箱のメモを起動時と定期的に削除するスレッドを起動）
+        let trash_service = Arc::new(TrashService::new(
+            note_service.clone(),
+            settings_service.clone(),
+            event_bus.clone(),
+        ));
+        trash_service.start();
 
         // Search Service
-        let search_service = SearchService::new(note_repository.clone());
+        let search_service = Arc::new(SearchService::new(note_repository.clone()));
 
         // Backlink Service
-        let backlink_service = Arc::new(BacklinkService::new(note_repository));
+        let backlink_service = Arc::new(BacklinkService::new(note_repository.clone()));
 
         // Build initial backlink index
         if let Err(e) = backlink_service.rebuild_index() {
             eprintln!("[AppState] Failed to build backlink index: {}", e);
         }
 
-        Self {
+        // Draft Service（未保存バッファのジャーナルは保管庫のデータディレクトリ配下）
+        let draft_service = Arc::new(DraftService::new(
+            DraftJournal::new(data_dir.join("drafts"), self.storage.clone()),
+            note_repository.clone(),
+        ));
+
+        // Autosave Service（保存スレッドを起動）
+        let autosave_service = Arc::new(AutosaveService::new(
+            note_service.clone(),
+            backlink_service.clone(),
+            draft_service.clone(),
+            settings_service.clone(),
+            event_bus.clone(),
+        ));
+        autosave_service.start();
+
+        // Versioning Service（保存ディレクトリを git にコミットするスレッドを起動、無効な間は何もしない）
+        let versioning_service = Arc::new(VersioningService::new(
+            note_repository.clone(),
+            settings_service.clone(),
+            event_bus.clone(),
+            root.clone(),
+        ));
+        versioning_service.start();
+
+        // Backup Service（定期バックアップのスレッドを起動、無効な間は何もしない）
+        let backup_service = Arc::new(BackupService::new(
+            self.storage.clone(),
+            note_repository.clone(),
+            backlink_service.clone(),
+            settings_service.clone(),
+            event_bus.clone(),
+            root.clone(),
+        ));
+        backup_service.start();
+
+        // Sync Service（ミラーとの定期同期のスレッドを起動、同期状態は保管庫のデータディレクトリ配下）
+        let sync_service = Arc::new(SyncService::new(
+            SyncStateStore::new(data_dir.to_path_buf(), self.storage.clone()),
+            self.storage.clone(),
+            note_service.clone(),
+            note_repository.clone(),
+            backlink_service.clone(),
+            autosave_service.clone(),
+            settings_service.clone(),
+            event_bus.clone(),
+            root.clone(),
+        ));
+        sync_service.start();
+
+        // Duplicate Service（同じ UID を持つファイル・競合コピーの解決）
+        let duplicate_service = Arc::new(DuplicateService::new(
+            note_repository.clone(),
+            note_service.clone(),
+            history_service.clone(),
+            backlink_service.clone(),
+            self.storage.clone(),
+            settings_service.clone(),
+            event_bus.clone(),
+            root.clone(),
+        ));
+
+        // Relocation Service（保存ディレクトリの変更時にノートを移転し、インデックスを作り直す）
+        let relocation_service = Arc::new(RelocationService::new(
+            note_repository,
+            self.storage.clone(),
+            backlink_service.clone(),
+            autosave_service.clone(),
+            settings_service.clone(),
+            event_bus.clone(),
+            root,
+        ));
+
+        VaultServices {
             note_service,
+            autosave_service,
+            draft_service,
             search_service,
             backlink_service,
-            settings_service,
-            event_bus,
+            history_service,
+            relocation_service,
+            encryption_service,
+            versioning_service,
+            backup_service,
+            sync_service,
+            duplicate_service,
+            trash_service,
         }
     }
 }
 
-impl Default for AppState {
-    fn default() -> Self {
-        Self::new()
+/// 設定で使用中の保管庫（設定の読み込み時に必ず登録される）
+fn active_vault(settings_service: &SettingsService) -> Vault {
+    let settings = settings_service.get_arc();
+    settings
+        .vaults
+        .get(&settings.vaults.active)
+        .cloned()
+        .expect("active vault is registered on settings load")
+}
+
+/// 保管庫のデータディレクトリにある SQLite インデックスを開く
+fn open_index(data_dir: &Path) -> Result<Arc<SqliteIndex>, IndexError> {
+    let db_path = data_dir.join("index.db");
+    let sqlite_index = SqliteIndex::open(db_path.clone())?;
+    info!("[AppState] SQLite index opened at {:?}", db_path);
+    Ok(Arc::new(sqlite_index))
+}
+
+/// 書き込み途中で残った一時ファイルを削除
+fn cleanup_temp_files(storage: &FileStorage, dir: &Path) {
+    match storage.cleanup_temp_files(dir, &load_ignore_rules(storage, dir)) {
+        Ok(0) => {}
+        Ok(removed) => info!("[AppState] Removed {} stale temp files in {:?}", removed, dir),
+        Err(e) => eprintln!("[AppState] Failed to clean up temp files in {:?}: {}", dir, e),
     }
 }
@@ -26,7 +26,7 @@ impl From<BacklinkInfo> for BacklinkDto {
 /// 指定ノートへのバックリンクを取得
 #[tauri::command]
 pub fn get_backlinks(state: State<AppState>, uid: String) -> Result<Vec<BacklinkDto>, String> {
-    let backlinks = state.backlink_service.get_backlinks_for_uid(&uid);
+    let backlinks = state.backlink_service().get_backlinks_for_uid(&uid);
     Ok(backlinks.into_iter().map(BacklinkDto::from).collect())
 }
 
@@ -34,7 +34,7 @@ pub fn get_backlinks(state: State<AppState>, uid: String) -> Result<Vec<Backlink
 #[tauri::command]
 pub fn rebuild_backlink_index(state: State<AppState>) -> Result<(), String> {
     state
-        .backlink_service
+        .backlink_service()
         .rebuild_index()
         .map_err(|e| e.to_string())
 }
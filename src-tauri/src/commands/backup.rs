@@ -0,0 +1,107 @@
+//! バックアップ関連コマンド
+
+use crate::domain::{BackupInfo, BackupReport, RestoreReport};
+use crate::AppState;
+use serde::Serialize;
+use tauri::State;
+
+/// バックアップ DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfoDto {
+    pub name: String,
+    pub vault: String,
+    pub created_at: String,
+}
+
+impl From<BackupInfo> for BackupInfoDto {
+    fn from(backup: BackupInfo) -> Self {
+        Self {
+            name: backup.name,
+            vault: backup.vault,
+            created_at: backup
+                .created_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+        }
+    }
+}
+
+/// バックアップ結果 DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupReportDto {
+    pub backup: BackupInfoDto,
+    pub files: usize,
+    pub pruned: usize,
+}
+
+impl From<BackupReport> for BackupReportDto {
+    fn from(report: BackupReport) -> Self {
+        Self {
+            backup: BackupInfoDto::from(report.backup),
+            files: report.files,
+            pruned: report.pruned,
+        }
+    }
+}
+
+/// 復元結果 DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReportDto {
+    pub restored: usize,
+    pub settings_restored: bool,
+    pub safety_backup: Option<BackupInfoDto>,
+}
+
+impl From<RestoreReport> for RestoreReportDto {
+    fn from(report: RestoreReport) -> Self {
+        Self {
+            restored: report.restored,
+            settings_restored: report.settings_restored,
+            safety_backup: report.safety_backup.map(BackupInfoDto::from),
+        }
+    }
+}
+
+/// 今すぐバックアップを作成（古いアーカイブは世代管理に従って削除する）
+#[tauri::command]
+pub fn backup_now(state: State<AppState>) -> Result<BackupReportDto, String> {
+    // 保存待ちの変更も含めてバックアップする
+    state.autosave_service().flush();
+
+    state
+        .backup_service()
+        .backup_now()
+        .map(BackupReportDto::from)
+        .map_err(|e| e.to_string())
+}
+
+/// 現在の保管庫のバックアップ一覧を取得（新しい順）
+#[tauri::command]
+pub fn list_backups(state: State<AppState>) -> Result<Vec<BackupInfoDto>, String> {
+    state
+        .backup_service()
+        .list_backups()
+        .map(|backups| backups.into_iter().map(BackupInfoDto::from).collect())
+        .map_err(|e| e.to_string())
+}
+
+/// バックアップから復元
+///
+/// 復元する前に現在の状態をバックアップするため、復元自体も元に戻せる。
+/// `include_settings` が true なら設定も復元する（ウィンドウ・保存先・保管庫の設定は除く）。
+#[tauri::command]
+pub fn restore_backup(
+    state: State<AppState>,
+    name: String,
+    include_settings: bool,
+) -> Result<RestoreReportDto, String> {
+    // 保存待ちの変更で復元したノートが上書きされないよう先に書き出す
+    state.autosave_service().flush();
+
+    state
+        .backup_service()
+        .restore(&name, include_settings)
+        .map(RestoreReportDto::from)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,102 @@
+//! デイリーノート関連コマンド
+
+use super::NoteDto;
+use crate::domain::{DailyDirection, DailyNoteEntry};
+use crate::AppState;
+use chrono::{Local, NaiveDate};
+use serde::Serialize;
+use tauri::State;
+
+/// カレンダーで一度に取得できる最大日数（DoS防止）
+const MAX_CALENDAR_DAYS: i64 = 366;
+
+/// デイリーノートの日付と UID の DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyNoteEntryDto {
+    pub date: String,
+    pub uid: String,
+}
+
+impl From<DailyNoteEntry> for DailyNoteEntryDto {
+    fn from(entry: DailyNoteEntry) -> Self {
+        Self {
+            date: entry.date.format("%Y-%m-%d").to_string(),
+            uid: entry.uid,
+        }
+    }
+}
+
+/// 日付（YYYY-MM-DD）をパース
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("Invalid date: '{}'", date))
+}
+
+/// デイリーノートを開く（なければテンプレートから作成）
+///
+/// `date` を省略するとローカルの今日になる。
+#[tauri::command]
+pub fn open_daily_note(state: State<AppState>, date: Option<String>) -> Result<NoteDto, String> {
+    let date = match date {
+        Some(date) => parse_date(&date)?,
+        None => Local::now().date_naive(),
+    };
+
+    let (note, created) = state
+        .note_service()
+        .open_daily_note(date)
+        .map_err(|e| e.to_string())?;
+
+    // テンプレートにウィキリンクが含まれる場合に備えてバックリンクインデックスに追加
+    if created {
+        let title = note.metadata.title.clone().unwrap_or_default();
+        state
+            .backlink_service()
+            .update_note(&note.metadata.uid, &title, &note.content);
+    }
+
+    Ok(NoteDto::from(note))
+}
+
+/// 前後の既存のデイリーノートを取得（なければ `None`）
+#[tauri::command]
+pub fn adjacent_daily_note(
+    state: State<AppState>,
+    date: String,
+    direction: DailyDirection,
+) -> Result<Option<NoteDto>, String> {
+    let date = parse_date(&date)?;
+
+    let entry = state
+        .note_service()
+        .adjacent_daily_note(date, direction)
+        .map_err(|e| e.to_string())?;
+
+    match entry {
+        Some(entry) => state
+            .note_service()
+            .load_note(&entry.uid)
+            .map(|note| Some(NoteDto::from(note)))
+            .map_err(|e| e.to_string()),
+        None => Ok(None),
+    }
+}
+
+/// 期間内のデイリーノートがある日付の一覧（カレンダー表示用、両端を含む）
+#[tauri::command]
+pub fn list_daily_notes(
+    state: State<AppState>,
+    from: String,
+    to: String,
+) -> Result<Vec<DailyNoteEntryDto>, String> {
+    let from = parse_date(&from)?;
+    let to = parse_date(&to)?;
+    if to < from || (to - from).num_days() >= MAX_CALENDAR_DAYS {
+        return Err("Invalid date range".to_string());
+    }
+
+    state
+        .note_service()
+        .list_daily_notes(from, to)
+        .map(|entries| entries.into_iter().map(DailyNoteEntryDto::from).collect())
+        .map_err(|e| e.to_string())
+}
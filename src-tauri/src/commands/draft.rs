@@ -0,0 +1,90 @@
+//! 下書き（クラッシュ復旧）関連コマンド
+
+use super::NoteDto;
+use crate::domain::{Draft, ExpectedVersion, Note};
+use crate::AppState;
+use serde::Serialize;
+use tauri::State;
+
+/// 復旧可能な下書き DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct DraftDto {
+    pub uid: String,
+    pub title: String,
+    pub content: String,
+    pub saved_at: String,
+}
+
+impl From<Draft> for DraftDto {
+    fn from(draft: Draft) -> Self {
+        let mut note = Note::with_uid(draft.uid.clone());
+        note.content = draft.content;
+        let title = note.extract_heading().unwrap_or_else(|| draft.uid.clone());
+
+        Self {
+            uid: draft.uid,
+            title,
+            content: note.content,
+            saved_at: draft.saved_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// エディタのバッファを下書きとして記録
+///
+/// 自動保存が無効な場合でもクラッシュから復旧できるようにする。
+/// 自動保存が有効な場合は `request_autosave` が記録するため不要。
+/// 暗号化ノートは平文を残さないよう記録しない。
+#[tauri::command]
+pub fn write_draft(state: State<AppState>, uid: String, content: String) -> Result<(), String> {
+    super::note::validate_uid(&uid)?;
+    super::note::validate_content(&content)?;
+    if state.note_service().is_encrypted(&uid) {
+        return Ok(());
+    }
+
+    state
+        .draft_service()
+        .record(&uid, &content)
+        .map_err(|e| e.to_string())
+}
+
+/// 復旧可能な下書き一覧を取得（起動時に呼び出す）
+#[tauri::command]
+pub fn list_drafts(state: State<AppState>) -> Result<Vec<DraftDto>, String> {
+    state
+        .draft_service()
+        .recoverable()
+        .map(|drafts| drafts.into_iter().map(DraftDto::from).collect())
+        .map_err(|e| e.to_string())
+}
+
+/// 下書きの内容でメモを保存して復旧
+#[tauri::command]
+pub fn recover_draft(state: State<AppState>, uid: String) -> Result<NoteDto, String> {
+    super::note::validate_uid(&uid)?;
+
+    let draft = state
+        .draft_service()
+        .get(&uid)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Draft not found: {}", uid))?;
+
+    // 下書きは保存済みの版より新しいため、無条件で上書きする（保存後に下書きは削除される）
+    state
+        .autosave_service()
+        .save_now(&uid, draft.content, ExpectedVersion::default())
+        .map(NoteDto::from)
+        .map_err(|e| e.to_string())
+}
+
+/// 下書きを破棄
+#[tauri::command]
+pub fn discard_draft(state: State<AppState>, uid: String) -> Result<(), String> {
+    super::note::validate_uid(&uid)?;
+
+    state
+        .draft_service()
+        .clear(&uid)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,70 @@
+//! 暗号化ノート関連コマンド
+
+use super::NoteDto;
+use crate::domain::{EncryptionError, EncryptionStatus};
+use crate::AppState;
+use tauri::State;
+
+/// パスフレーズで暗号化ノートのロックを解除
+///
+/// 暗号化ノートがまだない場合は、このパスフレーズで以降のノートを暗号化する。
+#[tauri::command]
+pub fn unlock_notes(state: State<AppState>, passphrase: String) -> Result<EncryptionStatus, String> {
+    let encryption_service = state.encryption_service();
+    encryption_service
+        .unlock(&passphrase)
+        .map_err(|e| e.to_string())?;
+    Ok(encryption_service.status())
+}
+
+/// 暗号化ノートをロック（パスフレーズと鍵をメモリから破棄）
+#[tauri::command]
+pub fn lock_notes(state: State<AppState>) {
+    state.encryption_service().lock();
+}
+
+/// ロック状態と自動ロックまでの残り時間を取得
+#[tauri::command]
+pub fn get_encryption_status(state: State<AppState>) -> EncryptionStatus {
+    state.encryption_service().status()
+}
+
+/// メモの暗号化を切り替える（ロック解除中のみ）
+///
+/// 暗号化するときは、平文が残らないよう履歴と下書きも削除する。変更後のメモ（本文は平文）を返す。
+/// git の履歴から平文は消せないため、コミット済みのメモは暗号化しない
+/// （未コミットのメモは暗号化した直後にコミットする）。
+#[tauri::command]
+pub fn set_note_encrypted(
+    state: State<AppState>,
+    uid: String,
+    encrypted: bool,
+) -> Result<NoteDto, String> {
+    super::note::validate_uid(&uid)?;
+    if encrypted && state.versioning_service().keeps_plaintext(&uid) {
+        return Err(EncryptionError::PlaintextInHistory.to_string());
+    }
+
+    // 保存待ちの変更を先に書き出し、最新の本文を暗号化・復号する
+    state.autosave_service().flush();
+
+    let note_service = state.note_service();
+    let note = if encrypted {
+        state
+            .versioning_service()
+            .encrypt_uncommitted(&uid, || note_service.set_encrypted(&uid, true))
+    } else {
+        note_service.set_encrypted(&uid, false)
+    }
+    .map_err(|e| e.to_string())?;
+
+    if encrypted {
+        // 暗号化の直前に届いた保存待ちのリクエストと平文の下書きを破棄
+        state
+            .autosave_service()
+            .discard(&uid)
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(NoteDto::from(note))
+}
@@ -0,0 +1,32 @@
+//! フォルダ関連コマンド
+
+use crate::domain::{normalize_folder, FolderNode};
+use crate::AppState;
+use tauri::State;
+
+/// フォルダ指定を検証して正規化（保存ディレクトリの外や除外されるフォルダは不可）
+pub(crate) fn parse_folder(folder: &str) -> Result<String, String> {
+    normalize_folder(folder).ok_or_else(|| format!("Invalid folder: '{}'", folder))
+}
+
+/// フォルダツリーを取得（ルートは保存ディレクトリ、ノート数付き）
+#[tauri::command]
+pub fn get_folder_tree(state: State<AppState>) -> Result<FolderNode, String> {
+    state.note_service().folder_tree().map_err(|e| e.to_string())
+}
+
+/// メモを別のフォルダへ移動（`folder` は保存ディレクトリからの相対パス、`""` は直下）
+///
+/// フォルダがなければ作成する。移動後のフォルダを返す。
+#[tauri::command]
+pub fn move_note(state: State<AppState>, uid: String, folder: String) -> Result<String, String> {
+    super::note::validate_uid(&uid)?;
+    let folder = parse_folder(&folder)?;
+
+    state
+        .note_service()
+        .move_note(&uid, &folder)
+        .map_err(|e| e.to_string())?;
+
+    Ok(folder)
+}
@@ -3,6 +3,10 @@
 // パフォーマンス最適化: SQLiteインデックスからキャッシュされたデータを取得
 // N+1問題を解消し、10-100倍の高速化を実現
 
+use super::PageDto;
+use crate::domain::{NoteSortOrder, ViewKind};
+use crate::infrastructure::GalleryNote;
+use crate::traits::{NoteFilter, PageCursor};
 use crate::AppState;
 use serde::Serialize;
 use tauri::State;
@@ -21,54 +25,125 @@ pub struct NoteGalleryItemDto {
     pub tags: Vec<String>,
     pub created_at: String,
     pub updated_at: String,
+    pub pinned: bool,
+    pub favorite: bool,
+    /// フォルダ（保存ディレクトリからの相対パス、直下は空文字列）
+    pub folder: String,
+    /// 暗号化ノートか（プレビューは空）
+    pub encrypted: bool,
 }
 
-/// ソート順
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum GallerySortOrder {
-    UpdatedAt,
-    CreatedAt,
-}
-
-impl Default for GallerySortOrder {
-    fn default() -> Self {
-        Self::UpdatedAt
+impl From<GalleryNote> for NoteGalleryItemDto {
+    fn from(note: GalleryNote) -> Self {
+        Self {
+            uid: note.uid,
+            title: note.title,
+            preview: note.preview,
+            tags: note.tags,
+            created_at: note.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            updated_at: note.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            pinned: note.pinned,
+            favorite: note.favorite,
+            folder: note.folder,
+            encrypted: note.encrypted,
+        }
     }
 }
 
+/// ソート順（ギャラリー・サイドバー共通）
+pub use crate::domain::NoteSortOrder as GallerySortOrder;
+
 /// ギャラリー用ノート一覧を取得（高速版 - インデックスから取得）
+///
+/// `sort_order` / `tag_filter` を省略した場合は設定に保存されたギャラリーの表示設定を使う。
+/// `folder` を指定するとそのフォルダ（サブフォルダを含む）のノートのみを返す。
 #[tauri::command]
 pub fn list_notes_gallery(
     state: State<AppState>,
     sort_order: Option<GallerySortOrder>,
     tag_filter: Option<String>,
+    folder: Option<String>,
 ) -> Result<Vec<NoteGalleryItemDto>, String> {
-    let sort = sort_order.unwrap_or_default();
-    let sort_by_created = matches!(sort, GallerySortOrder::CreatedAt);
+    let (sort, tag_filter) = resolve_view(&state, ViewKind::Gallery, sort_order, tag_filter);
+    let folder = folder.as_deref().map(super::folder::parse_folder).transpose()?;
+    let filter = NoteFilter {
+        tag: tag_filter.as_deref(),
+        folder: folder.as_deref(),
+    };
 
     // インデックスから直接取得（高速）
     let gallery_notes = state
-        .note_service
-        .list_gallery_notes(sort_by_created, tag_filter.as_deref())
+        .note_service()
+        .list_gallery_notes(sort, &filter)
         .map_err(|e| e.to_string())?;
 
     // DTOに変換
     let items: Vec<NoteGalleryItemDto> = gallery_notes
         .into_iter()
-        .map(|note| NoteGalleryItemDto {
-            uid: note.uid,
-            title: note.title,
-            preview: note.preview,
-            tags: note.tags,
-            created_at: note.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-            updated_at: note.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-        })
+        .map(NoteGalleryItemDto::from)
         .collect();
 
     Ok(items)
 }
 
+/// ギャラリー用ノート一覧を1ページ分取得（キーセットページネーション）
+///
+/// `cursor` には前回のレスポンスの `next_cursor` を渡す。
+/// ソート順やタグ・フォルダの絞り込みを変更した場合はカーソルを破棄して先頭から取得し直す。
+#[tauri::command]
+pub fn list_notes_gallery_page(
+    state: State<AppState>,
+    sort_order: Option<GallerySortOrder>,
+    tag_filter: Option<String>,
+    folder: Option<String>,
+    cursor: Option<PageCursor>,
+    limit: Option<usize>,
+) -> Result<PageDto<NoteGalleryItemDto>, String> {
+    let (sort, tag_filter) = resolve_view(&state, ViewKind::Gallery, sort_order, tag_filter);
+    let folder = folder.as_deref().map(super::folder::parse_folder).transpose()?;
+    let filter = NoteFilter {
+        tag: tag_filter.as_deref(),
+        folder: folder.as_deref(),
+    };
+
+    state
+        .note_service()
+        .list_gallery_page(
+            sort,
+            &filter,
+            cursor.as_ref(),
+            super::note::clamp_page_size(limit),
+        )
+        .map(PageDto::from_page)
+        .map_err(|e| e.to_string())
+}
+
+/// 手動ソート順を保存
+///
+/// `uids` はドラッグ&ドロップ後の表示順。含まれないノートは末尾に並ぶ。
+#[tauri::command]
+pub fn set_manual_order(state: State<AppState>, uids: Vec<String>) -> Result<(), String> {
+    state
+        .note_service()
+        .set_manual_order(&uids)
+        .map_err(|e| e.to_string())
+}
+
+/// 引数で省略されたソート順・タグフィルタを保存済みの表示設定で補完
+pub(crate) fn resolve_view(
+    state: &AppState,
+    view: ViewKind,
+    sort_order: Option<NoteSortOrder>,
+    tag_filter: Option<String>,
+) -> (NoteSortOrder, Option<String>) {
+    let settings = state.settings_service.get_arc();
+    let preference = settings.views.get(view);
+    (
+        sort_order.unwrap_or(preference.sort),
+        tag_filter.or_else(|| preference.tag_filter.clone()),
+    )
+}
+
 /// 本文からプレビューを生成（Markdown装飾を除去）
 pub fn generate_preview(content: &str, max_len: usize) -> String {
     let mut result = String::new();
@@ -0,0 +1,94 @@
+//! リビジョン履歴関連コマンド
+
+use super::NoteDto;
+use crate::domain::{diff_lines, DiffLine, Revision};
+use crate::AppState;
+use serde::Serialize;
+use tauri::State;
+
+/// リビジョン DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct RevisionDto {
+    pub id: String,
+    pub saved_at: String,
+    pub size: u64,
+}
+
+impl From<Revision> for RevisionDto {
+    fn from(revision: Revision) -> Self {
+        Self {
+            id: revision.id,
+            saved_at: revision.saved_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            size: revision.size,
+        }
+    }
+}
+
+/// メモのリビジョン一覧を取得（新しい順）
+#[tauri::command]
+pub fn list_revisions(state: State<AppState>, uid: String) -> Result<Vec<RevisionDto>, String> {
+    super::note::validate_uid(&uid)?;
+
+    state
+        .history_service()
+        .list_revisions(&uid)
+        .map(|revisions| revisions.into_iter().map(RevisionDto::from).collect())
+        .map_err(|e| e.to_string())
+}
+
+/// 2つのリビジョンの差分を取得
+///
+/// `to` を省略した場合は現在のメモ本文と比較する。
+#[tauri::command]
+pub fn diff_revisions(
+    state: State<AppState>,
+    uid: String,
+    from: String,
+    to: Option<String>,
+) -> Result<Vec<DiffLine>, String> {
+    super::note::validate_uid(&uid)?;
+
+    match to {
+        Some(to) => state
+            .history_service()
+            .diff(&uid, &from, &to)
+            .map_err(|e| e.to_string()),
+        None => {
+            let old = state
+                .history_service()
+                .load_revision(&uid, &from)
+                .map_err(|e| e.to_string())?;
+            let current = state
+                .note_service()
+                .load_note(&uid)
+                .map_err(|e| e.to_string())?;
+            Ok(diff_lines(&old, &current.content))
+        }
+    }
+}
+
+/// リビジョンの内容でメモを復元
+///
+/// 復元前の内容はリビジョンとして残る。バックリンクも更新する。
+#[tauri::command]
+pub fn restore_revision(
+    state: State<AppState>,
+    uid: String,
+    id: String,
+) -> Result<NoteDto, String> {
+    super::note::validate_uid(&uid)?;
+
+    let note = state
+        .note_service()
+        .restore_revision(&uid, &id)
+        .map_err(|e| e.to_string())?;
+
+    let title = note
+        .extract_heading()
+        .unwrap_or_else(|| note.metadata.uid.clone());
+    state
+        .backlink_service()
+        .update_note(&note.metadata.uid, &title, &note.content);
+
+    Ok(NoteDto::from(note))
+}
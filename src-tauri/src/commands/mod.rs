@@ -10,20 +10,45 @@ pub mod hotkey;
 pub mod backlink;
 pub mod gallery;
 pub mod tag;
+pub mod trash;
+pub mod history;
+pub mod draft;
+pub mod template;
+pub mod daily;
+pub mod folder;
+pub mod vault;
+pub mod encryption;
+pub mod versioning;
+pub mod backup;
+pub mod sync;
 
 // コマンド関数を re-export
-pub use note::{create_note, save_note, load_note, delete_note, list_notes, search_notes, resolve_wiki_link};
-pub use settings::{get_settings, update_settings};
+pub use note::{create_note, save_note, request_autosave, load_note, delete_note, list_notes, list_notes_page, search_notes, resolve_wiki_link, toggle_pinned, toggle_favorite};
+pub use settings::{get_settings, update_settings, update_view_preference, migrate_filenames, normalize_timestamps, relocate_storage};
 pub use window::{save_window_geometry, prepare_hide, set_last_note_uid, quit_app, hide_window, toggle_maximize};
 pub use hotkey::{update_hotkey, get_current_hotkey};
 pub use backlink::{get_backlinks, rebuild_backlink_index};
-pub use gallery::list_notes_gallery;
+pub use gallery::{list_notes_gallery, list_notes_gallery_page, set_manual_order};
 pub use tag::{get_all_tags, get_note_tags, update_note_tags};
+pub use trash::{list_trash, restore_note, empty_trash};
+pub use history::{list_revisions, diff_revisions, restore_revision};
+pub use draft::{write_draft, list_drafts, recover_draft, discard_draft};
+pub use template::{list_templates, create_note_from_template};
+pub use daily::{open_daily_note, adjacent_daily_note, list_daily_notes};
+pub use folder::{get_folder_tree, move_note};
+pub use vault::{list_vaults, add_vault, remove_vault, set_vault_mirror, switch_vault};
+pub use encryption::{unlock_notes, lock_notes, get_encryption_status, set_note_encrypted};
+pub use versioning::{get_versioning_status, commit_vault, list_note_commits, load_note_commit, restore_note_commit};
+pub use backup::{backup_now, list_backups, restore_backup};
+pub use sync::{
+    list_duplicate_uids, list_note_conflicts, merge_note_conflict, repair_duplicate_uids,
+    resolve_note_conflict, sync_mirror,
+};
 
 // ===== DTO 定義（共有）=====
 
-use crate::domain::Note;
-use crate::traits::NoteListItem;
+use crate::domain::{FilenameStyle, Note, NoteConflict, TimestampZone, UidFormat};
+use crate::traits::{NoteListItem, Page, PageCursor, RepositoryError};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -35,16 +60,99 @@ pub struct NoteDto {
     pub created_at: String,
     pub updated_at: String,
     pub is_dirty: bool,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub favorite: bool,
+    /// 本文のハッシュ（保存時の競合検出に使用）
+    #[serde(default)]
+    pub content_hash: String,
+    /// デイリーノートの日付（YYYY-MM-DD、デイリーノート以外は `None`）
+    #[serde(default)]
+    pub daily: Option<String>,
+    /// 暗号化ノートか（本文は復号済み）
+    #[serde(default)]
+    pub encrypted: bool,
 }
 
 impl From<Note> for NoteDto {
     fn from(note: Note) -> Self {
+        let content_hash = note.content_hash();
         Self {
             uid: note.metadata.uid,
             content: note.content,
             created_at: note.metadata.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
             updated_at: note.metadata.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
             is_dirty: note.is_dirty,
+            pinned: note.metadata.pinned,
+            favorite: note.metadata.favorite,
+            content_hash,
+            daily: note.metadata.daily.map(|date| date.format("%Y-%m-%d").to_string()),
+            encrypted: note.metadata.encrypted,
+        }
+    }
+}
+
+/// 保存結果DTO（次回の保存で前提とする版）
+#[derive(Debug, Clone, Serialize)]
+pub struct SaveResultDto {
+    pub content_hash: String,
+    pub updated_at: String,
+}
+
+impl From<&Note> for SaveResultDto {
+    fn from(note: &Note) -> Self {
+        Self {
+            content_hash: note.content_hash(),
+            updated_at: note.metadata.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// 保存時の競合DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteConflictDto {
+    pub uid: String,
+    /// 保存しようとした版
+    pub local: NoteDto,
+    /// 現在ファイルに保存されている版
+    pub remote: NoteDto,
+}
+
+impl From<NoteConflict> for NoteConflictDto {
+    fn from(conflict: NoteConflict) -> Self {
+        Self {
+            uid: conflict.uid().to_string(),
+            local: NoteDto::from(conflict.local),
+            remote: NoteDto::from(conflict.remote),
+        }
+    }
+}
+
+/// 保存コマンドのエラーDTO
+///
+/// 競合時はフロントエンドでマージできるよう両方の版を返す。
+/// それ以外のエラーは従来どおりメッセージのみ。
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SaveErrorDto {
+    Conflict(Box<NoteConflictDto>),
+    Failed { message: String },
+}
+
+impl From<String> for SaveErrorDto {
+    fn from(message: String) -> Self {
+        Self::Failed { message }
+    }
+}
+
+impl From<RepositoryError> for SaveErrorDto {
+    fn from(e: RepositoryError) -> Self {
+        match e {
+            RepositoryError::Conflict(conflict) => Self::Conflict(Box::new(NoteConflictDto::from(*conflict))),
+            e => Self::Failed {
+                message: e.to_string(),
+            },
         }
     }
 }
@@ -55,6 +163,14 @@ pub struct NoteListItemDto {
     pub uid: String,
     pub title: String,
     pub updated_at: String,
+    pub pinned: bool,
+    pub favorite: bool,
+    /// フォルダ（保存ディレクトリからの相対パス、直下は空文字列）
+    #[serde(default)]
+    pub folder: String,
+    /// 暗号化ノートか
+    #[serde(default)]
+    pub encrypted: bool,
 }
 
 impl From<NoteListItem> for NoteListItemDto {
@@ -63,6 +179,33 @@ impl From<NoteListItem> for NoteListItemDto {
             uid: item.uid,
             title: item.title,
             updated_at: item.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            pinned: item.pinned,
+            favorite: item.favorite,
+            folder: item.folder,
+            encrypted: item.encrypted,
+        }
+    }
+}
+
+/// ページ単位の一覧取得結果DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct PageDto<T> {
+    pub items: Vec<T>,
+    /// 次ページ取得用カーソル（最終ページの場合は null）
+    pub next_cursor: Option<PageCursor>,
+    pub total: usize,
+}
+
+impl<T> PageDto<T> {
+    /// ドメインのページを DTO に変換
+    pub fn from_page<U>(page: Page<U>) -> Self
+    where
+        T: From<U>,
+    {
+        Self {
+            items: page.items.into_iter().map(T::from).collect(),
+            next_cursor: page.next_cursor,
+            total: page.total,
         }
     }
 }
@@ -139,4 +282,36 @@ pub struct SettingsUpdateDto {
     pub shortcut_save_note: Option<String>,
     pub shortcut_find_in_note: Option<String>,
     pub shortcut_backlink_panel: Option<String>,
+    // Trash
+    pub trash_retention_days: Option<u32>,
+    // History
+    pub history_enabled: Option<bool>,
+    pub history_min_interval_secs: Option<u64>,
+    pub history_max_revisions: Option<usize>,
+    // Templates（空文字列で組み込みテンプレートに戻す）
+    pub default_template: Option<String>,
+    // Filenames（新しいノートのみに適用、既存のノートは migrate_filenames で付け直す）
+    pub filename_style: Option<FilenameStyle>,
+    pub filename_pattern: Option<String>,
+    // Daily notes（テンプレートは空文字列で組み込みに戻す）
+    pub daily_filename_pattern: Option<String>,
+    pub daily_template: Option<String>,
+    // Encryption（0 で自動ロックしない）
+    pub encryption_session_timeout_secs: Option<u64>,
+    // Versioning（git へのコミット、0 で変更が続く間の上限なし）
+    pub versioning_enabled: Option<bool>,
+    pub versioning_quiet_period_secs: Option<u64>,
+    pub versioning_max_interval_secs: Option<u64>,
+    // Backup（空文字列で既定の保存先に戻す）
+    pub backup_enabled: Option<bool>,
+    pub backup_interval_hours: Option<u32>,
+    pub backup_directory: Option<PathBuf>,
+    pub backup_keep_daily: Option<u32>,
+    pub backup_keep_weekly: Option<u32>,
+    // Mirror sync（0 は手動のみ）
+    pub sync_interval_minutes: Option<u32>,
+    // UIDs（新しいノートのみに適用）
+    pub uid_format: Option<UidFormat>,
+    // Timestamps（既存のノートは normalize_timestamps で書き直す）
+    pub timestamp_zone: Option<TimestampZone>,
 }
@@ -3,47 +3,61 @@
 // SOLID: Input Validation
 // フロントエンドからの入力を信頼せず、バックエンドで検証する
 
-use super::{NoteDto, NoteListItemDto, SearchResultDto};
+use super::{NoteDto, NoteListItemDto, PageDto, SaveErrorDto, SaveResultDto, SearchResultDto};
+use crate::domain::{ExpectedVersion, NoteSortOrder, ViewKind};
+use crate::traits::{NoteFilter, PageCursor};
 use crate::AppState;
 use tauri::State;
 
 /// クエリ長制限（DoS防止）
 const MAX_QUERY_LENGTH: usize = 200;
 
+/// UID の長さ制限（DoS防止）
+const MAX_UID_INPUT_LENGTH: usize = 1024;
+
+/// ページサイズのデフォルト値
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// ページサイズの上限（DoS防止）
+const MAX_PAGE_SIZE: usize = 500;
+
 // ===== 入力検証 =====
 
-/// UID検証（タイムスタンプ形式のみ）
+/// UID検証（形式は問わない）
 ///
-/// アプリで生成されるUIDはタイムスタンプ形式（数字のみ、14-20文字）
-/// 例: "2026011418102637208"
+/// アプリで生成されるUIDはタイムスタンプ・ULID・UUIDv7 のいずれか（設定で選択）。
+/// 他のアプリから取り込んだノートや手で書いた UID もそのまま受け付ける。
+/// 例: "2026011418102637208", "01J1Z9P6V9WQ7H9QXGQ2K5J1ZC"
 ///
 /// # セキュリティ
 ///
-/// - 数字のみ許可（パストラバーサル防止）
-/// - 長さ制限: 14-26文字（タイムスタンプ + ナノ秒）
+/// - 長さ制限: 1-1024文字、制御文字は不可
+/// - ノートのファイルのパスは UID からは組み立てず、インデックスから引く
+///   （インデックスにない UID は NotFound になる）
+/// - 履歴・下書きのパスは `uid_file_stem` で安全な名前に変換してから使う
 pub fn validate_uid(uid: &str) -> Result<(), String> {
-    // 長さチェック（タイムスタンプは14-26文字）
-    if uid.len() < 14 || uid.len() > 26 {
+    if uid.is_empty() || uid.len() > MAX_UID_INPUT_LENGTH {
         return Err(format!(
-            "Invalid UID length: {} characters (expected 14-26)",
-            uid.len()
+            "Invalid UID length: {} characters (expected 1-{})",
+            uid.len(),
+            MAX_UID_INPUT_LENGTH
         ));
     }
-
-    // 数字のみ許可
-    for c in uid.chars() {
-        if !c.is_ascii_digit() {
-            return Err(format!("Invalid character in UID: '{}'", c));
-        }
+    if uid.chars().any(char::is_control) {
+        return Err(format!("Invalid character in UID: {:?}", uid));
     }
-
     Ok(())
 }
 
+/// ページサイズを正規化（未指定はデフォルト、0と上限超過は丸める）
+pub fn clamp_page_size(limit: Option<usize>) -> usize {
+    limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+}
+
 /// コンテンツサイズ検証（最大10MB）
 const MAX_CONTENT_SIZE: usize = 10 * 1024 * 1024;
 
-fn validate_content(content: &str) -> Result<(), String> {
+pub(crate) fn validate_content(content: &str) -> Result<(), String> {
     if content.len() > MAX_CONTENT_SIZE {
         return Err(format!(
             "Content too large: {} bytes (max {} bytes)",
@@ -54,42 +68,83 @@ fn validate_content(content: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// クライアントが前提とする版を検証して変換
+///
+/// `content_hash` は blake3 の16進表現、`updated_at` は `NoteDto` と同じ形式（UTC）。
+pub(crate) fn parse_expected_version(
+    content_hash: Option<String>,
+    updated_at: Option<String>,
+) -> Result<ExpectedVersion, String> {
+    if let Some(hash) = &content_hash {
+        if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid content hash: '{}'", hash));
+        }
+    }
+
+    let updated_at = updated_at
+        .map(|s| {
+            chrono::NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S")
+                .map(|naive| naive.and_utc())
+                .map_err(|_| format!("Invalid updated_at: '{}'", s))
+        })
+        .transpose()?;
+
+    Ok(ExpectedVersion {
+        content_hash,
+        updated_at,
+    })
+}
+
 /// 新規メモを作成
 #[tauri::command]
 pub fn create_note(state: State<AppState>) -> Result<NoteDto, String> {
     state
-        .note_service
+        .note_service()
         .create_note()
         .map(NoteDto::from)
         .map_err(|e| e.to_string())
 }
 
 /// メモを保存
+///
+/// `expected_hash` / `expected_updated_at` にクライアントが最後に読み込んだ版を渡すと、
+/// ファイルがその後に変更されていた場合は保存せずに競合エラー（両方の版）を返す。
 #[tauri::command]
-pub fn save_note(state: State<AppState>, uid: String, content: String) -> Result<(), String> {
+pub fn save_note(
+    state: State<AppState>,
+    uid: String,
+    content: String,
+    expected_hash: Option<String>,
+    expected_updated_at: Option<String>,
+) -> Result<SaveResultDto, SaveErrorDto> {
     // 入力検証
     validate_uid(&uid)?;
     validate_content(&content)?;
+    let expected = parse_expected_version(expected_hash, expected_updated_at)?;
 
-    // 既存ノートをロード、なければ新規作成
-    let mut note = match state.note_service.load_note(&uid) {
-        Ok(n) => n,
-        Err(_) => {
-            // ノートが存在しない場合は新規作成（UIDを保持）
-            crate::domain::Note::with_uid(uid)
-        }
-    };
+    // 保存待ちの自動保存は破棄して即座に保存（バックリンクも更新）
+    let note = state.autosave_service().save_now(&uid, content, expected)?;
 
-    note.update_content(content.clone());
-    state
-        .note_service
-        .save_note(&note)
-        .map_err(|e| e.to_string())?;
+    Ok(SaveResultDto::from(&note))
+}
 
-    // バックリンクインデックスを更新
-    let title = note.metadata.title.clone().unwrap_or_default();
-    state.backlink_service.update_note(&note.metadata.uid, &title, &content);
+/// 自動保存をリクエスト
+///
+/// 設定の `autosave.delay_ms` の間に届いたリクエストは1回の保存にまとめられる。
+/// 保存はバックエンドで行われ、失敗時は `save:failed` イベントで通知される。
+#[tauri::command]
+pub fn request_autosave(
+    state: State<AppState>,
+    uid: String,
+    content: String,
+    expected_hash: Option<String>,
+    expected_updated_at: Option<String>,
+) -> Result<(), String> {
+    validate_uid(&uid)?;
+    validate_content(&content)?;
+    let expected = parse_expected_version(expected_hash, expected_updated_at)?;
 
+    state.autosave_service().request(&uid, content, expected);
     Ok(())
 }
 
@@ -99,7 +154,7 @@ pub fn load_note(state: State<AppState>, uid: String) -> Result<NoteDto, String>
     validate_uid(&uid)?;
 
     state
-        .note_service
+        .note_service()
         .load_note(&uid)
         .map(NoteDto::from)
         .map_err(|e| e.to_string())
@@ -111,26 +166,85 @@ pub fn delete_note(state: State<AppState>, uid: String) -> Result<(), String> {
     validate_uid(&uid)?;
 
     state
-        .note_service
+        .note_service()
         .delete_note(&uid)
         .map_err(|e| e.to_string())?;
 
     // バックリンクインデックスから削除
-    state.backlink_service.remove_note(&uid);
+    state.backlink_service().remove_note(&uid);
 
     Ok(())
 }
 
+/// ピン留めを切り替え（切り替え後の状態を返す）
+#[tauri::command]
+pub fn toggle_pinned(state: State<AppState>, uid: String) -> Result<bool, String> {
+    validate_uid(&uid)?;
+
+    state
+        .note_service()
+        .toggle_pinned(&uid)
+        .map_err(|e| e.to_string())
+}
+
+/// お気に入りを切り替え（切り替え後の状態を返す）
+///
+/// トレイメニューのお気に入り一覧は `note:updated` の通知で更新する。
+#[tauri::command]
+pub fn toggle_favorite(state: State<AppState>, uid: String) -> Result<bool, String> {
+    validate_uid(&uid)?;
+
+    state
+        .note_service()
+        .toggle_favorite(&uid)
+        .map_err(|e| e.to_string())
+}
+
 /// 全メモ一覧を取得
 #[tauri::command]
 pub fn list_notes(state: State<AppState>) -> Result<Vec<NoteListItemDto>, String> {
     state
-        .note_service
+        .note_service()
         .list_notes()
         .map(|items| items.into_iter().map(NoteListItemDto::from).collect())
         .map_err(|e| e.to_string())
 }
 
+/// メモ一覧を1ページ分取得（キーセットページネーション）
+///
+/// `cursor` には前回のレスポンスの `next_cursor` を渡す。
+/// 初回は `cursor` を省略する。
+/// `sort_order` / `tag_filter` を省略した場合は設定に保存されたサイドバーの表示設定を使う。
+/// `folder` を指定するとそのフォルダ（サブフォルダを含む）のノートのみを返す。
+#[tauri::command]
+pub fn list_notes_page(
+    state: State<AppState>,
+    sort_order: Option<NoteSortOrder>,
+    tag_filter: Option<String>,
+    folder: Option<String>,
+    cursor: Option<PageCursor>,
+    limit: Option<usize>,
+) -> Result<PageDto<NoteListItemDto>, String> {
+    let (sort, tag_filter) =
+        super::gallery::resolve_view(&state, ViewKind::Sidebar, sort_order, tag_filter);
+    let folder = folder.as_deref().map(super::folder::parse_folder).transpose()?;
+    let filter = NoteFilter {
+        tag: tag_filter.as_deref(),
+        folder: folder.as_deref(),
+    };
+
+    state
+        .note_service()
+        .list_notes_page(
+            sort,
+            &filter,
+            cursor.as_ref(),
+            clamp_page_size(limit),
+        )
+        .map(PageDto::from_page)
+        .map_err(|e| e.to_string())
+}
+
 /// ノートを検索
 ///
 /// # Performance
@@ -153,7 +267,7 @@ pub fn search_notes(
     }
 
     state
-        .search_service
+        .search_service()
         .search(&query, limit)
         .map(|results| results.into_iter().map(SearchResultDto::from).collect())
         .map_err(|e| e.to_string())
@@ -172,27 +286,26 @@ pub fn resolve_wiki_link(
 
     // タイトルでノートを検索
     if let Some(note_item) = state
-        .search_service
+        .search_service()
         .find_by_title(&title)
         .map_err(|e| e.to_string())?
     {
         // 既存ノートをロード
         return state
-            .note_service
+            .note_service()
             .load_note(&note_item.uid)
             .map(NoteDto::from)
             .map_err(|e| e.to_string());
     }
 
-    // ノートが見つからない場合は新規作成
-    let note = crate::domain::Note::with_title(&title);
-    state
-        .note_service
-        .save_note(&note)
+    // ノートが見つからない場合は新規作成（デフォルトテンプレートを適用）
+    let note = state
+        .note_service()
+        .create_titled_note(&title)
         .map_err(|e| e.to_string())?;
 
     // バックリンクインデックスに追加
-    state.backlink_service.update_note(&note.metadata.uid, &title, &note.content);
+    state.backlink_service().update_note(&note.metadata.uid, &title, &note.content);
 
     Ok(NoteDto::from(note))
 }
@@ -203,36 +316,39 @@ mod tests {
 
     #[test]
     fn test_validate_uid_valid() {
-        // タイムスタンプ形式（アプリ内生成）
+        // タイムスタンプ形式
         assert!(validate_uid("2026011418102637208").is_ok());
         assert!(validate_uid("20260114181236685512").is_ok());
         assert!(validate_uid("20260114181026").is_ok());
+        // ULID・UUID・取り込んだノートの UID
+        assert!(validate_uid("01J1Z9P6V9WQ7H9QXGQ2K5J1ZC").is_ok());
+        assert!(validate_uid("01929a3b-7c4d-7e8f-9a0b-1c2d3e4f5a6b").is_ok());
+        assert!(validate_uid("note_42").is_ok());
     }
 
     #[test]
-    fn test_validate_uid_too_short() {
-        // 14文字未満
-        assert!(validate_uid("1234567890123").is_err());
+    fn test_validate_uid_length() {
         assert!(validate_uid("").is_err());
+        assert!(validate_uid(&"1".repeat(MAX_UID_INPUT_LENGTH + 1)).is_err());
+        assert!(validate_uid(&"1".repeat(MAX_UID_INPUT_LENGTH)).is_ok());
     }
 
     #[test]
-    fn test_validate_uid_too_long() {
-        // 26文字を超える場合はエラー
-        let long_uid = "1".repeat(27);
-        assert!(validate_uid(&long_uid).is_err());
-        // 26文字以下はOK
-        let max_uid = "1".repeat(26);
-        assert!(validate_uid(&max_uid).is_ok());
+    fn test_validate_uid_invalid_chars() {
+        // 制御文字
+        assert!(validate_uid("2026\n0114").is_err());
+        assert!(validate_uid("2026\u{0}0114").is_err());
+        // パスになり得る文字はインデックスの検索とパスの変換で扱う
+        assert!(validate_uid("..").is_ok());
+        assert!(validate_uid("2026 0114").is_ok());
     }
 
     #[test]
-    fn test_validate_uid_invalid_chars() {
-        // 英字（数字のみ許可）
-        assert!(validate_uid("2026011418102a").is_err());
-        // 特殊文字
-        assert!(validate_uid("20260114181026/").is_err());
-        assert!(validate_uid("20260114181026.").is_err());
+    fn test_clamp_page_size() {
+        assert_eq!(clamp_page_size(None), DEFAULT_PAGE_SIZE);
+        assert_eq!(clamp_page_size(Some(0)), 1);
+        assert_eq!(clamp_page_size(Some(50)), 50);
+        assert_eq!(clamp_page_size(Some(MAX_PAGE_SIZE + 1)), MAX_PAGE_SIZE);
     }
 
     #[test]
@@ -1,7 +1,11 @@
 // 設定関連コマンド
 use super::SettingsUpdateDto;
+use crate::domain::{
+    is_valid_date_pattern, is_valid_filename_pattern, FilenameStyle, NormalizeReport,
+    NoteSortOrder, RelocationMode, RelocationReport, RenameReport, TimestampZone, ViewKind,
+};
 use crate::AppState;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tauri::State;
 
 /// 設定を取得
@@ -17,7 +21,7 @@ pub fn get_settings(state: State<AppState>) -> crate::domain::Settings {
 /// - パスが絶対パスであること
 /// - パスに不正な文字が含まれていないこと
 /// - ディレクトリが存在し書き込み可能、または作成可能であること
-fn validate_storage_directory(path: &Path) -> Result<(), String> {
+pub(crate) fn validate_storage_directory(path: &Path) -> Result<(), String> {
     // 絶対パスチェック
     if !path.is_absolute() {
         return Err("Storage directory must be an absolute path".to_string());
@@ -81,12 +85,36 @@ fn validate_storage_directory(path: &Path) -> Result<(), String> {
 }
 
 /// 設定を更新
+///
+/// 保存ディレクトリを変更した場合、既存のノートはそのまま残し、インデックスを新しいディレクトリで
+/// 作り直す（ノートを移す場合は `relocate_storage` を使う）。
 #[tauri::command]
 pub fn update_settings(state: State<AppState>, settings: SettingsUpdateDto) -> Result<(), String> {
     // storage_directory が指定されている場合は事前に検証
     if let Some(ref storage_directory) = settings.storage_directory {
         validate_storage_directory(storage_directory)?;
     }
+    if let Some(ref backup_directory) = settings.backup_directory {
+        if !backup_directory.as_os_str().is_empty() {
+            validate_storage_directory(backup_directory)?;
+        }
+    }
+    let versioning_enabled = settings.versioning_enabled == Some(true);
+    // 保存ディレクトリの変更は保管庫のサービスにも反映するため、他の設定の保存後に移転として行う
+    let storage_directory = settings
+        .storage_directory
+        .clone()
+        .filter(|dir| *dir != state.settings_service.storage_directory());
+    if let Some(ref pattern) = settings.filename_pattern {
+        if !is_valid_filename_pattern(pattern) {
+            return Err(format!("Invalid filename pattern: '{}'", pattern));
+        }
+    }
+    if let Some(ref pattern) = settings.daily_filename_pattern {
+        if !is_valid_date_pattern(pattern) {
+            return Err(format!("Invalid daily note filename pattern: '{}'", pattern));
+        }
+    }
 
     state
         .settings_service
@@ -118,9 +146,6 @@ pub fn update_settings(state: State<AppState>, settings: SettingsUpdateDto) -> R
             if let Some(restore_last) = settings.restore_last_note {
                 s.restore_last_note = restore_last;
             }
-            if let Some(storage_directory) = settings.storage_directory {
-                s.storage_directory = storage_directory;
-            }
             if let Some(shortcut) = settings.shortcut_new_note {
                 s.shortcuts.new_note = shortcut;
             }
@@ -148,7 +173,175 @@ pub fn update_settings(state: State<AppState>, settings: SettingsUpdateDto) -> R
             if let Some(shortcut) = settings.shortcut_backlink_panel {
                 s.shortcuts.backlink_panel = shortcut;
             }
+            if let Some(retention_days) = settings.trash_retention_days {
+                s.trash.retention_days = retention_days;
+            }
+            if let Some(enabled) = settings.history_enabled {
+                s.history.enabled = enabled;
+            }
+            if let Some(interval) = settings.history_min_interval_secs {
+                s.history.min_interval_secs = interval;
+            }
+            if let Some(max_revisions) = settings.history_max_revisions {
+                s.history.max_revisions = max_revisions.max(1);
+            }
+            if let Some(template) = settings.default_template {
+                s.templates.default_template = Some(template).filter(|t| !t.is_empty());
+            }
+            if let Some(style) = settings.filename_style {
+                s.filenames.style = style;
+            }
+            if let Some(pattern) = settings.filename_pattern {
+                s.filenames.pattern = pattern;
+            }
+            if let Some(pattern) = settings.daily_filename_pattern {
+                s.daily_notes.filename_pattern = pattern;
+            }
+            if let Some(template) = settings.daily_template {
+                s.daily_notes.template = Some(template).filter(|t| !t.is_empty());
+            }
+            if let Some(timeout) = settings.encryption_session_timeout_secs {
+                s.encryption.session_timeout_secs = timeout;
+            }
+            if let Some(enabled) = settings.versioning_enabled {
+                s.versioning.enabled = enabled;
+            }
+            if let Some(quiet_period) = settings.versioning_quiet_period_secs {
+                s.versioning.quiet_period_secs = quiet_period;
+            }
+            if let Some(max_interval) = settings.versioning_max_interval_secs {
+                s.versioning.max_interval_secs = max_interval;
+            }
+            if let Some(enabled) = settings.backup_enabled {
+                s.backup.enabled = enabled;
+            }
+            if let Some(interval) = settings.backup_interval_hours {
+                s.backup.interval_hours = interval.max(1);
+            }
+            if let Some(directory) = settings.backup_directory {
+                s.backup.directory = Some(directory).filter(|dir| !dir.as_os_str().is_empty());
+            }
+            if let Some(keep_daily) = settings.backup_keep_daily {
+                s.backup.keep_daily = keep_daily;
+            }
+            if let Some(keep_weekly) = settings.backup_keep_weekly {
+                s.backup.keep_weekly = keep_weekly;
+            }
+            if let Some(interval) = settings.sync_interval_minutes {
+                s.sync.interval_minutes = interval;
+            }
+            if let Some(format) = settings.uid_format {
+                s.uids.format = format;
+            }
+            if let Some(zone) = settings.timestamp_zone {
+                s.timestamps.zone = zone;
+            }
         })
+        .map_err(|e| e.to_string())?;
+
+    if let Some(storage_directory) = storage_directory {
+        state
+            .relocation_service()
+            .relocate(&storage_directory, RelocationMode::Keep)
+            .map_err(|e| e.to_string())?;
+    }
+    // 有効にしたときは既存のノートを次の機会にコミットする
+    if versioning_enabled {
+        state.versioning_service().mark_changed();
+    }
+    Ok(())
+}
+
+/// ビュー（ギャラリー・サイドバー）のソート順とタグフィルタを保存
+///
+/// `tag_filter` に `None` を渡すとフィルタを解除する。
+#[tauri::command]
+pub fn update_view_preference(
+    state: State<AppState>,
+    view: ViewKind,
+    sort_order: NoteSortOrder,
+    tag_filter: Option<String>,
+) -> Result<(), String> {
+    let tag_filter = tag_filter.filter(|tag| !tag.trim().is_empty());
+
+    state
+        .settings_service
+        .update(|s| {
+            let preference = s.views.get_mut(view);
+            preference.sort = sort_order;
+            preference.tag_filter = tag_filter;
+        })
+        .map_err(|e| e.to_string())
+}
+
+/// ファイル名の生成方式を変更し、既存のノートのファイル名を付け直す
+///
+/// front matter で方式を指定したノートはその方式で付け直す。
+#[tauri::command]
+pub fn migrate_filenames(
+    state: State<AppState>,
+    style: FilenameStyle,
+    pattern: Option<String>,
+) -> Result<RenameReport, String> {
+    if let Some(ref pattern) = pattern {
+        if !is_valid_filename_pattern(pattern) {
+            return Err(format!("Invalid filename pattern: '{}'", pattern));
+        }
+    }
+
+    state
+        .settings_service
+        .update(|s| {
+            s.filenames.style = style;
+            if let Some(pattern) = pattern {
+                s.filenames.pattern = pattern;
+            }
+        })
+        .map_err(|e| e.to_string())?;
+
+    state.autosave_service().flush();
+    state
+        .note_service()
+        .rename_all_files()
+        .map_err(|e| e.to_string())
+}
+
+/// front matter の日時のタイムゾーンを変更し、既存のノートの `created_at` / `updated_at` を書き直す
+///
+/// オフセットなしの古い形式のノートもこの形式に揃える。本文と更新日時は変えない。
+#[tauri::command]
+pub fn normalize_timestamps(
+    state: State<AppState>,
+    zone: TimestampZone,
+) -> Result<NormalizeReport, String> {
+    state
+        .settings_service
+        .update(|s| s.timestamps.zone = zone)
+        .map_err(|e| e.to_string())?;
+
+    state.autosave_service().flush();
+    state
+        .note_service()
+        .normalize_timestamps()
+        .map_err(|e| e.to_string())
+}
+
+/// 保存ディレクトリを変更し、既存のノートを移動またはコピーする
+///
+/// 進捗は `relocation-progress` イベントで通知する。移転できなかったファイルがあれば結果の
+/// `failed` に含め、保存ディレクトリは変更しない（`switched` が `false`）。
+/// トレイメニューのお気に入り一覧はインデックスを作り直したときの `index:rebuilt` の通知で更新する。
+#[tauri::command]
+pub fn relocate_storage(
+    state: State<AppState>,
+    storage_directory: PathBuf,
+    mode: RelocationMode,
+) -> Result<RelocationReport, String> {
+    validate_storage_directory(&storage_directory)?;
+
+    state
+        .relocation_service()
+        .relocate(&storage_directory, mode)
         .map_err(|e| e.to_string())
 }
 
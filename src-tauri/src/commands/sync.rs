@@ -0,0 +1,224 @@
+//! フォルダミラーとの同期関連コマンド
+
+use super::NoteDto;
+use crate::domain::{ConflictMerge, CopyKind, DuplicateGroup, SyncConflict, SyncReport, UidRepair};
+use crate::AppState;
+use serde::Serialize;
+use std::path::Path;
+use tauri::State;
+
+/// 競合 DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncConflictDto {
+    pub path: String,
+    pub conflict_copy: String,
+}
+
+impl From<SyncConflict> for SyncConflictDto {
+    fn from(conflict: SyncConflict) -> Self {
+        Self {
+            path: conflict.path,
+            conflict_copy: conflict.conflict_copy,
+        }
+    }
+}
+
+/// 同期結果 DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncReportDto {
+    pub pulled: usize,
+    pub pushed: usize,
+    pub deleted: usize,
+    pub conflicts: Vec<SyncConflictDto>,
+}
+
+impl From<SyncReport> for SyncReportDto {
+    fn from(report: SyncReport) -> Self {
+        Self {
+            pulled: report.pulled,
+            pushed: report.pushed,
+            deleted: report.deleted,
+            conflicts: report.conflicts.into_iter().map(SyncConflictDto::from).collect(),
+        }
+    }
+}
+
+/// 使用中の保管庫を今すぐミラーと同期
+///
+/// 両側で変更されたファイルは保存ディレクトリの版を残し、ミラーの版を競合コピーとして両側に保存する。
+#[tauri::command]
+pub fn sync_mirror(state: State<AppState>) -> Result<SyncReportDto, String> {
+    state
+        .sync_service()
+        .sync_now()
+        .map(SyncReportDto::from)
+        .map_err(|e| e.to_string())
+}
+
+/// 同じ UID を持つ複製 DTO（パスは保存ディレクトリからの `/` 区切りの相対パス）
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateCopyDto {
+    pub path: String,
+    pub kind: CopyKind,
+}
+
+/// 同じ UID を持つファイルの組 DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroupDto {
+    pub uid: String,
+    /// インデックスに登録している元のノート
+    pub path: String,
+    pub copies: Vec<DuplicateCopyDto>,
+}
+
+impl DuplicateGroupDto {
+    fn new(group: DuplicateGroup, base_dir: &Path) -> Self {
+        Self {
+            uid: group.uid,
+            path: relative_path(base_dir, &group.primary),
+            copies: group
+                .copies
+                .iter()
+                .map(|copy| DuplicateCopyDto {
+                    path: relative_path(base_dir, &copy.path),
+                    kind: copy.kind(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// 競合コピーのマージ結果 DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictMergeDto {
+    pub ours: String,
+    pub theirs: String,
+    pub base_found: bool,
+    /// マージした本文（競合箇所は `<<<<<<<` / `=======` / `>>>>>>>` で囲む）
+    pub merged: String,
+    pub conflicts: usize,
+}
+
+impl From<ConflictMerge> for ConflictMergeDto {
+    fn from(merge: ConflictMerge) -> Self {
+        Self {
+            ours: merge.ours,
+            theirs: merge.theirs,
+            base_found: merge.base_found,
+            merged: merge.merged.content,
+            conflicts: merge.merged.conflicts,
+        }
+    }
+}
+
+/// 新しい UID を振った複製 DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct UidRepairDto {
+    pub path: String,
+    pub old_uid: String,
+    pub new_uid: String,
+}
+
+impl UidRepairDto {
+    fn new(repair: UidRepair, base_dir: &Path) -> Self {
+        Self {
+            path: relative_path(base_dir, &repair.path),
+            old_uid: repair.old_uid,
+            new_uid: repair.new_uid,
+        }
+    }
+}
+
+/// 保存ディレクトリからの `/` 区切りの相対パス
+fn relative_path(base_dir: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(base_dir).unwrap_or(path);
+    relative
+        .iter()
+        .map(|part| part.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 同期ツールの競合コピー（Syncthing・Dropbox・ミラー同期）があるノートの一覧
+#[tauri::command]
+pub fn list_note_conflicts(state: State<AppState>) -> Result<Vec<DuplicateGroupDto>, String> {
+    let duplicate_service = state.duplicate_service();
+    let base_dir = duplicate_service.base_dir();
+    duplicate_service
+        .list_conflicts()
+        .map(|groups| {
+            groups
+                .into_iter()
+                .map(|group| DuplicateGroupDto::new(group, &base_dir))
+                .collect()
+        })
+        .map_err(|e| e.to_string())
+}
+
+/// ノートと競合コピーの本文を3方向マージする（保存はしない）
+#[tauri::command]
+pub fn merge_note_conflict(
+    state: State<AppState>,
+    uid: String,
+    copy_path: String,
+) -> Result<ConflictMergeDto, String> {
+    state
+        .duplicate_service()
+        .merge_conflict(&uid, &copy_path)
+        .map(ConflictMergeDto::from)
+        .map_err(|e| e.to_string())
+}
+
+/// 1つのファイルを残して競合を解決する
+///
+/// `content` を指定した場合（マージ結果を編集したものなど）はその本文で元のノートを置き換える。
+/// 競合コピーは保存ディレクトリの `.sync-trash` へ移す（手作業でコピーした複製は残す）。
+#[tauri::command]
+pub fn resolve_note_conflict(
+    state: State<AppState>,
+    uid: String,
+    keep_path: String,
+    content: Option<String>,
+) -> Result<NoteDto, String> {
+    state.autosave_service().flush();
+    state
+        .duplicate_service()
+        .resolve_conflict(&uid, &keep_path, content)
+        .map(NoteDto::from)
+        .map_err(|e| e.to_string())
+}
+
+/// 手作業でコピーしたファイルなど、同じ UID を持つ複製があるノートの一覧
+#[tauri::command]
+pub fn list_duplicate_uids(state: State<AppState>) -> Result<Vec<DuplicateGroupDto>, String> {
+    let duplicate_service = state.duplicate_service();
+    let base_dir = duplicate_service.base_dir();
+    duplicate_service
+        .list_duplicate_uids()
+        .map(|groups| {
+            groups
+                .into_iter()
+                .map(|group| DuplicateGroupDto::new(group, &base_dir))
+                .collect()
+        })
+        .map_err(|e| e.to_string())
+}
+
+/// 複製に新しい UID を振って別のノートとして登録する（競合コピーは対象外）
+///
+/// 複製の front matter の `uid` のみを書き換え、ファイル名と本文は変えない。
+#[tauri::command]
+pub fn repair_duplicate_uids(state: State<AppState>) -> Result<Vec<UidRepairDto>, String> {
+    let duplicate_service = state.duplicate_service();
+    let base_dir = duplicate_service.base_dir();
+    state.autosave_service().flush();
+    duplicate_service
+        .repair_duplicate_uids()
+        .map(|repairs| {
+            repairs
+                .into_iter()
+                .map(|repair| UidRepairDto::new(repair, &base_dir))
+                .collect()
+        })
+        .map_err(|e| e.to_string())
+}
@@ -37,7 +37,7 @@ pub fn update_note_tags(
 
     // ノートをロード
     let mut note = state
-        .note_service
+        .note_service()
         .load_note(&uid)
         .map_err(|e| e.to_string())?;
 
@@ -46,7 +46,7 @@ pub fn update_note_tags(
 
     // 保存
     state
-        .note_service
+        .note_service()
         .save_note(&note)
         .map_err(|e| e.to_string())?;
 
@@ -58,7 +58,7 @@ pub fn update_note_tags(
 pub fn get_all_tags(state: State<AppState>) -> Result<Vec<String>, String> {
     // 全ノートからタグを収集
     let notes = state
-        .note_service
+        .note_service()
         .list_notes()
         .map_err(|e| e.to_string())?;
 
@@ -66,7 +66,7 @@ pub fn get_all_tags(state: State<AppState>) -> Result<Vec<String>, String> {
 
     for note_item in notes {
         // 各ノートをロードしてタグを取得
-        if let Ok(note) = state.note_service.load_note(&note_item.uid) {
+        if let Ok(note) = state.note_service().load_note(&note_item.uid) {
             for tag in note.all_tags() {
                 if !all_tags.contains(&tag) {
                     all_tags.push(tag);
@@ -87,7 +87,7 @@ pub fn get_note_tags(state: State<AppState>, uid: String) -> Result<NoteTagsDto,
     super::note::validate_uid(&uid)?;
 
     let note = state
-        .note_service
+        .note_service()
         .load_note(&uid)
         .map_err(|e| e.to_string())?;
 
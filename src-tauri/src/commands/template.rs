@@ -0,0 +1,58 @@
+//! テンプレート関連コマンド
+
+use super::NoteDto;
+use crate::domain::TemplateVars;
+use crate::infrastructure::template_store::is_valid_name;
+use crate::AppState;
+use std::collections::HashMap;
+use tauri::State;
+
+/// 変数の数の上限（DoS防止）
+const MAX_TEMPLATE_VARS: usize = 50;
+
+/// 変数の値の長さの上限（クリップボードの内容を含むため大きめ）
+const MAX_TEMPLATE_VAR_LENGTH: usize = 1024 * 1024;
+
+/// テンプレート名の一覧を取得
+#[tauri::command]
+pub fn list_templates(state: State<AppState>) -> Result<Vec<String>, String> {
+    state
+        .template_service
+        .list_templates()
+        .map_err(|e| e.to_string())
+}
+
+/// テンプレートからメモを作成して保存
+///
+/// `vars` は `{{name}}` の値（`title`・`clipboard` など）。
+/// `date`・`time`・`datetime` は省略するとローカルの現在日時、`clipboard` は現在のクリップボードの内容になる。
+#[tauri::command]
+pub fn create_note_from_template(
+    state: State<AppState>,
+    name: String,
+    vars: Option<HashMap<String, String>>,
+) -> Result<NoteDto, String> {
+    if !is_valid_name(&name) {
+        return Err(format!("Invalid template name: '{}'", name));
+    }
+
+    let vars: TemplateVars = vars.unwrap_or_default();
+    if vars.len() > MAX_TEMPLATE_VARS
+        || vars.values().any(|v| v.len() > MAX_TEMPLATE_VAR_LENGTH)
+    {
+        return Err("Template variables too large".to_string());
+    }
+
+    let note = state
+        .note_service()
+        .create_note_from_template(&name, vars)
+        .map_err(|e| e.to_string())?;
+
+    // バックリンクインデックスに追加
+    let title = note.metadata.title.clone().unwrap_or_default();
+    state
+        .backlink_service()
+        .update_note(&note.metadata.uid, &title, &note.content);
+
+    Ok(NoteDto::from(note))
+}
@@ -0,0 +1,70 @@
+//! ゴミ箱関連コマンド
+
+use super::NoteDto;
+use crate::domain::TrashItem;
+use crate::AppState;
+use serde::Serialize;
+use tauri::State;
+
+/// ゴミ箱アイテム DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashItemDto {
+    pub uid: String,
+    pub title: String,
+    /// 削除前のパス（保存ディレクトリからの相対パス）
+    pub original_path: String,
+    pub deleted_at: String,
+}
+
+impl From<TrashItem> for TrashItemDto {
+    fn from(item: TrashItem) -> Self {
+        Self {
+            uid: item.uid,
+            title: item.title,
+            original_path: item.original_path.to_string_lossy().to_string(),
+            deleted_at: item.deleted_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// ゴミ箱内のメモ一覧を取得（削除日時の新しい順）
+#[tauri::command]
+pub fn list_trash(state: State<AppState>) -> Result<Vec<TrashItemDto>, String> {
+    state
+        .note_service()
+        .list_trash()
+        .map(|items| items.into_iter().map(TrashItemDto::from).collect())
+        .map_err(|e| e.to_string())
+}
+
+/// ゴミ箱からメモを復元
+///
+/// インデックスとバックリンクも再作成する。
+#[tauri::command]
+pub fn restore_note(state: State<AppState>, uid: String) -> Result<NoteDto, String> {
+    super::note::validate_uid(&uid)?;
+
+    let note = state
+        .note_service()
+        .restore_note(&uid)
+        .map_err(|e| e.to_string())?;
+
+    // バックリンクインデックスに再登録
+    let title = note
+        .extract_heading()
+        .unwrap_or_else(|| note.metadata.uid.clone());
+    state
+        .backlink_service()
+        .update_note(&note.metadata.uid, &title, &note.content);
+
+    Ok(NoteDto::from(note))
+}
+
+/// ゴミ箱を空にする（削除した件数を返す）
+#[tauri::command]
+pub fn empty_trash(state: State<AppState>) -> Result<usize, String> {
+    state
+        .note_service()
+        .empty_trash()
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,158 @@
+//! 保管庫（Vault）関連コマンド
+
+use super::settings::validate_storage_directory;
+use crate::domain::{is_nested_directory, is_valid_vault_name, Vault};
+use crate::AppState;
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+
+/// 保管庫DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct VaultDto {
+    pub id: String,
+    pub name: String,
+    pub storage_directory: PathBuf,
+    pub mirror_directory: Option<PathBuf>,
+    /// 使用中か
+    pub active: bool,
+}
+
+impl VaultDto {
+    fn new(vault: Vault, active_id: &str) -> Self {
+        Self {
+            active: vault.id == active_id,
+            id: vault.id,
+            name: vault.name,
+            storage_directory: vault.storage_directory,
+            mirror_directory: vault.mirror_directory,
+        }
+    }
+}
+
+/// 登録済みの保管庫一覧を取得
+#[tauri::command]
+pub fn list_vaults(state: State<AppState>) -> Vec<VaultDto> {
+    let vaults = state.settings_service.get_arc().vaults.clone();
+    vaults
+        .list
+        .into_iter()
+        .map(|vault| VaultDto::new(vault, &vaults.active))
+        .collect()
+}
+
+/// 保管庫を登録（同じ保存ディレクトリの保管庫は登録できない）
+#[tauri::command]
+pub fn add_vault(
+    state: State<AppState>,
+    name: String,
+    storage_directory: PathBuf,
+) -> Result<VaultDto, String> {
+    let name = name.trim().to_string();
+    if !is_valid_vault_name(&name) {
+        return Err(format!("Invalid vault name: '{}'", name));
+    }
+    validate_storage_directory(&storage_directory)?;
+
+    let mut added = None;
+    state
+        .settings_service
+        .update(|s| {
+            if s.vaults
+                .list
+                .iter()
+                .any(|vault| vault.storage_directory == storage_directory)
+            {
+                return;
+            }
+            let vault = Vault {
+                id: s.vaults.unique_id(&name),
+                name: name.clone(),
+                storage_directory: storage_directory.clone(),
+                mirror_directory: None,
+            };
+            s.vaults.list.push(vault.clone());
+            added = Some(VaultDto::new(vault, &s.vaults.active));
+        })
+        .map_err(|e| e.to_string())?;
+
+    added.ok_or_else(|| {
+        format!(
+            "A vault already uses this directory: {}",
+            storage_directory.display()
+        )
+    })
+}
+
+/// 保管庫の登録を解除（ノートやインデックスは削除しない）
+///
+/// 使用中の保管庫は解除できない。
+#[tauri::command]
+pub fn remove_vault(state: State<AppState>, id: String) -> Result<(), String> {
+    // 確認と解除を同じ更新の中で行い、その間の切り替えと食い違わないようにする
+    let mut result = Ok(());
+    state
+        .settings_service
+        .update(|s| {
+            if s.vaults.get(&id).is_none() {
+                result = Err(format!("Vault not found: '{}'", id));
+            } else if s.vaults.active == id {
+                result = Err("Cannot remove the active vault".to_string());
+            } else {
+                s.vaults.list.retain(|vault| vault.id != id);
+            }
+        })
+        .map_err(|e| e.to_string())?;
+    result
+}
+
+/// 保管庫のミラーのディレクトリを設定（`None` で同期をやめる）
+///
+/// 保存ディレクトリと同じ・入れ子のディレクトリは指定できない。
+#[tauri::command]
+pub fn set_vault_mirror(
+    state: State<AppState>,
+    id: String,
+    mirror_directory: Option<PathBuf>,
+) -> Result<VaultDto, String> {
+    if let Some(ref mirror) = mirror_directory {
+        validate_storage_directory(mirror)?;
+    }
+
+    // 保存ディレクトリとの比較と設定を同じ更新の中で行い、その間の移転と食い違わないようにする
+    let mut result = Err(format!("Vault not found: '{}'", id));
+    state
+        .settings_service
+        .update(|s| {
+            let active = s.vaults.active.clone();
+            let Some(vault) = s.vaults.list.iter_mut().find(|vault| vault.id == id) else {
+                return;
+            };
+            if let Some(ref mirror) = mirror_directory {
+                if *mirror == vault.storage_directory
+                    || is_nested_directory(mirror, &vault.storage_directory)
+                {
+                    result = Err("Mirror directory must be outside the storage directory".to_string());
+                    return;
+                }
+            }
+            vault.mirror_directory = mirror_directory.clone();
+            result = Ok(VaultDto::new(vault.clone(), &active));
+        })
+        .map_err(|e| e.to_string())?;
+    result
+}
+
+/// 保管庫を切り替える（保存待ちの変更を書き出してから、インデックスとサービスを差し替える）
+///
+/// トレイメニューのお気に入り一覧も切り替え先の保管庫に更新する。
+#[tauri::command]
+pub fn switch_vault(app: AppHandle, state: State<AppState>, id: String) -> Result<VaultDto, String> {
+    let vault = state.switch_vault(&id)?;
+
+    if let Err(e) = crate::platform::refresh_tray_menu(&app) {
+        eprintln!("[Tray] Failed to refresh favorites: {}", e);
+    }
+
+    Ok(VaultDto::new(vault, &id))
+}
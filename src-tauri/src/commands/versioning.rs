@@ -0,0 +1,148 @@
+//! Git によるバージョン管理関連コマンド
+
+use super::NoteDto;
+use crate::domain::{VaultCommit, VersioningStatus};
+use crate::AppState;
+use serde::Serialize;
+use tauri::State;
+
+/// コミット DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct VaultCommitDto {
+    pub id: String,
+    /// 短縮したハッシュ（表示用）
+    pub short_id: String,
+    pub summary: String,
+    pub committed_at: String,
+}
+
+impl From<VaultCommit> for VaultCommitDto {
+    fn from(commit: VaultCommit) -> Self {
+        Self {
+            short_id: commit.id.chars().take(7).collect(),
+            id: commit.id,
+            summary: commit.summary,
+            committed_at: commit
+                .committed_at
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+        }
+    }
+}
+
+/// バージョン管理の状態 DTO
+#[derive(Debug, Clone, Serialize)]
+pub struct VersioningStatusDto {
+    pub enabled: bool,
+    pub git_available: bool,
+    pub initialized: bool,
+    pub has_pending_changes: bool,
+    pub last_commit: Option<VaultCommitDto>,
+}
+
+impl From<VersioningStatus> for VersioningStatusDto {
+    fn from(status: VersioningStatus) -> Self {
+        Self {
+            enabled: status.enabled,
+            git_available: status.git_available,
+            initialized: status.initialized,
+            has_pending_changes: status.has_pending_changes,
+            last_commit: status.last_commit.map(VaultCommitDto::from),
+        }
+    }
+}
+
+/// バージョン管理の状態を取得
+#[tauri::command]
+pub fn get_versioning_status(state: State<AppState>) -> VersioningStatusDto {
+    VersioningStatusDto::from(state.versioning_service().status())
+}
+
+/// 未コミットの変更を今すぐコミット（変更がなければ null）
+///
+/// `message` を省略した場合は変更したノートからメッセージを作る。
+/// リポジトリがなければ作成する。
+#[tauri::command]
+pub fn commit_vault(
+    state: State<AppState>,
+    message: Option<String>,
+) -> Result<Option<VaultCommitDto>, String> {
+    // 保存待ちの変更も含めてコミットする
+    state.autosave_service().flush();
+
+    state
+        .versioning_service()
+        .commit_now(message.as_deref())
+        .map(|commit| commit.map(VaultCommitDto::from))
+        .map_err(|e| e.to_string())
+}
+
+/// メモのコミット履歴を取得（新しい順）
+#[tauri::command]
+pub fn list_note_commits(
+    state: State<AppState>,
+    uid: String,
+) -> Result<Vec<VaultCommitDto>, String> {
+    super::note::validate_uid(&uid)?;
+
+    state
+        .versioning_service()
+        .note_history(&uid)
+        .map(|commits| commits.into_iter().map(VaultCommitDto::from).collect())
+        .map_err(|e| e.to_string())
+}
+
+/// コミット時点のメモを取得（保存はしない、暗号化されていた版は復号する）
+#[tauri::command]
+pub fn load_note_commit(
+    state: State<AppState>,
+    uid: String,
+    commit_id: String,
+) -> Result<NoteDto, String> {
+    super::note::validate_uid(&uid)?;
+
+    let file_content = state
+        .versioning_service()
+        .note_file_at(&uid, &commit_id)
+        .map_err(|e| e.to_string())?;
+    state
+        .note_service()
+        .decode_version(&uid, &file_content)
+        .map(NoteDto::from)
+        .map_err(|e| e.to_string())
+}
+
+/// コミット時点の本文でメモを置き換える
+///
+/// 置き換える前の本文はリビジョン履歴に残すため、復元自体も元に戻せる。
+#[tauri::command]
+pub fn restore_note_commit(
+    state: State<AppState>,
+    uid: String,
+    commit_id: String,
+) -> Result<NoteDto, String> {
+    super::note::validate_uid(&uid)?;
+
+    // 保存待ちの変更で復元した本文が上書きされないよう先に書き出す
+    state.autosave_service().flush();
+
+    let file_content = state
+        .versioning_service()
+        .note_file_at(&uid, &commit_id)
+        .map_err(|e| e.to_string())?;
+    let note = state
+        .note_service()
+        .restore_version(&uid, &file_content)
+        .map_err(|e| e.to_string())?;
+
+    if !note.metadata.encrypted {
+        let title = note
+            .extract_heading()
+            .unwrap_or_else(|| note.metadata.uid.clone());
+        state
+            .backlink_service()
+            .update_note(&note.metadata.uid, &title, &note.content);
+    }
+
+    Ok(NoteDto::from(note))
+}
@@ -1,4 +1,6 @@
 // ウィンドウ関連コマンド
+use super::note::parse_expected_version;
+use super::SaveErrorDto;
 use crate::AppState;
 use tauri::{AppHandle, Manager, State};
 
@@ -28,23 +30,20 @@ pub async fn prepare_hide(
     state: State<'_, AppState>,
     uid: Option<String>,
     content: Option<String>,
-) -> Result<(), String> {
-    // コンテンツがあれば保存
+    expected_hash: Option<String>,
+    expected_updated_at: Option<String>,
+) -> Result<(), SaveErrorDto> {
+    // コンテンツがあれば保存（ファイルが他で変更されていれば競合エラー）
     if let (Some(ref uid_str), Some(content)) = (&uid, content) {
-        let mut note = state
-            .note_service
-            .load_note(uid_str)
-            .map_err(|e| e.to_string())?;
-
-        if note.content != content {
-            note.update_content(content);
-            state
-                .note_service
-                .save_note(&note)
-                .map_err(|e| e.to_string())?;
-        }
+        let expected = parse_expected_version(expected_hash, expected_updated_at)?;
+        state
+            .autosave_service()
+            .save_now(uid_str, content, expected)?;
     }
 
+    // 他のメモの保存待ちもここで書き出す
+    state.autosave_service().flush();
+
     // 最後に開いたノートを記録
     let _ = state.settings_service.update_last_note_uid(uid);
 
@@ -69,7 +68,13 @@ pub fn set_last_note_uid(state: State<AppState>, uid: Option<String>) -> Result<
 
 /// アプリケーションを終了
 #[tauri::command]
-pub fn quit_app(app: AppHandle) {
+pub fn quit_app(app: AppHandle, state: State<AppState>) {
+    // 保存待ちの自動保存を書き出し、未コミットの変更をコミットしてから終了
+    state.autosave_service().shutdown();
+    state.versioning_service().shutdown();
+    state.backup_service().shutdown();
+    state.sync_service().shutdown();
+    state.trash_service().shutdown();
     app.exit(0);
 }
 
@@ -0,0 +1,182 @@
+//! 保管庫のバックアップ
+//!
+//! 保存ディレクトリのファイル（ノート・添付ファイル・除外ルール）と設定を1つの圧縮アーカイブ
+//! （`.tar.gz`）にまとめて、バックアップ用のディレクトリに保存する。
+//!
+//! # アーカイブの構成
+//!
+//! ```text
+//! notes/<保存ディレクトリからの相対パス>   ノートと添付ファイル（ゴミ箱とドット始まりのファイルは除く）
+//! config.toml                            バックアップ時点の設定
+//! ```
+//!
+//! # 世代管理
+//!
+//! 新しい順に、日ごとの最新のアーカイブを `keep_daily` 日分、週（ISO 週）ごとの最新の
+//! アーカイブを `keep_weekly` 週分残し、それ以外を削除する。日・週の区切りは UTC。
+
+use chrono::{DateTime, Datelike, NaiveDateTime, SubsecRound, Utc};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// アーカイブ内のノートのディレクトリ
+pub const ARCHIVE_NOTES_DIR: &str = "notes";
+
+/// アーカイブ内の設定ファイル
+pub const ARCHIVE_SETTINGS_FILE: &str = "config.toml";
+
+/// アーカイブのファイル名の接頭辞と拡張子（`kaku-{保管庫}-{日時}.tar.gz`）
+const ARCHIVE_PREFIX: &str = "kaku-";
+pub const ARCHIVE_EXTENSION: &str = ".tar.gz";
+
+/// ファイル名の日時の形式（UTC、復元直前のバックアップと重ならないようミリ秒まで）
+const ARCHIVE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// バックアップのアーカイブ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BackupInfo {
+    /// ファイル名（復元時の指定に使う）
+    pub name: String,
+    /// 保管庫の ID
+    pub vault: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl BackupInfo {
+    /// 保管庫と日時からアーカイブを作る（日時はファイル名に合わせてミリ秒に切り捨てる）
+    pub fn new(vault: &str, created_at: DateTime<Utc>) -> Self {
+        let created_at = created_at.trunc_subsecs(3);
+        Self {
+            name: format!(
+                "{}{}-{}{}",
+                ARCHIVE_PREFIX,
+                vault,
+                created_at.format(ARCHIVE_TIME_FORMAT),
+                ARCHIVE_EXTENSION
+            ),
+            vault: vault.to_string(),
+            created_at,
+        }
+    }
+
+    /// ファイル名からアーカイブを読み取る（形式が違う場合は `None`）
+    pub fn parse(name: &str) -> Option<Self> {
+        let stem = name.strip_prefix(ARCHIVE_PREFIX)?.strip_suffix(ARCHIVE_EXTENSION)?;
+        let (vault, time) = stem.rsplit_once('-')?;
+        let created_at = NaiveDateTime::parse_from_str(time, ARCHIVE_TIME_FORMAT)
+            .ok()?
+            .and_utc();
+        (!vault.is_empty()).then(|| Self {
+            name: name.to_string(),
+            vault: vault.to_string(),
+            created_at,
+        })
+    }
+
+    /// パスのファイル名からアーカイブを読み取る
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.file_name().and_then(|name| name.to_str()).and_then(Self::parse)
+    }
+}
+
+/// バックアップ結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BackupReport {
+    pub backup: BackupInfo,
+    /// アーカイブに含めたファイル数（設定ファイルを除く）
+    pub files: usize,
+    /// 世代管理で削除したアーカイブ数
+    pub pruned: usize,
+}
+
+/// 復元結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RestoreReport {
+    /// 書き戻したファイル数
+    pub restored: usize,
+    /// 設定も復元したか
+    pub settings_restored: bool,
+    /// 復元する前に作成した現在の状態のバックアップ
+    pub safety_backup: Option<BackupInfo>,
+}
+
+/// 世代管理で削除するアーカイブ
+///
+/// 日ごと・週ごとに最新のアーカイブを残す。いずれにも残らないものを返す。
+pub fn backups_to_prune(backups: &[BackupInfo], keep_daily: u32, keep_weekly: u32) -> Vec<BackupInfo> {
+    let mut sorted: Vec<&BackupInfo> = backups.iter().collect();
+    sorted.sort_by_key(|backup| Reverse(backup.created_at));
+
+    let mut days = HashSet::new();
+    let mut weeks = HashSet::new();
+    sorted
+        .into_iter()
+        .filter(|backup| {
+            let day = backup.created_at.date_naive();
+            let week = day.iso_week();
+            let mut keep = false;
+            if days.len() < keep_daily as usize && days.insert(day) {
+                keep = true;
+            }
+            if weeks.len() < keep_weekly as usize && weeks.insert((week.year(), week.week())) {
+                keep = true;
+            }
+            !keep
+        })
+        .cloned()
+        .collect()
+}
+
+/// バックアップのエラー
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("バックアップが見つかりません: {0}")]
+    NotFound(String),
+    #[error("アーカイブが不正です: {0}")]
+    InvalidArchive(String),
+    #[error("ストレージエラー: {0}")]
+    Storage(#[from] crate::traits::StorageError),
+    #[error("設定エラー: {0}")]
+    Settings(#[from] super::SettingsError),
+    #[error("インデックスの再構築に失敗しました: {0}")]
+    Reindex(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn backup_at(day: u32, hour: u32) -> BackupInfo {
+        BackupInfo::new("default", Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn test_archive_name_roundtrip() {
+        let backup = backup_at(5, 9);
+        assert_eq!(backup.name, "kaku-default-20260305T090000.000Z.tar.gz");
+        assert_eq!(BackupInfo::parse(&backup.name), Some(backup));
+
+        let work = BackupInfo::new("work-2", Utc.with_ymd_and_hms(2026, 3, 5, 9, 0, 0).unwrap());
+        assert_eq!(BackupInfo::parse(&work.name).unwrap().vault, "work-2");
+        assert_eq!(BackupInfo::parse("notes.tar.gz"), None);
+    }
+
+    #[test]
+    fn test_backups_to_prune_keeps_daily_and_weekly() {
+        // 2026-03-02 は月曜日。3/2〜3/15 の毎日2回
+        let backups: Vec<BackupInfo> = (2..=15)
+            .flat_map(|day| [backup_at(day, 9), backup_at(day, 18)])
+            .collect();
+
+        let pruned = backups_to_prune(&backups, 3, 2);
+        let kept: Vec<&BackupInfo> = backups.iter().filter(|b| !pruned.contains(b)).collect();
+
+        // 直近3日の最新 + 前の週の最新（今週の分は日ごとで残っている）
+        let expected = [backup_at(15, 18), backup_at(14, 18), backup_at(13, 18), backup_at(8, 18)];
+        assert_eq!(kept.len(), expected.len());
+        assert!(expected.iter().all(|b| kept.contains(&b)));
+    }
+}
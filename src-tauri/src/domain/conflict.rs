@@ -0,0 +1,73 @@
+//! 保存時の競合検出（楽観的排他制御）関連のドメインモデル
+
+use super::Note;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 保存時にクライアントが前提とするメモの版
+///
+/// 最後に読み込んだ（または保存した）時点の `content_hash` / `updated_at` を保持する。
+/// どちらも `None` の場合は検査せずに保存する。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExpectedVersion {
+    pub content_hash: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl ExpectedVersion {
+    /// 検査対象がない（無条件で保存する）か
+    pub fn is_unconditional(&self) -> bool {
+        self.content_hash.is_none() && self.updated_at.is_none()
+    }
+
+    /// 現在のメモが前提の版と一致するか
+    ///
+    /// `updated_at` は表示形式に合わせて秒単位で比較する。
+    pub fn matches(&self, current: &Note) -> bool {
+        let hash_matches = self
+            .content_hash
+            .as_ref()
+            .is_none_or(|hash| *hash == current.content_hash());
+        let updated_matches = self
+            .updated_at
+            .is_none_or(|t| t.timestamp() == current.metadata.updated_at.timestamp());
+        hash_matches && updated_matches
+    }
+}
+
+/// 保存時の競合（ファイルが読み込み後に他の場所で変更された）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteConflict {
+    /// 保存しようとした版
+    pub local: Note,
+    /// 現在ファイルに保存されている版
+    pub remote: Note,
+}
+
+impl NoteConflict {
+    pub fn uid(&self) -> &str {
+        self.local.uid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_version_matches() {
+        let mut note = Note::with_uid("20260101000000".to_string());
+        note.update_content("# v1".to_string());
+
+        let expected = ExpectedVersion {
+            content_hash: Some(note.content_hash()),
+            updated_at: Some(note.metadata.updated_at),
+        };
+        assert!(expected.matches(&note));
+        assert!(ExpectedVersion::default().matches(&note));
+
+        let mut changed = note.clone();
+        changed.content = "# v2".to_string();
+        assert!(!expected.matches(&changed));
+    }
+}
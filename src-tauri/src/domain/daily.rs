@@ -0,0 +1,65 @@
+//! デイリーノート
+//!
+//! デイリーノートは front matter に `daily: YYYY-MM-DD` を持つ通常のノート。
+//! ファイル名は `DailyNoteSettings::filename_pattern`（strftime 形式）から生成する。
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// 既定のファイル名パターン
+pub const DEFAULT_DAILY_PATTERN: &str = "%Y-%m-%d";
+
+/// ファイル名パターンの最大長
+const MAX_PATTERN_LEN: usize = 100;
+
+/// デイリーノートの日付と UID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyNoteEntry {
+    pub date: NaiveDate,
+    pub uid: String,
+}
+
+/// デイリーノートの移動方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DailyDirection {
+    /// 前の（より古い）デイリーノート
+    Previous,
+    /// 次の（より新しい）デイリーノート
+    Next,
+}
+
+/// 日付パターンとして使えるか（不正な指定子を含む場合はフォーマット時に panic するため事前に検証する）
+pub fn is_valid_date_pattern(pattern: &str) -> bool {
+    !pattern.trim().is_empty()
+        && pattern.len() <= MAX_PATTERN_LEN
+        && StrftimeItems::new(pattern).all(|item| !matches!(item, Item::Error))
+}
+
+/// 日付パターンからファイル名（拡張子なし）を生成
+///
+/// パターンが不正な場合は既定のパターンを使う。
+pub fn format_daily_name(date: NaiveDate, pattern: &str) -> String {
+    let pattern = if is_valid_date_pattern(pattern) {
+        pattern
+    } else {
+        DEFAULT_DAILY_PATTERN
+    };
+    date.format(pattern).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_daily_name() {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 2).unwrap();
+        assert_eq!(format_daily_name(date, DEFAULT_DAILY_PATTERN), "2026-01-02");
+        assert_eq!(format_daily_name(date, "%Y%m%d 日記"), "20260102 日記");
+        // 不正なパターンは既定のパターンで置き換える
+        assert!(!is_valid_date_pattern("%Q"));
+        assert_eq!(format_daily_name(date, "%Q"), "2026-01-02");
+    }
+}
@@ -0,0 +1,12 @@
+//! 未保存の下書き（クラッシュ復旧用ジャーナル）
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 下書きジャーナルの1エントリ（エディタのバッファのスナップショット）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Draft {
+    pub uid: String,
+    pub content: String,
+    pub saved_at: DateTime<Utc>,
+}
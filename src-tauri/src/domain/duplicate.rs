@@ -0,0 +1,157 @@
+//! 同じ UID を持つノートファイル
+//!
+//! 同期ツールの競合コピー（`メモ.sync-conflict-20261017-120000-ABCDEFG.md`、
+//! `メモ (conflicted copy).md` など）や手作業で複製したファイルは、元のノートと同じ
+//! フロントマターの UID を持つ。インデックスは UID ごとに1つのファイル（元のファイル）のみを
+//! 登録し、それ以外を重複として記録する。
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Syncthing の競合コピー（`{名前}.sync-conflict-{日付}-{時刻}-{デバイス ID}`）
+static SYNCTHING_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\.sync-conflict-\d{8}-\d{6}(?:-[A-Z0-9]+)?$").unwrap());
+
+/// Dropbox の競合コピー（`{名前} ({ユーザー}'s conflicted copy {日付})` など）
+static DROPBOX_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i) \((?:[^()]*'s )?conflicted copy(?: \d{4}-\d{2}-\d{2})?(?: \(\d+\))?\)$").unwrap()
+});
+
+/// ミラーとの同期で作成した競合コピー（`{名前} (conflict {日付} {時刻})`）
+static KAKU_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r" \(conflict \d{4}-\d{2}-\d{2} \d{6}\)$").unwrap());
+
+/// 重複したファイルの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CopyKind {
+    /// Syncthing の競合コピー
+    Syncthing,
+    /// Dropbox の競合コピー
+    Dropbox,
+    /// ミラーとの同期で作成した競合コピー
+    Mirror,
+    /// 競合コピーの名前ではない複製
+    Duplicate,
+}
+
+impl CopyKind {
+    /// ファイル名から種類を判定
+    pub fn of(path: &Path) -> Self {
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+        if SYNCTHING_PATTERN.is_match(stem) {
+            CopyKind::Syncthing
+        } else if DROPBOX_PATTERN.is_match(stem) {
+            CopyKind::Dropbox
+        } else if KAKU_PATTERN.is_match(stem) {
+            CopyKind::Mirror
+        } else {
+            CopyKind::Duplicate
+        }
+    }
+
+    /// 同期ツールの競合コピーか
+    pub fn is_conflict_copy(self) -> bool {
+        self != CopyKind::Duplicate
+    }
+}
+
+/// インデックスに登録しなかった、同じ UID を持つファイル
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateFile {
+    pub uid: String,
+    pub path: PathBuf,
+}
+
+impl DuplicateFile {
+    pub fn kind(&self) -> CopyKind {
+        CopyKind::of(&self.path)
+    }
+}
+
+/// 同じ UID を持つファイルの組
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateGroup {
+    pub uid: String,
+    /// インデックスに登録したファイル
+    pub primary: PathBuf,
+    /// それ以外のファイル（パスの順）
+    pub copies: Vec<DuplicateFile>,
+}
+
+impl DuplicateGroup {
+    /// 同期ツールの競合コピーを含むか
+    pub fn has_conflict_copy(&self) -> bool {
+        self.copies.iter().any(|copy| copy.kind().is_conflict_copy())
+    }
+
+    /// 同期ツールの競合コピー（競合の解決で `.sync-trash` へ移す対象）
+    pub fn conflict_copies(&self) -> impl Iterator<Item = &DuplicateFile> {
+        self.copies.iter().filter(|copy| copy.kind().is_conflict_copy())
+    }
+
+    /// 競合コピーの名前ではない複製（手作業でのコピーなど、新しい UID を振る対象）
+    pub fn plain_copies(&self) -> impl Iterator<Item = &DuplicateFile> {
+        self.copies.iter().filter(|copy| !copy.kind().is_conflict_copy())
+    }
+}
+
+/// 複製に新しい UID を振った結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UidRepair {
+    pub path: PathBuf,
+    pub old_uid: String,
+    pub new_uid: String,
+}
+
+/// 同じ UID を持つファイルのうちインデックスに登録するもの（`paths` の位置）
+///
+/// 競合コピーの名前でないファイルを優先し、その中ではインデックスに登録済みのパス、
+/// なければパスの順で最初のものを選ぶ。同期のたびに登録先が入れ替わらないようにするため。
+pub fn choose_primary(paths: &[PathBuf], indexed: Option<&Path>) -> usize {
+    let rank = |path: &PathBuf| {
+        (
+            CopyKind::of(path).is_conflict_copy(),
+            Some(path.as_path()) != indexed,
+        )
+    };
+    paths
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| rank(a).cmp(&rank(b)).then_with(|| a.cmp(b)))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_kind() {
+        let kind = |name: &str| CopyKind::of(Path::new(name));
+        assert_eq!(kind("メモ.sync-conflict-20261017-120000-ABCDEFG.md"), CopyKind::Syncthing);
+        assert_eq!(kind("メモ (conflicted copy).md"), CopyKind::Dropbox);
+        assert_eq!(kind("メモ (Taro's conflicted copy 2026-10-17).md"), CopyKind::Dropbox);
+        assert_eq!(kind("仕事/メモ (conflict 2026-10-18 093000).md"), CopyKind::Mirror);
+        assert_eq!(kind("メモ のコピー.md"), CopyKind::Duplicate);
+        assert_eq!(kind("conflicted copy.md"), CopyKind::Duplicate);
+    }
+
+    #[test]
+    fn test_choose_primary() {
+        let paths = vec![
+            PathBuf::from("/notes/メモ.sync-conflict-20261017-120000-ABCDEFG.md"),
+            PathBuf::from("/notes/メモ 2.md"),
+            PathBuf::from("/notes/メモ.md"),
+        ];
+        // 競合コピーでないものをパスの順に
+        assert_eq!(choose_primary(&paths, None), 1);
+        // 登録済みのパスを優先する
+        assert_eq!(choose_primary(&paths, Some(Path::new("/notes/メモ.md"))), 2);
+        // 競合コピーが登録済みでも元のファイルを優先する
+        assert_eq!(choose_primary(&paths, Some(paths[0].as_path())), 1);
+    }
+}
@@ -0,0 +1,204 @@
+//! 暗号化ノート
+//!
+//! front matter に `encrypted: true` を持つノートは、本文をパスフレーズから導出した鍵
+//! （Argon2id）で暗号化（XChaCha20-Poly1305）して保存する。本文はロック解除後にメモリ上でのみ
+//! 復号し、ファイル・インデックス・履歴・下書きには平文を残さない。
+//!
+//! # 本文の形式
+//!
+//! ```text
+//! -----BEGIN KAKU ENCRYPTED NOTE-----
+//! kdf=argon2id m=19456 t=2 p=1 cipher=xchacha20poly1305 salt=<base64> nonce=<base64>
+//! <base64 の暗号文（64文字ごとに改行）>
+//! -----END KAKU ENCRYPTED NOTE-----
+//! ```
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Serialize;
+
+const BEGIN_MARKER: &str = "-----BEGIN KAKU ENCRYPTED NOTE-----";
+const END_MARKER: &str = "-----END KAKU ENCRYPTED NOTE-----";
+const KDF_NAME: &str = "argon2id";
+const CIPHER_NAME: &str = "xchacha20poly1305";
+
+/// 暗号文の1行の文字数
+const ARMOR_LINE_LENGTH: usize = 64;
+
+/// 鍵導出（Argon2id）のパラメータ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    /// メモリコスト（KiB）
+    pub m_cost: u32,
+    /// 反復回数
+    pub t_cost: u32,
+    /// 並列度
+    pub p_cost: u32,
+}
+
+impl Default for KdfParams {
+    /// OWASP 推奨の最小構成（19 MiB、2回、並列度1）
+    fn default() -> Self {
+        Self {
+            m_cost: 19456,
+            t_cost: 2,
+            p_cost: 1,
+        }
+    }
+}
+
+/// 暗号化された本文
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedBody {
+    pub params: KdfParams,
+    pub salt: Vec<u8>,
+    pub nonce: Vec<u8>,
+    /// 暗号文（認証タグを含む）
+    pub ciphertext: Vec<u8>,
+}
+
+impl EncryptedBody {
+    /// 本文が暗号化された形式か（中身の検証はしない）
+    pub fn is_armored(content: &str) -> bool {
+        content.trim_start().starts_with(BEGIN_MARKER)
+    }
+
+    /// ノートの本文として保存する形式に変換
+    pub fn to_armor(&self) -> String {
+        let encoded = STANDARD.encode(&self.ciphertext);
+        let lines: Vec<&str> = encoded
+            .as_bytes()
+            .chunks(ARMOR_LINE_LENGTH)
+            .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+            .collect();
+        format!(
+            "{}\nkdf={} m={} t={} p={} cipher={} salt={} nonce={}\n{}\n{}\n",
+            BEGIN_MARKER,
+            KDF_NAME,
+            self.params.m_cost,
+            self.params.t_cost,
+            self.params.p_cost,
+            CIPHER_NAME,
+            STANDARD.encode(&self.salt),
+            STANDARD.encode(&self.nonce),
+            lines.join("\n"),
+            END_MARKER
+        )
+    }
+
+    /// ノートの本文をパース
+    pub fn parse(content: &str) -> Result<Self, EncryptionError> {
+        let invalid = |reason: &str| EncryptionError::InvalidFormat(reason.to_string());
+
+        let mut lines = content.trim().lines().map(str::trim);
+        if lines.next() != Some(BEGIN_MARKER) {
+            return Err(invalid("missing BEGIN marker"));
+        }
+        let header = lines.next().ok_or_else(|| invalid("missing header"))?;
+
+        let mut params = KdfParams::default();
+        let (mut salt, mut nonce) = (None, None);
+        for field in header.split_whitespace() {
+            let (key, value) = field
+                .split_once('=')
+                .ok_or_else(|| invalid("malformed header"))?;
+            let number = || value.parse::<u32>().map_err(|_| invalid("malformed KDF parameter"));
+            match key {
+                "kdf" if value != KDF_NAME => return Err(invalid("unsupported KDF")),
+                "cipher" if value != CIPHER_NAME => return Err(invalid("unsupported cipher")),
+                "m" => params.m_cost = number()?,
+                "t" => params.t_cost = number()?,
+                "p" => params.p_cost = number()?,
+                "salt" => salt = STANDARD.decode(value).ok(),
+                "nonce" => nonce = STANDARD.decode(value).ok(),
+                _ => {}
+            }
+        }
+
+        let mut encoded = String::new();
+        let mut terminated = false;
+        for line in lines {
+            if line == END_MARKER {
+                terminated = true;
+                break;
+            }
+            encoded.push_str(line);
+        }
+        if !terminated {
+            return Err(invalid("missing END marker"));
+        }
+
+        Ok(Self {
+            params,
+            salt: salt.ok_or_else(|| invalid("missing salt"))?,
+            nonce: nonce.ok_or_else(|| invalid("missing nonce"))?,
+            ciphertext: STANDARD
+                .decode(encoded)
+                .map_err(|_| invalid("malformed ciphertext"))?,
+        })
+    }
+}
+
+/// 暗号化セッションの状態
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EncryptionStatus {
+    /// ロック解除済みか
+    pub unlocked: bool,
+    /// 自動でロックされるまでの秒数（ロック中または自動ロックなしは `None`）
+    pub expires_in_secs: Option<u64>,
+}
+
+/// 暗号化関連エラー
+#[derive(Debug, thiserror::Error)]
+pub enum EncryptionError {
+    #[error("暗号化されたノートはロックされています")]
+    Locked,
+    #[error("パスフレーズが正しくありません")]
+    WrongPassphrase,
+    #[error("パスフレーズが空です")]
+    EmptyPassphrase,
+    #[error("暗号化された本文の形式が不正です: {0}")]
+    InvalidFormat(String),
+    #[error("暗号処理に失敗しました: {0}")]
+    Crypto(String),
+    #[error("バージョン管理（git）の履歴に平文が残るため暗号化できません")]
+    PlaintextInHistory,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> EncryptedBody {
+        EncryptedBody {
+            params: KdfParams::default(),
+            salt: vec![1; 16],
+            nonce: vec![2; 24],
+            ciphertext: (0..=200).collect(),
+        }
+    }
+
+    #[test]
+    fn test_armor_roundtrip() {
+        let body = sample();
+        let armor = body.to_armor();
+
+        assert!(EncryptedBody::is_armored(&armor));
+        assert!(armor.lines().skip(2).all(|line| line.len() <= ARMOR_LINE_LENGTH));
+        assert_eq!(EncryptedBody::parse(&armor).unwrap(), body);
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_armor() {
+        let armor = sample().to_armor();
+
+        assert!(EncryptedBody::parse("# Plain note").is_err());
+        let truncated = armor.replace(END_MARKER, "");
+        assert!(matches!(
+            EncryptedBody::parse(&truncated),
+            Err(EncryptionError::InvalidFormat(_))
+        ));
+        let unknown = armor.replace("kdf=argon2id", "kdf=scrypt");
+        assert!(EncryptedBody::parse(&unknown).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use super::NoteConflict;
 use serde::{Deserialize, Serialize};
 
 /// ドメインイベント（Observer/EventBusパターン）
@@ -6,15 +7,22 @@ use serde::{Deserialize, Serialize};
 ///
 /// ## 使用中
 /// - `NoteCreated`: note_service.rs で発火
-/// - `NoteUpdated`: 将来のリアルタイム同期用（テストで使用）
-/// - `NoteDeleted`: note_service.rs で発火
+/// - `NoteUpdated`: note_service.rs で発火（フラグの変更・暗号化の切り替え・フォルダの移動・日時の形式の統一）
+/// - `NoteDeleted`: note_service.rs で発火（ゴミ箱への移動）
+/// - `NoteRestored`: note_service.rs で発火（ゴミ箱からの復元）
 /// - `NoteLoaded`: note_service.rs で発火
+/// - `SaveRequested`: autosave_service.rs で発火（保存キューへの追加）
 /// - `SaveCompleted`: note_service.rs で発火
+/// - `SaveFailed`: autosave_service.rs で発火（再試行しても保存できなかった場合）
 /// - `SettingsChanged`: settings_service.rs で発火
+/// - `RelocationProgress`: relocation_service.rs で発火（保存ディレクトリの移転中）
+/// - `NotesLocked`: encryption_service.rs で発火（暗号化ノートのロック・自動ロック）
+/// - `VaultCommitted`: versioning_service.rs で発火（保存ディレクトリを git にコミット）
+/// - `BackupProgress`/`BackupFailed`: backup_service.rs で発火（バックアップ・復元中、定期バックアップの失敗）
+/// - `Conflict`/`MirrorSynced`/`SyncFailed`: sync_service.rs で発火（ミラーとの同期）
+/// - `IndexRebuilt`: sync/backup/relocation/duplicate_service.rs で発火（インデックスの作り直し）
 ///
 /// ## 将来の拡張用（現在未使用）
-/// - `SaveRequested`: 保存キューイング実装時
-/// - `SaveFailed`: エラー通知UI実装時
 /// - `WindowShown`/`WindowHidden`: フロントエンド連携時
 /// - `AppQuitting`: 終了時クリーンアップ処理
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,16 +34,40 @@ pub enum DomainEvent {
     NoteUpdated { uid: String },
     /// メモが削除された
     NoteDeleted { uid: String },
+    /// メモがゴミ箱から復元された
+    NoteRestored { uid: String },
     /// メモがロードされた
     NoteLoaded { uid: String },
-    /// 保存がリクエストされた（将来の保存キューイング用）
+    /// 保存がリクエストされた（自動保存キューに追加された）
     SaveRequested { uid: String },
     /// 保存が完了した
     SaveCompleted { uid: String },
-    /// 保存が失敗した（将来のエラー通知UI用）
-    SaveFailed { uid: String, error: String },
+    /// 保存が失敗した（UIで警告を表示する、競合時は両方の版を `conflict` に含める）
+    SaveFailed {
+        uid: String,
+        error: String,
+        conflict: Option<Box<NoteConflict>>,
+    },
     /// 設定が変更された
     SettingsChanged,
+    /// 保存ディレクトリの移転が進んだ（`done` / `total` ファイル）
+    RelocationProgress { done: usize, total: usize },
+    /// 暗号化ノートがロックされた（手動または一定時間操作がなかった場合）
+    NotesLocked,
+    /// 保存ディレクトリを git にコミットした（`changed` は変更したファイル数）
+    VaultCommitted { id: String, changed: usize },
+    /// バックアップまたは復元が進んだ（`done` / `total` ファイル）
+    BackupProgress { restoring: bool, done: usize, total: usize },
+    /// 定期バックアップが失敗した
+    BackupFailed { error: String },
+    /// ミラーとの同期で両側の変更が競合した（ミラーの版を `conflict_copy` に保存した）
+    Conflict { path: String, conflict_copy: String },
+    /// ミラーとの同期が完了した
+    MirrorSynced { pulled: usize, pushed: usize, deleted: usize, conflicts: usize },
+    /// 定期同期が失敗した
+    SyncFailed { error: String },
+    /// 保存ディレクトリからノートのインデックスを作り直した
+    IndexRebuilt,
     /// ウィンドウが表示された（将来のフロントエンド連携用）
     WindowShown,
     /// ウィンドウが非表示になった（将来のフロントエンド連携用）
@@ -51,11 +83,21 @@ impl DomainEvent {
             DomainEvent::NoteCreated { .. } => "note:created",
             DomainEvent::NoteUpdated { .. } => "note:updated",
             DomainEvent::NoteDeleted { .. } => "note:deleted",
+            DomainEvent::NoteRestored { .. } => "note:restored",
             DomainEvent::NoteLoaded { .. } => "note:loaded",
             DomainEvent::SaveRequested { .. } => "save:requested",
             DomainEvent::SaveCompleted { .. } => "save:completed",
             DomainEvent::SaveFailed { .. } => "save:failed",
             DomainEvent::SettingsChanged => "settings:changed",
+            DomainEvent::RelocationProgress { .. } => "relocation:progress",
+            DomainEvent::NotesLocked => "encryption:locked",
+            DomainEvent::VaultCommitted { .. } => "versioning:committed",
+            DomainEvent::BackupProgress { .. } => "backup:progress",
+            DomainEvent::BackupFailed { .. } => "backup:failed",
+            DomainEvent::Conflict { .. } => "sync:conflict",
+            DomainEvent::MirrorSynced { .. } => "sync:completed",
+            DomainEvent::SyncFailed { .. } => "sync:failed",
+            DomainEvent::IndexRebuilt => "index:rebuilt",
             DomainEvent::WindowShown => "window:shown",
             DomainEvent::WindowHidden => "window:hidden",
             DomainEvent::AppQuitting => "app:quitting",
@@ -0,0 +1,122 @@
+//! ファイル名の生成方式
+//!
+//! 設定で全体の方式を選び、ノートごとに front matter の `filename:` で上書きできる。
+//!
+//! ```markdown
+//! ---
+//! uid: 20261017093000123456
+//! filename: slug
+//! ---
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// ユーザー定義パターンの既定値
+pub const DEFAULT_FILENAME_PATTERN: &str = "{{date}} {{title}}";
+
+/// ユーザー定義パターンの最大長
+const MAX_PATTERN_LEN: usize = 200;
+
+/// スラッグの最大長（文字数）
+const MAX_SLUG_LEN: usize = 100;
+
+/// ファイル名の生成方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FilenameStyle {
+    /// 見出し（`Plan.md`）
+    #[default]
+    Heading,
+    /// UID のみ（`20261017093000123456.md`）
+    Uid,
+    /// 作成日 + 見出し（`2026-10-17 Plan.md`）
+    DatePrefixed,
+    /// 見出しを ASCII に変換したスラッグ（`plan-for-q4.md`）
+    Slug,
+    /// ユーザー定義パターン（`FilenameSettings::pattern`）
+    Pattern,
+}
+
+impl FilenameStyle {
+    /// front matter の値から変換（`date-prefixed` のようなハイフン区切りも受け付ける）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().replace('-', "_").as_str() {
+            "heading" => Some(Self::Heading),
+            "uid" => Some(Self::Uid),
+            "date_prefixed" => Some(Self::DatePrefixed),
+            "slug" => Some(Self::Slug),
+            "pattern" => Some(Self::Pattern),
+            _ => None,
+        }
+    }
+
+    /// front matter に書き出す名前
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Heading => "heading",
+            Self::Uid => "uid",
+            Self::DatePrefixed => "date_prefixed",
+            Self::Slug => "slug",
+            Self::Pattern => "pattern",
+        }
+    }
+}
+
+/// ファイル名の付け直し結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RenameReport {
+    /// ファイル名を変更したノート数
+    pub renamed: usize,
+    /// ファイル名が変わらなかったノート数
+    pub unchanged: usize,
+    /// 変更に失敗したノートの UID
+    pub failed: Vec<String>,
+}
+
+/// ユーザー定義パターンとして使えるか
+pub fn is_valid_filename_pattern(pattern: &str) -> bool {
+    !pattern.trim().is_empty() && pattern.len() <= MAX_PATTERN_LEN
+}
+
+/// ASCII のスラッグに変換（英数字以外はハイフンにまとめる）
+///
+/// 日本語などは読みのローマ字表記に変換する。変換できる文字がなければ空文字列を返す。
+pub fn slugify(text: &str) -> String {
+    let ascii = deunicode::deunicode(text).to_lowercase();
+
+    let mut slug = String::with_capacity(ascii.len());
+    for c in ascii.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let slug: String = slug.chars().take(MAX_SLUG_LEN).collect();
+    slug.trim_end_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Plan for Q4!"), "plan-for-q4");
+        assert_eq!(slugify("  Café -- Menü  "), "cafe-menu");
+        assert!(slugify("買い物リスト").is_ascii());
+        assert!(!slugify("買い物リスト").is_empty());
+        assert_eq!(slugify("!!!"), "");
+    }
+
+    #[test]
+    fn test_parse_style() {
+        assert_eq!(FilenameStyle::parse("date-prefixed"), Some(FilenameStyle::DatePrefixed));
+        assert_eq!(FilenameStyle::parse(" Slug "), Some(FilenameStyle::Slug));
+        assert_eq!(FilenameStyle::parse("unknown"), None);
+        for style in [FilenameStyle::Heading, FilenameStyle::Uid, FilenameStyle::Pattern] {
+            assert_eq!(FilenameStyle::parse(style.as_str()), Some(style));
+        }
+    }
+}
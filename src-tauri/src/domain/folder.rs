@@ -0,0 +1,300 @@
+//! フォルダ（保存ディレクトリのサブディレクトリ）
+//!
+//! フォルダは保存ディレクトリからの相対パスを `/` 区切りで表す（直下は `""`）。
+//!
+//! # 除外ルール
+//!
+//! 走査時は `.git` や `.trash` などのドット始まりのエントリを常に除外し、
+//! 保存ディレクトリ直下の `.kakuignore` に書かれたパターンに一致するものも除外する。
+//!
+//! ```text
+//! # コメント
+//! attachments/      末尾の / はディレクトリのみに一致
+//! *.draft.md        / を含まないパターンは名前に一致
+//! archive/2019      / を含むパターンは相対パス全体に一致
+//! ```
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// 除外ルールファイルの名前（保存ディレクトリ直下）
+pub const IGNORE_FILE_NAME: &str = ".kakuignore";
+
+/// フォルダパスの最大長
+const MAX_FOLDER_LEN: usize = 500;
+
+/// ファイル名に使えない文字（Windows で不正な文字を含む）
+const INVALID_FOLDER_CHARS: &[char] = &['<', '>', ':', '"', '|', '?', '*'];
+
+/// 除外パターン
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IgnorePattern {
+    glob: String,
+    /// 相対パス全体に一致させるか（`false` は名前のみ）
+    anchored: bool,
+    /// ディレクトリのみに一致させるか
+    dir_only: bool,
+}
+
+/// 走査時の除外ルール
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IgnoreRules {
+    patterns: Vec<IgnorePattern>,
+}
+
+impl IgnoreRules {
+    /// `.kakuignore` の内容をパース（空行と `#` で始まる行は無視）
+    pub fn parse(text: &str) -> Self {
+        let patterns = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let dir_only = line.ends_with('/');
+                let glob = line.trim_end_matches('/');
+                let anchored = glob.contains('/');
+                let glob = glob.trim_start_matches('/');
+                (!glob.is_empty()).then(|| IgnorePattern {
+                    glob: glob.to_string(),
+                    anchored,
+                    dir_only,
+                })
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    /// 相対パス（`/` 区切り）のエントリを除外するか
+    pub fn is_ignored(&self, relative: &str, is_dir: bool) -> bool {
+        let name = relative.rsplit('/').next().unwrap_or(relative);
+        if name.starts_with('.') {
+            return true;
+        }
+        self.patterns.iter().any(|pattern| {
+            (is_dir || !pattern.dir_only)
+                && glob_match(&pattern.glob, if pattern.anchored { relative } else { name })
+        })
+    }
+}
+
+/// `*`（`/` 以外の任意の文字列）と `?`（`/` 以外の1文字）のワイルドカード一致
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // 直前の `*` の位置と、その `*` が吸収し始めたテキスト位置
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some('?') if text[t] != '/' => {
+                p += 1;
+                t += 1;
+            }
+            Some(&c) if c != '?' && c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) if text[star_t] != '/' => {
+                    star = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
+                }
+                _ => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// フォルダ指定を正規化（`\` は `/` に、前後と重複した `/` は除く）
+///
+/// 保存ディレクトリの外を指すもの（`..`、絶対パス）、除外されるドット始まりの名前、
+/// ファイル名に使えない文字を含むものは `None`。`""` は保存ディレクトリ直下。
+pub fn normalize_folder(folder: &str) -> Option<String> {
+    let folder = folder.trim().replace('\\', "/");
+    if folder.len() > MAX_FOLDER_LEN || folder.starts_with('/') {
+        return None;
+    }
+
+    let mut components = Vec::new();
+    for component in folder.split('/').map(str::trim) {
+        if component.is_empty() {
+            continue;
+        }
+        if component.starts_with('.')
+            || component.ends_with('.')
+            || component
+                .chars()
+                .any(|c| c.is_control() || INVALID_FOLDER_CHARS.contains(&c))
+        {
+            return None;
+        }
+        components.push(component);
+    }
+    Some(components.join("/"))
+}
+
+/// ファイルのフォルダ（保存ディレクトリ外のパスは直下とみなす）
+pub fn folder_of(base_dir: &Path, path: &Path) -> String {
+    path.parent()
+        .and_then(|parent| parent.strip_prefix(base_dir).ok())
+        .map(|relative| {
+            relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .unwrap_or_default()
+}
+
+/// `note_folder` が `folder` 自身またはそのサブフォルダか（`""` はすべてに一致）
+pub fn is_in_folder(note_folder: &str, folder: &str) -> bool {
+    folder.is_empty()
+        || note_folder == folder
+        || note_folder
+            .strip_prefix(folder)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// フォルダツリーのノード
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FolderNode {
+    /// フォルダ名（ルートは `""`）
+    pub name: String,
+    /// 保存ディレクトリからの相対パス
+    pub path: String,
+    /// このフォルダ直下のノート数
+    pub note_count: usize,
+    /// サブフォルダを含むノート数
+    pub total_count: usize,
+    /// サブフォルダ（名前順）
+    pub children: Vec<FolderNode>,
+}
+
+impl FolderNode {
+    fn new(name: &str, path: String) -> Self {
+        Self {
+            name: name.to_string(),
+            path,
+            note_count: 0,
+            total_count: 0,
+            children: Vec::new(),
+        }
+    }
+}
+
+/// フォルダごとのノート数からツリーを構築（途中のフォルダも補う）
+pub fn build_folder_tree(counts: &[(String, usize)]) -> FolderNode {
+    // 中間ノード: フォルダ名 → (直下のノート数, 子)
+    #[derive(Default)]
+    struct Branch {
+        count: usize,
+        children: BTreeMap<String, Branch>,
+    }
+
+    fn into_node(name: &str, path: String, branch: Branch) -> FolderNode {
+        let mut node = FolderNode::new(name, path);
+        node.note_count = branch.count;
+        for (child_name, child) in branch.children {
+            let child_path = if node.path.is_empty() {
+                child_name.clone()
+            } else {
+                format!("{}/{}", node.path, child_name)
+            };
+            node.children.push(into_node(&child_name, child_path, child));
+        }
+        node.total_count =
+            node.note_count + node.children.iter().map(|c| c.total_count).sum::<usize>();
+        node
+    }
+
+    let mut root = Branch::default();
+    for (folder, count) in counts {
+        let mut branch = &mut root;
+        for component in folder.split('/').filter(|c| !c.is_empty()) {
+            branch = branch.children.entry(component.to_string()).or_default();
+        }
+        branch.count += count;
+    }
+
+    into_node("", String::new(), root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_ignore_rules() {
+        let rules = IgnoreRules::parse("# comment\nattachments/\n*.draft.md\narchive/20??\n");
+
+        // ドット始まりは常に除外
+        assert!(rules.is_ignored(".git", true));
+        assert!(rules.is_ignored("work/.trash", true));
+
+        assert!(rules.is_ignored("attachments", true));
+        assert!(rules.is_ignored("work/attachments", true));
+        assert!(!rules.is_ignored("attachments", false));
+
+        assert!(rules.is_ignored("work/idea.draft.md", false));
+        assert!(!rules.is_ignored("work/idea.md", false));
+
+        assert!(rules.is_ignored("archive/2019", true));
+        assert!(!rules.is_ignored("work/archive/2019", true));
+        assert!(!rules.is_ignored("archive/2019/old", true));
+    }
+
+    #[test]
+    fn test_normalize_folder() {
+        assert_eq!(normalize_folder(""), Some(String::new()));
+        assert_eq!(normalize_folder(" work\\2026/ "), Some("work/2026".to_string()));
+        assert_eq!(normalize_folder("a//b/"), Some("a/b".to_string()));
+        assert_eq!(normalize_folder("../outside"), None);
+        assert_eq!(normalize_folder("/etc"), None);
+        assert_eq!(normalize_folder("work/.git"), None);
+        assert_eq!(normalize_folder("what?"), None);
+    }
+
+    #[test]
+    fn test_folder_of_and_is_in_folder() {
+        let base = PathBuf::from("/notes");
+        assert_eq!(folder_of(&base, &base.join("a.md")), "");
+        assert_eq!(folder_of(&base, &base.join("work").join("2026").join("a.md")), "work/2026");
+        assert_eq!(folder_of(&base, Path::new("/elsewhere/a.md")), "");
+
+        assert!(is_in_folder("work/2026", "work"));
+        assert!(is_in_folder("work", "work"));
+        assert!(!is_in_folder("workshop", "work"));
+        assert!(is_in_folder("", ""));
+    }
+
+    #[test]
+    fn test_build_folder_tree() {
+        let tree = build_folder_tree(&[
+            (String::new(), 2),
+            ("work/2026".to_string(), 3),
+            ("personal".to_string(), 1),
+        ]);
+
+        assert_eq!(tree.note_count, 2);
+        assert_eq!(tree.total_count, 6);
+        let names: Vec<&str> = tree.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["personal", "work"]);
+
+        // 中間のフォルダはノートがなくても作られる
+        let work = &tree.children[1];
+        assert_eq!((work.note_count, work.total_count), (0, 3));
+        assert_eq!(work.children[0].path, "work/2026");
+    }
+}
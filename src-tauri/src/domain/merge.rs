@@ -0,0 +1,205 @@
+//! 行単位の3方向マージ
+//!
+//! 共通の祖先（base）から両側（ours / theirs）への差分をそれぞれ `diff_lines` で求め、
+//! 重ならない変更は両方取り込む。同じ箇所を異なる内容に変更している場合は
+//! git と同じ形式の競合マーカーで両方の版を残す。
+
+use super::{diff_lines, DiffOp};
+use serde::Serialize;
+
+/// 競合マーカー（残す側・取り込む側の区切り）
+const MARKER_OURS: &str = "<<<<<<< ";
+const MARKER_SEPARATOR: &str = "=======";
+const MARKER_THEIRS: &str = ">>>>>>> ";
+
+/// マージ結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MergeResult {
+    /// マージした本文（競合箇所はマーカー付き）
+    pub content: String,
+    /// 競合箇所の数
+    pub conflicts: usize,
+}
+
+/// 競合コピーと元のノートのマージ結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConflictMerge {
+    /// 元のノートの本文
+    pub ours: String,
+    /// 競合コピーの本文
+    pub theirs: String,
+    /// 共通の祖先をリビジョン履歴から見つけたか（見つからなければ両方に共通する行を使う）
+    pub base_found: bool,
+    pub merged: MergeResult,
+}
+
+/// base の `start..end` 行を `lines` に置き換える変更
+#[derive(Debug)]
+struct Hunk<'a> {
+    start: usize,
+    end: usize,
+    lines: Vec<&'a str>,
+}
+
+/// base からの変更を置き換え単位にまとめる
+fn hunks<'a>(base: &str, changed: &'a str) -> Vec<Hunk<'a>> {
+    // diff_lines の行は所有文字列のため、挿入行は changed の行として参照し直す
+    let changed_lines: Vec<&'a str> = changed.lines().collect();
+    let mut result: Vec<Hunk<'a>> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let mut current: Option<Hunk<'a>> = None;
+
+    for line in diff_lines(base, changed) {
+        match line.op {
+            DiffOp::Equal => {
+                result.extend(current.take());
+                i += 1;
+                j += 1;
+            }
+            DiffOp::Delete => {
+                current.get_or_insert(Hunk { start: i, end: i, lines: Vec::new() }).end = i + 1;
+                i += 1;
+            }
+            DiffOp::Insert => {
+                current
+                    .get_or_insert(Hunk { start: i, end: i, lines: Vec::new() })
+                    .lines
+                    .push(changed_lines[j]);
+                j += 1;
+            }
+        }
+    }
+    result.extend(current);
+    result
+}
+
+/// base の `start..end` に変更を適用した行
+fn apply<'a>(base: &[&'a str], start: usize, end: usize, hunks: &[&Hunk<'a>]) -> Vec<&'a str> {
+    let mut lines = Vec::new();
+    let mut pos = start;
+    for hunk in hunks {
+        lines.extend_from_slice(&base[pos..hunk.start]);
+        lines.extend_from_slice(&hunk.lines);
+        pos = hunk.end;
+    }
+    lines.extend_from_slice(&base[pos..end]);
+    lines
+}
+
+/// 3方向マージ（`ours_label` / `theirs_label` は競合マーカーに付ける名前）
+pub fn merge_three_way(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    ours_label: &str,
+    theirs_label: &str,
+) -> MergeResult {
+    let base_lines: Vec<&str> = base.lines().collect();
+    let ours_hunks = hunks(base, ours);
+    let theirs_hunks = hunks(base, theirs);
+
+    let mut output: Vec<String> = Vec::new();
+    let mut conflicts = 0;
+    let mut pos = 0;
+    let (mut a, mut b) = (0, 0);
+    let owned = |lines: &[&str]| lines.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+
+    while a < ours_hunks.len() || b < theirs_hunks.len() {
+        // 開始位置の早い方から、接するか重なる変更を両側からまとめる
+        let take_ours = match (ours_hunks.get(a), theirs_hunks.get(b)) {
+            (Some(o), Some(t)) => o.start <= t.start,
+            (Some(_), None) => true,
+            _ => false,
+        };
+        let first = if take_ours { &ours_hunks[a] } else { &theirs_hunks[b] };
+        let (start, mut end) = (first.start, first.end);
+        let (mut ours_group, mut theirs_group) = (Vec::new(), Vec::new());
+        loop {
+            if let Some(hunk) = ours_hunks.get(a).filter(|h| h.start <= end) {
+                end = end.max(hunk.end);
+                ours_group.push(hunk);
+                a += 1;
+            } else if let Some(hunk) = theirs_hunks.get(b).filter(|h| h.start <= end) {
+                end = end.max(hunk.end);
+                theirs_group.push(hunk);
+                b += 1;
+            } else {
+                break;
+            }
+        }
+
+        output.extend(owned(&base_lines[pos..start]));
+        let ours_lines = apply(&base_lines, start, end, &ours_group);
+        let theirs_lines = apply(&base_lines, start, end, &theirs_group);
+        if theirs_group.is_empty() || ours_lines == theirs_lines {
+            output.extend(owned(&ours_lines));
+        } else if ours_group.is_empty() {
+            output.extend(owned(&theirs_lines));
+        } else {
+            conflicts += 1;
+            output.push(format!("{}{}", MARKER_OURS, ours_label));
+            output.extend(owned(&ours_lines));
+            output.push(MARKER_SEPARATOR.to_string());
+            output.extend(owned(&theirs_lines));
+            output.push(format!("{}{}", MARKER_THEIRS, theirs_label));
+        }
+        pos = end;
+    }
+    output.extend(owned(&base_lines[pos..]));
+
+    let mut content = output.join("\n");
+    if ours.ends_with('\n') || theirs.ends_with('\n') {
+        content.push('\n');
+    }
+    MergeResult { content, conflicts }
+}
+
+/// 両方に共通する行（共通の祖先がない場合の base の代わり）
+pub fn common_lines(a: &str, b: &str) -> String {
+    diff_lines(a, b)
+        .into_iter()
+        .filter(|line| line.op == DiffOp::Equal)
+        .map(|line| line.text)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_non_overlapping_changes() {
+        let base = "# 買い物\n牛乳\nパン\n卵\n";
+        let ours = "# 買い物\n牛乳（低脂肪）\nパン\n卵\n";
+        let theirs = "# 買い物\n牛乳\nパン\n卵\nバター\n";
+
+        let merged = merge_three_way(base, ours, theirs, "ours", "theirs");
+        assert_eq!(merged.conflicts, 0);
+        assert_eq!(merged.content, "# 買い物\n牛乳（低脂肪）\nパン\n卵\nバター\n");
+    }
+
+    #[test]
+    fn test_merge_conflicting_changes() {
+        let base = "# メモ\n予定: 未定\n以上";
+        let merged = merge_three_way(base, "# メモ\n予定: 月曜\n以上", "# メモ\n予定: 火曜\n以上", "メモ.md", "copy.md");
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            merged.content,
+            "# メモ\n<<<<<<< メモ.md\n予定: 月曜\n=======\n予定: 火曜\n>>>>>>> copy.md\n以上"
+        );
+
+        // 同じ変更は競合しない
+        let same = merge_three_way(base, "# メモ\n予定: 月曜\n以上", "# メモ\n予定: 月曜\n以上", "a", "b");
+        assert_eq!(same.conflicts, 0);
+    }
+
+    #[test]
+    fn test_merge_without_base_uses_common_lines() {
+        let ours = "# メモ\nA\n共通\n";
+        let theirs = "# メモ\n共通\nB\n";
+        let merged = merge_three_way(&common_lines(ours, theirs), ours, theirs, "a", "b");
+        assert_eq!(merged.conflicts, 0);
+        assert_eq!(merged.content, "# メモ\nA\n共通\nB\n");
+    }
+}
@@ -3,9 +3,45 @@ pub mod settings;
 pub mod events;
 pub mod search;
 pub mod backlink;
+pub mod sort;
+pub mod trash;
+pub mod revision;
+pub mod conflict;
+pub mod draft;
+pub mod template;
+pub mod daily;
+pub mod filename;
+pub mod folder;
+pub mod vault;
+pub mod relocation;
+pub mod encryption;
+pub mod versioning;
+pub mod backup;
+pub mod sync;
+pub mod duplicate;
+pub mod merge;
+pub mod uid;
 
-pub use note::{Note, NoteMetadata, NoteParseError};
-pub use settings::{Settings, SettingsError, WindowGeometry, EditorSettings, ThemeName, ThemeMode, AutosaveSettings, ShortcutSettings};
+pub use note::{NormalizeReport, Note, NoteMetadata, NoteParseError, TimestampZone};
+pub use settings::{Settings, SettingsError, WindowGeometry, EditorSettings, ThemeName, ThemeMode, AutosaveSettings, ShortcutSettings, TrashSettings, HistorySettings, TemplateSettings, DailyNoteSettings, FilenameSettings, EncryptionSettings, VersioningSettings, BackupSettings, SyncSettings, UidSettings, TimestampSettings, ViewSettings, ViewPreference, ViewKind};
 pub use events::DomainEvent;
 pub use search::{SearchResult, MatchRange, ContentPreview, SearchError};
 pub use backlink::{BacklinkInfo, ExtractedLink, extract_wiki_links, extract_context};
+pub use sort::{NoteSortOrder, title_sort_key};
+pub use trash::{TrashItem, TRASH_DIR_NAME};
+pub use revision::{Revision, DiffLine, DiffOp, diff_lines};
+pub use conflict::{ExpectedVersion, NoteConflict};
+pub use draft::Draft;
+pub use template::{NoteTemplate, TemplateVars, BUILTIN_TITLE_TEMPLATE, builtin_vars};
+pub use daily::{DailyDirection, DailyNoteEntry, DEFAULT_DAILY_PATTERN, is_valid_date_pattern, format_daily_name};
+pub use uid::{UidFormat, MAX_UID_LEN, is_valid_uid, timestamp_uid, uid_file_stem, ulid, uuid7};
+pub use filename::{FilenameStyle, RenameReport, DEFAULT_FILENAME_PATTERN, is_valid_filename_pattern, slugify};
+pub use folder::{FolderNode, IgnoreRules, IGNORE_FILE_NAME, normalize_folder, folder_of, is_in_folder, build_folder_tree};
+pub use vault::{Vault, VaultSettings, DEFAULT_VAULT_ID, is_valid_vault_name};
+pub use relocation::{RelocationMode, RelocationReport, RelocationFailure, is_nested_directory};
+pub use encryption::{EncryptedBody, EncryptionError, EncryptionStatus, KdfParams};
+pub use versioning::{ChangeKind, VaultChange, VaultCommit, VersioningError, VersioningStatus, commit_message};
+pub use backup::{BackupInfo, BackupReport, RestoreReport, BackupError, ARCHIVE_NOTES_DIR, ARCHIVE_SETTINGS_FILE, ARCHIVE_EXTENSION, backups_to_prune};
+pub use sync::{SyncAction, SyncConflict, SyncError, SyncReport, SyncState, SYNC_TRASH_DIR_NAME, SYNC_TRASH_TIME_FORMAT, conflict_copy_path, file_hash, plan_sync};
+pub use duplicate::{CopyKind, DuplicateFile, DuplicateGroup, UidRepair, choose_primary};
+pub use merge::{ConflictMerge, MergeResult, common_lines, merge_three_way};
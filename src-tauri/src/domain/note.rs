@@ -1,6 +1,30 @@
-use chrono::{DateTime, Utc};
+use super::filename::FilenameStyle;
+use super::uid::timestamp_uid;
+use chrono::{DateTime, Local, NaiveDate, SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
 
+/// front matter に書く日時のタイムゾーン（どちらも RFC 3339 でオフセット付き）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampZone {
+    /// ローカル時刻（`2026-10-17T18:30:00+09:00`）
+    #[default]
+    Local,
+    /// UTC（`2026-10-17T09:30:00Z`）
+    Utc,
+}
+
+/// front matter の書き直し結果（日時の形式の統一）
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NormalizeReport {
+    /// 書き直したノート数
+    pub updated: usize,
+    /// すでに現在の形式だったノート数
+    pub unchanged: usize,
+    /// 書き直しに失敗したノートの UID
+    pub failed: Vec<String>,
+}
+
 /// メモのメタデータ（YAML front matter）
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct NoteMetadata {
@@ -9,23 +33,38 @@ pub struct NoteMetadata {
     pub tags: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// 一覧の先頭に固定表示するか
+    #[serde(default)]
+    pub pinned: bool,
+    /// お気に入り（トレイメニューから開ける）
+    #[serde(default)]
+    pub favorite: bool,
+    /// デイリーノートの日付（デイリーノート以外は `None`）
+    #[serde(default)]
+    pub daily: Option<NaiveDate>,
+    /// このノートのファイル名の生成方式（`None` は設定に従う）
+    #[serde(default)]
+    pub filename_style: Option<FilenameStyle>,
+    /// 本文を暗号化して保存するか（本文は `EncryptedBody` の形式）
+    #[serde(default)]
+    pub encrypted: bool,
 }
 
 impl NoteMetadata {
     pub fn new() -> Self {
         let now = Utc::now();
-        // 時間ベースのUID: YYYYMMDDHHmmss形式 + ナノ秒の下6桁
-        let uid = format!(
-            "{}{}",
-            now.format("%Y%m%d%H%M%S"),
-            now.timestamp_subsec_nanos() % 1_000_000
-        );
+        // 時間ベースのUID（新しいノートの形式は NoteService の UidGenerator で変えられる）
         Self {
-            uid,
+            uid: timestamp_uid(now),
             title: None,
             tags: Vec::new(),
             created_at: now,
             updated_at: now,
+            pinned: false,
+            favorite: false,
+            daily: None,
+            filename_style: None,
+            encrypted: false,
         }
     }
 
@@ -38,6 +77,11 @@ impl NoteMetadata {
             tags: Vec::new(),
             created_at: now,
             updated_at: now,
+            pinned: false,
+            favorite: false,
+            daily: None,
+            filename_style: None,
+            encrypted: false,
         }
     }
 
@@ -49,6 +93,11 @@ impl NoteMetadata {
         let mut tags = Vec::new();
         let mut created_at = None;
         let mut updated_at = None;
+        let mut pinned = false;
+        let mut favorite = false;
+        let mut daily = None;
+        let mut filename_style = None;
+        let mut encrypted = false;
         let mut in_tags = false;
 
         for line in yaml.lines() {
@@ -94,6 +143,17 @@ impl NoteMetadata {
             } else if line_trimmed.starts_with("updated_at:") {
                 let value = line_trimmed.trim_start_matches("updated_at:").trim();
                 updated_at = Self::parse_datetime(value);
+            } else if line_trimmed.starts_with("pinned:") {
+                pinned = Self::parse_bool(line_trimmed.trim_start_matches("pinned:"));
+            } else if line_trimmed.starts_with("favorite:") {
+                favorite = Self::parse_bool(line_trimmed.trim_start_matches("favorite:"));
+            } else if line_trimmed.starts_with("daily:") {
+                let value = line_trimmed.trim_start_matches("daily:").trim();
+                daily = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok();
+            } else if line_trimmed.starts_with("filename:") {
+                filename_style = FilenameStyle::parse(line_trimmed.trim_start_matches("filename:"));
+            } else if line_trimmed.starts_with("encrypted:") {
+                encrypted = Self::parse_bool(line_trimmed.trim_start_matches("encrypted:"));
             }
         }
 
@@ -104,30 +164,51 @@ impl NoteMetadata {
                 tags,
                 created_at,
                 updated_at,
+                pinned,
+                favorite,
+                daily,
+                filename_style,
+                encrypted,
             }),
             _ => Err(serde_yaml_error::Error::InvalidFormat),
         }
     }
 
-    /// 日時をパース（新形式 "YYYY-MM-DD HH:MM:SS" と旧形式 RFC3339 の両方に対応）
+    /// 日時をパース（新形式 RFC 3339（オフセット付き）と旧形式 "YYYY-MM-DD HH:MM:SS"（UTC）の両方に対応）
     fn parse_datetime(value: &str) -> Option<DateTime<Utc>> {
-        // 新形式: "2025-12-15 02:42:38"
-        if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
-            return Some(naive.and_utc());
+        let value = value.trim_matches(|c| c == '"' || c == '\'');
+        // RFC 3339: "2025-12-15T11:42:38+09:00"
+        if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+            return Some(dt.with_timezone(&Utc));
         }
-        // 旧形式: RFC3339 (後方互換性)
-        DateTime::parse_from_rfc3339(value)
+        // オフセットなしの形式（UTC）: "2025-12-15 02:42:38"
+        chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
             .ok()
-            .map(|dt| dt.with_timezone(&Utc))
+            .map(|naive| naive.and_utc())
+    }
+
+    /// 真偽値をパース（true/yes 以外は false）
+    fn parse_bool(value: &str) -> bool {
+        matches!(value.trim().to_lowercase().as_str(), "true" | "yes")
     }
 
-    /// 日時を読みやすい形式にフォーマット
-    fn format_datetime(dt: &DateTime<Utc>) -> String {
-        dt.format("%Y-%m-%d %H:%M:%S").to_string()
+    /// 日時を RFC 3339（秒単位、オフセット付き）にフォーマット
+    fn format_datetime(dt: &DateTime<Utc>, zone: TimestampZone) -> String {
+        match zone {
+            TimestampZone::Local => dt
+                .with_timezone(&Local)
+                .to_rfc3339_opts(SecondsFormat::Secs, false),
+            TimestampZone::Utc => dt.to_rfc3339_opts(SecondsFormat::Secs, true),
+        }
     }
 
-    /// YAML front matterに変換
+    /// YAML front matterに変換（日時はローカル時刻）
     pub fn to_yaml(&self) -> String {
+        self.to_yaml_in(TimestampZone::default())
+    }
+
+    /// YAML front matterに変換（日時は指定のタイムゾーン）
+    pub fn to_yaml_in(&self, zone: TimestampZone) -> String {
         let title_line = match &self.title {
             Some(t) => format!("title: {}\n", t),
             None => String::new(),
@@ -137,13 +218,31 @@ impl NoteMetadata {
         } else {
             format!("tags:\n{}", self.tags.iter().map(|t| format!("  - {}", t)).collect::<Vec<_>>().join("\n")) + "\n"
         };
+        // false の場合は省略（既存ファイルとの差分を出さない）
+        let flags_line = format!(
+            "{}{}{}",
+            if self.pinned { "pinned: true\n" } else { "" },
+            if self.favorite { "favorite: true\n" } else { "" },
+            if self.encrypted { "encrypted: true\n" } else { "" }
+        );
+        let daily_line = match &self.daily {
+            Some(date) => format!("daily: {}\n", date.format("%Y-%m-%d")),
+            None => String::new(),
+        };
+        let filename_line = match &self.filename_style {
+            Some(style) => format!("filename: {}\n", style.as_str()),
+            None => String::new(),
+        };
         format!(
-            "uid: {}\n{}{}created_at: {}\nupdated_at: {}",
+            "uid: {}\n{}{}{}{}{}created_at: {}\nupdated_at: {}",
             self.uid,
             title_line,
             tags_line,
-            Self::format_datetime(&self.created_at),
-            Self::format_datetime(&self.updated_at)
+            flags_line,
+            daily_line,
+            filename_line,
+            Self::format_datetime(&self.created_at, zone),
+            Self::format_datetime(&self.updated_at, zone)
         )
     }
 }
@@ -227,9 +326,14 @@ impl Note {
         content.starts_with("---\n") && content[4..].contains("\n---")
     }
 
-    /// ファイル保存用の完全な内容を生成
+    /// ファイル保存用の完全な内容を生成（日時はローカル時刻）
     pub fn to_file_content(&self) -> String {
-        format!("---\n{}\n---\n\n{}", self.metadata.to_yaml(), self.content)
+        self.to_file_content_in(TimestampZone::default())
+    }
+
+    /// ファイル保存用の完全な内容を生成（日時は指定のタイムゾーン）
+    pub fn to_file_content_in(&self, zone: TimestampZone) -> String {
+        format!("---\n{}\n---\n\n{}", self.metadata.to_yaml_in(zone), self.content)
     }
 
     /// 本文の最初のH1またはH2見出しを抽出
@@ -246,11 +350,40 @@ impl Note {
         None
     }
 
+    /// 本文の単語数を数える
+    ///
+    /// 空白区切りの英数字の連なりを1語、かな・漢字は1文字を1語として数える。
+    pub fn word_count(&self) -> usize {
+        let mut count = 0;
+        let mut in_word = false;
+        for c in self.content.chars() {
+            if is_cjk(c) {
+                count += 1;
+                in_word = false;
+            } else if c.is_alphanumeric() {
+                if !in_word {
+                    count += 1;
+                    in_word = true;
+                }
+            } else {
+                in_word = false;
+            }
+        }
+        count
+    }
+
     /// UIDを取得
     pub fn uid(&self) -> &str {
         &self.metadata.uid
     }
 
+    /// 本文のハッシュ（blake3、保存時の競合検出に使用）
+    ///
+    /// フロントマターは含めないため、ピン留めなどのフラグ変更では変わらない。
+    pub fn content_hash(&self) -> String {
+        blake3::hash(self.content.as_bytes()).to_hex().to_string()
+    }
+
     /// コンテンツを更新
     pub fn update_content(&mut self, content: String) {
         if self.content != content {
@@ -279,6 +412,22 @@ impl Note {
         self.is_dirty = true;
     }
 
+    /// ピン留めを設定（本文の更新ではないため updated_at は変えない）
+    pub fn set_pinned(&mut self, pinned: bool) {
+        if self.metadata.pinned != pinned {
+            self.metadata.pinned = pinned;
+            self.is_dirty = true;
+        }
+    }
+
+    /// お気に入りを設定（本文の更新ではないため updated_at は変えない）
+    pub fn set_favorite(&mut self, favorite: bool) {
+        if self.metadata.favorite != favorite {
+            self.metadata.favorite = favorite;
+            self.is_dirty = true;
+        }
+    }
+
     /// 本文からハッシュタグを抽出
     pub fn extract_hashtags(&self) -> Vec<String> {
         let mut hashtags = Vec::new();
@@ -311,6 +460,15 @@ impl Default for Note {
     }
 }
 
+/// かな・漢字かどうか（単語数カウント用）
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{309F}' // ひらがな
+        | '\u{30A0}'..='\u{30FF}' // カタカナ
+        | '\u{4E00}'..='\u{9FFF}' // CJK統合漢字
+    )
+}
+
 /// メモパースエラー
 #[derive(Debug, thiserror::Error)]
 pub enum NoteParseError {
@@ -375,6 +533,94 @@ mod tests {
         assert_eq!(note.extract_heading(), None);
     }
 
+    #[test]
+    fn test_pinned_favorite_roundtrip() {
+        let mut note = Note::new();
+        note.content = "# 電話番号".to_string();
+        assert!(!note.to_file_content().contains("pinned"));
+
+        note.set_pinned(true);
+        note.set_favorite(true);
+        assert!(note.is_dirty);
+
+        let parsed = Note::from_file_content(&note.to_file_content()).unwrap();
+        assert!(parsed.metadata.pinned);
+        assert!(parsed.metadata.favorite);
+        assert_eq!(parsed.metadata.updated_at.timestamp(), note.metadata.updated_at.timestamp());
+    }
+
+    #[test]
+    fn test_timestamp_formats() {
+        let mut note = Note::new();
+        note.metadata.created_at = "2026-10-17T09:30:00Z".parse().unwrap();
+        note.metadata.updated_at = note.metadata.created_at;
+
+        let utc = note.to_file_content_in(TimestampZone::Utc);
+        assert!(utc.contains("created_at: 2026-10-17T09:30:00Z\n"));
+        let local = note.to_file_content_in(TimestampZone::Local);
+        let expected = note
+            .metadata
+            .created_at
+            .with_timezone(&Local)
+            .to_rfc3339_opts(SecondsFormat::Secs, false);
+        assert!(local.contains(&format!("created_at: {}\n", expected)));
+        // ローカル時刻は UTC でも `Z` ではなく `+HH:MM` のオフセットで書く
+        let offset = &expected[expected.len() - 6..];
+        assert!(offset.starts_with(['+', '-']) && offset.as_bytes()[3] == b':', "{}", expected);
+        for content in [&utc, &local] {
+            let parsed = Note::from_file_content(content).unwrap();
+            assert_eq!(parsed.metadata.created_at, note.metadata.created_at);
+        }
+
+        // オフセット付き・オフセットなし（UTC）・引用符付きの既存の書き方も読める
+        for value in ["2026-10-17T18:30:00+09:00", "2026-10-17 09:30:00", "\"2026-10-17T18:30:00+09:00\""] {
+            let content = utc.replace("created_at: 2026-10-17T09:30:00Z", &format!("created_at: {}", value));
+            let parsed = Note::from_file_content(&content).unwrap();
+            assert_eq!(parsed.metadata.created_at, note.metadata.created_at, "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_daily_roundtrip() {
+        let mut note = Note::new();
+        assert!(!note.to_file_content().contains("daily"));
+
+        note.metadata.daily = NaiveDate::from_ymd_opt(2026, 1, 2);
+        let content = note.to_file_content();
+        assert!(content.contains("daily: 2026-01-02\n"));
+        assert_eq!(Note::from_file_content(&content).unwrap().metadata.daily, note.metadata.daily);
+    }
+
+    #[test]
+    fn test_filename_style_roundtrip() {
+        let mut note = Note::new();
+        note.metadata.filename_style = Some(FilenameStyle::DatePrefixed);
+        let content = note.to_file_content();
+        assert!(content.contains("filename: date_prefixed\n"));
+
+        let parsed = Note::from_file_content(&content.replace("date_prefixed", "date-prefixed")).unwrap();
+        assert_eq!(parsed.metadata.filename_style, Some(FilenameStyle::DatePrefixed));
+    }
+
+    #[test]
+    fn test_encrypted_roundtrip() {
+        let mut note = Note::new();
+        assert!(!note.to_file_content().contains("encrypted"));
+
+        note.metadata.encrypted = true;
+        let content = note.to_file_content();
+        assert!(content.contains("encrypted: true\n"));
+        assert!(Note::from_file_content(&content).unwrap().metadata.encrypted);
+    }
+
+    #[test]
+    fn test_word_count() {
+        let mut note = Note::new();
+        note.content = "# Hello world\n\n日本語 test".to_string();
+        // Hello, world, 日, 本, 語, test
+        assert_eq!(note.word_count(), 6);
+    }
+
     #[test]
     fn test_update_content_marks_dirty() {
         let mut note = Note::new();
@@ -0,0 +1,61 @@
+//! 保存ディレクトリの移転
+//!
+//! 保存ディレクトリを変更するときに、既存のノート（サブフォルダ・ゴミ箱・除外ルールを含む）を
+//! 新しいディレクトリへ移動またはコピーする。移転後はインデックスを新しいディレクトリで作り直す。
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 既存のノートの扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelocationMode {
+    /// 新しいディレクトリへ移動（元のファイルは削除）
+    Move,
+    /// 新しいディレクトリへコピー（元のファイルは残す）
+    Copy,
+    /// ファイルはそのまま、保存ディレクトリの指定だけを変更
+    Keep,
+}
+
+/// 移転できなかったファイル
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RelocationFailure {
+    /// 元の保存ディレクトリからの相対パス
+    pub path: PathBuf,
+    pub error: String,
+}
+
+/// 移転結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RelocationReport {
+    /// 移動・コピーしたファイル数
+    pub transferred: usize,
+    /// 移転先に同じ内容のファイルがあったため書き込まなかったファイル数
+    pub skipped: usize,
+    /// 移転できなかったファイル（1件でもあれば保存ディレクトリを変更しない）
+    pub failed: Vec<RelocationFailure>,
+    /// 保存ディレクトリを変更したか
+    pub switched: bool,
+}
+
+/// 一方のディレクトリがもう一方の配下にあるか（同じディレクトリは含まない）
+///
+/// 入れ子のディレクトリへの移転は、移転先のファイルを再び走査してしまうため行わない。
+pub fn is_nested_directory(a: &Path, b: &Path) -> bool {
+    a != b && (a.starts_with(b) || b.starts_with(a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_nested_directory() {
+        let notes = Path::new("/home/user/notes");
+        assert!(is_nested_directory(notes, Path::new("/home/user/notes/sub")));
+        assert!(is_nested_directory(Path::new("/home/user/notes/sub"), notes));
+        assert!(!is_nested_directory(notes, notes));
+        assert!(!is_nested_directory(notes, Path::new("/home/user/notes2")));
+    }
+}
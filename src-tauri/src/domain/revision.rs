@@ -0,0 +1,157 @@
+//! リビジョン履歴関連のドメインモデル
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// ノートのリビジョン（保存時のスナップショット）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Revision {
+    /// 本文の blake3 ハッシュ（コンテンツアドレス）
+    pub id: String,
+    /// スナップショットを取得した日時
+    pub saved_at: DateTime<Utc>,
+    /// 本文のサイズ（バイト）
+    pub size: u64,
+}
+
+/// 差分の行の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffOp {
+    /// 両方に存在する
+    Equal,
+    /// 新しい側にのみ存在する
+    Insert,
+    /// 古い側にのみ存在する
+    Delete,
+}
+
+/// 差分の1行
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+/// LCS を計算する行数の上限（これを超える部分は全削除 + 全追加として扱う）
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// 行単位の差分を計算
+///
+/// 共通の先頭・末尾を除いた部分に LCS を適用する。
+/// 変更部分が巨大な場合は計算量を抑えるため、置き換えとして扱う。
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_mid = &old_lines[prefix..old_lines.len() - suffix];
+    let new_mid = &new_lines[prefix..new_lines.len() - suffix];
+
+    let line = |op, text: &str| DiffLine {
+        op,
+        text: text.to_string(),
+    };
+
+    let mut result: Vec<DiffLine> = old_lines[..prefix]
+        .iter()
+        .map(|t| line(DiffOp::Equal, t))
+        .collect();
+
+    if old_mid.len().saturating_mul(new_mid.len()) > MAX_LCS_CELLS {
+        result.extend(old_mid.iter().map(|t| line(DiffOp::Delete, t)));
+        result.extend(new_mid.iter().map(|t| line(DiffOp::Insert, t)));
+    } else {
+        result.extend(lcs_diff(old_mid, new_mid));
+    }
+
+    result.extend(
+        old_lines[old_lines.len() - suffix..]
+            .iter()
+            .map(|t| line(DiffOp::Equal, t)),
+    );
+    result
+}
+
+/// LCS（最長共通部分列）による差分
+fn lcs_diff(old: &[&str], new: &[&str]) -> Vec<DiffLine> {
+    let (n, m) = (old.len(), new.len());
+
+    // lengths[i][j] = old[i..] と new[j..] の LCS 長
+    let mut lengths = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            result.push(DiffLine { op: DiffOp::Equal, text: old[i].to_string() });
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            result.push(DiffLine { op: DiffOp::Delete, text: old[i].to_string() });
+            i += 1;
+        } else {
+            result.push(DiffLine { op: DiffOp::Insert, text: new[j].to_string() });
+            j += 1;
+        }
+    }
+    result.extend(old[i..].iter().map(|t| DiffLine { op: DiffOp::Delete, text: t.to_string() }));
+    result.extend(new[j..].iter().map(|t| DiffLine { op: DiffOp::Insert, text: t.to_string() }));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ops(diff: &[DiffLine]) -> Vec<(DiffOp, &str)> {
+        diff.iter().map(|l| (l.op, l.text.as_str())).collect()
+    }
+
+    #[test]
+    fn test_diff_lines_identical() {
+        let diff = diff_lines("a\nb", "a\nb");
+        assert_eq!(ops(&diff), vec![(DiffOp::Equal, "a"), (DiffOp::Equal, "b")]);
+    }
+
+    #[test]
+    fn test_diff_lines_insert_and_delete() {
+        let diff = diff_lines("# メモ\n牛乳\nパン\n卵", "# メモ\n牛乳\nバター\n卵\n");
+        assert_eq!(
+            ops(&diff),
+            vec![
+                (DiffOp::Equal, "# メモ"),
+                (DiffOp::Equal, "牛乳"),
+                (DiffOp::Delete, "パン"),
+                (DiffOp::Insert, "バター"),
+                (DiffOp::Equal, "卵"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_lines_from_empty() {
+        let diff = diff_lines("", "a\nb");
+        assert_eq!(ops(&diff), vec![(DiffOp::Insert, "a"), (DiffOp::Insert, "b")]);
+    }
+}
@@ -1,3 +1,8 @@
+use super::filename::FilenameStyle;
+use super::sort::NoteSortOrder;
+use super::note::TimestampZone;
+use super::uid::UidFormat;
+use super::vault::VaultSettings;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -122,6 +127,172 @@ impl Default for AutosaveSettings {
     }
 }
 
+/// テンプレート設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct TemplateSettings {
+    /// 新規ノート・ウィキリンクから作成するノートに使うテンプレート名（`None` は組み込み）
+    #[serde(default)]
+    pub default_template: Option<String>,
+}
+
+/// ファイル名設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FilenameSettings {
+    /// 新しいノートのファイル名の生成方式（ノートごとに front matter で上書き可能）
+    #[serde(default)]
+    pub style: FilenameStyle,
+    /// `FilenameStyle::Pattern` のパターン（`{{title}}` `{{slug}}` `{{uid}}` `{{date}}` など）
+    pub pattern: String,
+}
+
+impl Default for FilenameSettings {
+    fn default() -> Self {
+        Self {
+            style: FilenameStyle::default(),
+            pattern: super::filename::DEFAULT_FILENAME_PATTERN.to_string(),
+        }
+    }
+}
+
+/// デイリーノート設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DailyNoteSettings {
+    /// ファイル名の日付パターン（strftime 形式、例: `%Y-%m-%d`）
+    pub filename_pattern: String,
+    /// デイリーノートに使うテンプレート名（`None` は組み込み）
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+impl Default for DailyNoteSettings {
+    fn default() -> Self {
+        Self {
+            filename_pattern: super::daily::DEFAULT_DAILY_PATTERN.to_string(),
+            template: None,
+        }
+    }
+}
+
+/// ゴミ箱設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrashSettings {
+    /// ゴミ箱に残す日数（0 で自動削除しない）
+    pub retention_days: u32,
+}
+
+impl Default for TrashSettings {
+    fn default() -> Self {
+        Self { retention_days: 30 }
+    }
+}
+
+/// リビジョン履歴設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistorySettings {
+    pub enabled: bool,
+    /// スナップショットの最小間隔（秒）。自動保存のたびに履歴が増えないよう間引く
+    pub min_interval_secs: u64,
+    /// ノートごとに保持するリビジョン数の上限
+    pub max_revisions: usize,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_interval_secs: 300,
+            max_revisions: 100,
+        }
+    }
+}
+
+/// 暗号化ノートの設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EncryptionSettings {
+    /// 最後の操作からこの秒数が経つとロックする（0 は自動でロックしない）
+    pub session_timeout_secs: u64,
+}
+
+impl Default for EncryptionSettings {
+    fn default() -> Self {
+        Self {
+            session_timeout_secs: 600,
+        }
+    }
+}
+
+/// Git によるバージョン管理の設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VersioningSettings {
+    /// 保存ディレクトリを git リポジトリとしてコミットする
+    pub enabled: bool,
+    /// 最後の変更からこの秒数が経つとコミットする
+    pub quiet_period_secs: u64,
+    /// 変更が続いても、最初の未コミットの変更からこの秒数が経つとコミットする（0 は上限なし）
+    pub max_interval_secs: u64,
+}
+
+impl Default for VersioningSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            quiet_period_secs: 120,
+            max_interval_secs: 1800,
+        }
+    }
+}
+
+/// バックアップ設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BackupSettings {
+    /// 定期的にバックアップする
+    pub enabled: bool,
+    /// バックアップの間隔（時間）
+    pub interval_hours: u32,
+    /// アーカイブの保存先（`None` は設定ディレクトリの `backups`）
+    #[serde(default)]
+    pub directory: Option<PathBuf>,
+    /// 日ごとに残すアーカイブの日数
+    pub keep_daily: u32,
+    /// 週ごとに残すアーカイブの週数
+    pub keep_weekly: u32,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: 24,
+            directory: None,
+            keep_daily: 7,
+            keep_weekly: 4,
+        }
+    }
+}
+
+/// フォルダミラーとの同期設定（ミラーのディレクトリは保管庫ごとに `Vault::mirror_directory`）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SyncSettings {
+    /// 定期的に同期する間隔（分、0 は手動のみ）
+    pub interval_minutes: u32,
+}
+
+/// UID 設定
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct UidSettings {
+    /// 新しいノートの UID の形式（既存のノートの UID は変えない）
+    #[serde(default)]
+    pub format: UidFormat,
+}
+
+/// front matter の日時の設定
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TimestampSettings {
+    /// `created_at` / `updated_at` を書くタイムゾーン（読み込みはどちらの形式も受け付ける）
+    #[serde(default)]
+    pub zone: TimestampZone,
+}
+
 /// ショートカットキー設定
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ShortcutSettings {
@@ -173,6 +344,52 @@ impl Default for ShortcutSettings {
     }
 }
 
+/// ビューごとの表示設定（ソート順とフィルタ）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ViewPreference {
+    #[serde(default)]
+    pub sort: NoteSortOrder,
+    #[serde(default)]
+    pub tag_filter: Option<String>,
+}
+
+/// 表示設定を持つビューの種類
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ViewKind {
+    /// ホーム画面のギャラリー
+    Gallery,
+    /// サイドバーのノート一覧
+    Sidebar,
+}
+
+/// ビュー設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ViewSettings {
+    #[serde(default)]
+    pub gallery: ViewPreference,
+    #[serde(default)]
+    pub sidebar: ViewPreference,
+}
+
+impl ViewSettings {
+    /// 指定ビューの表示設定を取得
+    pub fn get(&self, view: ViewKind) -> &ViewPreference {
+        match view {
+            ViewKind::Gallery => &self.gallery,
+            ViewKind::Sidebar => &self.sidebar,
+        }
+    }
+
+    /// 指定ビューの表示設定を可変参照で取得
+    pub fn get_mut(&mut self, view: ViewKind) -> &mut ViewPreference {
+        match view {
+            ViewKind::Gallery => &mut self.gallery,
+            ViewKind::Sidebar => &mut self.sidebar,
+        }
+    }
+}
+
 /// アプリケーション設定
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Settings {
@@ -189,6 +406,32 @@ pub struct Settings {
     pub restore_last_note: bool,
     #[serde(default)]
     pub last_note_uid: Option<String>,
+    #[serde(default)]
+    pub views: ViewSettings,
+    #[serde(default)]
+    pub trash: TrashSettings,
+    #[serde(default)]
+    pub history: HistorySettings,
+    #[serde(default)]
+    pub templates: TemplateSettings,
+    #[serde(default)]
+    pub daily_notes: DailyNoteSettings,
+    #[serde(default)]
+    pub filenames: FilenameSettings,
+    #[serde(default)]
+    pub vaults: VaultSettings,
+    #[serde(default)]
+    pub encryption: EncryptionSettings,
+    #[serde(default)]
+    pub versioning: VersioningSettings,
+    #[serde(default)]
+    pub backup: BackupSettings,
+    #[serde(default)]
+    pub sync: SyncSettings,
+    #[serde(default)]
+    pub uids: UidSettings,
+    #[serde(default)]
+    pub timestamps: TimestampSettings,
 }
 
 impl Settings {
@@ -251,6 +494,19 @@ impl Default for Settings {
             autosave: AutosaveSettings::default(),
             restore_last_note: false,
             last_note_uid: None,
+            views: ViewSettings::default(),
+            trash: TrashSettings::default(),
+            history: HistorySettings::default(),
+            templates: TemplateSettings::default(),
+            daily_notes: DailyNoteSettings::default(),
+            filenames: FilenameSettings::default(),
+            vaults: VaultSettings::default(),
+            encryption: EncryptionSettings::default(),
+            versioning: VersioningSettings::default(),
+            backup: BackupSettings::default(),
+            sync: SyncSettings::default(),
+            uids: UidSettings::default(),
+            timestamps: TimestampSettings::default(),
         }
     }
 }
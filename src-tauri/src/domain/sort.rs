@@ -0,0 +1,89 @@
+//! ノート一覧のソート順
+
+use serde::{Deserialize, Serialize};
+
+/// ノート一覧（ギャラリー・サイドバー）のソート順
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteSortOrder {
+    /// 更新日時（新しい順）
+    #[default]
+    UpdatedAt,
+    /// 作成日時（新しい順）
+    CreatedAt,
+    /// タイトル（五十音順・アルファベット順）
+    Title,
+    /// バックリンク数（多い順）
+    BacklinkCount,
+    /// 文字数・単語数（多い順）
+    WordCount,
+    /// ファイルサイズ（大きい順）
+    FileSize,
+    /// 手動で並べた順（並び順未設定のノートは末尾）
+    Manual,
+}
+
+impl NoteSortOrder {
+    /// 昇順で並べるソートか
+    ///
+    /// タイトルと手動順は昇順、それ以外は降順。
+    /// 同一値のノートは常に uid の降順（新しい順）で並ぶ。
+    pub fn is_ascending(self) -> bool {
+        matches!(self, Self::Title | Self::Manual)
+    }
+
+    /// ソート値が数値か（カーソルの sort_key を整数として比較する）
+    pub fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            Self::BacklinkCount | Self::WordCount | Self::FileSize | Self::Manual
+        )
+    }
+}
+
+/// タイトルのソートキーを生成
+///
+/// 日本語の並び順に近づけるための正規化:
+///
+/// - 全角英数記号 → 半角
+/// - 全角スペース → 半角スペース
+/// - カタカナ → ひらがな（「アイス」と「あいす」を同じ位置に並べる）
+/// - 英字は小文字化
+///
+/// 漢字は読みを持たないためコードポイント順（かなの後ろ）になる。
+pub fn title_sort_key(title: &str) -> String {
+    title
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            '\u{3000}' => ' ',
+            '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+            _ => c,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_sort_key_katakana_to_hiragana() {
+        assert_eq!(title_sort_key("アイス"), "あいす");
+        assert_eq!(title_sort_key("ガッコウ"), "がっこう");
+    }
+
+    #[test]
+    fn test_title_sort_key_fullwidth_ascii() {
+        assert_eq!(title_sort_key("ＡＢＣ　１２３"), "abc 123");
+    }
+
+    #[test]
+    fn test_title_sort_key_orders_kana() {
+        let mut titles = vec!["さくら", "カメラ", "あめ", "イヌ"];
+        titles.sort_by_key(|t| title_sort_key(t));
+        assert_eq!(titles, vec!["あめ", "イヌ", "カメラ", "さくら"]);
+    }
+}
@@ -0,0 +1,237 @@
+//! フォルダミラーとの双方向同期
+//!
+//! 保存ディレクトリと別のローカルディレクトリ（USB メモリ・NAS・Syncthing のフォルダなど）を
+//! ファイル単位で同期する。前回の同期時点の各ファイルの `content_hash` を保管庫ごとに記録し、
+//! 両側の現在のハッシュと比べてどちらで変更・削除されたかを判定する。
+//!
+//! # 判定
+//!
+//! | 保存ディレクトリ | ミラー | 操作 |
+//! |---|---|---|
+//! | 変更 | 前回のまま | ミラーへコピー |
+//! | 前回のまま | 変更 | 保存ディレクトリへコピー |
+//! | 変更 | 変更（内容が異なる） | 競合（保存ディレクトリの版を残し、ミラーの版を競合コピーにする） |
+//! | 前回のまま | 削除 | 保存ディレクトリでも削除 |
+//! | 変更 | 削除 | 削除より変更を優先してミラーへコピー |
+//!
+//! 削除は元に戻せるよう、ノートはゴミ箱へ、それ以外はそれぞれの側の `.sync-trash` へ移動する。
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// 同期で削除したファイルの移動先（保存ディレクトリ・ミラーそれぞれの直下）
+pub const SYNC_TRASH_DIR_NAME: &str = ".sync-trash";
+
+/// 削除したファイルを移すフォルダ名の日時の形式（`.sync-trash/{日時}/{相対パス}`）
+pub const SYNC_TRASH_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+/// 競合コピーのファイル名に付ける日時の形式
+const CONFLICT_TIME_FORMAT: &str = "%Y-%m-%d %H%M%S";
+
+/// ファイル内容のハッシュ（blake3）
+pub fn file_hash(content: &[u8]) -> String {
+    blake3::hash(content).to_hex().to_string()
+}
+
+/// 前回の同期時点の状態（保管庫ごとに保存する）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncState {
+    /// 同期したミラーのディレクトリ（変わった場合は記録を使わない）
+    pub mirror: Option<PathBuf>,
+    /// `/` 区切りの相対パス → 同期時点の `content_hash`
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+}
+
+impl SyncState {
+    /// 指定のミラーとの同期の記録（別のミラーの記録なら空）
+    pub fn for_mirror(self, mirror: &Path) -> Self {
+        if self.mirror.as_deref() == Some(mirror) {
+            self
+        } else {
+            Self {
+                mirror: Some(mirror.to_path_buf()),
+                files: BTreeMap::new(),
+            }
+        }
+    }
+}
+
+/// 同期の操作（パスは `/` 区切りの相対パス）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncAction {
+    /// 保存ディレクトリからミラーへコピー
+    Push(String),
+    /// ミラーから保存ディレクトリへコピー
+    Pull(String),
+    /// 保存ディレクトリのファイルを削除（ミラーで削除された）
+    DeleteInVault(String),
+    /// ミラーのファイルを削除（保存ディレクトリで削除された）
+    DeleteInMirror(String),
+    /// 両側で異なる変更があった
+    Conflict(String),
+    /// 両側で同じ内容になっている（記録のみ更新）
+    Record(String),
+    /// 両側で削除されている（記録から消す）
+    Forget(String),
+}
+
+/// 両側のハッシュと前回の記録から同期の操作を決める（パスの順）
+pub fn plan_sync(
+    vault: &HashMap<String, String>,
+    mirror: &HashMap<String, String>,
+    base: &BTreeMap<String, String>,
+) -> Vec<SyncAction> {
+    let paths: BTreeSet<&String> = vault.keys().chain(mirror.keys()).chain(base.keys()).collect();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let base = base.get(path);
+            let action = match (vault.get(path), mirror.get(path)) {
+                (Some(v), Some(m)) if v == m => {
+                    (base != Some(v)).then(|| SyncAction::Record(path.clone()))?
+                }
+                (Some(v), Some(_)) if base == Some(v) => SyncAction::Pull(path.clone()),
+                (Some(_), Some(m)) if base == Some(m) => SyncAction::Push(path.clone()),
+                (Some(_), Some(_)) => SyncAction::Conflict(path.clone()),
+                (Some(v), None) if base == Some(v) => SyncAction::DeleteInVault(path.clone()),
+                (Some(_), None) => SyncAction::Push(path.clone()),
+                (None, Some(m)) if base == Some(m) => SyncAction::DeleteInMirror(path.clone()),
+                (None, Some(_)) => SyncAction::Pull(path.clone()),
+                (None, None) => SyncAction::Forget(path.clone()),
+            };
+            Some(action)
+        })
+        .collect()
+}
+
+/// 競合コピーのパス（`dir/メモ.md` → `dir/メモ (conflict 2026-10-18 093000).md`）
+pub fn conflict_copy_path(path: &str, at: chrono::DateTime<chrono::Local>) -> String {
+    let (dir, name) = match path.rsplit_once('/') {
+        Some((dir, name)) => (Some(dir), name),
+        None => (None, path),
+    };
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, Some(ext)),
+        _ => (name, None),
+    };
+
+    let mut copy = format!("{} (conflict {})", stem, at.format(CONFLICT_TIME_FORMAT));
+    if let Some(ext) = extension {
+        copy = format!("{}.{}", copy, ext);
+    }
+    match dir {
+        Some(dir) => format!("{}/{}", dir, copy),
+        None => copy,
+    }
+}
+
+/// 同期で見つかった競合
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SyncConflict {
+    /// 競合したファイル（保存ディレクトリの版を残す）
+    pub path: String,
+    /// ミラーの版を保存した競合コピー
+    pub conflict_copy: String,
+}
+
+/// 同期結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SyncReport {
+    /// ミラーから保存ディレクトリへコピーしたファイル数
+    pub pulled: usize,
+    /// 保存ディレクトリからミラーへコピーしたファイル数
+    pub pushed: usize,
+    /// 削除を反映したファイル数（両側の合計）
+    pub deleted: usize,
+    pub conflicts: Vec<SyncConflict>,
+}
+
+/// 同期のエラー
+#[derive(Debug, thiserror::Error)]
+pub enum SyncError {
+    #[error("ミラーのディレクトリが設定されていません")]
+    MirrorNotConfigured,
+    #[error("ミラーのディレクトリが見つかりません: {0}")]
+    MirrorUnavailable(PathBuf),
+    #[error("ミラーのディレクトリが不正です: {0}")]
+    InvalidMirror(String),
+    #[error("{0} のファイルがすべてなくなっているため同期を中止しました")]
+    EmptySide(&'static str),
+    #[error("ストレージエラー: {0}")]
+    Storage(#[from] crate::traits::StorageError),
+    #[error("インデックスの再構築に失敗しました: {0}")]
+    Reindex(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn hashes(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries.iter().map(|(p, h)| (p.to_string(), h.to_string())).collect()
+    }
+
+    #[test]
+    fn test_plan_sync() {
+        let base: BTreeMap<String, String> = [
+            ("same.md", "a"),
+            ("vault-edit.md", "a"),
+            ("mirror-edit.md", "a"),
+            ("both-edit.md", "a"),
+            ("mirror-delete.md", "a"),
+            ("edit-vs-delete.md", "a"),
+            ("gone.md", "a"),
+        ]
+        .iter()
+        .map(|(p, h)| (p.to_string(), h.to_string()))
+        .collect();
+        let vault = hashes(&[
+            ("same.md", "a"),
+            ("vault-edit.md", "b"),
+            ("mirror-edit.md", "a"),
+            ("both-edit.md", "b"),
+            ("mirror-delete.md", "a"),
+            ("edit-vs-delete.md", "b"),
+            ("new-in-vault.md", "x"),
+        ]);
+        let mirror = hashes(&[
+            ("same.md", "a"),
+            ("vault-edit.md", "a"),
+            ("mirror-edit.md", "c"),
+            ("both-edit.md", "c"),
+            ("edit-vs-delete.md", "a"),
+            ("new-in-mirror.md", "y"),
+        ]);
+
+        let actions = plan_sync(&vault, &mirror, &base);
+        let expected = [
+            SyncAction::Conflict("both-edit.md".to_string()),
+            SyncAction::Push("edit-vs-delete.md".to_string()),
+            SyncAction::Forget("gone.md".to_string()),
+            SyncAction::DeleteInVault("mirror-delete.md".to_string()),
+            SyncAction::Pull("mirror-edit.md".to_string()),
+            SyncAction::Pull("new-in-mirror.md".to_string()),
+            SyncAction::Push("new-in-vault.md".to_string()),
+            SyncAction::Push("vault-edit.md".to_string()),
+        ];
+        assert_eq!(actions, expected);
+
+        // 記録がなく内容が同じなら記録のみ
+        let actions = plan_sync(&vault, &vault, &BTreeMap::new());
+        assert!(actions.iter().all(|a| matches!(a, SyncAction::Record(_))));
+    }
+
+    #[test]
+    fn test_conflict_copy_path() {
+        let at = chrono::Local.with_ymd_and_hms(2026, 10, 18, 9, 30, 0).unwrap();
+        assert_eq!(
+            conflict_copy_path("仕事/メモ.md", at),
+            "仕事/メモ (conflict 2026-10-18 093000).md"
+        );
+        assert_eq!(conflict_copy_path(".kakuignore", at), ".kakuignore (conflict 2026-10-18 093000)");
+    }
+}
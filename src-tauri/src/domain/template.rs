@@ -0,0 +1,241 @@
+//! ノートテンプレート
+//!
+//! テンプレートは Markdown ファイルで、`{{name}}` 形式の変数を含められる。
+//! front matter を書いた場合は、そのタグ・フラグが新しいノートの初期値になる。
+//!
+//! ```markdown
+//! ---
+//! tags: [meeting]
+//! ---
+//!
+//! # {{title}}
+//!
+//! 日時: {{date}} {{time}}
+//! ```
+
+use super::Note;
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
+
+/// 新規ノート・ウィキリンクで作成するノートの組み込みテンプレート（テンプレート未設定時）
+pub const BUILTIN_TITLE_TEMPLATE: &str = "# {{title}}\n\n";
+
+/// テンプレート変数
+pub type TemplateVars = HashMap<String, String>;
+
+/// パース済みのテンプレート
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteTemplate {
+    pub name: String,
+    /// テンプレート本文（変数は未展開）
+    pub source: String,
+}
+
+impl NoteTemplate {
+    pub fn new(name: impl Into<String>, source: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            source: source.into(),
+        }
+    }
+
+    /// テンプレートが変数 `name` を使っているか
+    pub fn uses_var(&self, name: &str) -> bool {
+        let mut rest = self.source.as_str();
+        while let Some(start) = rest.find("{{") {
+            let after = &rest[start + 2..];
+            let Some(end) = after.find("}}") else {
+                return false;
+            };
+            if after[..end].trim() == name {
+                return true;
+            }
+            rest = &after[end + 2..];
+        }
+        false
+    }
+
+    /// 変数を展開してノートを作成
+    ///
+    /// front matter のタグ・`pinned`・`favorite` を初期値として適用する。
+    /// `uid` や日時はテンプレートに書かれていても新しいノートの値を使う。
+    pub fn instantiate(&self, vars: &TemplateVars) -> Note {
+        let mut note = Note::new();
+        let mut vars = vars.clone();
+        vars.entry("uid".to_string())
+            .or_insert_with(|| note.metadata.uid.clone());
+
+        let rendered = render(&self.source, &vars);
+        let (front_matter, body) = split_front_matter(&rendered);
+        if let Some(yaml) = front_matter {
+            apply_defaults(&mut note, yaml);
+        }
+
+        note.content = body.to_string();
+        note.metadata.title = note.extract_heading();
+        note.is_dirty = true;
+        note
+    }
+}
+
+/// 組み込み変数（`date` / `time` / `datetime`）と `title` を含む変数表を作成
+///
+/// 日時はローカル時刻。呼び出し側の変数が優先される。
+/// `clipboard` はクリップボードを読む必要があるため `TemplateService` が追加する。
+pub fn builtin_vars(now: DateTime<Local>, title: Option<&str>, extra: TemplateVars) -> TemplateVars {
+    let mut vars = TemplateVars::new();
+    vars.insert("date".to_string(), now.format("%Y-%m-%d").to_string());
+    vars.insert("time".to_string(), now.format("%H:%M").to_string());
+    vars.insert("datetime".to_string(), now.format("%Y-%m-%d %H:%M").to_string());
+    vars.insert("title".to_string(), title.unwrap_or_default().to_string());
+    vars.extend(extra);
+    vars
+}
+
+/// `{{name}}` を変数の値に置き換える（前後の空白は無視、未定義の変数はそのまま残す）
+pub fn render(source: &str, vars: &TemplateVars) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut rest = source;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let key = after[..end].trim();
+                match vars.get(key) {
+                    Some(value) => output.push_str(value),
+                    None => output.push_str(&rest[start..start + 2 + end + 2]),
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                output.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// front matter と本文に分割（front matter がなければ `None`）
+fn split_front_matter(content: &str) -> (Option<&str>, &str) {
+    if !Note::has_front_matter(content) {
+        return (None, content);
+    }
+    let end = content[4..].find("\n---").map(|i| i + 4).unwrap_or(4);
+    let body = content[end + 4..].trim_start_matches('\n');
+    (Some(&content[4..end]), body)
+}
+
+/// front matter のタグ・フラグをノートに適用
+fn apply_defaults(note: &mut Note, yaml: &str) {
+    let mut in_tags = false;
+    let mut tags = Vec::new();
+
+    for line in yaml.lines() {
+        let trimmed = line.trim();
+        if in_tags {
+            if let Some(tag) = trimmed.strip_prefix("- ") {
+                if !tag.trim().is_empty() {
+                    tags.push(tag.trim().to_string());
+                }
+                continue;
+            }
+            in_tags = false;
+        }
+
+        if let Some(value) = trimmed.strip_prefix("tags:") {
+            let value = value.trim();
+            if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                tags.extend(
+                    inner
+                        .split(',')
+                        .map(|t| t.trim().to_string())
+                        .filter(|t| !t.is_empty()),
+                );
+            } else if value.is_empty() {
+                in_tags = true;
+            }
+        } else if let Some(value) = trimmed.strip_prefix("pinned:") {
+            note.metadata.pinned = is_true(value);
+        } else if let Some(value) = trimmed.strip_prefix("favorite:") {
+            note.metadata.favorite = is_true(value);
+        }
+    }
+
+    note.metadata.tags = tags;
+}
+
+fn is_true(value: &str) -> bool {
+    matches!(value.trim().to_lowercase().as_str(), "true" | "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> TemplateVars {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_render_variables() {
+        let rendered = render(
+            "# {{ title }}\n{{date}} {{unknown}} {{",
+            &vars(&[("title", "会議"), ("date", "2026-01-01")]),
+        );
+        assert_eq!(rendered, "# 会議\n2026-01-01 {{unknown}} {{");
+    }
+
+    #[test]
+    fn test_uses_var() {
+        let template = NoteTemplate::new("", "# {{title}}\n\n{{ clipboard }} {{");
+        assert!(template.uses_var("clipboard"));
+        assert!(!template.uses_var("date"));
+    }
+
+    #[test]
+    fn test_instantiate_with_front_matter_defaults() {
+        let template = NoteTemplate::new(
+            "meeting",
+            "---\ntags:\n  - meeting\n  - {{date}}\npinned: true\n---\n\n# {{title}}\n\n{{clipboard}}",
+        );
+        let note = template.instantiate(&vars(&[
+            ("title", "定例"),
+            ("date", "2026-01-01"),
+            ("clipboard", "議題"),
+        ]));
+
+        assert_eq!(note.content, "# 定例\n\n議題");
+        assert_eq!(note.metadata.title.as_deref(), Some("定例"));
+        assert_eq!(note.metadata.tags, vec!["meeting", "2026-01-01"]);
+        assert!(note.metadata.pinned);
+        assert!(!note.metadata.favorite);
+    }
+
+    #[test]
+    fn test_builtin_title_template() {
+        let note = NoteTemplate::new("", BUILTIN_TITLE_TEMPLATE)
+            .instantiate(&builtin_vars(Local::now(), Some("メモ"), TemplateVars::new()));
+        assert_eq!(note.content, Note::with_title("メモ").content);
+    }
+
+    #[test]
+    fn test_builtin_vars_keep_caller_variables() {
+        let now = Local::now();
+        let extra = vars(&[("clipboard", "議題"), ("date", "2026-01-01")]);
+        let vars = builtin_vars(now, Some("定例"), extra);
+
+        assert_eq!(vars["title"], "定例");
+        assert_eq!(vars["date"], "2026-01-01");
+        assert_eq!(vars["clipboard"], "議題");
+        // クリップボードは TemplateService が読み取って追加する
+        let vars = builtin_vars(now, None, TemplateVars::new());
+        assert!(!vars.contains_key("clipboard"));
+    }
+}
@@ -0,0 +1,18 @@
+//! ゴミ箱
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// ゴミ箱フォルダ名（保存ディレクトリ直下）
+pub const TRASH_DIR_NAME: &str = ".trash";
+
+/// ゴミ箱内のノート
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrashItem {
+    pub uid: String,
+    pub title: String,
+    /// 削除前のパス（保存ディレクトリからの相対パス）
+    pub original_path: PathBuf,
+    pub deleted_at: DateTime<Utc>,
+}
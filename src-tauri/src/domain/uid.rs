@@ -0,0 +1,150 @@
+//! ノートの UID
+//!
+//! UID は front matter の `uid:` に書くノートの識別子で、インデックスのキーのほか
+//! 履歴・下書きのディレクトリ名や `FilenameStyle::Uid` のファイル名にも使う。
+//! 新しいノートの UID の形式は設定で選べる（既存のノートの UID は変えない）。
+//!
+//! - タイムスタンプ: `20261017093000123456`（UTC の日時 + ナノ秒の下6桁）
+//! - ULID: `01J1Z9P6V9WQ7H9QXGQ2K5J1ZC`
+//! - UUIDv7: `01929a3b-7c4d-7e8f-9a0b-1c2d3e4f5a6b`
+//!
+//! 読み込むノートの UID は形式を問わない。ファイル名に使えない文字を含む UID（手で書いたものなど）も
+//! インデックスに登録し、履歴・下書きのファイル名には `uid_file_stem` で安全な名前に変換して使う。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// UID の最大長
+pub const MAX_UID_LEN: usize = 64;
+
+/// Crockford の Base32（ULID の文字）
+const CROCKFORD_BASE32: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// 新しいノートの UID の形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UidFormat {
+    /// 日時の数字（`20261017093000123456`）
+    #[default]
+    Timestamp,
+    /// ULID（`01J1Z9P6V9WQ7H9QXGQ2K5J1ZC`）
+    Ulid,
+    /// UUID バージョン7（`01929a3b-7c4d-7e8f-9a0b-1c2d3e4f5a6b`）
+    Uuid7,
+}
+
+/// UID として使える文字列か（英数字・`-`・`_` のみ、1〜`MAX_UID_LEN` 文字）
+///
+/// UID はパスの一部になるため、`/` や `.` を含むものは受け付けない。
+pub fn is_valid_uid(uid: &str) -> bool {
+    !uid.is_empty()
+        && uid.len() <= MAX_UID_LEN
+        && uid
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+/// 履歴・下書きのファイル名に使う UID の表現
+///
+/// 安全な UID はそのまま使い、それ以外は UID の blake3 ハッシュの16進数に `.uid` を付ける
+/// （`.` を含むため、安全な UID をそのまま使った名前とは重ならない）。
+pub fn uid_file_stem(uid: &str) -> String {
+    if is_valid_uid(uid) {
+        uid.to_string()
+    } else {
+        format!("{}.uid", blake3::hash(uid.as_bytes()).to_hex())
+    }
+}
+
+/// タイムスタンプ形式の UID（日時の数字 + ナノ秒の下6桁）
+pub fn timestamp_uid(now: DateTime<Utc>) -> String {
+    format!(
+        "{}{}",
+        now.format("%Y%m%d%H%M%S"),
+        now.timestamp_subsec_nanos() % 1_000_000
+    )
+}
+
+/// ULID（48 ビットのミリ秒 + 80 ビットの乱数を Crockford の Base32 で 26 文字に）
+pub fn ulid(now: DateTime<Utc>, random: [u8; 10]) -> String {
+    let mut value = u128::from(unix_millis(now)) << 80;
+    for (i, byte) in random.iter().enumerate() {
+        value |= u128::from(*byte) << (72 - i * 8);
+    }
+    (0..26)
+        .rev()
+        .map(|i| CROCKFORD_BASE32[((value >> (i * 5)) & 0x1f) as usize] as char)
+        .collect()
+}
+
+/// UUID バージョン7（48 ビットのミリ秒 + バージョン・バリアント + 74 ビットの乱数）
+pub fn uuid7(now: DateTime<Utc>, random: [u8; 10]) -> String {
+    let mut bytes = [0u8; 16];
+    bytes[..6].copy_from_slice(&unix_millis(now).to_be_bytes()[2..]);
+    bytes[6..].copy_from_slice(&random);
+    bytes[6] = 0x70 | (bytes[6] & 0x0f);
+    bytes[8] = 0x80 | (bytes[8] & 0x3f);
+
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// UNIX 時間のミリ秒（48 ビットに収まる範囲）
+fn unix_millis(now: DateTime<Utc>) -> u64 {
+    (now.timestamp_millis().max(0) as u64) & 0xffff_ffff_ffff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_is_valid_uid() {
+        assert!(is_valid_uid("2026011418102637208"));
+        assert!(is_valid_uid("01J1Z9P6V9WQ7H9QXGQ2K5J1ZC"));
+        assert!(is_valid_uid("01929a3b-7c4d-7e8f-9a0b-1c2d3e4f5a6b"));
+        assert!(is_valid_uid("imported_note_1"));
+        assert!(!is_valid_uid(""));
+        assert!(!is_valid_uid("../secret"));
+        assert!(!is_valid_uid("a/b"));
+        assert!(!is_valid_uid("メモ"));
+        assert!(!is_valid_uid(&"1".repeat(MAX_UID_LEN + 1)));
+    }
+
+    #[test]
+    fn test_uid_file_stem() {
+        assert_eq!(uid_file_stem("01J1Z9P6V9WQ7H9QXGQ2K5J1ZC"), "01J1Z9P6V9WQ7H9QXGQ2K5J1ZC");
+        let stem = uid_file_stem("../secret");
+        assert!(stem.ends_with(".uid") && !stem.contains('/'));
+        assert_eq!(stem.len(), 64 + ".uid".len());
+        assert_ne!(stem, uid_file_stem("../secret2"));
+    }
+
+    #[test]
+    fn test_generated_formats() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 9, 30, 0).unwrap();
+
+        assert!(timestamp_uid(now).starts_with("20261017093000"));
+
+        let id = ulid(now, [0; 10]);
+        assert_eq!(id.len(), 26);
+        assert!(id.ends_with("0000000000000000"));
+        assert!(ulid(now, [0; 10]) < ulid(now + chrono::Duration::milliseconds(1), [0; 10]));
+        assert!(is_valid_uid(&ulid(now, [0xff; 10])));
+
+        let id = uuid7(now, [0xff; 10]);
+        assert_eq!(id.len(), 36);
+        assert_eq!(&id[14..15], "7");
+        assert_eq!(&id[19..20], "b");
+        assert_eq!(&id[..8], &format!("{:012x}", now.timestamp_millis())[..8]);
+        assert!(is_valid_uid(&id));
+    }
+}
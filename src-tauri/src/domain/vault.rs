@@ -0,0 +1,169 @@
+//! 保管庫（Vault）
+//!
+//! 保管庫は名前付きのノートの集合で、それぞれ保存ディレクトリと SQLite インデックスを持つ。
+//! 使用中の保管庫の保存ディレクトリは `Settings::storage_directory` に反映される。
+//!
+//! インデックス・リビジョン履歴・下書きは保管庫ごとのデータディレクトリに置く。
+//! 既定の保管庫は従来どおり設定ディレクトリ直下を使う。
+
+use super::slugify;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 既定の保管庫の ID（複数保管庫に対応する前の保存ディレクトリ）
+pub const DEFAULT_VAULT_ID: &str = "default";
+
+/// 既定の保管庫の表示名
+const DEFAULT_VAULT_NAME: &str = "Default";
+
+/// 保管庫名の最大長（文字数）
+const MAX_VAULT_NAME_LEN: usize = 100;
+
+/// 保管庫のデータディレクトリを置くフォルダ（設定ディレクトリ配下）
+const VAULTS_DIR_NAME: &str = "vaults";
+
+/// 保管庫
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Vault {
+    /// 識別子（名前から生成、データディレクトリ名に使う）
+    pub id: String,
+    /// 表示名
+    pub name: String,
+    /// ノートの保存ディレクトリ
+    pub storage_directory: PathBuf,
+    /// 双方向に同期するミラーのディレクトリ（`None` は同期しない）
+    #[serde(default)]
+    pub mirror_directory: Option<PathBuf>,
+}
+
+impl Vault {
+    /// インデックスや履歴を置くデータディレクトリ
+    pub fn data_dir(&self, config_dir: &Path) -> PathBuf {
+        if self.id == DEFAULT_VAULT_ID {
+            config_dir.to_path_buf()
+        } else {
+            config_dir.join(VAULTS_DIR_NAME).join(&self.id)
+        }
+    }
+}
+
+/// 保管庫の設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct VaultSettings {
+    /// 使用中の保管庫の ID
+    pub active: String,
+    /// 登録済みの保管庫
+    pub list: Vec<Vault>,
+}
+
+impl Default for VaultSettings {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_VAULT_ID.to_string(),
+            list: Vec::new(),
+        }
+    }
+}
+
+impl VaultSettings {
+    /// ID で保管庫を取得
+    pub fn get(&self, id: &str) -> Option<&Vault> {
+        self.list.iter().find(|vault| vault.id == id)
+    }
+
+    /// 使用中の保管庫を、保存ディレクトリ（`storage_directory`）と一致させる
+    ///
+    /// 保管庫が未登録の場合（旧バージョンの設定）は既定の保管庫として登録する。
+    pub fn sync_active(&mut self, storage_directory: &Path) {
+        if self.get(&self.active).is_none() {
+            self.active = DEFAULT_VAULT_ID.to_string();
+            if self.get(DEFAULT_VAULT_ID).is_none() {
+                self.list.insert(
+                    0,
+                    Vault {
+                        id: DEFAULT_VAULT_ID.to_string(),
+                        name: DEFAULT_VAULT_NAME.to_string(),
+                        storage_directory: storage_directory.to_path_buf(),
+                        mirror_directory: None,
+                    },
+                );
+            }
+        }
+
+        let active = self.active.clone();
+        if let Some(vault) = self.list.iter_mut().find(|vault| vault.id == active) {
+            vault.storage_directory = storage_directory.to_path_buf();
+        }
+    }
+
+    /// 名前から未使用の ID を生成（`work`, `work-2`, ...）
+    ///
+    /// 既定の保管庫の ID は生成しない。データディレクトリが設定ディレクトリ直下のため、
+    /// 既定の保管庫の登録を解除した後に同じ名前で登録すると、元のインデックスや履歴を使ってしまう。
+    pub fn unique_id(&self, name: &str) -> String {
+        let base = match slugify(name) {
+            slug if slug.is_empty() => "vault".to_string(),
+            slug => slug,
+        };
+        let is_free = |id: &str| id != DEFAULT_VAULT_ID && self.get(id).is_none();
+        if is_free(&base) {
+            return base;
+        }
+        (2..)
+            .map(|n| format!("{}-{}", base, n))
+            .find(|id| is_free(id))
+            .expect("unbounded range")
+    }
+}
+
+/// 保管庫名として使えるか
+pub fn is_valid_vault_name(name: &str) -> bool {
+    let name = name.trim();
+    !name.is_empty()
+        && name.chars().count() <= MAX_VAULT_NAME_LEN
+        && !name.chars().any(char::is_control)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_active_registers_default_vault() {
+        let mut vaults = VaultSettings::default();
+        vaults.sync_active(Path::new("/notes"));
+
+        assert_eq!(vaults.active, DEFAULT_VAULT_ID);
+        assert_eq!(vaults.list.len(), 1);
+        assert_eq!(vaults.list[0].storage_directory, PathBuf::from("/notes"));
+
+        // 保存ディレクトリの変更は使用中の保管庫に反映する
+        vaults.sync_active(Path::new("/moved"));
+        assert_eq!(vaults.list.len(), 1);
+        assert_eq!(vaults.list[0].storage_directory, PathBuf::from("/moved"));
+    }
+
+    #[test]
+    fn test_unique_id_and_data_dir() {
+        let mut vaults = VaultSettings::default();
+        vaults.sync_active(Path::new("/notes"));
+        assert_eq!(vaults.unique_id("Default"), "default-2");
+        assert_eq!(vaults.unique_id("仕事"), slugify("仕事"));
+        assert_eq!(vaults.unique_id("!!!"), "vault");
+
+        let config = Path::new("/config");
+        assert_eq!(vaults.list[0].data_dir(config), PathBuf::from("/config"));
+        let work = Vault {
+            id: "work".to_string(),
+            name: "Work".to_string(),
+            storage_directory: PathBuf::from("/work"),
+            mirror_directory: None,
+        };
+        assert_eq!(work.data_dir(config), PathBuf::from("/config/vaults/work"));
+
+        // 既定の保管庫の登録を解除しても、その ID（設定ディレクトリ直下のデータ）は使わない
+        vaults.list.clear();
+        assert_eq!(vaults.unique_id("Default"), "default-2");
+    }
+}
@@ -0,0 +1,172 @@
+//! Git による保管庫のバージョン管理
+//!
+//! 保存ディレクトリをローカルの git リポジトリとしてコミットする。コミットは変更が一定時間
+//! 落ち着いた後、または変更が続いても一定間隔ごとに行う。リモートへの push は行わない。
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+
+/// コミットの件名に並べるノート名の上限（超えた分は件数のみ）
+const MAX_NAMES_IN_SUBJECT: usize = 3;
+
+/// 変更の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+/// コミットに含まれるファイルの変更
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VaultChange {
+    pub kind: ChangeKind,
+    /// 保存ディレクトリからの相対パス（`/` 区切り）
+    pub path: String,
+    /// 名前を変更する前のパス
+    pub previous_path: Option<String>,
+}
+
+impl VaultChange {
+    /// メッセージに表示するノート名（拡張子を除いたファイル名）
+    fn note_name(&self) -> &str {
+        let name = Path::new(&self.path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(&self.path);
+        if name.is_empty() {
+            &self.path
+        } else {
+            name
+        }
+    }
+}
+
+/// 保管庫のコミット
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VaultCommit {
+    /// コミットのハッシュ
+    pub id: String,
+    /// コミットメッセージの件名
+    pub summary: String,
+    pub committed_at: DateTime<Utc>,
+    /// コミット時点のノートのパス（ノートの履歴のみ、名前の変更を追跡するため）
+    #[serde(skip)]
+    pub path: Option<String>,
+}
+
+/// バージョン管理の状態
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VersioningStatus {
+    pub enabled: bool,
+    /// git コマンドを実行できるか
+    pub git_available: bool,
+    /// 保存ディレクトリが git リポジトリか
+    pub initialized: bool,
+    /// まだコミットしていない変更があるか
+    pub has_pending_changes: bool,
+    pub last_commit: Option<VaultCommit>,
+}
+
+/// 変更内容からコミットメッセージを作る
+///
+/// 件名は変更したノート名（多い場合は件数）、本文は変更ごとに1行。
+pub fn commit_message(changes: &[VaultChange]) -> String {
+    let subject = match changes {
+        [] => "Update notes".to_string(),
+        [change] => match change.kind {
+            ChangeKind::Added => format!("Add {}", change.note_name()),
+            ChangeKind::Modified => format!("Edit {}", change.note_name()),
+            ChangeKind::Deleted => format!("Delete {}", change.note_name()),
+            ChangeKind::Renamed => format!("Rename {}", change.note_name()),
+        },
+        changes if changes.len() <= MAX_NAMES_IN_SUBJECT => {
+            let names: Vec<&str> = changes.iter().map(VaultChange::note_name).collect();
+            format!("Update {}", names.join(", "))
+        }
+        changes => format!("Update {} notes", changes.len()),
+    };
+
+    let body: Vec<String> = changes
+        .iter()
+        .map(|change| match (&change.kind, &change.previous_path) {
+            (ChangeKind::Renamed, Some(previous)) => {
+                format!("renamed:  {} -> {}", previous, change.path)
+            }
+            (kind, _) => format!("{:<9} {}", format!("{}:", kind_label(*kind)), change.path),
+        })
+        .collect();
+
+    if body.is_empty() {
+        subject
+    } else {
+        format!("{}\n\n{}\n", subject, body.join("\n"))
+    }
+}
+
+fn kind_label(kind: ChangeKind) -> &'static str {
+    match kind {
+        ChangeKind::Added => "added",
+        ChangeKind::Modified => "modified",
+        ChangeKind::Deleted => "deleted",
+        ChangeKind::Renamed => "renamed",
+    }
+}
+
+/// バージョン管理のエラー
+#[derive(Debug, thiserror::Error)]
+pub enum VersioningError {
+    #[error("git コマンドが見つかりません")]
+    GitUnavailable,
+    #[error("保存ディレクトリは git リポジトリではありません")]
+    NotARepository,
+    #[error("ノートが見つかりません: {0}")]
+    NoteNotFound(String),
+    #[error("コミットが見つかりません: {0}")]
+    CommitNotFound(String),
+    #[error("git {command} に失敗しました: {message}")]
+    Command { command: String, message: String },
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(kind: ChangeKind, path: &str) -> VaultChange {
+        VaultChange {
+            kind,
+            path: path.to_string(),
+            previous_path: None,
+        }
+    }
+
+    #[test]
+    fn test_commit_message_names_changed_notes() {
+        let message = commit_message(&[change(ChangeKind::Modified, "work/買い物.md")]);
+        assert_eq!(message, "Edit 買い物\n\nmodified: work/買い物.md\n");
+
+        let message = commit_message(&[
+            change(ChangeKind::Added, "a.md"),
+            VaultChange {
+                previous_path: Some("old.md".to_string()),
+                ..change(ChangeKind::Renamed, "new.md")
+            },
+        ]);
+        assert!(message.starts_with("Update a, new\n\n"));
+        assert!(message.contains("added:    a.md\n"));
+        assert!(message.contains("renamed:  old.md -> new.md\n"));
+    }
+
+    #[test]
+    fn test_commit_message_counts_many_changes() {
+        let changes: Vec<VaultChange> = (0..5)
+            .map(|i| change(ChangeKind::Deleted, &format!("{}.md", i)))
+            .collect();
+        assert!(commit_message(&changes).starts_with("Update 5 notes\n"));
+    }
+}
@@ -0,0 +1,231 @@
+//! バックアップのアーカイブ（`.tar.gz`）の作成と展開
+//!
+//! 保管庫全体をメモリに載せないよう、作成時は元のファイルを1つずつ読みながら圧縮して
+//! 一時ファイルに書き出し、完了したらアーカイブの名前にリネームする。展開時も同じように
+//! エントリを1つずつ一時ファイルに書き出してから展開先にリネームする。
+
+use crate::domain::BackupError;
+use crate::traits::StorageError;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+
+/// ファイルを1つずつ追加してアーカイブを書き出す
+///
+/// `finish` を呼ばずに破棄した場合（途中で失敗した場合）は一時ファイルを削除する。
+pub struct ArchiveWriter {
+    builder: Option<tar::Builder<GzEncoder<File>>>,
+    path: PathBuf,
+    temp_path: PathBuf,
+    mtime: u64,
+}
+
+impl ArchiveWriter {
+    /// `path` に書き出すアーカイブを作る（完了するまでは `<path>.tmp` に書き込む）
+    pub fn create(path: &Path) -> Result<Self, BackupError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|_| StorageError::CreateDirFailed(parent.to_path_buf()))?;
+        }
+        let mut temp_path = OsString::from(path.as_os_str());
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+
+        let file = File::create(&temp_path).map_err(io_error)?;
+        Ok(Self {
+            builder: Some(tar::Builder::new(GzEncoder::new(file, Compression::default()))),
+            path: path.to_path_buf(),
+            temp_path,
+            mtime: chrono::Utc::now().timestamp().max(0) as u64,
+        })
+    }
+
+    /// ディスク上のファイルを読みながら追加する
+    pub fn append_file(&mut self, path: &str, source: &Path) -> Result<(), BackupError> {
+        let file = File::open(source).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => StorageError::NotFound(source.to_path_buf()),
+            _ => StorageError::Io(e),
+        })?;
+        let size = file.metadata().map_err(io_error)?.len();
+        // 読み込み中に伸びたファイルでもヘッダーのサイズを超えて書かない
+        self.append(path, size, BufReader::new(file).take(size))
+    }
+
+    /// メモリ上の内容を追加する
+    pub fn append_data(&mut self, path: &str, content: &[u8]) -> Result<(), BackupError> {
+        self.append(path, content.len() as u64, content)
+    }
+
+    /// 圧縮を終えてディスクに反映し、アーカイブの名前にリネームする
+    pub fn finish(mut self) -> Result<(), BackupError> {
+        let Some(builder) = self.builder.take() else {
+            return Ok(());
+        };
+        let file = builder
+            .into_inner()
+            .and_then(|encoder| encoder.finish())
+            .map_err(io_error)?;
+        file.sync_all().map_err(io_error)?;
+        drop(file);
+        fs::rename(&self.temp_path, &self.path).map_err(io_error)
+    }
+
+    fn append(&mut self, path: &str, size: u64, data: impl Read) -> Result<(), BackupError> {
+        let builder = self
+            .builder
+            .as_mut()
+            .expect("ArchiveWriter used after finish");
+        let mut header = tar::Header::new_gnu();
+        header.set_size(size);
+        header.set_mode(0o644);
+        header.set_mtime(self.mtime);
+        builder.append_data(&mut header, path, data).map_err(io_error)
+    }
+}
+
+impl Drop for ArchiveWriter {
+    fn drop(&mut self) {
+        if self.builder.take().is_some() {
+            let _ = fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+fn io_error(e: std::io::Error) -> BackupError {
+    BackupError::Storage(StorageError::Io(e))
+}
+
+/// アーカイブの通常のファイルを先頭から1つずつ読みながら `visit` に渡す
+///
+/// 内容はメモリに読み込まず、`visit` には `/` 区切りの相対パスと内容を読むための `Read` を渡す。
+/// 絶対パスや `..` を含むエントリは、展開先の外に書き込まないようエラーにする。
+pub fn for_each_file(
+    path: &Path,
+    visit: impl FnMut(&str, &mut dyn Read) -> Result<(), BackupError>,
+) -> Result<(), BackupError> {
+    let file = File::open(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => StorageError::NotFound(path.to_path_buf()),
+        _ => StorageError::Io(e),
+    })?;
+    visit_files(BufReader::new(file), visit)
+}
+
+fn visit_files(
+    archive: impl Read,
+    mut visit: impl FnMut(&str, &mut dyn Read) -> Result<(), BackupError>,
+) -> Result<(), BackupError> {
+    let invalid = |e: std::io::Error| BackupError::InvalidArchive(e.to_string());
+
+    let mut tar = tar::Archive::new(GzDecoder::new(archive));
+    for entry in tar.entries().map_err(invalid)? {
+        let mut entry = entry.map_err(invalid)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path().map_err(invalid)?.into_owned();
+        let path = safe_relative_path(&path)
+            .ok_or_else(|| BackupError::InvalidArchive(format!("unsafe path: {}", path.display())))?;
+        visit(&path, &mut entry)?;
+    }
+    Ok(())
+}
+
+/// アーカイブのエントリの内容を `dest` に書き出す
+///
+/// `<dest>.tmp` に書き込んでディスクに反映してからリネームする。途中で失敗した場合は
+/// 一時ファイルを削除し、`dest` の元の内容はそのまま残る。
+pub fn extract_to(content: &mut dyn Read, dest: &Path) -> Result<(), BackupError> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|_| StorageError::CreateDirFailed(parent.to_path_buf()))?;
+    }
+    let mut temp_path = OsString::from(dest.as_os_str());
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let mut write = || -> std::io::Result<()> {
+        let mut file = File::create(&temp_path)?;
+        std::io::copy(content, &mut file)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, dest)
+    };
+    write().map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        io_error(e)
+    })
+}
+
+/// 通常の名前のみからなる相対パスを `/` 区切りで返す
+fn safe_relative_path(path: &Path) -> Option<String> {
+    let parts = path
+        .components()
+        .map(|component| match component {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect::<Option<Vec<&str>>>()?;
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_and_extract() {
+        let temp_dir = TempDir::new().unwrap();
+        let image = temp_dir.path().join("image.png");
+        fs::write(&image, [0x89, b'P', b'N', b'G', 0]).unwrap();
+        let archive = temp_dir.path().join("backups/backup.tar.gz");
+
+        let mut writer = ArchiveWriter::create(&archive).unwrap();
+        writer
+            .append_data("notes/仕事/メモ.md", "# メモ".as_bytes())
+            .unwrap();
+        writer
+            .append_file("notes/attachments/image.png", &image)
+            .unwrap();
+        writer.finish().unwrap();
+
+        let mut paths = Vec::new();
+        let restored = temp_dir.path().join("restored");
+        for_each_file(&archive, |path, content| {
+            paths.push(path.to_string());
+            extract_to(content, &restored.join(path))
+        })
+        .unwrap();
+        assert_eq!(paths, ["notes/仕事/メモ.md", "notes/attachments/image.png"]);
+        assert_eq!(
+            fs::read_to_string(restored.join("notes/仕事/メモ.md")).unwrap(),
+            "# メモ"
+        );
+        assert_eq!(
+            fs::read(restored.join("notes/attachments/image.png")).unwrap(),
+            [0x89, b'P', b'N', b'G', 0]
+        );
+        assert!(!restored.join("notes/attachments/image.png.tmp").exists());
+        assert!(!temp_dir.path().join("backups/backup.tar.gz.tmp").exists());
+
+        assert!(visit_files(b"not an archive".as_slice(), |_, _| Ok(())).is_err());
+        assert_eq!(safe_relative_path(Path::new("notes/../../etc/passwd")), None);
+    }
+
+    #[test]
+    fn test_failed_write_removes_temp_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("backup.tar.gz");
+
+        let mut writer = ArchiveWriter::create(&archive).unwrap();
+        assert!(writer
+            .append_file("notes/missing.md", &temp_dir.path().join("missing.md"))
+            .is_err());
+        drop(writer);
+
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+}
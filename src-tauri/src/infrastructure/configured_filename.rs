@@ -0,0 +1,74 @@
+use crate::domain::{FilenameStyle, Note};
+use crate::infrastructure::{
+    DatePrefixedFilenameStrategy, HeadingFilenameStrategy, PatternFilenameStrategy,
+    SlugFilenameStrategy, UidFilenameStrategy,
+};
+use crate::services::SettingsService;
+use crate::traits::FilenameStrategy;
+use std::sync::Arc;
+
+/// 設定で選択されたファイル名生成戦略
+///
+/// front matter の `filename:` があればそちらを優先し、なければ `FilenameSettings::style` に従う。
+pub struct ConfiguredFilenameStrategy {
+    settings_service: Arc<SettingsService>,
+    heading: HeadingFilenameStrategy,
+    uid: UidFilenameStrategy,
+    date_prefixed: DatePrefixedFilenameStrategy,
+    slug: SlugFilenameStrategy,
+}
+
+impl ConfiguredFilenameStrategy {
+    pub fn new(settings_service: Arc<SettingsService>) -> Self {
+        Self {
+            settings_service,
+            heading: HeadingFilenameStrategy::new(),
+            uid: UidFilenameStrategy::new(),
+            date_prefixed: DatePrefixedFilenameStrategy::new(),
+            slug: SlugFilenameStrategy::new(),
+        }
+    }
+}
+
+impl FilenameStrategy for ConfiguredFilenameStrategy {
+    fn generate(&self, note: &Note, is_taken: &dyn Fn(&str) -> bool) -> String {
+        let settings = self.settings_service.get_arc();
+        let style = note.metadata.filename_style.unwrap_or(settings.filenames.style);
+
+        match style {
+            FilenameStyle::Heading => self.heading.generate(note, is_taken),
+            FilenameStyle::Uid => self.uid.generate(note, is_taken),
+            FilenameStyle::DatePrefixed => self.date_prefixed.generate(note, is_taken),
+            FilenameStyle::Slug => self.slug.generate(note, is_taken),
+            FilenameStyle::Pattern => PatternFilenameStrategy::new(settings.filenames.pattern.as_str())
+                .generate(note, is_taken),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::EventBusImpl;
+    use crate::services::test_support;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_settings_style_and_front_matter_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings_service =
+            test_support::settings_service(temp_dir.path(), Arc::new(EventBusImpl::new()));
+        let strategy = ConfiguredFilenameStrategy::new(settings_service.clone());
+
+        let mut note = Note::with_title("Plan for Q4");
+        assert_eq!(strategy.generate(&note, &|_| false), "Plan for Q4");
+
+        settings_service
+            .update(|settings| settings.filenames.style = FilenameStyle::Slug)
+            .unwrap();
+        assert_eq!(strategy.generate(&note, &|_| false), "plan-for-q4");
+
+        note.metadata.filename_style = Some(FilenameStyle::Uid);
+        assert_eq!(strategy.generate(&note, &|_| false), note.metadata.uid);
+    }
+}
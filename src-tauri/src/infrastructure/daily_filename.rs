@@ -0,0 +1,64 @@
+use crate::domain::{format_daily_name, Note};
+use crate::infrastructure::HeadingFilenameStrategy;
+use crate::services::SettingsService;
+use crate::traits::FilenameStrategy;
+use std::sync::Arc;
+
+/// デイリーノート用のファイル名生成戦略
+///
+/// `daily` を持つノートは設定の日付パターンで命名し、それ以外は委譲先の戦略に任せる。
+pub struct DailyFilenameStrategy {
+    fallback: Arc<dyn FilenameStrategy>,
+    settings_service: Arc<SettingsService>,
+}
+
+impl DailyFilenameStrategy {
+    pub fn new(fallback: Arc<dyn FilenameStrategy>, settings_service: Arc<SettingsService>) -> Self {
+        Self {
+            fallback,
+            settings_service,
+        }
+    }
+}
+
+impl FilenameStrategy for DailyFilenameStrategy {
+    fn generate(&self, note: &Note, is_taken: &dyn Fn(&str) -> bool) -> String {
+        match note.metadata.daily {
+            Some(date) => {
+                let settings = self.settings_service.get_arc();
+                let name = format_daily_name(date, &settings.daily_notes.filename_pattern);
+                HeadingFilenameStrategy::finish(&name, is_taken)
+            }
+            None => self.fallback.generate(note, is_taken),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::EventBusImpl;
+    use crate::services::test_support;
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_generate_daily_and_fallback() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings_service =
+            test_support::settings_service(temp_dir.path(), Arc::new(EventBusImpl::new()));
+        let strategy =
+            DailyFilenameStrategy::new(Arc::new(HeadingFilenameStrategy::new()), settings_service.clone());
+
+        let mut note = Note::with_title("買い物");
+        assert_eq!(strategy.generate(&note, &|_| false), "買い物");
+
+        note.metadata.daily = NaiveDate::from_ymd_opt(2026, 1, 2);
+        assert_eq!(strategy.generate(&note, &|_| false), "2026-01-02");
+
+        settings_service
+            .update(|settings| settings.daily_notes.filename_pattern = "%Y/%m/%d".to_string())
+            .unwrap();
+        assert_eq!(strategy.generate(&note, &|_| false), "2026_01_02");
+    }
+}
@@ -0,0 +1,47 @@
+use crate::domain::Note;
+use crate::infrastructure::HeadingFilenameStrategy;
+use crate::traits::FilenameStrategy;
+use chrono::Local;
+
+/// 作成日 + 見出しのファイル名生成戦略（`2026-10-17 Plan`）
+///
+/// 作成日はローカル時刻。見出しがなければ UID を使う。
+pub struct DatePrefixedFilenameStrategy;
+
+impl DatePrefixedFilenameStrategy {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for DatePrefixedFilenameStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FilenameStrategy for DatePrefixedFilenameStrategy {
+    fn generate(&self, note: &Note, is_taken: &dyn Fn(&str) -> bool) -> String {
+        let date = note.metadata.created_at.with_timezone(&Local).format("%Y-%m-%d");
+        let title = note
+            .extract_heading()
+            .unwrap_or_else(|| note.metadata.uid.clone());
+
+        HeadingFilenameStrategy::finish(&format!("{} {}", date, title), is_taken)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_date_prefixed() {
+        let note = Note::with_title("Plan");
+        let date = note.metadata.created_at.with_timezone(&Local).format("%Y-%m-%d");
+        assert_eq!(
+            DatePrefixedFilenameStrategy::new().generate(&note, &|_| false),
+            format!("{} Plan", date)
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! 下書きジャーナル
+//!
+//! 保存前のエディタバッファを設定ディレクトリ配下に追記していく先行書き込みログ。
+//! アプリや WebView がクラッシュしても、次回起動時に最後のスナップショットから復旧できる。
+//!
+//! # 構成
+//!
+//! - `drafts/{uid}.jsonl`: `Draft` を1行1件の JSON で追記（最後の行が最新）
+//!   （ファイル名に使えない文字を含む UID は `uid_file_stem` で変換する）
+//!
+//! 書き込み途中でクラッシュした末尾の行は読み込み時に無視する。
+//! 追記のたびに fsync はしない（プロセスのクラッシュには OS のページキャッシュで十分なため）。
+
+use crate::domain::{uid_file_stem, Draft};
+use crate::traits::{Storage, StorageError};
+use chrono::Utc;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// ジャーナルファイルの拡張子
+const JOURNAL_EXTENSION: &str = "jsonl";
+
+/// ジャーナルを最新のエントリだけに詰め直すサイズ（バイト）
+const MAX_JOURNAL_BYTES: u64 = 1024 * 1024;
+
+/// ファイルベースの下書きジャーナル
+pub struct DraftJournal {
+    root: PathBuf,
+    storage: Arc<dyn Storage>,
+}
+
+impl DraftJournal {
+    pub fn new(root: PathBuf, storage: Arc<dyn Storage>) -> Self {
+        Self { root, storage }
+    }
+
+    fn journal_path(&self, uid: &str) -> PathBuf {
+        self.root.join(format!("{}.{}", uid_file_stem(uid), JOURNAL_EXTENSION))
+    }
+
+    /// バッファのスナップショットを追記
+    ///
+    /// ジャーナルが大きくなった場合は最新のエントリだけに詰め直す。
+    pub fn append(&self, uid: &str, content: &str) -> Result<Draft, StorageError> {
+        let draft = Draft {
+            uid: uid.to_string(),
+            content: content.to_string(),
+            saved_at: Utc::now(),
+        };
+        let mut line = serde_json::to_string(&draft).map_err(invalid_data)?;
+        line.push('\n');
+
+        let path = self.journal_path(uid);
+        let size = self.storage.append(&path, &line)?;
+        if size > MAX_JOURNAL_BYTES {
+            self.storage.save_atomic(&path, &line)?;
+        }
+
+        Ok(draft)
+    }
+
+    /// 最新の下書きを取得
+    pub fn latest(&self, uid: &str) -> Result<Option<Draft>, StorageError> {
+        let path = self.journal_path(uid);
+        if !self.storage.exists(&path) {
+            return Ok(None);
+        }
+        let journal = self.storage.load(&path)?;
+        Ok(parse_latest(&journal))
+    }
+
+    /// 下書きのあるメモの最新の下書き一覧
+    pub fn list(&self) -> Result<Vec<Draft>, StorageError> {
+        let files = self.storage.list_files(&self.root, JOURNAL_EXTENSION)?;
+        Ok(files
+            .iter()
+            .filter_map(|path| self.storage.load(path).ok())
+            .filter_map(|journal| parse_latest(&journal))
+            .collect())
+    }
+
+    /// 下書きを削除（保存が完了したとき）
+    pub fn clear(&self, uid: &str) -> Result<(), StorageError> {
+        let path = self.journal_path(uid);
+        if self.storage.exists(&path) {
+            self.storage.delete(&path)?;
+        }
+        Ok(())
+    }
+}
+
+/// 最後の完全な行を下書きとして読み込む（書き込み途中の行は無視）
+fn parse_latest(journal: &str) -> Option<Draft> {
+    journal
+        .lines()
+        .rev()
+        .find_map(|line| serde_json::from_str::<Draft>(line).ok())
+}
+
+fn invalid_data(e: serde_json::Error) -> StorageError {
+    StorageError::Io(io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::FileStorage;
+    use tempfile::TempDir;
+
+    const UID: &str = "20260101000000";
+
+    #[test]
+    fn test_append_latest_and_clear() {
+        let temp_dir = TempDir::new().unwrap();
+        let journal = DraftJournal::new(temp_dir.path().to_path_buf(), Arc::new(FileStorage::new()));
+
+        journal.append(UID, "# 下書き").unwrap();
+        journal.append(UID, "# 下書き\n\n続き").unwrap();
+
+        // クラッシュで途中まで書かれた行は無視する
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(journal.journal_path(UID))
+            .and_then(|mut f| std::io::Write::write_all(&mut f, b"{\"uid\":\"2026"))
+            .unwrap();
+
+        let latest = journal.latest(UID).unwrap().unwrap();
+        assert_eq!(latest.content, "# 下書き\n\n続き");
+        assert_eq!(journal.list().unwrap().len(), 1);
+
+        journal.clear(UID).unwrap();
+        assert!(journal.latest(UID).unwrap().is_none());
+        assert!(journal.list().unwrap().is_empty());
+    }
+}
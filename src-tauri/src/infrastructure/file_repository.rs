@@ -41,13 +41,20 @@
 //! - ノートファイルの外部編集は避ける
 //! - 大規模コレクション（1000+ノート）ではキャッシュウォームアップを検討
 
-use crate::domain::Note;
-use crate::infrastructure::GalleryNote;
+use crate::domain::{
+    extract_wiki_links, folder_of, is_in_folder, title_sort_key, Note,
+    NoteSortOrder, TimestampZone, TrashItem,
+};
+use crate::infrastructure::{list_note_files, GalleryNote, HeadingFilenameStrategy, TrashStore};
 use crate::services::SettingsService;
-use crate::traits::{FilenameStrategy, NoteRepository, NoteListItem, RepositoryError, Storage};
+use crate::traits::{
+    FilenameStrategy, NoteFilter, NoteListItem, NoteRepository, Page, PageCursor, RepositoryError,
+    Storage,
+};
 use crate::commands::gallery::{generate_preview, PREVIEW_LENGTH};
-use parking_lot::RwLock;
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use parking_lot::{Mutex, RwLock};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -84,6 +91,12 @@ pub struct FileNoteRepository {
     list_cache: RwLock<Vec<NoteListItem>>,
     /// リストキャッシュが無効（再構築が必要）かどうか
     list_cache_dirty: AtomicBool,
+    /// 手動ソート順（メモリ上のみ、永続化しない）
+    manual_order: RwLock<Vec<String>>,
+    /// ゴミ箱
+    trash: TrashStore,
+    /// 保存の書き込みロック（`save_checked` の版の確認と保存の間に別の保存を挟まない）
+    write_lock: Mutex<()>,
 }
 
 impl FileNoteRepository {
@@ -93,12 +106,15 @@ impl FileNoteRepository {
         settings_service: Arc<SettingsService>,
     ) -> Self {
         Self {
+            trash: TrashStore::new(storage.clone()),
             storage,
             filename_strategy,
             base_dir_source: BaseDirSource::Settings(settings_service),
             path_cache: RwLock::new(HashMap::new()),
             list_cache: RwLock::new(Vec::new()),
             list_cache_dirty: AtomicBool::new(true), // 初回は再構築が必要
+            manual_order: RwLock::new(Vec::new()),
+            write_lock: Mutex::new(()),
         }
     }
 
@@ -110,12 +126,15 @@ impl FileNoteRepository {
         base_dir: PathBuf,
     ) -> Self {
         Self {
+            trash: TrashStore::new(storage.clone()),
             storage,
             filename_strategy,
             base_dir_source: BaseDirSource::Fixed(base_dir),
             path_cache: RwLock::new(HashMap::new()),
             list_cache: RwLock::new(Vec::new()),
             list_cache_dirty: AtomicBool::new(true),
+            manual_order: RwLock::new(Vec::new()),
+            write_lock: Mutex::new(()),
         }
     }
 
@@ -128,9 +147,18 @@ impl FileNoteRepository {
         }
     }
 
+    /// front matter の日時を書くタイムゾーン（設定から動的に）
+    fn timestamp_zone(&self) -> TimestampZone {
+        match &self.base_dir_source {
+            BaseDirSource::Settings(settings) => settings.get_arc().timestamps.zone,
+            #[cfg(test)]
+            BaseDirSource::Fixed(_) => TimestampZone::default(),
+        }
+    }
+
     /// キャッシュを再構築
     pub fn rebuild_cache(&self) -> Result<(), RepositoryError> {
-        let files = self.storage.list_files(&self.base_dir(), "md")?;
+        let files = list_note_files(self.storage.as_ref(), &self.base_dir())?;
         let mut cache = self.path_cache.write();
         cache.clear();
 
@@ -171,6 +199,10 @@ impl FileNoteRepository {
             title,
             path: path.to_path_buf(),
             updated_at: note.metadata.updated_at,
+            pinned: note.metadata.pinned,
+            favorite: note.metadata.favorite,
+            folder: folder_of(&self.base_dir(), path),
+            encrypted: note.metadata.encrypted,
         };
 
         let mut cache = self.list_cache.write();
@@ -182,8 +214,8 @@ impl FileNoteRepository {
             cache.push(new_item);
         }
 
-        // 更新日時でソート（新しい順）
-        cache.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        // ピン留め → 更新日時（新しい順）でソート
+        cache.sort_by(|a, b| b.pinned.cmp(&a.pinned).then(b.updated_at.cmp(&a.updated_at)));
     }
 
     /// リストキャッシュから特定アイテムを削除
@@ -192,16 +224,131 @@ impl FileNoteRepository {
         cache.retain(|item| item.uid != uid);
     }
 
-    /// 既存ファイルパスの一覧を取得
-    fn get_existing_files(&self) -> Vec<PathBuf> {
-        self.storage
-            .list_files(&self.base_dir(), "md")
-            .unwrap_or_default()
+    /// ファイル名生成戦略で `dir` 内の新しいパスを生成
+    ///
+    /// 衝突はディレクトリを一覧せずに存在確認で判定する（大文字小文字を区別しない
+    /// ファイルシステムでは、大文字小文字だけが異なる既存ファイルも使用済みになる）。
+    /// `own_path` はファイル名を付け直すノート自身のパスで、使用済みとはみなさない。
+    fn generate_path(&self, note: &Note, dir: &Path, own_path: Option<&Path>) -> PathBuf {
+        let is_taken = |name: &str| {
+            let path = dir.join(format!("{}.md", name));
+            let is_own = own_path.is_some_and(|own| {
+                own.to_string_lossy().to_lowercase() == path.to_string_lossy().to_lowercase()
+            });
+            !is_own && self.storage.exists(&path)
+        };
+        let filename = self.filename_strategy.generate(note, &is_taken);
+        dir.join(format!("{}.md", filename))
     }
-}
 
-impl NoteRepository for FileNoteRepository {
-    fn save(&self, note: &Note) -> Result<PathBuf, RepositoryError> {
+    /// 全ノートを読み込み、絞り込み条件とソートを適用する
+    fn collect_sorted(
+        &self,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
+    ) -> Result<Vec<SortedEntry>, RepositoryError> {
+        let base_dir = self.base_dir();
+        let files = list_note_files(self.storage.as_ref(), &base_dir)?;
+        let mut loaded = Vec::new();
+
+        for path in files {
+            if let Ok(content) = self.storage.load(&path) {
+                if let Ok(note) = Note::from_file_content(&content) {
+                    loaded.push((note, path, content.len() as u64));
+                }
+            }
+        }
+
+        // バックリンク数（リンク先タイトル → リンク元UIDの集合）
+        let mut backlinks: HashMap<String, HashSet<String>> = HashMap::new();
+        if sort == NoteSortOrder::BacklinkCount {
+            for (note, _, _) in &loaded {
+                for link in extract_wiki_links(&note.content) {
+                    backlinks
+                        .entry(link.title.to_lowercase())
+                        .or_default()
+                        .insert(note.metadata.uid.clone());
+                }
+            }
+        }
+
+        let manual_order = self.manual_order.read().clone();
+        let mut entries = Vec::new();
+
+        for (note, path, file_size) in loaded {
+            let title = note
+                .extract_heading()
+                .unwrap_or_else(|| note.metadata.uid.clone());
+
+            // タグを抽出（フロントマター + ハッシュタグ）
+            let tags = note.all_tags();
+
+            // タグフィルタを適用
+            if let Some(tag) = filter.tag {
+                if !tags.iter().any(|t| t == tag) {
+                    continue;
+                }
+            }
+
+            // フォルダフィルタを適用（サブフォルダを含む）
+            let folder = folder_of(&base_dir, &path);
+            if filter.folder.is_some_and(|f| !is_in_folder(&folder, f)) {
+                continue;
+            }
+
+            let sort_key = match sort {
+                NoteSortOrder::UpdatedAt => format_sort_key(&note.metadata.updated_at),
+                NoteSortOrder::CreatedAt => format_sort_key(&note.metadata.created_at),
+                NoteSortOrder::Title => title_sort_key(&title),
+                NoteSortOrder::BacklinkCount => numeric_sort_key(
+                    backlinks
+                        .get(&title.to_lowercase())
+                        .map_or(0, |sources| sources.len() as u64),
+                ),
+                NoteSortOrder::WordCount => numeric_sort_key(note.word_count() as u64),
+                NoteSortOrder::FileSize => numeric_sort_key(file_size),
+                NoteSortOrder::Manual => numeric_sort_key(
+                    manual_order
+                        .iter()
+                        .position(|uid| *uid == note.metadata.uid)
+                        .map_or(i32::MAX as u64, |p| p as u64),
+                ),
+            };
+
+            // プレビュー生成（暗号化ノートは本文を表示しない）
+            let preview = if note.metadata.encrypted {
+                String::new()
+            } else {
+                generate_preview(&note.content, PREVIEW_LENGTH)
+            };
+
+            entries.push(SortedEntry {
+                note: GalleryNote {
+                    uid: note.metadata.uid,
+                    title,
+                    preview,
+                    tags,
+                    created_at: note.metadata.created_at,
+                    updated_at: note.metadata.updated_at,
+                    pinned: note.metadata.pinned,
+                    favorite: note.metadata.favorite,
+                    folder,
+                    encrypted: note.metadata.encrypted,
+                },
+                path,
+                sort_key,
+            });
+        }
+
+        // ソート（ピン留めが先頭、同一値は uid 降順）
+        let ascending = sort.is_ascending();
+        entries.sort_by(|a, b| compare_positions(&a.position(), &b.position(), ascending));
+
+        Ok(entries)
+    }
+
+    /// メモを保存（書き込みロックは呼び出し側で取得済み）
+    fn save_locked(&self, note: &Note) -> Result<PathBuf, RepositoryError> {
         // 既存のパスがあればそれを使用、なければ新規生成
         let path = {
             let cache = self.path_cache.read();
@@ -210,16 +357,11 @@ impl NoteRepository for FileNoteRepository {
 
         let path = match path {
             Some(existing_path) => existing_path,
-            None => {
-                let existing_files = self.get_existing_files();
-                let refs: Vec<&Path> = existing_files.iter().map(|p| p.as_path()).collect();
-                let filename = self.filename_strategy.generate(note, &refs);
-                self.base_dir().join(format!("{}.md", filename))
-            }
+            None => self.generate_path(note, &self.base_dir(), None),
         };
 
         // ファイルに保存
-        let content = note.to_file_content();
+        let content = note.to_file_content_in(self.timestamp_zone());
         self.storage.save_atomic(&path, &content)?;
 
         // パスキャッシュを更新
@@ -236,6 +378,23 @@ impl NoteRepository for FileNoteRepository {
 
         Ok(path)
     }
+}
+
+impl NoteRepository for FileNoteRepository {
+    fn save(&self, note: &Note) -> Result<PathBuf, RepositoryError> {
+        let _guard = self.write_lock.lock();
+        self.save_locked(note)
+    }
+
+    fn save_verified(
+        &self,
+        note: &Note,
+        verify: &dyn Fn() -> Result<(), RepositoryError>,
+    ) -> Result<PathBuf, RepositoryError> {
+        let _guard = self.write_lock.lock();
+        verify()?;
+        self.save_locked(note)
+    }
 
     fn load(&self, uid: &str) -> Result<Note, RepositoryError> {
         // Try cache first
@@ -244,7 +403,7 @@ impl NoteRepository for FileNoteRepository {
             None => {
                 // Cache miss: search all files for matching UID
                 // 一時的なHashMapに収集し、最後に一度だけキャッシュを更新（競合状態を回避）
-                let files = self.storage.list_files(&self.base_dir(), "md")?;
+                let files = list_note_files(self.storage.as_ref(), &self.base_dir())?;
                 let mut found_path = None;
                 let mut discovered_entries: Vec<(String, PathBuf)> = Vec::new();
 
@@ -278,11 +437,13 @@ impl NoteRepository for FileNoteRepository {
     }
 
     fn delete(&self, uid: &str) -> Result<(), RepositoryError> {
+        let _guard = self.write_lock.lock();
         let path = self
             .get_path(uid)
             .ok_or_else(|| RepositoryError::not_found(uid))?;
 
-        self.storage.delete(&path)?;
+        // ゴミ箱へ移動
+        self.trash.discard(&self.base_dir(), &path, uid)?;
 
         // パスキャッシュから削除
         {
@@ -308,7 +469,8 @@ impl NoteRepository for FileNoteRepository {
         }
 
         // キャッシュが無効または空の場合はファイルをスキャン
-        let files = self.storage.list_files(&self.base_dir(), "md")?;
+        let base_dir = self.base_dir();
+        let files = list_note_files(self.storage.as_ref(), &base_dir)?;
         let mut items = Vec::new();
         let mut cache_updates: Vec<(String, PathBuf)> = Vec::new();
 
@@ -324,6 +486,10 @@ impl NoteRepository for FileNoteRepository {
                         title,
                         path: path.clone(),
                         updated_at: note.metadata.updated_at,
+                        pinned: note.metadata.pinned,
+                        favorite: note.metadata.favorite,
+                        folder: folder_of(&base_dir, &path),
+                        encrypted: note.metadata.encrypted,
                     });
 
                     // Collect cache updates to batch
@@ -340,8 +506,8 @@ impl NoteRepository for FileNoteRepository {
             }
         }
 
-        // 更新日時でソート（新しい順）
-        items.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        // ピン留め → 更新日時（新しい順）でソート
+        items.sort_by(|a, b| b.pinned.cmp(&a.pinned).then(b.updated_at.cmp(&a.updated_at)));
 
         // リストキャッシュを更新
         self.update_list_cache(items.clone());
@@ -356,53 +522,223 @@ impl NoteRepository for FileNoteRepository {
 
     fn list_gallery(
         &self,
-        sort_by_created: bool,
-        tag_filter: Option<&str>,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
     ) -> Result<Vec<GalleryNote>, RepositoryError> {
         // FileNoteRepositoryはN+1クエリになるがフォールバック用として実装
-        let files = self.storage.list_files(&self.base_dir(), "md")?;
-        let mut items = Vec::new();
+        let entries = self.collect_sorted(sort, filter)?;
+        Ok(entries.into_iter().map(|entry| entry.note).collect())
+    }
 
-        for path in files {
-            if let Ok(content) = self.storage.load(&path) {
-                if let Ok(note) = Note::from_file_content(&content) {
-                    let title = note
-                        .extract_heading()
-                        .unwrap_or_else(|| note.metadata.uid.clone());
+    fn list_page(
+        &self,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
+        cursor: Option<&PageCursor>,
+        limit: usize,
+    ) -> Result<Page<NoteListItem>, RepositoryError> {
+        let entries = self.collect_sorted(sort, filter)?;
+        let page = paginate(entries, cursor, limit, sort.is_ascending());
+        Ok(Page {
+            items: page
+                .items
+                .into_iter()
+                .map(|entry| NoteListItem {
+                    uid: entry.note.uid,
+                    title: entry.note.title,
+                    path: entry.path,
+                    updated_at: entry.note.updated_at,
+                    pinned: entry.note.pinned,
+                    favorite: entry.note.favorite,
+                    folder: entry.note.folder,
+                    encrypted: entry.note.encrypted,
+                })
+                .collect(),
+            next_cursor: page.next_cursor,
+            total: page.total,
+        })
+    }
 
-                    // タグを抽出（フロントマター + ハッシュタグ）
-                    let tags = note.all_tags();
+    fn list_gallery_page(
+        &self,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
+        cursor: Option<&PageCursor>,
+        limit: usize,
+    ) -> Result<Page<GalleryNote>, RepositoryError> {
+        let entries = self.collect_sorted(sort, filter)?;
+        let page = paginate(entries, cursor, limit, sort.is_ascending());
+        Ok(Page {
+            items: page.items.into_iter().map(|entry| entry.note).collect(),
+            next_cursor: page.next_cursor,
+            total: page.total,
+        })
+    }
 
-                    // タグフィルタを適用
-                    if let Some(filter) = tag_filter {
-                        if !tags.iter().any(|t| t == filter) {
-                            continue;
-                        }
-                    }
+    fn list_trash(&self) -> Result<Vec<TrashItem>, RepositoryError> {
+        self.trash
+            .list(&self.base_dir())
+            .map_err(|e| RepositoryError::storage("list_trash", e))
+    }
 
-                    // プレビュー生成
-                    let preview = generate_preview(&note.content, PREVIEW_LENGTH);
+    fn restore(&self, uid: &str) -> Result<Note, RepositoryError> {
+        let _guard = self.write_lock.lock();
+        let base_dir = self.base_dir();
+        let target = self
+            .trash
+            .restore_target(&base_dir, uid, |note, dir| self.generate_path(note, dir, None))?;
 
-                    items.push(GalleryNote {
-                        uid: note.metadata.uid,
-                        title,
-                        preview,
-                        tags,
-                        created_at: note.metadata.created_at,
-                        updated_at: note.metadata.updated_at,
-                    });
-                }
-            }
+        self.path_cache.write().insert(uid.to_string(), target.path);
+        self.save_locked(&target.note)?;
+        self.invalidate_list_cache();
+
+        self.trash
+            .remove(&base_dir, uid)
+            .map_err(|e| RepositoryError::storage("trash_remove", e))?;
+
+        Ok(target.note)
+    }
+
+    fn purge_trash(&self, deleted_before: Option<DateTime<Utc>>) -> Result<usize, RepositoryError> {
+        self.trash
+            .purge(&self.base_dir(), deleted_before)
+            .map_err(|e| RepositoryError::storage("purge_trash", e))
+    }
+
+    fn relocate(&self, uid: &str) -> Result<Option<PathBuf>, RepositoryError> {
+        let _guard = self.write_lock.lock();
+        // パスキャッシュにない場合はスキャンして見つける
+        let note = self.load(uid)?;
+        let old_path = self
+            .get_path(uid)
+            .ok_or_else(|| RepositoryError::not_found(uid))?;
+
+        // 同じフォルダ内で、自分自身のファイル名とは衝突しないものとして生成
+        let dir = old_path.parent().map(Path::to_path_buf).unwrap_or_else(|| self.base_dir());
+        let new_path = self.generate_path(&note, &dir, Some(&old_path));
+
+        if new_path == old_path {
+            return Ok(None);
         }
 
-        // ソート
-        if sort_by_created {
-            items.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-        } else {
-            items.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        self.storage.rename(&old_path, &new_path)?;
+        self.path_cache.write().insert(uid.to_string(), new_path.clone());
+        self.invalidate_list_cache();
+
+        Ok(Some(new_path))
+    }
+
+    fn move_to_folder(&self, uid: &str, folder: &str) -> Result<PathBuf, RepositoryError> {
+        let _guard = self.write_lock.lock();
+        // パスキャッシュにない場合はスキャンして見つける
+        self.load(uid)?;
+        let old_path = self
+            .get_path(uid)
+            .ok_or_else(|| RepositoryError::not_found(uid))?;
+
+        let dir = self.base_dir().join(folder);
+        if old_path.parent() == Some(dir.as_path()) {
+            return Ok(old_path);
         }
 
-        Ok(items)
+        // ファイル名はそのまま、移動先で使用中の場合のみ連番を付ける
+        let stem = old_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| uid.to_string());
+        let filename = HeadingFilenameStrategy::finish(&stem, &|name| {
+            self.storage.exists(&dir.join(format!("{}.md", name)))
+        });
+        let new_path = dir.join(format!("{}.md", filename));
+
+        self.storage.rename(&old_path, &new_path)?;
+        self.path_cache.write().insert(uid.to_string(), new_path.clone());
+        self.invalidate_list_cache();
+
+        Ok(new_path)
+    }
+
+    fn set_manual_order(&self, uids: &[String]) -> Result<(), RepositoryError> {
+        *self.manual_order.write() = uids.to_vec();
+        Ok(())
+    }
+}
+
+/// ソート済みのギャラリーエントリ（メモリ上のソート用）
+struct SortedEntry {
+    note: GalleryNote,
+    path: PathBuf,
+    /// ソート値の文字列表現（数値は桁揃えして文字列比較で正しく並ぶようにする）
+    sort_key: String,
+}
+
+impl SortedEntry {
+    /// 並び順上の位置（カーソルと同じ表現）
+    fn position(&self) -> PageCursor {
+        PageCursor {
+            pinned: self.note.pinned,
+            sort_key: self.sort_key.clone(),
+            uid: self.note.uid.clone(),
+        }
+    }
+}
+
+/// 並び順の比較（ピン留め → `sort_key`（`ascending` に従う）→ `uid` 降順）
+fn compare_positions(a: &PageCursor, b: &PageCursor, ascending: bool) -> std::cmp::Ordering {
+    let by_key = if ascending {
+        a.sort_key.cmp(&b.sort_key)
+    } else {
+        b.sort_key.cmp(&a.sort_key)
+    };
+    b.pinned
+        .cmp(&a.pinned)
+        .then(by_key)
+        .then_with(|| b.uid.cmp(&a.uid))
+}
+
+/// ソートキー用の日時フォーマット（SQLiteインデックスと同じ表現）
+fn format_sort_key(dt: &DateTime<Utc>) -> String {
+    dt.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// 数値のソート値を文字列比較可能な形式に変換
+fn numeric_sort_key(value: u64) -> String {
+    format!("{:020}", value)
+}
+
+/// ソート済みエントリにキーセットページネーションを適用
+///
+/// 並び順は `compare_positions` に従う。
+fn paginate(
+    entries: Vec<SortedEntry>,
+    cursor: Option<&PageCursor>,
+    limit: usize,
+    ascending: bool,
+) -> Page<SortedEntry> {
+    let total = entries.len();
+
+    let is_after_cursor = |entry: &SortedEntry| match cursor {
+        Some(c) => compare_positions(&entry.position(), c, ascending) == std::cmp::Ordering::Greater,
+        None => true,
+    };
+
+    let mut items: Vec<SortedEntry> = entries
+        .into_iter()
+        .filter(|entry| is_after_cursor(entry))
+        .take(limit + 1)
+        .collect();
+
+    let next_cursor = if items.len() > limit {
+        items.truncate(limit);
+        items.last().map(SortedEntry::position)
+    } else {
+        None
+    };
+
+    Page {
+        items,
+        next_cursor,
+        total,
     }
 }
 
@@ -498,4 +834,31 @@ mod tests {
         assert_eq!(items4.len(), 1);
         assert_eq!(items4[0].uid, note2.metadata.uid);
     }
+
+    #[test]
+    fn test_list_gallery_page_sort_by_title() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = create_test_repo(&temp_dir);
+
+        for title in ["さくら", "カメラ", "あめ"] {
+            let mut note = Note::new();
+            note.content = format!("# {}\n\n本文", title);
+            repo.save(&note).unwrap();
+        }
+
+        let filter = NoteFilter::default();
+        let first = repo
+            .list_gallery_page(NoteSortOrder::Title, &filter, None, 2)
+            .unwrap();
+        let titles: Vec<_> = first.items.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(titles, vec!["あめ", "カメラ"]);
+        assert_eq!(first.total, 3);
+
+        let second = repo
+            .list_gallery_page(NoteSortOrder::Title, &filter, first.next_cursor.as_ref(), 2)
+            .unwrap();
+        assert_eq!(second.items.len(), 1);
+        assert_eq!(second.items[0].title, "さくら");
+        assert!(second.next_cursor.is_none());
+    }
 }
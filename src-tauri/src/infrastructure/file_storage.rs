@@ -1,14 +1,116 @@
+use crate::domain::IgnoreRules;
 use crate::traits::{Storage, StorageError};
+use parking_lot::Mutex;
+use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// 一時ファイルの拡張子
+const TEMP_EXTENSION: &str = "tmp";
+
+/// 旧形式の一時ファイルの接尾辞（固定名 `{name}.md.tmp`）
+const LEGACY_TEMP_SUFFIX: &str = ".md.tmp";
 
 /// ファイルシステムベースのストレージ実装
-pub struct FileStorage;
+///
+/// # 書き込みの耐久性
+///
+/// `save_atomic` は書き込みごとに一意な一時ファイルへ書き込み、fsync してから
+/// リネームし、最後に親ディレクトリを fsync する。電源断でも空のファイルが残らない。
+/// 同じパスへの書き込み（`save_atomic` / `append`）はパスごとのロックで直列化する。
+pub struct FileStorage {
+    /// パスごとの書き込みロック
+    write_locks: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>,
+    /// 一時ファイル名の連番
+    temp_seq: AtomicU64,
+}
 
 impl FileStorage {
     pub fn new() -> Self {
-        Self
+        Self {
+            write_locks: Mutex::new(HashMap::new()),
+            temp_seq: AtomicU64::new(0),
+        }
+    }
+
+    /// パスの書き込みロックを取得して処理を実行
+    fn with_write_lock<T>(&self, path: &Path, f: impl FnOnce() -> T) -> T {
+        let lock = self
+            .write_locks
+            .lock()
+            .entry(path.to_path_buf())
+            .or_default()
+            .clone();
+
+        let result = {
+            let _guard = lock.lock();
+            f()
+        };
+
+        // 他に待っているスレッドがなければロックを片付ける
+        let mut locks = self.write_locks.lock();
+        if Arc::strong_count(&lock) == 2 {
+            locks.remove(path);
+        }
+        result
+    }
+
+    /// 書き込みごとに一意な一時ファイルのパス（`.{name}.{pid}-{seq}.tmp`）
+    fn temp_path(&self, path: &Path) -> PathBuf {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let seq = self.temp_seq.fetch_add(1, Ordering::Relaxed);
+        path.with_file_name(format!(
+            ".{}.{}-{}.{}",
+            name,
+            std::process::id(),
+            seq,
+            TEMP_EXTENSION
+        ))
+    }
+
+    /// 書き込み途中で残ったノートの一時ファイルを削除（起動時用）
+    ///
+    /// サブディレクトリも対象にするが、ドットで始まるディレクトリ（`.git` など）と
+    /// `ignore` で除外したディレクトリは辿らない。削除した件数を返す。
+    pub fn cleanup_temp_files(&self, dir: &Path, ignore: &IgnoreRules) -> Result<usize, StorageError> {
+        if !dir.exists() {
+            return Ok(0);
+        }
+        remove_temp_files(dir, "", ignore)
+    }
+}
+
+/// `dir` 以下のノートの一時ファイルを削除（`prefix` は走査ルートからの相対パス）
+fn remove_temp_files(dir: &Path, prefix: &str, ignore: &IgnoreRules) -> Result<usize, StorageError> {
+    let mut removed = 0;
+    for entry in fs::read_dir(dir)?.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = entry.path();
+
+        if file_type.is_dir() {
+            let relative = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            if !ignore.is_ignored(&relative, true) {
+                removed += remove_temp_files(&path, &relative, ignore)?;
+            }
+        } else if file_type.is_file() && is_temp_file(&name) {
+            fs::remove_file(&path)?;
+            removed += 1;
+        }
     }
+    Ok(removed)
 }
 
 impl Default for FileStorage {
@@ -17,8 +119,48 @@ impl Default for FileStorage {
     }
 }
 
+/// ノートの `save_atomic` の一時ファイルか（`.{name}.md.{pid}-{seq}.tmp` と旧形式の `{name}.md.tmp`）
+///
+/// 同期ツールの作業中のファイル（Syncthing の `.syncthing.{name}.tmp` など）は含めない。
+fn is_temp_file(name: &str) -> bool {
+    if !name.starts_with('.') {
+        return name.len() > LEGACY_TEMP_SUFFIX.len() && name.ends_with(LEGACY_TEMP_SUFFIX);
+    }
+
+    let Some(rest) = name
+        .strip_prefix('.')
+        .and_then(|rest| rest.strip_suffix(&format!(".{}", TEMP_EXTENSION)))
+    else {
+        return false;
+    };
+    let Some((file, unique)) = rest.rsplit_once('.') else {
+        return false;
+    };
+    let Some((pid, seq)) = unique.split_once('-') else {
+        return false;
+    };
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    file.len() > ".md".len() && file.ends_with(".md") && is_number(pid) && is_number(seq)
+}
+
+/// ディレクトリエントリの変更（リネーム）を永続化
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    fs::File::open(dir)?.sync_all()
+}
+
+/// Windows ではディレクトリを開いて fsync できないため、リネームの永続化は OS に任せる
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
 impl Storage for FileStorage {
     fn save_atomic(&self, path: &Path, content: &str) -> Result<(), StorageError> {
+        self.save_atomic_bytes(path, content.as_bytes())
+    }
+
+    fn save_atomic_bytes(&self, path: &Path, content: &[u8]) -> Result<(), StorageError> {
         // 親ディレクトリを作成
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).map_err(|_| {
@@ -26,14 +168,32 @@ impl Storage for FileStorage {
             })?;
         }
 
-        // 一時ファイルに書き込み
-        let temp_path = path.with_extension("md.tmp");
-        fs::write(&temp_path, content)?;
+        self.with_write_lock(path, || {
+            // 一意な一時ファイルに書き込み、内容をディスクに反映
+            let temp_path = self.temp_path(path);
+            let write = || -> std::io::Result<()> {
+                let mut file = fs::File::create(&temp_path)?;
+                file.write_all(content)?;
+                file.sync_all()
+            };
+            if let Err(e) = write() {
+                let _ = fs::remove_file(&temp_path);
+                return Err(e.into());
+            }
+
+            // アトミックにリネーム
+            if let Err(e) = fs::rename(&temp_path, path) {
+                let _ = fs::remove_file(&temp_path);
+                return Err(e.into());
+            }
 
-        // アトミックにリネーム
-        fs::rename(&temp_path, path)?;
+            // リネームを永続化
+            if let Some(parent) = path.parent() {
+                sync_dir(parent)?;
+            }
 
-        Ok(())
+            Ok(())
+        })
     }
 
     fn load(&self, path: &Path) -> Result<String, StorageError> {
@@ -43,6 +203,30 @@ impl Storage for FileStorage {
         fs::read_to_string(path).map_err(StorageError::from)
     }
 
+    fn load_bytes(&self, path: &Path) -> Result<Vec<u8>, StorageError> {
+        if !path.exists() {
+            return Err(StorageError::NotFound(path.to_path_buf()));
+        }
+        fs::read(path).map_err(StorageError::from)
+    }
+
+    fn append(&self, path: &Path, content: &str) -> Result<u64, StorageError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|_| {
+                StorageError::CreateDirFailed(parent.to_path_buf())
+            })?;
+        }
+
+        self.with_write_lock(path, || {
+            let mut file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            file.write_all(content.as_bytes())?;
+            Ok(file.metadata()?.len())
+        })
+    }
+
     fn delete(&self, path: &Path) -> Result<(), StorageError> {
         if !path.exists() {
             return Err(StorageError::NotFound(path.to_path_buf()));
@@ -50,6 +234,18 @@ impl Storage for FileStorage {
         fs::remove_file(path).map_err(StorageError::from)
     }
 
+    fn rename(&self, from: &Path, to: &Path) -> Result<(), StorageError> {
+        if !from.exists() {
+            return Err(StorageError::NotFound(from.to_path_buf()));
+        }
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).map_err(|_| {
+                StorageError::CreateDirFailed(parent.to_path_buf())
+            })?;
+        }
+        fs::rename(from, to).map_err(StorageError::from)
+    }
+
     fn exists(&self, path: &Path) -> bool {
         path.exists()
     }
@@ -75,6 +271,68 @@ impl Storage for FileStorage {
 
         Ok(files)
     }
+
+    fn list_files_recursive(
+        &self,
+        dir: &Path,
+        extension: &str,
+        ignore: &IgnoreRules,
+    ) -> Result<Vec<PathBuf>, StorageError> {
+        let mut files = Vec::new();
+        if dir.exists() {
+            collect_files(dir, "", Some(extension), ignore, &mut files)?;
+        }
+        Ok(files)
+    }
+
+    fn list_all_files_recursive(
+        &self,
+        dir: &Path,
+        ignore: &IgnoreRules,
+    ) -> Result<Vec<PathBuf>, StorageError> {
+        let mut files = Vec::new();
+        if dir.exists() {
+            collect_files(dir, "", None, ignore, &mut files)?;
+        }
+        Ok(files)
+    }
+}
+
+/// `dir` 以下のファイルを再帰的に収集（`prefix` は走査ルートからの相対パス）
+///
+/// `extension` が `None` の場合はすべてのファイルを収集する。
+/// シンボリックリンクのディレクトリは循環を避けるため辿らない。
+fn collect_files(
+    dir: &Path,
+    prefix: &str,
+    extension: Option<&str>,
+    ignore: &IgnoreRules,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), StorageError> {
+    for entry in fs::read_dir(dir)?.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let name = entry.file_name().to_string_lossy().to_string();
+        let relative = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let path = entry.path();
+
+        if file_type.is_dir() {
+            if !ignore.is_ignored(&relative, true) {
+                collect_files(&path, &relative, extension, ignore, files)?;
+            }
+        } else if extension.is_none_or(|extension| path.extension().is_some_and(|ext| ext == extension))
+            && path.is_file()
+            && !ignore.is_ignored(&relative, false)
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -118,4 +376,90 @@ mod tests {
         let files = storage.list_files(temp_dir.path(), "md").unwrap();
         assert_eq!(files.len(), 2);
     }
+
+    #[test]
+    fn test_list_files_recursive() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new();
+        let root = temp_dir.path();
+
+        for dir in ["work/2026", ".git", ".trash", "attachments"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in ["a.md", "work/b.md", "work/2026/c.md", ".git/d.md", ".trash/e.md", "attachments/f.md"] {
+            fs::write(root.join(file), "content").unwrap();
+        }
+
+        let ignore = IgnoreRules::parse("attachments/");
+        let mut files: Vec<PathBuf> = storage
+            .list_files_recursive(root, "md", &ignore)
+            .unwrap()
+            .into_iter()
+            .map(|path| path.strip_prefix(root).unwrap().to_path_buf())
+            .collect();
+        files.sort();
+
+        assert_eq!(
+            files,
+            [
+                PathBuf::from("a.md"),
+                PathBuf::from("work").join("2026").join("c.md"),
+                PathBuf::from("work").join("b.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_concurrent_saves_of_same_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = Arc::new(FileStorage::new());
+        let path = temp_dir.path().join("test.md");
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let storage = storage.clone();
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let content = format!("{}", i).repeat(10_000);
+                    storage.save_atomic(&path, &content).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // どれか1つの書き込みが完全な形で残り、一時ファイルは残らない
+        let content = storage.load(&path).unwrap();
+        assert_eq!(content.len(), 10_000);
+        assert!(content.chars().all(|c| c == content.chars().next().unwrap()));
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+        assert!(storage.write_locks.lock().is_empty());
+    }
+
+    #[test]
+    fn test_cleanup_temp_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new();
+
+        fs::write(temp_dir.path().join("note.md"), "content").unwrap();
+        fs::write(temp_dir.path().join("note.md.tmp"), "").unwrap();
+        fs::create_dir(temp_dir.path().join("sub")).unwrap();
+        fs::write(temp_dir.path().join("sub").join(".note.md.123-0.tmp"), "").unwrap();
+        // 同期ツールの作業中のファイル・ドットで始まるディレクトリ・ノート以外の一時ファイルは残す
+        fs::write(temp_dir.path().join(".syncthing.note.md.tmp"), "").unwrap();
+        fs::write(temp_dir.path().join(".config.toml.123-1.tmp"), "").unwrap();
+        fs::create_dir(temp_dir.path().join(".git")).unwrap();
+        fs::write(temp_dir.path().join(".git").join(".note.md.123-2.tmp"), "").unwrap();
+        fs::create_dir(temp_dir.path().join("archive")).unwrap();
+        fs::write(temp_dir.path().join("archive").join("old.md.tmp"), "").unwrap();
+
+        let ignore = IgnoreRules::parse("archive/");
+        assert_eq!(storage.cleanup_temp_files(temp_dir.path(), &ignore).unwrap(), 2);
+        assert!(temp_dir.path().join("note.md").exists());
+        assert!(temp_dir.path().join(".syncthing.note.md.tmp").exists());
+        assert!(temp_dir.path().join(".config.toml.123-1.tmp").exists());
+        assert!(temp_dir.path().join(".git").join(".note.md.123-2.tmp").exists());
+        assert!(temp_dir.path().join("archive").join("old.md.tmp").exists());
+    }
 }
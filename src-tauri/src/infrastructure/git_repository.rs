@@ -0,0 +1,286 @@
+//! git コマンドによる保存ディレクトリのコミット・履歴の取得
+//!
+//! libgit2 には依存せず、インストール済みの `git` を子プロセスとして実行する。
+//! 保存ディレクトリが既存のリポジトリの中にある場合（手動で管理している場合など）は
+//! そのリポジトリを使い、操作は保存ディレクトリ配下のパスに限定する。
+
+use crate::domain::{ChangeKind, VaultChange, VaultCommit, VersioningError};
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// 新しく作成したリポジトリの `.gitignore`
+const DEFAULT_GITIGNORE: &str = "# kaku\n.trash/\n.*.tmp\n*.md.tmp\n";
+
+/// 作者が設定されていない環境で使う名前とメールアドレス
+const FALLBACK_AUTHOR: (&str, &str) = ("kaku", "kaku@localhost");
+
+/// 保存ディレクトリ配下からゴミ箱を除くパス指定（既存のリポジトリで `.gitignore` がない場合のため）
+const PATHSPEC: [&str; 2] = [".", ":(exclude).trash"];
+
+/// ログの区切り（レコード・フィールド）
+const RECORD_SEPARATOR: char = '\x1e';
+const FIELD_SEPARATOR: char = '\x1f';
+
+/// git リポジトリとしての保存ディレクトリ
+pub struct GitRepository {
+    dir: PathBuf,
+}
+
+impl GitRepository {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// git コマンドを実行できるか
+    pub fn is_git_available() -> bool {
+        Command::new("git")
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| output.status.success())
+    }
+
+    /// 保存ディレクトリがリポジトリ（の作業ツリー）の中にあるか
+    pub fn is_repository(&self) -> bool {
+        self.dir.is_dir()
+            && self
+                .git(&["rev-parse", "--is-inside-work-tree"])
+                .is_ok_and(|stdout| stdout.trim() == "true")
+    }
+
+    /// リポジトリでなければ作成する（`.gitignore` でゴミ箱と一時ファイルを除外）
+    ///
+    /// 新しく作成した場合は `true` を返す。
+    pub fn ensure_initialized(&self) -> Result<bool, VersioningError> {
+        if self.is_repository() {
+            return Ok(false);
+        }
+        std::fs::create_dir_all(&self.dir)?;
+        self.git(&["init", "--quiet"])?;
+
+        let gitignore = self.dir.join(".gitignore");
+        if !gitignore.exists() {
+            std::fs::write(&gitignore, DEFAULT_GITIGNORE)?;
+        }
+        Ok(true)
+    }
+
+    /// 未コミットの変更をステージして一覧を返す
+    pub fn stage_changes(&self) -> Result<Vec<VaultChange>, VersioningError> {
+        self.git(&["add", "--all", "--", PATHSPEC[0], PATHSPEC[1]])?;
+        let stdout = self.git(&[
+            "diff",
+            "--cached",
+            "--name-status",
+            "--find-renames",
+            "--relative",
+            "-z",
+            "--",
+            PATHSPEC[0],
+            PATHSPEC[1],
+        ])?;
+        Ok(parse_name_status(&stdout))
+    }
+
+    /// ステージした変更をコミット（保存ディレクトリ配下のみ）
+    pub fn commit(&self, message: &str) -> Result<VaultCommit, VersioningError> {
+        let mut args: Vec<String> = Vec::new();
+        if self.git(&["config", "user.email"]).is_err() {
+            let (name, email) = FALLBACK_AUTHOR;
+            args.extend(["-c".to_string(), format!("user.name={}", name)]);
+            args.extend(["-c".to_string(), format!("user.email={}", email)]);
+        }
+        args.extend(
+            ["-c", "commit.gpgsign=false", "commit", "--quiet", "--no-verify", "-m", message, "--"]
+                .into_iter()
+                .chain(PATHSPEC)
+                .map(String::from),
+        );
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.git(&args)?;
+
+        self.last_commit()?
+            .ok_or_else(|| VersioningError::CommitNotFound("HEAD".to_string()))
+    }
+
+    /// 保存ディレクトリ配下の最新のコミット（まだコミットがなければ `None`）
+    pub fn last_commit(&self) -> Result<Option<VaultCommit>, VersioningError> {
+        if self.git(&["rev-parse", "--verify", "--quiet", "HEAD"]).is_err() {
+            return Ok(None);
+        }
+        let stdout = self.git(&["log", "-1", &log_format(), "--", "."])?;
+        Ok(parse_log(&stdout).into_iter().next())
+    }
+
+    /// ファイルの履歴（新しい順、名前の変更を追跡）
+    ///
+    /// 各コミットの `path` はその時点のファイルのパス。
+    pub fn file_history(&self, path: &str) -> Result<Vec<VaultCommit>, VersioningError> {
+        if self.git(&["rev-parse", "--verify", "--quiet", "HEAD"]).is_err() {
+            return Ok(Vec::new());
+        }
+        let stdout = self.git(&[
+            "log",
+            "--follow",
+            "--name-only",
+            "--relative",
+            &log_format(),
+            "--",
+            path,
+        ])?;
+        Ok(parse_log(&stdout))
+    }
+
+    /// コミット時点のファイルの内容
+    pub fn file_at(&self, commit_id: &str, path: &str) -> Result<String, VersioningError> {
+        self.git(&["show", &format!("{}:./{}", commit_id, path)])
+            .map_err(|_| VersioningError::CommitNotFound(commit_id.to_string()))
+    }
+
+    /// 保存ディレクトリからの相対パス（`/` 区切り、保存ディレクトリ外なら `None`）
+    pub fn relative_path(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.dir).ok()?;
+        let parts: Vec<&str> = relative.iter().map(|part| part.to_str()).collect::<Option<_>>()?;
+        Some(parts.join("/"))
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String, VersioningError> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            // 日本語のファイル名を引用符とエスケープなしで出力させる
+            .args(["-c", "core.quotePath=false"])
+            .args(args)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .output()
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => VersioningError::GitUnavailable,
+                _ => VersioningError::Io(e),
+            })?;
+        check_output(args, output)
+    }
+}
+
+fn check_output(args: &[&str], output: Output) -> Result<String, VersioningError> {
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+    let command = args
+        .iter()
+        .find(|arg| !arg.starts_with('-') && !arg.contains('='))
+        .copied()
+        .unwrap_or_default()
+        .to_string();
+    Err(VersioningError::Command {
+        command,
+        message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    })
+}
+
+fn log_format() -> String {
+    format!(
+        "--format={}%H{}%cI{}%s",
+        RECORD_SEPARATOR, FIELD_SEPARATOR, FIELD_SEPARATOR
+    )
+}
+
+/// `git log` の出力をパース（`--name-only` のパスがあれば `path` に入れる）
+fn parse_log(stdout: &str) -> Vec<VaultCommit> {
+    stdout
+        .split(RECORD_SEPARATOR)
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let mut fields = lines.next()?.split(FIELD_SEPARATOR);
+            let id = fields.next()?.to_string();
+            let committed_at = DateTime::parse_from_rfc3339(fields.next()?).ok()?;
+            let summary = fields.next().unwrap_or_default().to_string();
+            let path = lines
+                .map(str::trim)
+                .rfind(|line| !line.is_empty())
+                .map(String::from);
+            Some(VaultCommit {
+                id,
+                summary,
+                committed_at: committed_at.with_timezone(&Utc),
+                path,
+            })
+        })
+        .collect()
+}
+
+/// `git diff --name-status -z` の出力をパース
+fn parse_name_status(stdout: &str) -> Vec<VaultChange> {
+    let mut fields = stdout.split('\0').filter(|field| !field.is_empty());
+    let mut changes = Vec::new();
+    while let Some(status) = fields.next() {
+        let kind = match status.chars().next() {
+            Some('A') => ChangeKind::Added,
+            Some('D') => ChangeKind::Deleted,
+            Some('R') => ChangeKind::Renamed,
+            _ => ChangeKind::Modified,
+        };
+        let previous_path = match kind {
+            ChangeKind::Renamed => fields.next().map(String::from),
+            _ => None,
+        };
+        let Some(path) = fields.next() else {
+            break;
+        };
+        changes.push(VaultChange {
+            kind,
+            path: path.to_string(),
+            previous_path,
+        });
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_commit_and_file_history() {
+        if !GitRepository::is_git_available() {
+            return;
+        }
+        let temp_dir = TempDir::new().unwrap();
+        let repo = GitRepository::new(temp_dir.path().join("notes"));
+        assert!(repo.ensure_initialized().unwrap());
+        assert!(repo.is_repository());
+        assert!(!repo.ensure_initialized().unwrap());
+
+        std::fs::write(temp_dir.path().join("notes/a.md"), "first").unwrap();
+        let changes = repo.stage_changes().unwrap();
+        assert!(changes.contains(&VaultChange {
+            kind: ChangeKind::Added,
+            path: "a.md".to_string(),
+            previous_path: None,
+        }));
+        let first = repo.commit("Add a").unwrap();
+        assert_eq!(first.summary, "Add a");
+
+        std::fs::write(temp_dir.path().join("notes/a.md"), "second").unwrap();
+        repo.stage_changes().unwrap();
+        repo.commit("Edit a").unwrap();
+        assert!(repo.stage_changes().unwrap().is_empty());
+
+        let history = repo.file_history("a.md").unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].id, first.id);
+        assert_eq!(history[1].path.as_deref(), Some("a.md"));
+        assert_eq!(repo.file_at(&first.id, "a.md").unwrap(), "first");
+    }
+
+    #[test]
+    fn test_parse_name_status() {
+        let stdout = "M\0a.md\0R087\0old.md\0new.md\0D\0b.md\0";
+        let changes = parse_name_status(stdout);
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[2].kind, ChangeKind::Deleted);
+        assert_eq!(changes[1].kind, ChangeKind::Renamed);
+        assert_eq!(changes[1].previous_path.as_deref(), Some("old.md"));
+        assert_eq!(changes[1].path, "new.md");
+    }
+}
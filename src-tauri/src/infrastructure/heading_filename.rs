@@ -1,6 +1,5 @@
 use crate::domain::Note;
 use crate::traits::FilenameStrategy;
-use std::path::Path;
 
 /// H1/H2見出しベースのファイル名生成戦略
 pub struct HeadingFilenameStrategy;
@@ -31,23 +30,24 @@ impl HeadingFilenameStrategy {
         }
     }
 
+    /// 候補名をファイル名として確定（サニタイズ・切り詰め・重複回避）
+    ///
+    /// 他の戦略も同じ規則でファイル名を確定させるために使う。
+    pub(crate) fn finish(name: &str, is_taken: &dyn Fn(&str) -> bool) -> String {
+        let base_name = Self::truncate(&Self::sanitize(name), 200);
+        Self::make_unique(&base_name, is_taken)
+    }
+
     /// 重複を避けるための連番付きファイル名を生成
-    fn make_unique(base_name: &str, existing_files: &[&Path]) -> String {
-        let existing_names: Vec<String> = existing_files
-            .iter()
-            .filter_map(|p| p.file_stem())
-            .filter_map(|s| s.to_str())
-            .map(|s| s.to_string())
-            .collect();
-
-        if !existing_names.contains(&base_name.to_string()) {
+    fn make_unique(base_name: &str, is_taken: &dyn Fn(&str) -> bool) -> String {
+        if !is_taken(base_name) {
             return base_name.to_string();
         }
 
-        // 連番を付与
+        // 連番を付与（候補ごとに1回だけ判定する）
         for i in 2..=999 {
             let candidate = format!("{}_{}", base_name, i);
-            if !existing_names.contains(&candidate) {
+            if !is_taken(&candidate) {
                 return candidate;
             }
         }
@@ -64,16 +64,12 @@ impl Default for HeadingFilenameStrategy {
 }
 
 impl FilenameStrategy for HeadingFilenameStrategy {
-    fn generate(&self, note: &Note, existing_files: &[&Path]) -> String {
-        let base_name = match note.extract_heading() {
-            Some(heading) => {
-                let sanitized = Self::sanitize(&heading);
-                Self::truncate(&sanitized, 200)
-            }
-            None => note.metadata.uid.clone(),
-        };
+    fn generate(&self, note: &Note, is_taken: &dyn Fn(&str) -> bool) -> String {
+        let base_name = note
+            .extract_heading()
+            .unwrap_or_else(|| note.metadata.uid.clone());
 
-        Self::make_unique(&base_name, existing_files)
+        Self::finish(&base_name, is_taken)
     }
 }
 
@@ -81,7 +77,6 @@ impl FilenameStrategy for HeadingFilenameStrategy {
 mod tests {
     use super::*;
     use crate::domain::Note;
-    use std::path::PathBuf;
 
     #[test]
     fn test_generate_from_h1() {
@@ -89,7 +84,7 @@ mod tests {
         let mut note = Note::new();
         note.content = "# 買い物リスト\n\nアイテム".to_string();
 
-        let filename = strategy.generate(&note, &[]);
+        let filename = strategy.generate(&note, &|_| false);
         assert_eq!(filename, "買い物リスト");
     }
 
@@ -98,7 +93,7 @@ mod tests {
         let strategy = HeadingFilenameStrategy::new();
         let note = Note::new();
 
-        let filename = strategy.generate(&note, &[]);
+        let filename = strategy.generate(&note, &|_| false);
         assert_eq!(filename, note.metadata.uid);
     }
 
@@ -118,13 +113,15 @@ mod tests {
 
     #[test]
     fn test_make_unique() {
-        let existing = vec![
-            PathBuf::from("/path/to/テスト.md"),
-            PathBuf::from("/path/to/テスト_2.md"),
-        ];
-        let refs: Vec<&Path> = existing.iter().map(|p| p.as_path()).collect();
+        let existing = ["テスト", "テスト_2"];
+        let probes = std::cell::Cell::new(0);
+        let is_taken = |name: &str| {
+            probes.set(probes.get() + 1);
+            existing.contains(&name)
+        };
 
-        let unique = HeadingFilenameStrategy::make_unique("テスト", &refs);
+        let unique = HeadingFilenameStrategy::make_unique("テスト", &is_taken);
         assert_eq!(unique, "テスト_3");
+        assert_eq!(probes.get(), 3);
     }
 }
@@ -0,0 +1,182 @@
+//! リビジョン履歴ストア
+//!
+//! ノート本文のスナップショットを設定ディレクトリ配下にコンテンツアドレスで保存する。
+//!
+//! # 構成
+//!
+//! - `history/{uid}/{blake3}.md`: スナップショット本文（同一内容は1ファイルに集約）
+//! - `history/{uid}/revisions.json`: リビジョン一覧（古い順）
+//!
+//! ファイル名に使えない文字を含む UID は `uid_file_stem` で変換した名前のディレクトリに保存する。
+
+use crate::domain::{uid_file_stem, Revision};
+use crate::traits::{Storage, StorageError};
+use chrono::{DateTime, Utc};
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// リビジョン一覧のファイル名
+const REVISIONS_FILE: &str = "revisions.json";
+
+/// ファイルベースのリビジョン履歴ストア
+pub struct HistoryStore {
+    root: PathBuf,
+    storage: Arc<dyn Storage>,
+}
+
+impl HistoryStore {
+    pub fn new(root: PathBuf, storage: Arc<dyn Storage>) -> Self {
+        Self { root, storage }
+    }
+
+    fn note_dir(&self, uid: &str) -> PathBuf {
+        self.root.join(uid_file_stem(uid))
+    }
+
+    fn object_path(&self, uid: &str, id: &str) -> PathBuf {
+        self.note_dir(uid).join(format!("{}.md", id))
+    }
+
+    fn revisions_path(&self, uid: &str) -> PathBuf {
+        self.note_dir(uid).join(REVISIONS_FILE)
+    }
+
+    /// リビジョン一覧を取得（古い順）
+    pub fn revisions(&self, uid: &str) -> Result<Vec<Revision>, StorageError> {
+        let path = self.revisions_path(uid);
+        if !self.storage.exists(&path) {
+            return Ok(Vec::new());
+        }
+        let json = self.storage.load(&path)?;
+        serde_json::from_str(&json).map_err(invalid_data)
+    }
+
+    /// 最新リビジョンを取得
+    pub fn latest(&self, uid: &str) -> Result<Option<Revision>, StorageError> {
+        Ok(self.revisions(uid)?.pop())
+    }
+
+    /// スナップショットを追加
+    ///
+    /// 最新リビジョンと同じ内容の場合は追加せず `None` を返す。
+    /// `max_revisions` を超えた古いリビジョンは削除する。
+    pub fn append(
+        &self,
+        uid: &str,
+        content: &str,
+        saved_at: DateTime<Utc>,
+        max_revisions: usize,
+    ) -> Result<Option<Revision>, StorageError> {
+        let id = blake3::hash(content.as_bytes()).to_hex().to_string();
+        let mut revisions = self.revisions(uid)?;
+
+        if revisions.last().is_some_and(|latest| latest.id == id) {
+            return Ok(None);
+        }
+
+        let object_path = self.object_path(uid, &id);
+        if !self.storage.exists(&object_path) {
+            self.storage.save_atomic(&object_path, content)?;
+        }
+
+        let revision = Revision {
+            id,
+            saved_at,
+            size: content.len() as u64,
+        };
+        revisions.push(revision.clone());
+
+        // 上限を超えた古いリビジョンを削除（他のリビジョンが参照する本文は残す）
+        let excess = revisions.len().saturating_sub(max_revisions.max(1));
+        let removed: Vec<Revision> = revisions.drain(..excess).collect();
+        for old in removed {
+            if !revisions.iter().any(|r| r.id == old.id) {
+                let path = self.object_path(uid, &old.id);
+                if self.storage.exists(&path) {
+                    self.storage.delete(&path)?;
+                }
+            }
+        }
+
+        self.save_revisions(uid, &revisions)?;
+        Ok(Some(revision))
+    }
+
+    /// リビジョンの本文を読み込み
+    pub fn load(&self, uid: &str, id: &str) -> Result<String, StorageError> {
+        let path = self.object_path(uid, id);
+        if !is_valid_id(id) || !self.revisions(uid)?.iter().any(|r| r.id == id) {
+            return Err(StorageError::NotFound(path));
+        }
+        self.storage.load(&path)
+    }
+
+    /// ノートのリビジョンをすべて削除
+    pub fn clear(&self, uid: &str) -> Result<(), StorageError> {
+        for revision in self.revisions(uid)? {
+            let path = self.object_path(uid, &revision.id);
+            if self.storage.exists(&path) {
+                self.storage.delete(&path)?;
+            }
+        }
+        let path = self.revisions_path(uid);
+        if self.storage.exists(&path) {
+            self.storage.delete(&path)?;
+        }
+        Ok(())
+    }
+
+    fn save_revisions(&self, uid: &str, revisions: &[Revision]) -> Result<(), StorageError> {
+        let json = serde_json::to_string_pretty(revisions).map_err(invalid_data)?;
+        self.storage.save_atomic(&self.revisions_path(uid), &json)
+    }
+}
+
+/// リビジョンIDの形式チェック（blake3 の16進表現、パストラバーサル防止）
+fn is_valid_id(id: &str) -> bool {
+    id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn invalid_data(e: serde_json::Error) -> StorageError {
+    StorageError::Io(io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::FileStorage;
+    use tempfile::TempDir;
+
+    const UID: &str = "20260101000000";
+
+    #[test]
+    fn test_append_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = HistoryStore::new(temp_dir.path().to_path_buf(), Arc::new(FileStorage::new()));
+
+        let first = store.append(UID, "# v1", Utc::now(), 10).unwrap().unwrap();
+        // 同じ内容は追加しない
+        assert!(store.append(UID, "# v1", Utc::now(), 10).unwrap().is_none());
+        store.append(UID, "# v2", Utc::now(), 10).unwrap().unwrap();
+
+        assert_eq!(store.revisions(UID).unwrap().len(), 2);
+        assert_eq!(store.load(UID, &first.id).unwrap(), "# v1");
+        assert!(store.load(UID, "../../etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_append_prunes_old_revisions() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = HistoryStore::new(temp_dir.path().to_path_buf(), Arc::new(FileStorage::new()));
+
+        let first = store.append(UID, "v1", Utc::now(), 2).unwrap().unwrap();
+        store.append(UID, "v2", Utc::now(), 2).unwrap();
+        store.append(UID, "v3", Utc::now(), 2).unwrap();
+
+        let revisions = store.revisions(UID).unwrap();
+        assert_eq!(revisions.len(), 2);
+        assert!(!store.object_path(UID, &first.id).exists());
+        assert!(store.load(UID, &first.id).is_err());
+    }
+}
@@ -10,7 +10,8 @@
 //! # 一貫性保証
 //!
 //! - 保存時: ファイル → SQLite の順で更新（ファイルが真のソース）
-//! - 削除時: SQLite → ファイル の順で削除
+//! - 版を確認する保存: 確認と保存を書き込みロックの中で行う
+//! - 削除時: ファイル → SQLite の順で削除（ファイルはゴミ箱へ移動、移動できなければ一覧に残す）
 //! - 同期: ファイルシステムとインデックスの整合性を定期的にチェック
 //!
 //! # API互換性
@@ -19,11 +20,23 @@
 //! 同じインターフェースを提供。既存コードの変更なしに置き換え可能。
 
 use crate::commands::gallery::{generate_preview, PREVIEW_LENGTH};
-use crate::domain::Note;
-use crate::infrastructure::sqlite_index::{compute_hash, GalleryNote, IndexedNote, SqliteIndex};
+use crate::domain::{
+    choose_primary, folder_of, DailyDirection, DailyNoteEntry, DuplicateFile, Note,
+    NoteSortOrder, TimestampZone, TrashItem,
+};
+use crate::infrastructure::sqlite_index::{
+    compute_hash, path_key, GalleryNote, IndexedNote, SqliteIndex,
+};
+use crate::infrastructure::{list_note_files, HeadingFilenameStrategy, TrashStore, VaultRoot};
 use crate::services::SettingsService;
-use crate::traits::{FilenameStrategy, NoteListItem, NoteRepository, RepositoryError, Storage};
-use log::{debug, info};
+use crate::traits::{
+    FilenameStrategy, NoteFilter, NoteListItem, NoteRepository, Page, PageCursor, RepositoryError,
+    Storage,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use log::{debug, info, warn};
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -33,6 +46,8 @@ pub struct SyncResult {
     pub added: usize,
     pub updated: usize,
     pub removed: usize,
+    /// インデックスに登録しなかった、同じ UID を持つファイルの数
+    pub duplicates: usize,
 }
 
 /// ハイブリッドリポジトリ実装
@@ -41,6 +56,10 @@ pub struct HybridRepository {
     storage: Arc<dyn Storage>,
     filename_strategy: Arc<dyn FilenameStrategy>,
     settings_service: Arc<SettingsService>,
+    root: Arc<VaultRoot>,
+    trash: TrashStore,
+    /// 保存の書き込みロック（`save_checked` の版の確認と保存の間に別の保存を挟まない）
+    write_lock: Mutex<()>,
 }
 
 impl HybridRepository {
@@ -50,25 +69,50 @@ impl HybridRepository {
         storage: Arc<dyn Storage>,
         filename_strategy: Arc<dyn FilenameStrategy>,
         settings_service: Arc<SettingsService>,
+        root: Arc<VaultRoot>,
     ) -> Self {
+        // インデックスのパスは保存ディレクトリからの相対パスで保存する
+        index.set_root(&root.get());
         Self {
             index,
+            trash: TrashStore::new(storage.clone()),
             storage,
             filename_strategy,
             settings_service,
+            root,
+            write_lock: Mutex::new(()),
         }
     }
 
-    /// 保存ディレクトリを取得
+    /// 保存ディレクトリを取得（保管庫の構築時に固定、移転時に更新される）
     fn base_dir(&self) -> PathBuf {
-        self.settings_service.storage_directory()
+        self.root.get()
     }
 
-    /// 既存ファイルパスの一覧を取得
-    fn get_existing_files(&self) -> Vec<PathBuf> {
-        self.storage
-            .list_files(&self.base_dir(), "md")
-            .unwrap_or_default()
+    /// front matter の日時を書くタイムゾーン
+    fn timestamp_zone(&self) -> TimestampZone {
+        self.settings_service.get_arc().timestamps.zone
+    }
+
+    /// ファイル名（拡張子なし）が使用済みか（ディレクトリを一覧せずに判定）
+    ///
+    /// インデックスのパスは大文字小文字を区別せずに比較するため、大文字小文字を区別しない
+    /// ファイルシステムへ移しても衝突しない。インデックスにない外部ファイルは存在確認で検出する。
+    /// `own_path` はファイル名を付け直すノート自身のパスで、使用済みとはみなさない。
+    fn is_name_taken(&self, dir: &Path, name: &str, own_path: Option<&Path>) -> bool {
+        let path = dir.join(format!("{}.md", name));
+        if own_path.is_some_and(|own| path_key(own) == path_key(&path)) {
+            return false;
+        }
+        self.storage.exists(&path) || self.index.is_path_taken(&path).unwrap_or(true)
+    }
+
+    /// ファイル名生成戦略で `dir` 内の新しいパスを生成
+    fn generate_path(&self, note: &Note, dir: &Path, own_path: Option<&Path>) -> PathBuf {
+        let filename = self
+            .filename_strategy
+            .generate(note, &|name| self.is_name_taken(dir, name, own_path));
+        dir.join(format!("{}.md", filename))
     }
 
     /// パスを解決または生成
@@ -78,11 +122,8 @@ impl HybridRepository {
             return Ok(path);
         }
 
-        // 新規生成
-        let existing_files = self.get_existing_files();
-        let refs: Vec<&Path> = existing_files.iter().map(|p| p.as_path()).collect();
-        let filename = self.filename_strategy.generate(note, &refs);
-        Ok(self.base_dir().join(format!("{}.md", filename)))
+        // 新規生成（保存ディレクトリ直下）
+        Ok(self.generate_path(note, &self.base_dir(), None))
     }
 
     /// ページネーション対応リスト取得
@@ -117,65 +158,86 @@ impl HybridRepository {
 
     /// インデックス同期チェック
     ///
-    /// ファイルシステムとインデックスの整合性を確認・修復
+    /// ファイルシステムとインデックスの整合性を確認・修復（サブフォルダを含む）
     pub fn sync_index(&self) -> Result<SyncResult, RepositoryError> {
         let base_dir = self.base_dir();
-        let files = self.storage.list_files(&base_dir, "md")?;
+        // 保存ディレクトリが変わった場合（移転・別の場所へのマウント）も相対パスはそのまま使える
+        self.index.set_root(&base_dir);
+        let files = list_note_files(self.storage.as_ref(), &base_dir)?;
 
         let added = 0;
         let mut updated = 0;
+        let mut found = HashSet::new();
+        // 読み込めなかったファイル（書き込み途中・front matter の破損など）のノートはインデックスに残す
+        let mut unreadable = HashSet::new();
 
-        // ファイルを走査してインデックスを更新
+        // ファイルを UID ごとにまとめる（同じ UID のファイルは競合コピーか複製）
+        let mut by_uid: BTreeMap<String, Vec<(PathBuf, String, Note)>> = BTreeMap::new();
         for path in files {
-            if let Ok(content) = self.storage.load(&path) {
-                if let Ok(note) = Note::from_file_content(&content) {
-                    let hash = compute_hash(&content);
-
-                    // インデックスにない or ハッシュが違う場合は更新
-                    let needs_update = self
-                        .index
-                        .needs_update(&note.metadata.uid, &hash)
-                        .unwrap_or(true);
-
-                    if needs_update {
-                        let title = note
-                            .extract_heading()
-                            .unwrap_or_else(|| note.metadata.uid.clone());
-
-                        let indexed = IndexedNote {
-                            uid: note.metadata.uid.clone(),
-                            title,
-                            content: note.content.clone(),
-                            file_path: path.clone(),
-                            content_hash: hash,
-                            created_at: note.metadata.created_at,
-                            updated_at: note.metadata.updated_at,
-                        };
-
-                        // ギャラリー用プレビューとタグを生成
-                        let preview = generate_preview(&note.content, PREVIEW_LENGTH);
-                        let tags = note.all_tags();
-
-                        self.index
-                            .upsert_note_with_gallery(&indexed, &preview, &tags)
-                            .map_err(|e| RepositoryError::storage("sync", storage_error_from_index(e)))?;
-
-                        updated += 1;
-                    }
+            let parsed = self
+                .storage
+                .load(&path)
+                .ok()
+                .and_then(|content| Note::from_file_content(&content).ok().map(|note| (content, note)));
+            match parsed {
+                Some((content, note)) => by_uid
+                    .entry(note.metadata.uid.clone())
+                    .or_default()
+                    .push((path, content, note)),
+                None => {
+                    unreadable.insert(path);
                 }
             }
         }
 
-        // インデックスにあってファイルにないものを削除
+        // UID ごとに1つのファイルを登録し、それ以外は重複として記録する
+        let mut duplicates = Vec::new();
+        for (uid, mut entries) in by_uid {
+            let indexed = self.index.get_path(&uid).ok().flatten();
+            let paths: Vec<PathBuf> = entries.iter().map(|(path, _, _)| path.clone()).collect();
+            let (path, content, note) = entries.swap_remove(choose_primary(&paths, indexed.as_deref()));
+            duplicates.extend(entries.into_iter().map(|(path, _, _)| DuplicateFile {
+                uid: uid.clone(),
+                path,
+            }));
+            found.insert(uid);
+            let hash = compute_hash(&content);
+
+            // インデックスにない or ハッシュが違う or 外部で移動された場合は更新
+            let needs_update = self
+                .index
+                .needs_update(&note.metadata.uid, &hash)
+                .unwrap_or(true)
+                || indexed.as_ref() != Some(&path);
+
+            if needs_update {
+                self.index_note(&note, &path, &content)?;
+                updated += 1;
+            }
+        }
+        for duplicate in &duplicates {
+            warn!(
+                "Duplicate uid {} in {:?} ({:?}); not indexed",
+                duplicate.uid,
+                duplicate.path,
+                duplicate.kind()
+            );
+        }
+        self.index
+            .replace_duplicates(&duplicates)
+            .map_err(|e| RepositoryError::storage("replace_duplicates", storage_error_from_index(e)))?;
+
+        // インデックスにあって保存ディレクトリで見つからなかったものを削除
         let removed = self
             .index
-            .remove_orphans(&base_dir)
+            .remove_orphans(&found, &unreadable)
             .map_err(|e| RepositoryError::storage("remove_orphans", storage_error_from_index(e)))?;
 
         Ok(SyncResult {
             added,
             updated,
             removed,
+            duplicates: duplicates.len(),
         })
     }
 
@@ -202,46 +264,82 @@ impl HybridRepository {
         Ok(())
     }
 
-    /// SQLiteインデックスへの参照を取得
-    pub fn index(&self) -> &Arc<SqliteIndex> {
-        &self.index
-    }
-}
-
-impl NoteRepository for HybridRepository {
-    fn save(&self, note: &Note) -> Result<PathBuf, RepositoryError> {
-        // 1. ファイルパスを決定
-        let path = self.resolve_or_generate_path(note)?;
-
-        // 2. ファイルに保存（アトミック）
-        let content = note.to_file_content();
-        self.storage.save_atomic(&path, &content)?;
-
-        // 3. インデックスを更新（ギャラリー情報も含む）
+    /// 保存済みのノートをインデックスに登録（ギャラリー情報も含む）
+    ///
+    /// 暗号化ノートの本文は暗号文のため、タイトルは UID とし、本文の情報は登録しない。
+    fn index_note(&self, note: &Note, path: &Path, content: &str) -> Result<(), RepositoryError> {
+        let encrypted = note.metadata.encrypted;
         let title = note
             .extract_heading()
+            .filter(|_| !encrypted)
             .unwrap_or_else(|| note.metadata.uid.clone());
 
         let indexed_note = IndexedNote {
             uid: note.metadata.uid.clone(),
             title,
             content: note.content.clone(),
-            file_path: path.clone(),
-            content_hash: compute_hash(&content),
+            file_path: path.to_path_buf(),
+            content_hash: compute_hash(content),
             created_at: note.metadata.created_at,
             updated_at: note.metadata.updated_at,
+            word_count: if encrypted { 0 } else { note.word_count() },
+            file_size: content.len() as u64,
+            pinned: note.metadata.pinned,
+            favorite: note.metadata.favorite,
+            daily_date: note.metadata.daily,
+            folder: folder_of(&self.base_dir(), path),
+            encrypted,
         };
 
-        // ギャラリー用プレビューとタグを生成
+        // ギャラリー用プレビューとタグを生成（暗号化ノートはフロントマターのタグのみ）
         let preview = generate_preview(&note.content, PREVIEW_LENGTH);
-        let tags = note.all_tags();
+        let tags = if encrypted {
+            note.metadata.tags.clone()
+        } else {
+            note.all_tags()
+        };
 
         self.index
             .upsert_note_with_gallery(&indexed_note, &preview, &tags)
-            .map_err(|e| RepositoryError::storage("index_upsert", storage_error_from_index(e)))?;
+            .map_err(|e| RepositoryError::storage("index_upsert", storage_error_from_index(e)))
+    }
+
+    /// SQLiteインデックスへの参照を取得
+    pub fn index(&self) -> &Arc<SqliteIndex> {
+        &self.index
+    }
+
+    /// メモを保存（書き込みロックは呼び出し側で取得済み）
+    fn save_locked(&self, note: &Note) -> Result<PathBuf, RepositoryError> {
+        // 1. ファイルパスを決定
+        let path = self.resolve_or_generate_path(note)?;
+
+        // 2. ファイルに保存（アトミック）
+        let content = note.to_file_content_in(self.timestamp_zone());
+        self.storage.save_atomic(&path, &content)?;
+
+        // 3. インデックスを更新（ギャラリー情報も含む）
+        self.index_note(note, &path, &content)?;
 
         Ok(path)
     }
+}
+
+impl NoteRepository for HybridRepository {
+    fn save(&self, note: &Note) -> Result<PathBuf, RepositoryError> {
+        let _guard = self.write_lock.lock();
+        self.save_locked(note)
+    }
+
+    fn save_verified(
+        &self,
+        note: &Note,
+        verify: &dyn Fn() -> Result<(), RepositoryError>,
+    ) -> Result<PathBuf, RepositoryError> {
+        let _guard = self.write_lock.lock();
+        verify()?;
+        self.save_locked(note)
+    }
 
     fn load(&self, uid: &str) -> Result<Note, RepositoryError> {
         // インデックスからパスを取得（O(1)）
@@ -259,6 +357,9 @@ impl NoteRepository for HybridRepository {
     }
 
     fn delete(&self, uid: &str) -> Result<(), RepositoryError> {
+        // ゴミ箱へ移した後に保存がファイルを作り直さないようロックする
+        let _guard = self.write_lock.lock();
+
         // 1. パスを取得
         let path = self
             .index
@@ -266,14 +367,14 @@ impl NoteRepository for HybridRepository {
             .map_err(|e| RepositoryError::storage("get_path", storage_error_from_index(e)))?
             .ok_or_else(|| RepositoryError::not_found(uid))?;
 
-        // 2. インデックスから削除
+        // 2. ゴミ箱へ移動
+        self.trash.discard(&self.base_dir(), &path, uid)?;
+
+        // 3. インデックスから削除
         self.index
             .delete_note(uid)
             .map_err(|e| RepositoryError::storage("index_delete", storage_error_from_index(e)))?;
 
-        // 3. ファイルを削除
-        self.storage.delete(&path)?;
-
         Ok(())
     }
 
@@ -288,16 +389,207 @@ impl NoteRepository for HybridRepository {
         self.index.get_path(uid).ok().flatten()
     }
 
+    fn get_list_item(&self, uid: &str) -> Result<Option<NoteListItem>, RepositoryError> {
+        self.index
+            .get_note_by_uid(uid)
+            .map_err(|e| RepositoryError::storage("get_note_by_uid", storage_error_from_index(e)))
+    }
+
     fn list_gallery(
         &self,
-        sort_by_created: bool,
-        tag_filter: Option<&str>,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
     ) -> Result<Vec<GalleryNote>, RepositoryError> {
         // SQLiteインデックスから直接取得（高速）
         self.index
-            .list_gallery_notes(sort_by_created, tag_filter)
+            .list_gallery_notes(sort, filter)
             .map_err(|e| RepositoryError::storage("list_gallery", storage_error_from_index(e)))
     }
+
+    fn list_page(
+        &self,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
+        cursor: Option<&PageCursor>,
+        limit: usize,
+    ) -> Result<Page<NoteListItem>, RepositoryError> {
+        self.index
+            .list_notes_page(sort, filter, cursor, limit)
+            .map_err(|e| RepositoryError::storage("list_page", storage_error_from_index(e)))
+    }
+
+    fn list_gallery_page(
+        &self,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
+        cursor: Option<&PageCursor>,
+        limit: usize,
+    ) -> Result<Page<GalleryNote>, RepositoryError> {
+        self.index
+            .list_gallery_page(sort, filter, cursor, limit)
+            .map_err(|e| RepositoryError::storage("list_gallery_page", storage_error_from_index(e)))
+    }
+
+    fn list_trash(&self) -> Result<Vec<TrashItem>, RepositoryError> {
+        self.trash
+            .list(&self.base_dir())
+            .map_err(|e| RepositoryError::storage("list_trash", e))
+    }
+
+    fn restore(&self, uid: &str) -> Result<Note, RepositoryError> {
+        let _guard = self.write_lock.lock();
+        let base_dir = self.base_dir();
+        let target = self
+            .trash
+            .restore_target(&base_dir, uid, |note, dir| self.generate_path(note, dir, None))?;
+
+        self.storage.save_atomic(&target.path, &target.content)?;
+        self.index_note(&target.note, &target.path, &target.content)?;
+        self.trash
+            .remove(&base_dir, uid)
+            .map_err(|e| RepositoryError::storage("trash_remove", e))?;
+
+        Ok(target.note)
+    }
+
+    fn purge_trash(&self, deleted_before: Option<DateTime<Utc>>) -> Result<usize, RepositoryError> {
+        self.trash
+            .purge(&self.base_dir(), deleted_before)
+            .map_err(|e| RepositoryError::storage("purge_trash", e))
+    }
+
+    fn set_manual_order(&self, uids: &[String]) -> Result<(), RepositoryError> {
+        self.index
+            .set_manual_order(uids)
+            .map_err(|e| RepositoryError::storage("set_manual_order", storage_error_from_index(e)))
+    }
+
+    fn relocate(&self, uid: &str) -> Result<Option<PathBuf>, RepositoryError> {
+        // 付け直し中の保存が元のパスに書き込まないよう、読み込みから再インデックスまでロックする
+        let _guard = self.write_lock.lock();
+        let old_path = self
+            .index
+            .get_path(uid)
+            .map_err(|e| RepositoryError::storage("get_path", storage_error_from_index(e)))?
+            .ok_or_else(|| RepositoryError::not_found(uid))?;
+
+        let content = self.storage.load(&old_path)?;
+        let note = Note::from_file_content(&content).map_err(|_| {
+            RepositoryError::parse("Invalid note format", Some(old_path.clone()))
+        })?;
+
+        // 同じフォルダ内で、自分自身のファイル名とは衝突しないものとして生成
+        let dir = old_path.parent().map(Path::to_path_buf).unwrap_or_else(|| self.base_dir());
+        let new_path = self.generate_path(&note, &dir, Some(&old_path));
+
+        if new_path == old_path {
+            return Ok(None);
+        }
+
+        self.storage.rename(&old_path, &new_path)?;
+        self.index_note(&note, &new_path, &content)?;
+
+        Ok(Some(new_path))
+    }
+
+    fn move_to_folder(&self, uid: &str, folder: &str) -> Result<PathBuf, RepositoryError> {
+        // 移動中の保存が元のパスに書き込まないよう、パスの取得から再インデックスまでロックする
+        let _guard = self.write_lock.lock();
+        let old_path = self
+            .index
+            .get_path(uid)
+            .map_err(|e| RepositoryError::storage("get_path", storage_error_from_index(e)))?
+            .ok_or_else(|| RepositoryError::not_found(uid))?;
+
+        let dir = self.base_dir().join(folder);
+        if old_path.parent() == Some(dir.as_path()) {
+            return Ok(old_path);
+        }
+
+        let content = self.storage.load(&old_path)?;
+        let note = Note::from_file_content(&content).map_err(|_| {
+            RepositoryError::parse("Invalid note format", Some(old_path.clone()))
+        })?;
+
+        // ファイル名はそのまま、移動先で使用中の場合のみ連番を付ける
+        let stem = old_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| uid.to_string());
+        let filename =
+            HeadingFilenameStrategy::finish(&stem, &|name| self.is_name_taken(&dir, name, None));
+        let new_path = dir.join(format!("{}.md", filename));
+
+        self.storage.rename(&old_path, &new_path)?;
+        self.index_note(&note, &new_path, &content)?;
+
+        Ok(new_path)
+    }
+
+    fn list_folders(&self) -> Result<Vec<(String, usize)>, RepositoryError> {
+        self.index
+            .list_folders()
+            .map_err(|e| RepositoryError::storage("list_folders", storage_error_from_index(e)))
+    }
+
+    fn reindex(&self) -> Result<(), RepositoryError> {
+        let result = self.sync_index()?;
+        info!(
+            "Index synced with {:?} (updated: {}, removed: {}, duplicates: {})",
+            self.base_dir(),
+            result.updated,
+            result.removed,
+            result.duplicates
+        );
+        Ok(())
+    }
+
+    fn rewrite_front_matter(&self, uid: &str) -> Result<bool, RepositoryError> {
+        // 読み込みから書き込みまでの間の保存を古い本文で戻さないようロックする
+        let _guard = self.write_lock.lock();
+        let path = self
+            .index
+            .get_path(uid)
+            .map_err(|e| RepositoryError::storage("get_path", storage_error_from_index(e)))?
+            .ok_or_else(|| RepositoryError::not_found(uid))?;
+        let current = self.storage.load(&path)?;
+        let note = Note::from_file_content(&current)
+            .map_err(|_| RepositoryError::parse("Invalid note format", Some(path.clone())))?;
+
+        let content = note.to_file_content_in(self.timestamp_zone());
+        if content == current {
+            return Ok(false);
+        }
+        self.storage.save_atomic(&path, &content)?;
+        self.index_note(&note, &path, &content)?;
+        Ok(true)
+    }
+
+    fn list_duplicates(&self) -> Result<Vec<DuplicateFile>, RepositoryError> {
+        self.index
+            .list_duplicates()
+            .map_err(|e| RepositoryError::storage("list_duplicates", storage_error_from_index(e)))
+    }
+
+    fn list_daily_notes(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<DailyNoteEntry>, RepositoryError> {
+        self.index
+            .list_daily_notes(from, to)
+            .map_err(|e| RepositoryError::storage("list_daily_notes", storage_error_from_index(e)))
+    }
+
+    fn adjacent_daily_note(
+        &self,
+        date: NaiveDate,
+        direction: DailyDirection,
+    ) -> Result<Option<DailyNoteEntry>, RepositoryError> {
+        self.index
+            .adjacent_daily_note(date, direction)
+            .map_err(|e| RepositoryError::storage("adjacent_daily_note", storage_error_from_index(e)))
+    }
 }
 
 /// IndexErrorをStorageErrorに変換するヘルパー
@@ -314,7 +606,8 @@ mod tests {
     use crate::infrastructure::{FileStorage, HeadingFilenameStrategy, SqliteIndex};
     use crate::infrastructure::EventBusImpl;
     use crate::infrastructure::FileSettingsRepository;
-    use crate::services::SettingsService;
+    use crate::domain::{ExpectedVersion, DEFAULT_VAULT_ID, TRASH_DIR_NAME};
+    use crate::services::{test_support, SettingsService};
     use tempfile::TempDir;
 
     fn create_test_repo(temp_dir: &TempDir) -> HybridRepository {
@@ -329,14 +622,10 @@ mod tests {
         let event_bus = Arc::new(EventBusImpl::new());
         let settings_service = Arc::new(SettingsService::new(settings_repo, event_bus));
 
-        // storage_directory を temp_dir に設定
-        settings_service
-            .update(|s| {
-                s.storage_directory = temp_dir.path().to_path_buf();
-            })
-            .unwrap();
+        // 保存ディレクトリは temp_dir
+        let root = Arc::new(VaultRoot::new(DEFAULT_VAULT_ID, temp_dir.path()));
 
-        HybridRepository::new(index, storage, filename_strategy, settings_service)
+        HybridRepository::new(index, storage, filename_strategy, settings_service, root)
     }
 
     #[test]
@@ -370,6 +659,108 @@ mod tests {
         assert_eq!(items.len(), 3);
     }
 
+    #[test]
+    fn test_filename_collision_ignores_case() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = create_test_repo(&temp_dir);
+
+        let first = repo.save(&Note::with_title("Plan")).unwrap();
+        // 大文字小文字を区別するファイルシステムでも、区別しない環境へ移せるように別名にする
+        let second = repo.save(&Note::with_title("plan")).unwrap();
+
+        assert_eq!(first, temp_dir.path().join("Plan.md"));
+        assert_eq!(second, temp_dir.path().join("plan_2.md"));
+    }
+
+    #[test]
+    fn test_relocate_renames_to_current_strategy() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = create_test_repo(&temp_dir);
+
+        let mut note = Note::new();
+        note.content = "# 元の題".to_string();
+        let old_path = repo.save(&note).unwrap();
+
+        // 見出しを変えても保存先のファイル名はそのまま
+        note.update_content("# 新しい題".to_string());
+        assert_eq!(repo.save(&note).unwrap(), old_path);
+
+        let new_path = repo.relocate(note.uid()).unwrap().unwrap();
+        assert_eq!(new_path, temp_dir.path().join("新しい題.md"));
+        assert!(!old_path.exists());
+        assert_eq!(repo.load(note.uid()).unwrap().content, "# 新しい題");
+        assert_eq!(repo.relocate(note.uid()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_save_checked_detects_conflict() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = create_test_repo(&temp_dir);
+
+        let mut note = Note::new();
+        note.content = "# 競合テスト\n\n元の本文".to_string();
+        let path = repo.save(&note).unwrap();
+        let expected = ExpectedVersion {
+            content_hash: Some(note.content_hash()),
+            updated_at: None,
+        };
+
+        // 外部エディタでの変更
+        let mut external = note.clone();
+        external.content = "# 競合テスト\n\n外部で編集".to_string();
+        std::fs::write(&path, external.to_file_content()).unwrap();
+
+        let mut local = note.clone();
+        local.content = "# 競合テスト\n\nkaku で編集".to_string();
+        match repo.save_checked(&local, &expected) {
+            Err(RepositoryError::Conflict(conflict)) => {
+                assert_eq!(conflict.local.content, local.content);
+                assert_eq!(conflict.remote.content, external.content);
+            }
+            other => panic!("expected conflict, got {:?}", other),
+        }
+        // 外部の変更は上書きされていない
+        assert_eq!(repo.load(note.uid()).unwrap().content, external.content);
+
+        // 最新の版を前提にすれば保存できる
+        let expected = ExpectedVersion {
+            content_hash: Some(external.content_hash()),
+            updated_at: None,
+        };
+        repo.save_checked(&local, &expected).unwrap();
+        assert_eq!(repo.load(note.uid()).unwrap().content, local.content);
+    }
+
+    #[test]
+    fn test_concurrent_save_checked_accepts_one() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Arc::new(create_test_repo(&temp_dir));
+        let note = Note::with_title("同時保存");
+        repo.save(&note).unwrap();
+        let expected = ExpectedVersion {
+            content_hash: Some(note.content_hash()),
+            updated_at: None,
+        };
+
+        // 同じ版を前提にした保存は1件だけ成功し、残りは競合になる
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let repo = repo.clone();
+                let expected = expected.clone();
+                let mut local = note.clone();
+                local.content = format!("# 同時保存\n\n編集 {}", i);
+                std::thread::spawn(move || repo.save_checked(&local, &expected))
+            })
+            .collect();
+        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        assert!(results
+            .iter()
+            .filter_map(|r| r.as_ref().err())
+            .all(|e| matches!(e, RepositoryError::Conflict(_))));
+    }
+
     #[test]
     fn test_delete() {
         let temp_dir = TempDir::new().unwrap();
@@ -388,6 +779,67 @@ mod tests {
         assert_eq!(repo.list_all().unwrap().len(), 0);
     }
 
+    #[test]
+    fn test_delete_moves_to_trash_and_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = create_test_repo(&temp_dir);
+
+        let mut target = Note::new();
+        target.content = "# 電話番号\n\n03-0000-0000".to_string();
+        let path = repo.save(&target).unwrap();
+
+        let mut source = Note::with_uid("20260101000000".to_string());
+        source.content = "# 連絡先\n\n[[電話番号]]".to_string();
+        repo.save(&source).unwrap();
+
+        repo.delete(&target.metadata.uid).unwrap();
+        assert!(!path.exists());
+        assert!(repo.index().get_backlinks(&target.metadata.uid).unwrap().is_empty());
+
+        let trash = repo.list_trash().unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].title, "電話番号");
+
+        let restored = repo.restore(&target.metadata.uid).unwrap();
+        assert_eq!(restored.content, target.content);
+        assert!(path.exists());
+        assert!(repo.list_trash().unwrap().is_empty());
+
+        // インデックスとバックリンクが復元されている
+        assert_eq!(repo.get_path(&target.metadata.uid), Some(path));
+        let backlinks = repo.index().get_backlinks(&target.metadata.uid).unwrap();
+        assert_eq!(backlinks.len(), 1);
+        assert_eq!(backlinks[0].source_uid, source.metadata.uid);
+    }
+
+    #[test]
+    fn test_delete_keeps_index_when_trash_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = create_test_repo(&temp_dir);
+        let note = Note::with_title("残るメモ");
+        let path = repo.save(&note).unwrap();
+
+        // `.trash` がファイルのためゴミ箱へ移動できない
+        std::fs::write(temp_dir.path().join(TRASH_DIR_NAME), "").unwrap();
+
+        assert!(repo.delete(note.uid()).is_err());
+        assert!(path.exists());
+        assert_eq!(repo.get_path(note.uid()), Some(path));
+    }
+
+    #[test]
+    fn test_delete_empty_note_skips_trash() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = create_test_repo(&temp_dir);
+
+        let note = Note::new();
+        let path = repo.save(&note).unwrap();
+
+        repo.delete(&note.metadata.uid).unwrap();
+        assert!(!path.exists());
+        assert!(repo.list_trash().unwrap().is_empty());
+    }
+
     #[test]
     fn test_find_by_title() {
         let temp_dir = TempDir::new().unwrap();
@@ -435,6 +887,35 @@ mod tests {
         assert_eq!(items.len(), 1);
     }
 
+    #[test]
+    fn test_list_page_cursor() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = create_test_repo(&temp_dir);
+
+        for i in 0..5 {
+            let mut note = Note::new();
+            note.content = format!("# ページ {}\n\n本文", i);
+            repo.save(&note).unwrap();
+        }
+
+        let filter = NoteFilter::default();
+        let first = repo.list_page(NoteSortOrder::UpdatedAt, &filter, None, 2).unwrap();
+        assert_eq!(first.total, 5);
+        assert_eq!(first.items.len(), 2);
+
+        let second = repo
+            .list_page(NoteSortOrder::UpdatedAt, &filter, first.next_cursor.as_ref(), 10)
+            .unwrap();
+        assert_eq!(second.items.len(), 3);
+        assert!(second.next_cursor.is_none());
+
+        // ページ間で重複がない
+        assert!(second
+            .items
+            .iter()
+            .all(|item| first.items.iter().all(|f| f.uid != item.uid)));
+    }
+
     #[test]
     fn test_equivalence_with_file_repo() {
     
@@ -454,4 +935,164 @@ mod tests {
         assert_eq!(list[0].uid, note.metadata.uid);
         assert_eq!(list[0].title, "テスト");
     }
+
+    #[test]
+    fn test_sync_discovers_subfolders() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = create_test_repo(&temp_dir);
+
+        let work = temp_dir.path().join("work").join("2026");
+        std::fs::create_dir_all(&work).unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("attachments")).unwrap();
+        std::fs::write(work.join("plan.md"), Note::with_title("Plan").to_file_content()).unwrap();
+        std::fs::write(
+            temp_dir.path().join("attachments").join("skip.md"),
+            Note::with_title("Skip").to_file_content(),
+        )
+        .unwrap();
+        std::fs::write(temp_dir.path().join(".kakuignore"), "attachments/\n").unwrap();
+
+        repo.sync_index().unwrap();
+
+        let items = repo.list_all().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].folder, "work/2026");
+        assert_eq!(repo.list_folders().unwrap(), vec![("work/2026".to_string(), 1)]);
+
+        let filter = NoteFilter {
+            folder: Some("work"),
+            ..Default::default()
+        };
+        assert_eq!(repo.list_gallery(NoteSortOrder::UpdatedAt, &filter).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_index_is_reused_after_moving_the_vault() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("index.db");
+        let settings_service =
+            test_support::settings_service(temp_dir.path(), Arc::new(EventBusImpl::new()));
+        let open_repo = |dir: &Path| {
+            HybridRepository::new(
+                Arc::new(SqliteIndex::open(db_path.clone()).unwrap()),
+                Arc::new(FileStorage::new()),
+                Arc::new(HeadingFilenameStrategy::new()),
+                settings_service.clone(),
+                Arc::new(VaultRoot::new(DEFAULT_VAULT_ID, dir)),
+            )
+        };
+
+        let old_dir = temp_dir.path().join("old");
+        let note = Note::with_title("Plan");
+        {
+            let repo = open_repo(&old_dir);
+            repo.save(&note).unwrap();
+            repo.move_to_folder(&note.metadata.uid, "work").unwrap();
+        }
+
+        // 保管庫ごと別の場所へ移しても、インデックスを作り直さずに使える
+        let new_dir = temp_dir.path().join("new");
+        std::fs::rename(&old_dir, &new_dir).unwrap();
+        let repo = open_repo(&new_dir);
+
+        assert!(!repo.index().needs_rebuild().unwrap());
+        let result = repo.sync_index().unwrap();
+        assert_eq!((result.updated, result.removed), (0, 0));
+        assert_eq!(
+            repo.get_path(&note.metadata.uid),
+            Some(new_dir.join("work").join("Plan.md"))
+        );
+        assert_eq!(repo.load(&note.metadata.uid).unwrap().content, note.content);
+    }
+
+    #[test]
+    fn test_move_to_folder() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = create_test_repo(&temp_dir);
+
+        let note = Note::with_title("Plan");
+        repo.save(&note).unwrap();
+        // 移動先に同名のファイルがある場合は連番を付ける
+        std::fs::create_dir_all(temp_dir.path().join("work")).unwrap();
+        std::fs::write(temp_dir.path().join("work").join("Plan.md"), "").unwrap();
+
+        let path = repo.move_to_folder(note.uid(), "work").unwrap();
+        assert_eq!(path, temp_dir.path().join("work").join("Plan_2.md"));
+        assert!(!temp_dir.path().join("Plan.md").exists());
+        assert_eq!(repo.list_all().unwrap()[0].folder, "work");
+
+        // 保存は移動後のパスに行う
+        assert_eq!(repo.save(&note).unwrap(), path);
+
+        // 直下へ戻してもファイル名は変えない
+        let path = repo.move_to_folder(note.uid(), "").unwrap();
+        assert_eq!(path, temp_dir.path().join("Plan_2.md"));
+        assert_eq!(repo.list_folders().unwrap(), vec![(String::new(), 1)]);
+    }
+
+    #[test]
+    fn test_rewrite_front_matter() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = create_test_repo(&temp_dir);
+        repo.settings_service
+            .update(|s| s.timestamps.zone = TimestampZone::Utc)
+            .unwrap();
+
+        // オフセットなしの古い形式
+        let path = temp_dir.path().join("old.md");
+        std::fs::write(
+            &path,
+            "---\nuid: 20250101000000\ncreated_at: 2025-01-01 00:00:00\nupdated_at: 2025-01-02 03:04:05\n---\n\n# Old",
+        )
+        .unwrap();
+        repo.reindex().unwrap();
+
+        assert!(repo.rewrite_front_matter("20250101000000").unwrap());
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("created_at: 2025-01-01T00:00:00Z\nupdated_at: 2025-01-02T03:04:05Z"));
+        assert!(content.ends_with("# Old"));
+        // すでに現在の形式なら書き込まない
+        assert!(!repo.rewrite_front_matter("20250101000000").unwrap());
+    }
+
+    #[test]
+    fn test_note_with_unsafe_uid_is_indexed() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = create_test_repo(&temp_dir);
+        let path = temp_dir.path().join("imported.md");
+        std::fs::write(
+            &path,
+            "---\nuid: ../imported note\ncreated_at: 2025-01-01T00:00:00Z\nupdated_at: 2025-01-01T00:00:00Z\n---\n\n# Imported",
+        )
+        .unwrap();
+        repo.reindex().unwrap();
+
+        assert_eq!(repo.get_path("../imported note"), Some(path.clone()));
+        repo.reindex().unwrap();
+        assert_eq!(repo.list_all().unwrap().len(), 1);
+
+        // ゴミ箱のファイル名は変換した UID で保存ディレクトリの外に出ない
+        repo.delete("../imported note").unwrap();
+        assert!(!path.exists());
+        assert!(!temp_dir.path().join("imported note.md").exists());
+        assert_eq!(repo.restore("../imported note").unwrap().content, "# Imported");
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_unreadable_note_stays_indexed() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = create_test_repo(&temp_dir);
+        let note = Note::with_title("Plan");
+        let path = repo.save(&note).unwrap();
+
+        // front matter が壊れていてもファイルがある間は一覧に残す
+        std::fs::write(&path, "---\nuid: [broken\n---\n\n# Plan").unwrap();
+        repo.reindex().unwrap();
+        assert_eq!(repo.get_path(note.uid()), Some(path.clone()));
+
+        std::fs::remove_file(&path).unwrap();
+        repo.reindex().unwrap();
+        assert_eq!(repo.get_path(note.uid()), None);
+    }
 }
@@ -1,15 +1,47 @@
 pub mod file_storage;
 pub mod file_repository;
+pub mod note_files;
 pub mod heading_filename;
+pub mod daily_filename;
+pub mod uid_filename;
+pub mod date_prefixed_filename;
+pub mod slug_filename;
+pub mod pattern_filename;
+pub mod configured_filename;
 pub mod event_bus_impl;
 pub mod file_settings_repository;
 pub mod sqlite_index;
 pub mod hybrid_repository;
+pub mod trash_store;
+pub mod history_store;
+pub mod draft_journal;
+pub mod template_store;
+pub mod note_cipher;
+pub mod git_repository;
+pub mod backup_archive;
+pub mod sync_state_store;
+pub mod uid_generator;
+pub mod vault_root;
 
 pub use file_storage::FileStorage;
 pub use file_repository::FileNoteRepository;
+pub use note_files::{list_note_files, load_ignore_rules};
 pub use heading_filename::HeadingFilenameStrategy;
+pub use daily_filename::DailyFilenameStrategy;
+pub use uid_filename::UidFilenameStrategy;
+pub use date_prefixed_filename::DatePrefixedFilenameStrategy;
+pub use slug_filename::SlugFilenameStrategy;
+pub use pattern_filename::PatternFilenameStrategy;
+pub use configured_filename::ConfiguredFilenameStrategy;
 pub use event_bus_impl::EventBusImpl;
 pub use file_settings_repository::FileSettingsRepository;
 pub use sqlite_index::{SqliteIndex, GalleryNote, IndexedNote, compute_hash};
 pub use hybrid_repository::HybridRepository;
+pub use trash_store::TrashStore;
+pub use history_store::HistoryStore;
+pub use draft_journal::DraftJournal;
+pub use template_store::TemplateStore;
+pub use git_repository::GitRepository;
+pub use sync_state_store::SyncStateStore;
+pub use uid_generator::{ConfiguredUidGenerator, TimestampUidGenerator, UlidGenerator, Uuid7Generator};
+pub use vault_root::VaultRoot;
@@ -0,0 +1,127 @@
+//! ノート本文の暗号化・復号
+//!
+//! 鍵はパスフレーズから Argon2id で導出し、本文は XChaCha20-Poly1305 で暗号化する。
+//! ノンスは暗号化のたびに乱数で生成する（192ビットのため衝突を考慮しなくてよい）。
+
+use crate::domain::{EncryptedBody, EncryptionError, KdfParams};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use zeroize::Zeroizing;
+
+/// 鍵の長さ（バイト）
+pub const KEY_LENGTH: usize = 32;
+
+/// ソルトの長さ（バイト）
+pub const SALT_LENGTH: usize = 16;
+
+/// ノンスの長さ（バイト、XChaCha20）
+const NONCE_LENGTH: usize = 24;
+
+/// 追加認証データ（形式の版を暗号文に結び付ける）
+const AAD: &[u8] = b"kaku-encrypted-note-v1";
+
+/// 導出した鍵（破棄時にメモリを消去）
+pub type NoteKey = Zeroizing<[u8; KEY_LENGTH]>;
+
+/// パスフレーズから鍵を導出
+pub fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    params: &KdfParams,
+) -> Result<NoteKey, EncryptionError> {
+    let argon_params = Params::new(params.m_cost, params.t_cost, params.p_cost, Some(KEY_LENGTH))
+        .map_err(|e| EncryptionError::Crypto(e.to_string()))?;
+    let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, argon_params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key[..])
+        .map_err(|e| EncryptionError::Crypto(e.to_string()))?;
+    Ok(key)
+}
+
+/// 新しいソルトを生成
+pub fn generate_salt() -> Result<Vec<u8>, EncryptionError> {
+    random_bytes(SALT_LENGTH)
+}
+
+/// 本文を暗号化（`salt` と `params` は `key` の導出に使ったもの）
+pub fn seal(
+    key: &NoteKey,
+    salt: &[u8],
+    params: KdfParams,
+    plaintext: &str,
+) -> Result<EncryptedBody, EncryptionError> {
+    let nonce = random_bytes(NONCE_LENGTH)?;
+    let ciphertext = cipher(key)
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext.as_bytes(),
+                aad: AAD,
+            },
+        )
+        .map_err(|e| EncryptionError::Crypto(e.to_string()))?;
+
+    Ok(EncryptedBody {
+        params,
+        salt: salt.to_vec(),
+        nonce,
+        ciphertext,
+    })
+}
+
+/// 本文を復号（認証に失敗した場合は鍵が違うものとして扱う）
+pub fn open(key: &NoteKey, body: &EncryptedBody) -> Result<String, EncryptionError> {
+    if body.nonce.len() != NONCE_LENGTH {
+        return Err(EncryptionError::InvalidFormat("invalid nonce length".to_string()));
+    }
+    let plaintext = cipher(key)
+        .decrypt(
+            XNonce::from_slice(&body.nonce),
+            Payload {
+                msg: &body.ciphertext,
+                aad: AAD,
+            },
+        )
+        .map_err(|_| EncryptionError::WrongPassphrase)?;
+
+    String::from_utf8(plaintext)
+        .map_err(|_| EncryptionError::InvalidFormat("plaintext is not UTF-8".to_string()))
+}
+
+fn cipher(key: &NoteKey) -> XChaCha20Poly1305 {
+    XChaCha20Poly1305::new(Key::from_slice(&key[..]))
+}
+
+fn random_bytes(len: usize) -> Result<Vec<u8>, EncryptionError> {
+    let mut bytes = vec![0u8; len];
+    getrandom::getrandom(&mut bytes).map_err(|e| EncryptionError::Crypto(e.to_string()))?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// テスト用の軽いパラメータ
+    const TEST_PARAMS: KdfParams = KdfParams {
+        m_cost: 64,
+        t_cost: 1,
+        p_cost: 1,
+    };
+
+    #[test]
+    fn test_seal_and_open() {
+        let salt = generate_salt().unwrap();
+        let key = derive_key("correct horse", &salt, &TEST_PARAMS).unwrap();
+
+        let body = seal(&key, &salt, TEST_PARAMS, "# 口座番号\n\n1234").unwrap();
+        assert!(!body.to_armor().contains("1234"));
+
+        let parsed = EncryptedBody::parse(&body.to_armor()).unwrap();
+        assert_eq!(open(&key, &parsed).unwrap(), "# 口座番号\n\n1234");
+
+        let wrong = derive_key("wrong horse", &salt, &TEST_PARAMS).unwrap();
+        assert!(matches!(open(&wrong, &parsed), Err(EncryptionError::WrongPassphrase)));
+    }
+}
@@ -0,0 +1,24 @@
+//! 保存ディレクトリ内のノートファイルの走査
+//!
+//! サブフォルダも含めて走査し、`.kakuignore`（`IGNORE_FILE_NAME`）の除外ルールを適用する。
+
+use crate::domain::{IgnoreRules, IGNORE_FILE_NAME};
+use crate::traits::{Storage, StorageError};
+use std::path::{Path, PathBuf};
+
+/// ノートファイルの拡張子
+pub const NOTE_EXTENSION: &str = "md";
+
+/// 保存ディレクトリの除外ルールを読み込む（ファイルがなければ既定のルール）
+pub fn load_ignore_rules(storage: &dyn Storage, base_dir: &Path) -> IgnoreRules {
+    storage
+        .load(&base_dir.join(IGNORE_FILE_NAME))
+        .map(|text| IgnoreRules::parse(&text))
+        .unwrap_or_default()
+}
+
+/// 保存ディレクトリ以下のノートファイル一覧
+pub fn list_note_files(storage: &dyn Storage, base_dir: &Path) -> Result<Vec<PathBuf>, StorageError> {
+    let ignore = load_ignore_rules(storage, base_dir);
+    storage.list_files_recursive(base_dir, NOTE_EXTENSION, &ignore)
+}
@@ -0,0 +1,83 @@
+use crate::domain::template::render;
+use crate::domain::{slugify, Note, TemplateVars};
+use crate::infrastructure::HeadingFilenameStrategy;
+use crate::traits::FilenameStrategy;
+use chrono::Local;
+
+/// ユーザー定義パターンのファイル名生成戦略
+///
+/// テンプレートと同じ `{{name}}` 形式で、次の変数を使える（日時は作成日時のローカル時刻）。
+///
+/// - `title`: 見出し（なければ UID）
+/// - `slug`: 見出しの ASCII スラッグ（なければ UID）
+/// - `uid`
+/// - `date`（`2026-10-17`）・`time`（`0930`）・`year`・`month`・`day`
+///
+/// 展開結果が空になる場合は UID を使う。
+pub struct PatternFilenameStrategy {
+    pattern: String,
+}
+
+impl PatternFilenameStrategy {
+    pub fn new(pattern: impl Into<String>) -> Self {
+        Self {
+            pattern: pattern.into(),
+        }
+    }
+
+    /// ノートからパターン変数を作成
+    fn vars(note: &Note) -> TemplateVars {
+        let uid = note.metadata.uid.clone();
+        let heading = note.extract_heading();
+        let slug = heading
+            .as_deref()
+            .map(slugify)
+            .filter(|slug| !slug.is_empty())
+            .unwrap_or_else(|| uid.clone());
+        let created = note.metadata.created_at.with_timezone(&Local);
+
+        let mut vars = TemplateVars::new();
+        vars.insert("title".to_string(), heading.unwrap_or_else(|| uid.clone()));
+        vars.insert("slug".to_string(), slug);
+        vars.insert("date".to_string(), created.format("%Y-%m-%d").to_string());
+        vars.insert("time".to_string(), created.format("%H%M").to_string());
+        vars.insert("year".to_string(), created.format("%Y").to_string());
+        vars.insert("month".to_string(), created.format("%m").to_string());
+        vars.insert("day".to_string(), created.format("%d").to_string());
+        vars.insert("uid".to_string(), uid);
+        vars
+    }
+}
+
+impl FilenameStrategy for PatternFilenameStrategy {
+    fn generate(&self, note: &Note, is_taken: &dyn Fn(&str) -> bool) -> String {
+        let rendered = render(&self.pattern, &Self::vars(note));
+        let name = match rendered.trim() {
+            "" => note.metadata.uid.as_str(),
+            name => name,
+        };
+
+        HeadingFilenameStrategy::finish(name, is_taken)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_from_pattern() {
+        let note = Note::with_title("Plan for Q4");
+        let created = note.metadata.created_at.with_timezone(&Local);
+
+        let strategy = PatternFilenameStrategy::new("{{year}}/{{slug}} ({{uid}})");
+        assert_eq!(
+            strategy.generate(&note, &|_| false),
+            format!("{}_plan-for-q4 ({})", created.format("%Y"), note.metadata.uid)
+        );
+
+        // 展開結果が空なら UID
+        let strategy = PatternFilenameStrategy::new("  ");
+        assert_eq!(strategy.generate(&note, &|_| false), note.metadata.uid);
+    }
+}
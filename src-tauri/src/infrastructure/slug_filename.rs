@@ -0,0 +1,50 @@
+use crate::domain::{slugify, Note};
+use crate::infrastructure::HeadingFilenameStrategy;
+use crate::traits::FilenameStrategy;
+
+/// ASCII スラッグのファイル名生成戦略（`plan-for-q4`）
+///
+/// Unicode のファイル名を正しく扱えないファイルシステムや Git ホスティング向け。
+/// スラッグにできる文字がなければ UID を使う。
+pub struct SlugFilenameStrategy;
+
+impl SlugFilenameStrategy {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for SlugFilenameStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FilenameStrategy for SlugFilenameStrategy {
+    fn generate(&self, note: &Note, is_taken: &dyn Fn(&str) -> bool) -> String {
+        let slug = note
+            .extract_heading()
+            .map(|heading| slugify(&heading))
+            .filter(|slug| !slug.is_empty())
+            .unwrap_or_else(|| note.metadata.uid.clone());
+
+        HeadingFilenameStrategy::finish(&slug, is_taken)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_slug() {
+        let strategy = SlugFilenameStrategy::new();
+        let is_taken = |name: &str| name == "plan-for-q4";
+
+        assert_eq!(strategy.generate(&Note::with_title("Plan for Q4"), &is_taken), "plan-for-q4_2");
+        assert!(strategy.generate(&Note::with_title("買い物"), &|_| false).is_ascii());
+
+        let note = Note::with_title("!!!");
+        assert_eq!(strategy.generate(&note, &|_| false), note.metadata.uid);
+    }
+}
@@ -17,10 +17,13 @@
 //! Connection は Mutex で保護されており、複数スレッドから安全にアクセス可能。
 
 use crate::domain::backlink::extract_wiki_links;
-use crate::traits::NoteListItem;
-use chrono::{DateTime, NaiveDateTime, Utc};
-use parking_lot::Mutex;
-use rusqlite::{params, Connection};
+use crate::domain::{title_sort_key, DailyDirection, DailyNoteEntry, DuplicateFile, NoteSortOrder};
+use crate::traits::{NoteFilter, NoteListItem, Page, PageCursor};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use parking_lot::{Mutex, RwLock};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -47,6 +50,20 @@ pub struct IndexedNote {
     pub content_hash: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// 本文の単語数（ソート用）
+    pub word_count: usize,
+    /// ファイルサイズ（バイト、ソート用）
+    pub file_size: u64,
+    /// ピン留め（一覧の先頭に固定）
+    pub pinned: bool,
+    /// お気に入り
+    pub favorite: bool,
+    /// デイリーノートの日付
+    pub daily_date: Option<NaiveDate>,
+    /// フォルダ（保存ディレクトリからの相対パス、直下は `""`）
+    pub folder: String,
+    /// 暗号化ノートか（本文は全文検索・バックリンクに登録しない）
+    pub encrypted: bool,
 }
 
 /// ギャラリー用ノート情報（プレビュー・タグ付き）
@@ -58,6 +75,11 @@ pub struct GalleryNote {
     pub tags: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub pinned: bool,
+    pub favorite: bool,
+    pub folder: String,
+    /// 暗号化ノートか（プレビューは空）
+    pub encrypted: bool,
 }
 
 /// バックリンク情報（SQLite用）
@@ -68,8 +90,15 @@ pub struct IndexedBacklink {
 }
 
 /// SQLiteインデックスマネージャー
+///
+/// ノートのパスは基準ディレクトリ（保存ディレクトリ）からの相対パス（`/` 区切り）で保存し、
+/// 取得時に基準ディレクトリと結合する。保管庫を別の場所や別のマシンにマウントしても、
+/// 基準ディレクトリを設定し直せばインデックスを作り直さずに使える。
+/// 基準ディレクトリの外のパスは絶対パスのまま保存する。
 pub struct SqliteIndex {
     conn: Mutex<Connection>,
+    /// パスの基準ディレクトリ（未設定の場合は絶対パスのまま保存）
+    root: RwLock<Option<PathBuf>>,
     #[allow(dead_code)]
     db_path: PathBuf,
 }
@@ -96,6 +125,7 @@ impl SqliteIndex {
 
         let index = Self {
             conn: Mutex::new(conn),
+            root: RwLock::new(None),
             db_path,
         };
 
@@ -109,6 +139,7 @@ impl SqliteIndex {
         let conn = Connection::open_in_memory()?;
         let index = Self {
             conn: Mutex::new(conn),
+            root: RwLock::new(None),
             db_path: PathBuf::from(":memory:"),
         };
         index.run_migrations()?;
@@ -204,9 +235,193 @@ impl SqliteIndex {
             )?;
         }
 
+        // マイグレーション V3: キーセットページネーション用の複合インデックス
+        if current_version < 3 {
+            conn.execute_batch(
+                "
+                CREATE INDEX IF NOT EXISTS idx_notes_updated_uid ON notes(updated_at DESC, uid DESC);
+                CREATE INDEX IF NOT EXISTS idx_notes_created_uid ON notes(created_at DESC, uid DESC);
+
+                INSERT INTO schema_version (version, applied_at) VALUES (3, datetime('now'));
+                ",
+            )?;
+        }
+
+        // マイグレーション V4: 追加ソート順用のカラムと手動ソート順テーブル
+        if current_version < 4 {
+            conn.execute_batch(
+                "
+                ALTER TABLE notes ADD COLUMN title_sort TEXT NOT NULL DEFAULT '';
+                ALTER TABLE notes ADD COLUMN word_count INTEGER NOT NULL DEFAULT 0;
+                ALTER TABLE notes ADD COLUMN file_size INTEGER NOT NULL DEFAULT 0;
+
+                CREATE INDEX IF NOT EXISTS idx_notes_title_sort ON notes(title_sort ASC, uid DESC);
+                CREATE INDEX IF NOT EXISTS idx_notes_word_count ON notes(word_count DESC, uid DESC);
+                CREATE INDEX IF NOT EXISTS idx_notes_file_size ON notes(file_size DESC, uid DESC);
+
+                -- 手動ソート順（インデックス再構築でも消えないよう notes とは独立）
+                CREATE TABLE IF NOT EXISTS manual_order (
+                    uid TEXT PRIMARY KEY,
+                    position INTEGER NOT NULL
+                );
+
+                -- 既存行の新カラムを埋めるため、次回起動時に再同期させる
+                UPDATE notes SET content_hash = '';
+
+                INSERT INTO schema_version (version, applied_at) VALUES (4, datetime('now'));
+                ",
+            )?;
+        }
+
+        // マイグレーション V5: ピン留め・お気に入りフラグ
+        if current_version < 5 {
+            conn.execute_batch(
+                "
+                ALTER TABLE notes ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+                ALTER TABLE notes ADD COLUMN favorite INTEGER NOT NULL DEFAULT 0;
+
+                CREATE INDEX IF NOT EXISTS idx_notes_pinned_updated ON notes(pinned DESC, updated_at DESC, uid DESC);
+                CREATE INDEX IF NOT EXISTS idx_notes_favorite ON notes(favorite) WHERE favorite = 1;
+
+                -- フロントマターのフラグを取り込むため、次回起動時に再同期させる
+                UPDATE notes SET content_hash = '';
+
+                INSERT INTO schema_version (version, applied_at) VALUES (5, datetime('now'));
+                ",
+            )?;
+        }
+
+        // マイグレーション V6: デイリーノートの日付
+        if current_version < 6 {
+            conn.execute_batch(
+                "
+                ALTER TABLE notes ADD COLUMN daily_date TEXT;
+
+                CREATE INDEX IF NOT EXISTS idx_notes_daily_date ON notes(daily_date) WHERE daily_date IS NOT NULL;
+
+                -- フロントマターの日付を取り込むため、次回起動時に再同期させる
+                UPDATE notes SET content_hash = '';
+
+                INSERT INTO schema_version (version, applied_at) VALUES (6, datetime('now'));
+                ",
+            )?;
+        }
+
+        // マイグレーション V7: ファイル名衝突判定用の正規化パス（大文字小文字を区別しない）
+        if current_version < 7 {
+            conn.execute_batch(
+                "
+                ALTER TABLE notes ADD COLUMN path_key TEXT NOT NULL DEFAULT '';
+
+                CREATE INDEX IF NOT EXISTS idx_notes_path_key ON notes(path_key);
+
+                -- SQLite の lower() は ASCII のみのため、次回起動時の再同期で設定する
+                UPDATE notes SET content_hash = '';
+
+                INSERT INTO schema_version (version, applied_at) VALUES (7, datetime('now'));
+                ",
+            )?;
+        }
+
+        // マイグレーション V8: サブフォルダ
+        if current_version < 8 {
+            conn.execute_batch(
+                "
+                ALTER TABLE notes ADD COLUMN folder TEXT NOT NULL DEFAULT '';
+
+                CREATE INDEX IF NOT EXISTS idx_notes_folder ON notes(folder);
+
+                -- サブフォルダのノートを取り込むため、次回起動時に再同期させる
+                UPDATE notes SET content_hash = '';
+
+                INSERT INTO schema_version (version, applied_at) VALUES (8, datetime('now'));
+                ",
+            )?;
+        }
+
+        // マイグレーション V9: 保存ディレクトリからの相対パス
+        if current_version < 9 {
+            conn.execute_batch(
+                "
+                -- 絶対パスで保存されたエントリを相対パスで保存し直すため、次回起動時に再同期させる
+                UPDATE notes SET content_hash = '';
+
+                INSERT INTO schema_version (version, applied_at) VALUES (9, datetime('now'));
+                ",
+            )?;
+        }
+
+        // マイグレーション V10: 暗号化ノート
+        if current_version < 10 {
+            conn.execute_batch(
+                "
+                ALTER TABLE notes ADD COLUMN encrypted INTEGER NOT NULL DEFAULT 0;
+
+                -- 暗号化ノートの本文を全文検索・プレビューから除くため、次回起動時に再同期させる
+                UPDATE notes SET content_hash = '';
+
+                INSERT INTO schema_version (version, applied_at) VALUES (10, datetime('now'));
+                ",
+            )?;
+        }
+
+        // マイグレーション V11: 同じ UID を持つファイル（競合コピー・複製）
+        if current_version < 11 {
+            conn.execute_batch(
+                "
+                -- インデックスに登録しなかったファイル（同期のたびに作り直す）
+                CREATE TABLE IF NOT EXISTS duplicate_files (
+                    file_path TEXT PRIMARY KEY,
+                    uid TEXT NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_duplicate_files_uid ON duplicate_files(uid);
+
+                -- 既存の重複を検出するため、次回起動時に再同期させる
+                UPDATE notes SET content_hash = '';
+
+                INSERT INTO schema_version (version, applied_at) VALUES (11, datetime('now'));
+                ",
+            )?;
+        }
+
         Ok(())
     }
 
+    /// パスの基準ディレクトリ（保存ディレクトリ）を設定
+    pub fn set_root(&self, root: &Path) {
+        *self.root.write() = Some(root.to_path_buf());
+    }
+
+    /// 保存用のパス（基準ディレクトリ配下は `/` 区切りの相対パス）
+    fn stored_path(&self, path: &Path) -> String {
+        let root = self.root.read();
+        match root.as_deref().and_then(|root| path.strip_prefix(root).ok()) {
+            Some(relative) => relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+            None => path.to_string_lossy().to_string(),
+        }
+    }
+
+    /// 保存されたパスを基準ディレクトリと結合
+    fn resolve_path(&self, stored: &str) -> PathBuf {
+        let path = Path::new(stored);
+        match self.root.read().as_deref() {
+            Some(root) if path.is_relative() => root.join(path),
+            _ => path.to_path_buf(),
+        }
+    }
+
+    /// notes テーブルの行を NoteListItem に変換（パスは基準ディレクトリと結合）
+    fn row_to_list_item(&self, row: &rusqlite::Row<'_>) -> rusqlite::Result<NoteListItem> {
+        let mut item = row_to_list_item(row)?;
+        item.path = self.resolve_path(&item.path.to_string_lossy());
+        Ok(item)
+    }
+
     /// ノートをインデックスに追加/更新
     pub fn upsert_note(&self, note: &IndexedNote) -> Result<(), IndexError> {
         self.upsert_note_with_gallery(note, "", &[])
@@ -222,10 +437,17 @@ impl SqliteIndex {
         let conn = self.conn.lock();
         let now = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
         let tags_json = serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string());
+        let file_path = self.stored_path(&note.file_path);
+        // 暗号化ノートの本文（平文・暗号文とも）はインデックスに残さない
+        let (content, preview) = if note.encrypted {
+            ("", "")
+        } else {
+            (note.content.as_str(), preview)
+        };
 
         conn.execute(
-            "INSERT INTO notes (uid, title, file_path, content_hash, created_at, updated_at, indexed_at, preview, tags_json)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "INSERT INTO notes (uid, title, file_path, content_hash, created_at, updated_at, indexed_at, preview, tags_json, title_sort, word_count, file_size, pinned, favorite, daily_date, path_key, folder, encrypted)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
              ON CONFLICT(uid) DO UPDATE SET
                 title = excluded.title,
                 file_path = excluded.file_path,
@@ -233,17 +455,35 @@ impl SqliteIndex {
                 updated_at = excluded.updated_at,
                 indexed_at = ?7,
                 preview = ?8,
-                tags_json = ?9",
+                tags_json = ?9,
+                title_sort = ?10,
+                word_count = ?11,
+                file_size = ?12,
+                pinned = ?13,
+                favorite = ?14,
+                daily_date = ?15,
+                path_key = ?16,
+                folder = ?17,
+                encrypted = ?18",
             params![
                 note.uid,
                 note.title,
-                note.file_path.to_string_lossy().to_string(),
+                file_path,
                 note.content_hash,
                 format_datetime(&note.created_at),
                 format_datetime(&note.updated_at),
                 now,
                 preview,
                 tags_json,
+                title_sort_key(&note.title),
+                note.word_count as i64,
+                note.file_size as i64,
+                note.pinned,
+                note.favorite,
+                note.daily_date.map(format_date),
+                path_key(Path::new(&file_path)),
+                note.folder,
+                note.encrypted,
             ],
         )?;
 
@@ -251,11 +491,11 @@ impl SqliteIndex {
         conn.execute("DELETE FROM notes_fts WHERE uid = ?1", params![note.uid])?;
         conn.execute(
             "INSERT INTO notes_fts (uid, title, content) VALUES (?1, ?2, ?3)",
-            params![note.uid, note.title, note.content],
+            params![note.uid, note.title, content],
         )?;
 
         // バックリンク更新
-        self.update_backlinks_internal(&conn, &note.uid, &note.content)?;
+        self.update_backlinks_internal(&conn, &note.uid, content)?;
 
         // タイトルインデックス更新
         let title_normalized = note.title.to_lowercase();
@@ -285,6 +525,9 @@ impl SqliteIndex {
         // FTSを削除
         conn.execute("DELETE FROM notes_fts WHERE uid = ?1", params![uid])?;
 
+        // 手動ソート順を削除
+        conn.execute("DELETE FROM manual_order WHERE uid = ?1", params![uid])?;
+
         // メインテーブルを削除
         conn.execute("DELETE FROM notes WHERE uid = ?1", params![uid])?;
 
@@ -302,98 +545,237 @@ impl SqliteIndex {
         let total: usize =
             conn.query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))?;
 
-        let mut stmt = conn.prepare(
-            "SELECT uid, title, file_path, updated_at
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {}
              FROM notes
-             ORDER BY updated_at DESC
+             ORDER BY pinned DESC, updated_at DESC, uid DESC
              LIMIT ?1 OFFSET ?2",
-        )?;
+            LIST_COLUMNS
+        ))?;
 
         let items: Vec<NoteListItem> = stmt
-            .query_map(params![limit as i64, offset as i64], |row| {
-                let uid: String = row.get(0)?;
-                let title: String = row.get(1)?;
-                let file_path: String = row.get(2)?;
-                let updated_at_str: String = row.get(3)?;
-                let updated_at = parse_datetime(&updated_at_str);
-
-                Ok(NoteListItem {
-                    uid,
-                    title,
-                    path: PathBuf::from(file_path),
-                    updated_at,
-                })
-            })?
+            .query_map(params![limit as i64, offset as i64], |row| self.row_to_list_item(row))?
             .filter_map(|r| r.ok())
             .collect();
 
         Ok((items, total))
     }
 
-    /// 全件取得（後方互換用）
+    /// 全件取得（後方互換用、ピン留めが先頭）
     pub fn list_all_notes(&self) -> Result<Vec<NoteListItem>, IndexError> {
-        let (items, _) = self.list_notes(0, usize::MAX)?;
+        let conn = self.conn.lock();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {}
+             FROM notes
+             ORDER BY pinned DESC, updated_at DESC, uid DESC",
+            LIST_COLUMNS
+        ))?;
+
+        let items: Vec<NoteListItem> = stmt
+            .query_map([], |row| self.row_to_list_item(row))?
+            .filter_map(|r| r.ok())
+            .collect();
+
         Ok(items)
     }
 
+    /// ノート一覧をカーソル位置から取得（キーセットページネーション）
+    ///
+    /// `sort` の順（同一値は uid 降順）で、カーソルより後ろのアイテムを最大 `limit` 件返す。
+    /// OFFSET を使わないため、ページの深さに関係なく一定コストで取得できる。
+    pub fn list_notes_page(
+        &self,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
+        cursor: Option<&PageCursor>,
+        limit: usize,
+    ) -> Result<Page<NoteListItem>, IndexError> {
+        self.query_sorted_page(
+            LIST_COLUMNS,
+            |row| self.row_to_list_item(row),
+            sort,
+            filter,
+            cursor,
+            limit,
+        )
+    }
+
     /// ギャラリー用ノート一覧を取得（キャッシュから高速取得）
     pub fn list_gallery_notes(
         &self,
-        sort_by_created: bool,
-        tag_filter: Option<&str>,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
     ) -> Result<Vec<GalleryNote>, IndexError> {
         let conn = self.conn.lock();
+        let spec = SortSpec::of(sort);
 
-        let order = if sort_by_created {
-            "created_at DESC"
-        } else {
-            "updated_at DESC"
-        };
+        let mut conditions = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
+        push_filter_conditions(filter, &mut conditions, &mut values);
 
         let query = format!(
-            "SELECT uid, title, preview, tags_json, created_at, updated_at
+            "SELECT {}, {} AS sort_value
              FROM notes
-             ORDER BY {}",
-            order
+             {}
+             ORDER BY pinned DESC, sort_value {}, uid DESC",
+            GALLERY_COLUMNS,
+            spec.expr,
+            where_clause(&conditions),
+            spec.direction()
         );
 
         let mut stmt = conn.prepare(&query)?;
-
         let items: Vec<GalleryNote> = stmt
-            .query_map([], |row| {
-                let uid: String = row.get(0)?;
-                let title: String = row.get(1)?;
-                let preview: String = row.get(2)?;
-                let tags_json: String = row.get(3)?;
-                let created_at_str: String = row.get(4)?;
-                let updated_at_str: String = row.get(5)?;
-
-                let tags: Vec<String> =
-                    serde_json::from_str(&tags_json).unwrap_or_default();
-
-                Ok(GalleryNote {
-                    uid,
-                    title,
-                    preview,
-                    tags,
-                    created_at: parse_datetime(&created_at_str),
-                    updated_at: parse_datetime(&updated_at_str),
-                })
-            })?
+            .query_map(params_from_iter(values), row_to_gallery_note)?
             .filter_map(|r| r.ok())
-            .filter(|note| {
-                // タグフィルタを適用
-                if let Some(filter) = tag_filter {
-                    note.tags.iter().any(|t| t == filter)
-                } else {
-                    true
-                }
-            })
             .collect();
 
         Ok(items)
     }
 
+    /// ギャラリー用ノート一覧をカーソル位置から取得（キーセットページネーション）
+    ///
+    /// タグ・フォルダの絞り込みもSQL側で適用するため、総件数は絞り込み後の件数になる。
+    pub fn list_gallery_page(
+        &self,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
+        cursor: Option<&PageCursor>,
+        limit: usize,
+    ) -> Result<Page<GalleryNote>, IndexError> {
+        self.query_sorted_page(
+            GALLERY_COLUMNS,
+            row_to_gallery_note,
+            sort,
+            filter,
+            cursor,
+            limit,
+        )
+    }
+
+    /// ソート・絞り込み条件・カーソルを適用して1ページ分取得する共通処理
+    ///
+    /// `columns` の直後に `sort_value` 列を追加して取得し、
+    /// 最終アイテムの `sort_value` を次ページのカーソルとして返す。
+    fn query_sorted_page<T, F>(
+        &self,
+        columns: &str,
+        map_row: F,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
+        cursor: Option<&PageCursor>,
+        limit: usize,
+    ) -> Result<Page<T>, IndexError>
+    where
+        F: Fn(&rusqlite::Row<'_>) -> rusqlite::Result<T>,
+    {
+        let conn = self.conn.lock();
+        let spec = SortSpec::of(sort);
+        let sort_value_index = columns.split(',').count();
+
+        let mut conditions = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
+        push_filter_conditions(filter, &mut conditions, &mut values);
+
+        let total: usize = conn.query_row(
+            &format!("SELECT COUNT(*) FROM notes {}", where_clause(&conditions)),
+            params_from_iter(values.iter()),
+            |row| row.get(0),
+        )?;
+
+        if let Some(cursor) = cursor {
+            let sort_key = if spec.numeric {
+                let n: i64 = cursor.sort_key.parse().map_err(|_| {
+                    IndexError::DataInconsistency(format!(
+                        "invalid cursor sort_key: {}",
+                        cursor.sort_key
+                    ))
+                })?;
+                Value::Integer(n)
+            } else {
+                Value::Text(cursor.sort_key.clone())
+            };
+            // ピン留め → ソート値 → uid の順で、カーソルより後ろの行
+            conditions.push(format!(
+                "(pinned < ? OR (pinned = ? AND ({expr} {op} ? OR ({expr} = ? AND uid < ?))))",
+                expr = spec.expr,
+                op = if spec.ascending { ">" } else { "<" }
+            ));
+            values.push(Value::Integer(cursor.pinned as i64));
+            values.push(Value::Integer(cursor.pinned as i64));
+            values.push(sort_key.clone());
+            values.push(sort_key);
+            values.push(Value::Text(cursor.uid.clone()));
+        }
+
+        let query = format!(
+            "SELECT {}, {} AS sort_value, pinned
+             FROM notes
+             {}
+             ORDER BY pinned DESC, sort_value {}, uid DESC
+             LIMIT ?",
+            columns,
+            spec.expr,
+            where_clause(&conditions),
+            spec.direction()
+        );
+        // 次ページの有無を判定するため1件多く取得
+        values.push(Value::Integer(limit as i64 + 1));
+
+        let mut stmt = conn.prepare(&query)?;
+        let mut rows: Vec<(T, PageCursor)> = stmt
+            .query_map(params_from_iter(values), |row| {
+                let sort_key = match row.get::<_, Value>(sort_value_index)? {
+                    Value::Integer(n) => n.to_string(),
+                    Value::Text(t) => t,
+                    Value::Real(f) => f.to_string(),
+                    _ => String::new(),
+                };
+                let position = PageCursor {
+                    pinned: row.get(sort_value_index + 1)?,
+                    sort_key,
+                    uid: row.get(0)?,
+                };
+                Ok((map_row(row)?, position))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let next_cursor = if rows.len() > limit {
+            rows.truncate(limit);
+            rows.last().map(|(_, position)| position.clone())
+        } else {
+            None
+        };
+
+        Ok(Page {
+            items: rows.into_iter().map(|(item, _)| item).collect(),
+            next_cursor,
+            total,
+        })
+    }
+
+    /// 手動ソート順を設定
+    ///
+    /// `uids` の並び順がそのまま表示順になる。リストにないノートは末尾に並ぶ。
+    pub fn set_manual_order(&self, uids: &[String]) -> Result<(), IndexError> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+
+        tx.execute("DELETE FROM manual_order", [])?;
+        {
+            let mut stmt =
+                tx.prepare("INSERT OR IGNORE INTO manual_order (uid, position) VALUES (?1, ?2)")?;
+            for (position, uid) in uids.iter().enumerate() {
+                stmt.execute(params![uid, position as i64])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
     /// UIDからファイルパスを取得
     pub fn get_path(&self, uid: &str) -> Result<Option<PathBuf>, IndexError> {
         let conn = self.conn.lock();
@@ -403,7 +785,7 @@ impl SqliteIndex {
             params![uid],
             |row| {
                 let path: String = row.get(0)?;
-                Ok(PathBuf::from(path))
+                Ok(self.resolve_path(&path))
             },
         );
 
@@ -414,6 +796,23 @@ impl SqliteIndex {
         }
     }
 
+    /// パスが使用済みか（大文字小文字を区別しない、O(log n)）
+    pub fn is_path_taken(&self, path: &Path) -> Result<bool, IndexError> {
+        let conn = self.conn.lock();
+
+        let result = conn.query_row(
+            "SELECT 1 FROM notes WHERE path_key = ?1 LIMIT 1",
+            params![path_key(Path::new(&self.stored_path(path)))],
+            |_| Ok(()),
+        );
+
+        match result {
+            Ok(()) => Ok(true),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// タイトルでノートを検索（O(1)）
     pub fn find_by_title(&self, title: &str) -> Result<Option<String>, IndexError> {
         let conn = self.conn.lock();
@@ -437,22 +836,9 @@ impl SqliteIndex {
         let conn = self.conn.lock();
 
         let result = conn.query_row(
-            "SELECT uid, title, file_path, updated_at FROM notes WHERE uid = ?1",
+            &format!("SELECT {} FROM notes WHERE uid = ?1", LIST_COLUMNS),
             params![uid],
-            |row| {
-                let uid: String = row.get(0)?;
-                let title: String = row.get(1)?;
-                let file_path: String = row.get(2)?;
-                let updated_at_str: String = row.get(3)?;
-                let updated_at = parse_datetime(&updated_at_str);
-
-                Ok(NoteListItem {
-                    uid,
-                    title,
-                    path: PathBuf::from(file_path),
-                    updated_at,
-                })
-            },
+            |row| self.row_to_list_item(row),
         );
 
         match result {
@@ -462,6 +848,96 @@ impl SqliteIndex {
         }
     }
 
+    /// 期間内のデイリーノートを日付の昇順で取得（`None` は期間の指定なし）
+    pub fn list_daily_notes(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<DailyNoteEntry>, IndexError> {
+        let conn = self.conn.lock();
+
+        let mut conditions = vec!["daily_date IS NOT NULL".to_string()];
+        let mut values = Vec::new();
+        if let Some(from) = from {
+            conditions.push("daily_date >= ?".to_string());
+            values.push(Value::Text(format_date(from)));
+        }
+        if let Some(to) = to {
+            conditions.push("daily_date <= ?".to_string());
+            values.push(Value::Text(format_date(to)));
+        }
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT daily_date, uid FROM notes {} ORDER BY daily_date ASC, uid ASC",
+            where_clause(&conditions)
+        ))?;
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            let date: String = row.get(0)?;
+            let uid: String = row.get(1)?;
+            Ok((date, uid))
+        })?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            let (date, uid) = row?;
+            if let Ok(date) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                entries.push(DailyNoteEntry { date, uid });
+            }
+        }
+        Ok(entries)
+    }
+
+    /// 指定日の前後で最も近いデイリーノート（指定日自身は含まない）
+    pub fn adjacent_daily_note(
+        &self,
+        date: NaiveDate,
+        direction: DailyDirection,
+    ) -> Result<Option<DailyNoteEntry>, IndexError> {
+        let conn = self.conn.lock();
+
+        let sql = match direction {
+            DailyDirection::Previous => {
+                "SELECT daily_date, uid FROM notes WHERE daily_date < ?1 \
+                 ORDER BY daily_date DESC, uid DESC LIMIT 1"
+            }
+            DailyDirection::Next => {
+                "SELECT daily_date, uid FROM notes WHERE daily_date > ?1 \
+                 ORDER BY daily_date ASC, uid ASC LIMIT 1"
+            }
+        };
+        let result = conn.query_row(sql, params![format_date(date)], |row| {
+            let date: String = row.get(0)?;
+            let uid: String = row.get(1)?;
+            Ok((date, uid))
+        });
+
+        match result {
+            Ok((date, uid)) => Ok(NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .ok()
+                .map(|date| DailyNoteEntry { date, uid })),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// フォルダごとのノート数（フォルダ名順）
+    pub fn list_folders(&self) -> Result<Vec<(String, usize)>, IndexError> {
+        let conn = self.conn.lock();
+
+        let mut stmt =
+            conn.prepare("SELECT folder, COUNT(*) FROM notes GROUP BY folder ORDER BY folder")?;
+        let folders = stmt
+            .query_map([], |row| {
+                let folder: String = row.get(0)?;
+                let count: i64 = row.get(1)?;
+                Ok((folder, count as usize))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(folders)
+    }
+
     /// バックリンク取得
     pub fn get_backlinks(&self, uid: &str) -> Result<Vec<IndexedBacklink>, IndexError> {
         let conn = self.conn.lock();
@@ -522,42 +998,67 @@ impl SqliteIndex {
 
     /// 孤立したインデックスエントリを削除
     ///
-    /// ファイルが存在しないエントリを削除し、削除数を返す
-    pub fn remove_orphans(&self, base_dir: &Path) -> Result<usize, IndexError> {
+    /// 同期時に保存ディレクトリで見つかったノート（`found` の UID）以外を削除し、削除数を返す。
+    /// ファイルが削除されたもの、保存ディレクトリの変更前を指すもの、
+    /// 相対パスの先が別のファイルになったものが対象になる。
+    /// 読み込み・解析に失敗したファイル（`unreadable`）を指すエントリは、ファイルが残っているため削除しない。
+    pub fn remove_orphans(
+        &self,
+        found: &HashSet<String>,
+        unreadable: &HashSet<PathBuf>,
+    ) -> Result<usize, IndexError> {
         let conn = self.conn.lock();
 
         // 全エントリを取得
         let mut stmt = conn.prepare("SELECT uid, file_path FROM notes")?;
-        let entries: Vec<(String, PathBuf)> = stmt
-            .query_map([], |row| {
-                let uid: String = row.get(0)?;
-                let path: String = row.get(1)?;
-                Ok((uid, PathBuf::from(path)))
-            })?
+        let orphans: Vec<String> = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
             .filter_map(|r| r.ok())
+            .filter(|(uid, path)| !found.contains(uid) && !unreadable.contains(&self.resolve_path(path)))
+            .map(|(uid, _)| uid)
             .collect();
 
-        let mut removed = 0;
+        for uid in &orphans {
+            // 削除（lockを解放してからは呼べないので、直接SQLを実行）
+            conn.execute("DELETE FROM title_index WHERE uid = ?1", params![uid])?;
+            conn.execute("DELETE FROM backlinks WHERE source_uid = ?1", params![uid])?;
+            conn.execute("DELETE FROM notes_fts WHERE uid = ?1", params![uid])?;
+            conn.execute("DELETE FROM manual_order WHERE uid = ?1", params![uid])?;
+            conn.execute("DELETE FROM notes WHERE uid = ?1", params![uid])?;
+        }
 
-        for (uid, path) in entries {
-            // base_dirを考慮してパスが存在するか確認
-            let full_path = if path.is_absolute() {
-                path
-            } else {
-                base_dir.join(&path)
-            };
+        Ok(orphans.len())
+    }
 
-            if !full_path.exists() {
-                // 削除（lockを解放してからは呼べないので、直接SQLを実行）
-                conn.execute("DELETE FROM title_index WHERE uid = ?1", params![uid])?;
-                conn.execute("DELETE FROM backlinks WHERE source_uid = ?1", params![uid])?;
-                conn.execute("DELETE FROM notes_fts WHERE uid = ?1", params![uid])?;
-                conn.execute("DELETE FROM notes WHERE uid = ?1", params![uid])?;
-                removed += 1;
-            }
+    /// 同じ UID を持つファイルの記録を置き換える（同期時に呼ぶ）
+    pub fn replace_duplicates(&self, files: &[DuplicateFile]) -> Result<(), IndexError> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM duplicate_files", [])?;
+        for file in files {
+            tx.execute(
+                "INSERT OR REPLACE INTO duplicate_files (file_path, uid) VALUES (?1, ?2)",
+                params![self.stored_path(&file.path), file.uid],
+            )?;
         }
+        tx.commit()?;
+        Ok(())
+    }
 
-        Ok(removed)
+    /// 同じ UID を持つファイル一覧（UID・パスの順、パスは基準ディレクトリと結合）
+    pub fn list_duplicates(&self) -> Result<Vec<DuplicateFile>, IndexError> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare("SELECT uid, file_path FROM duplicate_files ORDER BY uid, file_path")?;
+        let files = stmt
+            .query_map([], |row| {
+                let path: String = row.get(1)?;
+                Ok(DuplicateFile {
+                    uid: row.get(0)?,
+                    path: self.resolve_path(&path),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(files)
     }
 
     /// インデックスの完全再構築
@@ -580,28 +1081,39 @@ impl SqliteIndex {
 
         // バルクインサート
         for note in notes {
+            let file_path = self.stored_path(&note.file_path);
+            let content = if note.encrypted { "" } else { note.content.as_str() };
             conn.execute(
-                "INSERT INTO notes (uid, title, file_path, content_hash, created_at, updated_at, indexed_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                "INSERT INTO notes (uid, title, file_path, content_hash, created_at, updated_at, indexed_at, title_sort, word_count, file_size, pinned, favorite, daily_date, path_key, folder, encrypted)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
                 params![
                     note.uid,
                     note.title,
-                    note.file_path.to_string_lossy().to_string(),
+                    file_path,
                     note.content_hash,
                     format_datetime(&note.created_at),
                     format_datetime(&note.updated_at),
                     now,
+                    title_sort_key(&note.title),
+                    note.word_count as i64,
+                    note.file_size as i64,
+                    note.pinned,
+                    note.favorite,
+                    note.daily_date.map(format_date),
+                    path_key(Path::new(&file_path)),
+                    note.folder,
+                    note.encrypted,
                 ],
             )?;
 
             // FTS
             conn.execute(
                 "INSERT INTO notes_fts (uid, title, content) VALUES (?1, ?2, ?3)",
-                params![note.uid, note.title, note.content],
+                params![note.uid, note.title, content],
             )?;
 
             // バックリンク
-            self.update_backlinks_internal(&conn, &note.uid, &note.content)?;
+            self.update_backlinks_internal(&conn, &note.uid, content)?;
 
             // タイトルインデックス
             let title_normalized = note.title.to_lowercase();
@@ -616,11 +1128,19 @@ impl SqliteIndex {
         Ok(())
     }
 
-    /// 再構築が必要か判定（DBが空の場合）
+    /// 再構築が必要か判定
+    ///
+    /// DBが空の場合、またはマイグレーションで再同期が必要とマークされた行
+    /// （content_hash が空）がある場合に true を返す。
     pub fn needs_rebuild(&self) -> Result<bool, IndexError> {
         let conn = self.conn.lock();
         let count: i64 = conn.query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))?;
-        Ok(count == 0)
+        let stale: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM notes WHERE content_hash = ''",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count == 0 || stale > 0)
     }
 
     /// バックリンクの内部更新
@@ -654,11 +1174,149 @@ impl SqliteIndex {
     }
 }
 
+/// notes テーブルの行を NoteListItem に変換
+///
+/// 列順: uid, title, file_path, updated_at, pinned, favorite, folder, encrypted
+fn row_to_list_item(row: &rusqlite::Row<'_>) -> rusqlite::Result<NoteListItem> {
+    let uid: String = row.get(0)?;
+    let title: String = row.get(1)?;
+    let file_path: String = row.get(2)?;
+    let updated_at_str: String = row.get(3)?;
+
+    Ok(NoteListItem {
+        uid,
+        title,
+        path: PathBuf::from(file_path),
+        updated_at: parse_datetime(&updated_at_str),
+        pinned: row.get(4)?,
+        favorite: row.get(5)?,
+        folder: row.get(6)?,
+        encrypted: row.get(7)?,
+    })
+}
+
+/// notes テーブルの行を GalleryNote に変換
+///
+/// 列順: uid, title, preview, tags_json, created_at, updated_at, pinned, favorite, folder, encrypted
+fn row_to_gallery_note(row: &rusqlite::Row<'_>) -> rusqlite::Result<GalleryNote> {
+    let tags_json: String = row.get(3)?;
+    let created_at_str: String = row.get(4)?;
+    let updated_at_str: String = row.get(5)?;
+
+    Ok(GalleryNote {
+        uid: row.get(0)?,
+        title: row.get(1)?,
+        preview: row.get(2)?,
+        tags: serde_json::from_str(&tags_json).unwrap_or_default(),
+        created_at: parse_datetime(&created_at_str),
+        updated_at: parse_datetime(&updated_at_str),
+        pinned: row.get(6)?,
+        favorite: row.get(7)?,
+        folder: row.get(8)?,
+        encrypted: row.get(9)?,
+    })
+}
+
+/// 一覧取得時の列（row_to_list_item の列順と一致させる）
+const LIST_COLUMNS: &str =
+    "uid, title, file_path, updated_at, pinned, favorite, folder, encrypted";
+
+/// ギャラリー取得時の列（row_to_gallery_note の列順と一致させる）
+const GALLERY_COLUMNS: &str =
+    "uid, title, preview, tags_json, created_at, updated_at, pinned, favorite, folder, encrypted";
+
+/// バックリンク数を求めるSQL式（notes の行ごとに評価）
+const BACKLINK_COUNT_EXPR: &str = "(SELECT COUNT(DISTINCT b.source_uid)
+     FROM backlinks b
+     JOIN title_index t ON t.title_normalized = b.target_title
+     WHERE t.uid = notes.uid)";
+
+/// 手動ソート位置を求めるSQL式（未設定は末尾）
+const MANUAL_ORDER_EXPR: &str =
+    "COALESCE((SELECT m.position FROM manual_order m WHERE m.uid = notes.uid), 2147483647)";
+
+/// ソート順ごとのSQL表現
+struct SortSpec {
+    /// ソート値を表すSQL式（固定文字列のみ、ユーザー入力は含まない）
+    expr: &'static str,
+    ascending: bool,
+    numeric: bool,
+}
+
+impl SortSpec {
+    fn of(sort: NoteSortOrder) -> Self {
+        let expr = match sort {
+            NoteSortOrder::UpdatedAt => "updated_at",
+            NoteSortOrder::CreatedAt => "created_at",
+            NoteSortOrder::Title => "title_sort",
+            NoteSortOrder::BacklinkCount => BACKLINK_COUNT_EXPR,
+            NoteSortOrder::WordCount => "word_count",
+            NoteSortOrder::FileSize => "file_size",
+            NoteSortOrder::Manual => MANUAL_ORDER_EXPR,
+        };
+        Self {
+            expr,
+            ascending: sort.is_ascending(),
+            numeric: sort.is_numeric(),
+        }
+    }
+
+    fn direction(&self) -> &'static str {
+        if self.ascending {
+            "ASC"
+        } else {
+            "DESC"
+        }
+    }
+}
+
+/// 絞り込み条件を追加
+fn push_filter_conditions(
+    filter: &NoteFilter,
+    conditions: &mut Vec<String>,
+    values: &mut Vec<Value>,
+) {
+    if let Some(tag) = filter.tag {
+        conditions.push(
+            "EXISTS (SELECT 1 FROM json_each(notes.tags_json) WHERE json_each.value = ?)"
+                .to_string(),
+        );
+        values.push(Value::Text(tag.to_string()));
+    }
+    // フォルダ自身とサブフォルダ（`""` はすべて）
+    if let Some(folder) = filter.folder.filter(|folder| !folder.is_empty()) {
+        conditions.push("(folder = ? OR substr(folder, 1, ?) = ?)".to_string());
+        let prefix = format!("{}/", folder);
+        values.push(Value::Text(folder.to_string()));
+        values.push(Value::Integer(prefix.chars().count() as i64));
+        values.push(Value::Text(prefix));
+    }
+}
+
+/// 条件リストから WHERE 句を組み立てる
+fn where_clause(conditions: &[String]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    }
+}
+
 /// 日時をフォーマット
 fn format_datetime(dt: &DateTime<Utc>) -> String {
     dt.format("%Y-%m-%d %H:%M:%S").to_string()
 }
 
+/// ファイル名の衝突判定用に正規化したパス（Unicode の大文字小文字を区別しない）
+pub fn path_key(path: &Path) -> String {
+    path.to_string_lossy().to_lowercase()
+}
+
+/// 日付をフォーマット（デイリーノート用）
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
 /// 日時をパース
 fn parse_datetime(s: &str) -> DateTime<Utc> {
     NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
@@ -685,6 +1343,13 @@ mod tests {
             content_hash: compute_hash(content),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            word_count: content.split_whitespace().count(),
+            file_size: content.len() as u64,
+            pinned: false,
+            favorite: false,
+            daily_date: None,
+            folder: String::new(),
+            encrypted: false,
         }
     }
 
@@ -743,6 +1408,35 @@ mod tests {
         assert_eq!(backlinks[0].source_title, "Source Note");
     }
 
+    #[test]
+    fn test_encrypted_note_content_is_not_indexed() {
+        let index = SqliteIndex::open_in_memory().unwrap();
+        let target = create_test_note("001", "Target Note", "# Target Note");
+        let mut secret = create_test_note("002", "002", "# Secret\n\n[[Target Note]] pin 1234");
+        secret.encrypted = true;
+
+        index.upsert_note(&target).unwrap();
+        index
+            .upsert_note_with_gallery(&secret, "Secret pin 1234", &[])
+            .unwrap();
+
+        assert!(index.get_backlinks("001").unwrap().is_empty());
+        let gallery = index
+            .list_gallery_notes(NoteSortOrder::UpdatedAt, &NoteFilter::default())
+            .unwrap();
+        let item = gallery.iter().find(|note| note.uid == "002").unwrap();
+        assert!(item.encrypted);
+        assert!(item.preview.is_empty());
+
+        let fts_content: String = index
+            .conn
+            .lock()
+            .query_row("SELECT content FROM notes_fts WHERE uid = '002'", [], |row| row.get(0))
+            .unwrap();
+        assert!(fts_content.is_empty());
+        assert!(index.get_note_by_uid("002").unwrap().unwrap().encrypted);
+    }
+
     #[test]
     fn test_delete_note() {
         let index = SqliteIndex::open_in_memory().unwrap();
@@ -800,4 +1494,320 @@ mod tests {
         let (items, _) = index.list_notes(9, 10).unwrap();
         assert_eq!(items.len(), 1);
     }
+
+    #[test]
+    fn test_list_notes_page_cursor() {
+        let index = SqliteIndex::open_in_memory().unwrap();
+
+        // 同一タイムスタンプでも uid で順序が決まることを確認
+        for i in 0..7 {
+            let note = create_test_note(
+                &format!("{:03}", i),
+                &format!("Note {}", i),
+                &format!("Content {}", i),
+            );
+            index.upsert_note(&note).unwrap();
+        }
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = index
+                .list_notes_page(NoteSortOrder::UpdatedAt, &NoteFilter::default(), cursor.as_ref(), 3)
+                .unwrap();
+            assert_eq!(page.total, 7);
+            seen.extend(page.items.into_iter().map(|item| item.uid));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        let expected: Vec<String> = (0..7).rev().map(|i| format!("{:03}", i)).collect();
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn test_list_gallery_page_with_tag_filter() {
+        let index = SqliteIndex::open_in_memory().unwrap();
+
+        for i in 0..5 {
+            let note = create_test_note(
+                &format!("{:03}", i),
+                &format!("Note {}", i),
+                &format!("Content {}", i),
+            );
+            let tags = if i % 2 == 0 {
+                vec!["even".to_string()]
+            } else {
+                vec!["odd".to_string()]
+            };
+            index.upsert_note_with_gallery(&note, "", &tags).unwrap();
+        }
+
+        let filter = NoteFilter {
+            tag: Some("even"),
+            ..Default::default()
+        };
+        let page = index
+            .list_gallery_page(NoteSortOrder::UpdatedAt, &filter, None, 2)
+            .unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.items[0].uid, "004");
+        assert_eq!(page.items[1].uid, "002");
+
+        let page = index
+            .list_gallery_page(
+                NoteSortOrder::UpdatedAt,
+                &filter,
+                page.next_cursor.as_ref(),
+                2,
+            )
+            .unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].uid, "000");
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_gallery_sort_by_title() {
+        let index = SqliteIndex::open_in_memory().unwrap();
+
+        index.upsert_note(&create_test_note("001", "さくら", "")).unwrap();
+        index.upsert_note(&create_test_note("002", "カメラ", "")).unwrap();
+        index.upsert_note(&create_test_note("003", "あめ", "")).unwrap();
+
+        let notes = index.list_gallery_notes(NoteSortOrder::Title, &NoteFilter::default()).unwrap();
+        let titles: Vec<&str> = notes.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(titles, vec!["あめ", "カメラ", "さくら"]);
+    }
+
+    #[test]
+    fn test_gallery_page_sort_by_word_count() {
+        let index = SqliteIndex::open_in_memory().unwrap();
+
+        index.upsert_note(&create_test_note("001", "A", "one")).unwrap();
+        index.upsert_note(&create_test_note("002", "B", "one two three")).unwrap();
+        index.upsert_note(&create_test_note("003", "C", "one two")).unwrap();
+
+        let page = index
+            .list_gallery_page(NoteSortOrder::WordCount, &NoteFilter::default(), None, 2)
+            .unwrap();
+        let uids: Vec<&str> = page.items.iter().map(|n| n.uid.as_str()).collect();
+        assert_eq!(uids, vec!["002", "003"]);
+        assert_eq!(page.next_cursor.as_ref().unwrap().sort_key, "2");
+
+        let page = index
+            .list_gallery_page(NoteSortOrder::WordCount, &NoteFilter::default(), page.next_cursor.as_ref(), 2)
+            .unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].uid, "001");
+    }
+
+    #[test]
+    fn test_gallery_sort_by_backlink_count() {
+        let index = SqliteIndex::open_in_memory().unwrap();
+
+        index.upsert_note(&create_test_note("001", "Hub", "")).unwrap();
+        index.upsert_note(&create_test_note("002", "Leaf", "")).unwrap();
+        index
+            .upsert_note(&create_test_note("003", "S1", "[[Hub]] [[Leaf]]"))
+            .unwrap();
+        index.upsert_note(&create_test_note("004", "S2", "[[hub]]")).unwrap();
+
+        let notes = index
+            .list_gallery_notes(NoteSortOrder::BacklinkCount, &NoteFilter::default())
+            .unwrap();
+        assert_eq!(notes[0].uid, "001");
+        assert_eq!(notes[1].uid, "002");
+    }
+
+    #[test]
+    fn test_gallery_sort_manual() {
+        let index = SqliteIndex::open_in_memory().unwrap();
+
+        for i in 1..=4 {
+            let uid = format!("{:03}", i);
+            index.upsert_note(&create_test_note(&uid, &uid, "")).unwrap();
+        }
+        index
+            .set_manual_order(&["002".to_string(), "004".to_string()])
+            .unwrap();
+
+        let notes = index.list_gallery_notes(NoteSortOrder::Manual, &NoteFilter::default()).unwrap();
+        let uids: Vec<&str> = notes.iter().map(|n| n.uid.as_str()).collect();
+        // 手動順 → 未設定（新しい順）
+        assert_eq!(uids, vec!["002", "004", "003", "001"]);
+    }
+
+    #[test]
+    fn test_pinned_notes_come_first() {
+        let index = SqliteIndex::open_in_memory().unwrap();
+
+        let base = Utc::now();
+        for i in 1..=5 {
+            let uid = format!("{:03}", i);
+            let mut note = create_test_note(&uid, &uid, "");
+            note.updated_at = base + chrono::Duration::seconds(i);
+            note.pinned = i == 2 || i == 4;
+            index.upsert_note(&note).unwrap();
+        }
+
+        let all = index.list_all_notes().unwrap();
+        let uids: Vec<&str> = all.iter().map(|n| n.uid.as_str()).collect();
+        assert_eq!(uids, vec!["004", "002", "005", "003", "001"]);
+
+        // ピン留めの境界をまたいでもカーソルで正しく続きを取得できる
+        let first = index
+            .list_notes_page(NoteSortOrder::UpdatedAt, &NoteFilter::default(), None, 3)
+            .unwrap();
+        let cursor = first.next_cursor.unwrap();
+        assert!(!cursor.pinned);
+        let second = index
+            .list_notes_page(NoteSortOrder::UpdatedAt, &NoteFilter::default(), Some(&cursor), 3)
+            .unwrap();
+        let uids: Vec<&str> = first
+            .items
+            .iter()
+            .chain(second.items.iter())
+            .map(|n| n.uid.as_str())
+            .collect();
+        assert_eq!(uids, vec!["004", "002", "005", "003", "001"]);
+    }
+
+    #[test]
+    fn test_list_daily_notes() {
+        let index = SqliteIndex::open_in_memory().unwrap();
+        let date = |d| NaiveDate::from_ymd_opt(2026, 1, d).unwrap();
+
+        for (uid, day) in [("001", 3), ("002", 1), ("003", 2)] {
+            let mut note = create_test_note(uid, uid, "");
+            note.daily_date = Some(date(day));
+            index.upsert_note(&note).unwrap();
+        }
+        index.upsert_note(&create_test_note("004", "通常", "")).unwrap();
+
+        let all: Vec<_> = index
+            .list_daily_notes(None, None)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.uid)
+            .collect();
+        assert_eq!(all, vec!["002", "003", "001"]);
+
+        let range = index.list_daily_notes(Some(date(2)), Some(date(2))).unwrap();
+        assert_eq!(range, vec![DailyNoteEntry { date: date(2), uid: "003".to_string() }]);
+    }
+
+    #[test]
+    fn test_adjacent_daily_note() {
+        let index = SqliteIndex::open_in_memory().unwrap();
+        let date = |d| NaiveDate::from_ymd_opt(2026, 1, d).unwrap();
+
+        for (uid, day) in [("001", 1), ("002", 5), ("003", 9)] {
+            let mut note = create_test_note(uid, uid, "");
+            note.daily_date = Some(date(day));
+            index.upsert_note(&note).unwrap();
+        }
+
+        let adjacent = |d, direction| {
+            index
+                .adjacent_daily_note(date(d), direction)
+                .unwrap()
+                .map(|e| e.uid)
+        };
+        assert_eq!(adjacent(5, DailyDirection::Previous).as_deref(), Some("001"));
+        assert_eq!(adjacent(5, DailyDirection::Next).as_deref(), Some("003"));
+        assert_eq!(adjacent(7, DailyDirection::Previous).as_deref(), Some("002"));
+        assert_eq!(adjacent(1, DailyDirection::Previous), None);
+        assert_eq!(adjacent(9, DailyDirection::Next), None);
+    }
+
+    #[test]
+    fn test_is_path_taken_ignores_case() {
+        let index = SqliteIndex::open_in_memory().unwrap();
+        let mut note = create_test_note("001", "Plan", "# Plan");
+        note.file_path = PathBuf::from("/notes/Plan Ä.md");
+        index.upsert_note(&note).unwrap();
+
+        assert!(index.is_path_taken(Path::new("/notes/Plan Ä.md")).unwrap());
+        assert!(index.is_path_taken(Path::new("/notes/plan ä.md")).unwrap());
+        assert!(!index.is_path_taken(Path::new("/notes/Plan_2.md")).unwrap());
+    }
+
+    #[test]
+    fn test_paths_are_stored_relative_to_root() {
+        let index = SqliteIndex::open_in_memory().unwrap();
+        index.set_root(Path::new("/notes"));
+        let mut note = create_test_note("001", "Plan", "# Plan");
+        note.file_path = PathBuf::from("/notes/work/Plan.md");
+        index.upsert_note(&note).unwrap();
+        let mut outside = create_test_note("002", "Elsewhere", "# Elsewhere");
+        outside.file_path = PathBuf::from("/elsewhere/Elsewhere.md");
+        index.upsert_note(&outside).unwrap();
+
+        let stored: Vec<String> = {
+            let conn = index.conn.lock();
+            let mut stmt = conn.prepare("SELECT file_path FROM notes ORDER BY uid").unwrap();
+            let rows = stmt.query_map([], |row| row.get(0)).unwrap();
+            rows.map(|r| r.unwrap()).collect()
+        };
+        assert_eq!(stored, ["work/Plan.md", "/elsewhere/Elsewhere.md"]);
+
+        // 別の場所にマウントしても相対パスはそのまま使える
+        index.set_root(Path::new("/mnt/notes"));
+        let moved = PathBuf::from("/mnt/notes/work/Plan.md");
+        assert_eq!(index.get_path("001").unwrap(), Some(moved.clone()));
+        assert_eq!(index.get_note_by_uid("001").unwrap().unwrap().path, moved);
+        assert!(index.is_path_taken(Path::new("/mnt/notes/WORK/plan.md")).unwrap());
+        assert_eq!(
+            index.get_path("002").unwrap(),
+            Some(PathBuf::from("/elsewhere/Elsewhere.md"))
+        );
+    }
+
+    #[test]
+    fn test_folder_filter_and_list_folders() {
+        let index = SqliteIndex::open_in_memory().unwrap();
+        for (uid, folder) in [("001", ""), ("002", "work"), ("003", "work/2026"), ("004", "workshop")] {
+            let mut note = create_test_note(uid, uid, "");
+            note.folder = folder.to_string();
+            index.upsert_note(&note).unwrap();
+        }
+
+        let filter = NoteFilter {
+            folder: Some("work"),
+            ..Default::default()
+        };
+        let mut uids: Vec<String> = index
+            .list_gallery_notes(NoteSortOrder::UpdatedAt, &filter)
+            .unwrap()
+            .into_iter()
+            .map(|note| note.uid)
+            .collect();
+        uids.sort();
+        assert_eq!(uids, vec!["002", "003"]);
+
+        // 直下（""）はすべてのノート
+        let filter = NoteFilter {
+            folder: Some(""),
+            ..Default::default()
+        };
+        let page = index
+            .list_notes_page(NoteSortOrder::UpdatedAt, &filter, None, 10)
+            .unwrap();
+        assert_eq!(page.total, 4);
+
+        let folders = index.list_folders().unwrap();
+        assert_eq!(
+            folders,
+            vec![
+                (String::new(), 1),
+                ("work".to_string(), 1),
+                ("work/2026".to_string(), 1),
+                ("workshop".to_string(), 1),
+            ]
+        );
+    }
 }
@@ -0,0 +1,73 @@
+//! ミラーとの同期状態のストア
+//!
+//! 前回の同期時点の各ファイルの `content_hash`（`SyncState`）を保管庫のデータディレクトリの
+//! `sync_state.json` に保存する。ミラー側には置かないため、同じミラーを複数の PC で共有しても
+//! それぞれの PC が自分の同期時点を持つ。
+
+use crate::domain::SyncState;
+use crate::traits::{Storage, StorageError};
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// 同期状態のファイル名
+const STATE_FILE_NAME: &str = "sync_state.json";
+
+/// ファイルベースの同期状態ストア
+pub struct SyncStateStore {
+    path: PathBuf,
+    storage: Arc<dyn Storage>,
+}
+
+impl SyncStateStore {
+    /// 保管庫のデータディレクトリに置くストア
+    pub fn new(data_dir: PathBuf, storage: Arc<dyn Storage>) -> Self {
+        Self {
+            path: data_dir.join(STATE_FILE_NAME),
+            storage,
+        }
+    }
+
+    /// 同期状態を読み込む（まだ同期していなければ空）
+    pub fn load(&self) -> Result<SyncState, StorageError> {
+        if !self.storage.exists(&self.path) {
+            return Ok(SyncState::default());
+        }
+        let json = self.storage.load(&self.path)?;
+        serde_json::from_str(&json).map_err(invalid_data)
+    }
+
+    /// 同期状態を保存
+    pub fn save(&self, state: &SyncState) -> Result<(), StorageError> {
+        let json = serde_json::to_string_pretty(state).map_err(invalid_data)?;
+        self.storage.save_atomic(&self.path, &json)
+    }
+}
+
+fn invalid_data(e: serde_json::Error) -> StorageError {
+    StorageError::Io(io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::FileStorage;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SyncStateStore::new(temp_dir.path().to_path_buf(), Arc::new(FileStorage::new()));
+        assert_eq!(store.load().unwrap(), SyncState::default());
+
+        let mut state = SyncState::default().for_mirror(Path::new("/mnt/usb/notes"));
+        state.files.insert("仕事/メモ.md".to_string(), "abc".to_string());
+        store.save(&state).unwrap();
+
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded, state);
+        // 別のミラーとの同期では記録を使わない
+        assert!(loaded.for_mirror(Path::new("/mnt/nas")).files.is_empty());
+    }
+}
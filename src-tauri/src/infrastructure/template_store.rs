@@ -0,0 +1,84 @@
+//! テンプレートストア
+//!
+//! テンプレートは設定ディレクトリ配下の `templates/{name}.md` に置く。
+//! ノートの保存ディレクトリとは分けているため、ノート一覧には表示されない。
+
+use crate::domain::NoteTemplate;
+use crate::traits::{Storage, StorageError};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// テンプレートファイルの拡張子
+const TEMPLATE_EXTENSION: &str = "md";
+
+/// ファイルベースのテンプレートストア
+pub struct TemplateStore {
+    root: PathBuf,
+    storage: Arc<dyn Storage>,
+}
+
+impl TemplateStore {
+    pub fn new(root: PathBuf, storage: Arc<dyn Storage>) -> Self {
+        Self { root, storage }
+    }
+
+    /// テンプレートフォルダのパス
+    pub fn root(&self) -> &PathBuf {
+        &self.root
+    }
+
+    fn template_path(&self, name: &str) -> PathBuf {
+        self.root.join(format!("{}.{}", name, TEMPLATE_EXTENSION))
+    }
+
+    /// テンプレート名の一覧（名前順）
+    pub fn list(&self) -> Result<Vec<String>, StorageError> {
+        let mut names: Vec<String> = self
+            .storage
+            .list_files(&self.root, TEMPLATE_EXTENSION)?
+            .iter()
+            .filter_map(|path| path.file_stem())
+            .map(|stem| stem.to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// テンプレートを読み込み
+    pub fn load(&self, name: &str) -> Result<NoteTemplate, StorageError> {
+        let path = self.template_path(name);
+        if !is_valid_name(name) {
+            return Err(StorageError::NotFound(path));
+        }
+        let source = self.storage.load(&path)?;
+        Ok(NoteTemplate::new(name, source))
+    }
+}
+
+/// テンプレート名の検証（パストラバーサル防止）
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 100
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\', '\0'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::FileStorage;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_list_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = TemplateStore::new(temp_dir.path().to_path_buf(), Arc::new(FileStorage::new()));
+
+        std::fs::write(temp_dir.path().join("日報.md"), "# {{date}}").unwrap();
+        std::fs::write(temp_dir.path().join("会議.md"), "# {{title}}").unwrap();
+
+        assert_eq!(store.list().unwrap(), vec!["会議", "日報"]);
+        assert_eq!(store.load("日報").unwrap().source, "# {{date}}");
+        assert!(store.load("../日報").is_err());
+    }
+}
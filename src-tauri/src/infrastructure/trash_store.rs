@@ -0,0 +1,239 @@
+//! ゴミ箱ストア
+//!
+//! 削除されたノートを保存ディレクトリ直下の `.trash` フォルダに移動して保持する。
+//!
+//! # 構成
+//!
+//! - `.trash/{uid}.md`: 削除時のファイル内容（そのまま移動）
+//! - `.trash/{uid}.json`: 元のパスと削除日時（`TrashItem`）
+//!
+//! ファイル名に使えない文字を含む UID は `uid_file_stem` で変換した名前にする。
+//!
+//! `.trash` はドット始まりのサブフォルダのため、ノート一覧の走査対象にはならない。
+
+use crate::domain::{uid_file_stem, Note, TrashItem, TRASH_DIR_NAME};
+use crate::traits::{RepositoryError, Storage, StorageError};
+use chrono::{DateTime, Utc};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// ゴミ箱から復元するノート
+pub struct RestoreTarget {
+    pub note: Note,
+    /// ゴミ箱に保存されていたファイル内容
+    pub content: String,
+    /// 復元先のパス
+    pub path: PathBuf,
+}
+
+/// ファイルベースのゴミ箱
+pub struct TrashStore {
+    storage: Arc<dyn Storage>,
+}
+
+impl TrashStore {
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self { storage }
+    }
+
+    /// ゴミ箱フォルダのパス
+    pub fn trash_dir(base_dir: &Path) -> PathBuf {
+        base_dir.join(TRASH_DIR_NAME)
+    }
+
+    fn content_path(base_dir: &Path, uid: &str) -> PathBuf {
+        Self::trash_dir(base_dir).join(format!("{}.md", uid_file_stem(uid)))
+    }
+
+    fn meta_path(base_dir: &Path, uid: &str) -> PathBuf {
+        Self::trash_dir(base_dir).join(format!("{}.json", uid_file_stem(uid)))
+    }
+
+    /// ノートファイルをゴミ箱へ移動
+    ///
+    /// 同じUIDのアイテムが既にある場合（復元後に再削除など）は置き換える。
+    pub fn move_to_trash(
+        &self,
+        base_dir: &Path,
+        path: &Path,
+        uid: &str,
+        title: &str,
+    ) -> Result<TrashItem, StorageError> {
+        let original_path = path
+            .strip_prefix(base_dir)
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|_| PathBuf::from(path.file_name().unwrap_or_default()));
+
+        let item = TrashItem {
+            uid: uid.to_string(),
+            title: title.to_string(),
+            original_path,
+            deleted_at: Utc::now(),
+        };
+
+        let content_path = Self::content_path(base_dir, uid);
+        if self.storage.exists(&content_path) {
+            self.storage.delete(&content_path)?;
+        }
+        self.storage.rename(path, &content_path)?;
+
+        let meta = serde_json::to_string_pretty(&item)
+            .map_err(|e| StorageError::Io(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+        self.storage
+            .save_atomic(&Self::meta_path(base_dir, uid), &meta)?;
+
+        Ok(item)
+    }
+
+    /// ノートファイルを削除（ゴミ箱へ移動。本文が空のメモは残す価値がないため完全に削除）
+    ///
+    /// リポジトリの削除で共通に使う。読み込めないファイルも UID を題にしてゴミ箱へ移す。
+    pub fn discard(&self, base_dir: &Path, path: &Path, uid: &str) -> Result<(), RepositoryError> {
+        let note = self
+            .storage
+            .load(path)
+            .ok()
+            .and_then(|content| Note::from_file_content(&content).ok());
+
+        match note {
+            Some(note) if note.content.trim().is_empty() => self.storage.delete(path)?,
+            note => {
+                let title = note
+                    .and_then(|n| n.extract_heading())
+                    .unwrap_or_else(|| uid.to_string());
+                self.move_to_trash(base_dir, path, uid, &title)
+                    .map_err(|e| RepositoryError::storage("move_to_trash", e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 復元するノートと復元先のパスを取得（ゴミ箱からは削除しない）
+    ///
+    /// 元の場所が空いていればそこへ、使用中なら `generate_path` で同じフォルダに新しいファイル名を付ける。
+    /// リポジトリの復元で共通に使い、書き込んだ後に `remove` でゴミ箱から取り除く。
+    pub fn restore_target(
+        &self,
+        base_dir: &Path,
+        uid: &str,
+        generate_path: impl FnOnce(&Note, &Path) -> PathBuf,
+    ) -> Result<RestoreTarget, RepositoryError> {
+        let (item, content) = self.get(base_dir, uid).map_err(|e| match e {
+            StorageError::NotFound(_) => RepositoryError::not_found(uid),
+            e => RepositoryError::storage("trash_get", e),
+        })?;
+
+        let note = Note::from_file_content(&content).map_err(|_| {
+            RepositoryError::parse("Invalid note format in trash", Some(item.original_path.clone()))
+        })?;
+
+        let original_path = base_dir.join(&item.original_path);
+        let path = if self.storage.exists(&original_path) {
+            let dir = original_path.parent().unwrap_or(base_dir);
+            generate_path(&note, dir)
+        } else {
+            original_path
+        };
+
+        Ok(RestoreTarget { note, content, path })
+    }
+
+    /// ゴミ箱内のアイテム一覧（削除日時の新しい順）
+    ///
+    /// メタデータが壊れている、または本体のないアイテムは無視する。
+    pub fn list(&self, base_dir: &Path) -> Result<Vec<TrashItem>, StorageError> {
+        let files = self
+            .storage
+            .list_files(&Self::trash_dir(base_dir), "json")?;
+
+        let mut items: Vec<TrashItem> = files
+            .iter()
+            .filter_map(|path| self.storage.load(path).ok())
+            .filter_map(|json| serde_json::from_str::<TrashItem>(&json).ok())
+            .filter(|item| self.storage.exists(&Self::content_path(base_dir, &item.uid)))
+            .collect();
+
+        items.sort_by_key(|item| std::cmp::Reverse(item.deleted_at));
+        Ok(items)
+    }
+
+    /// アイテムとファイル内容を取得（ゴミ箱からは削除しない）
+    pub fn get(&self, base_dir: &Path, uid: &str) -> Result<(TrashItem, String), StorageError> {
+        let meta_path = Self::meta_path(base_dir, uid);
+        let json = self.storage.load(&meta_path)?;
+        let item: TrashItem = serde_json::from_str(&json)
+            .map_err(|e| StorageError::Io(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+        let content = self.storage.load(&Self::content_path(base_dir, uid))?;
+        Ok((item, content))
+    }
+
+    /// アイテムをゴミ箱から完全に削除
+    pub fn remove(&self, base_dir: &Path, uid: &str) -> Result<(), StorageError> {
+        for path in [
+            Self::content_path(base_dir, uid),
+            Self::meta_path(base_dir, uid),
+        ] {
+            if self.storage.exists(&path) {
+                self.storage.delete(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 指定日時より前に削除されたアイテムを完全に削除（`None` は全件）
+    ///
+    /// 削除した件数を返す。
+    pub fn purge(
+        &self,
+        base_dir: &Path,
+        deleted_before: Option<DateTime<Utc>>,
+    ) -> Result<usize, StorageError> {
+        let mut purged = 0;
+        for item in self.list(base_dir)? {
+            if deleted_before.is_none_or(|cutoff| item.deleted_at < cutoff) {
+                self.remove(base_dir, &item.uid)?;
+                purged += 1;
+            }
+        }
+        Ok(purged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::FileStorage;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_move_list_and_purge() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path();
+        let store = TrashStore::new(Arc::new(FileStorage::new()));
+
+        let path = base_dir.join("メモ.md");
+        std::fs::write(&path, "---\nuid: 1\n---\n\n# メモ").unwrap();
+
+        let item = store
+            .move_to_trash(base_dir, &path, "20260101000000", "メモ")
+            .unwrap();
+        assert!(!path.exists());
+        assert_eq!(item.original_path, PathBuf::from("メモ.md"));
+
+        let items = store.list(base_dir).unwrap();
+        assert_eq!(items, vec![item]);
+
+        let (_, content) = store.get(base_dir, "20260101000000").unwrap();
+        assert!(content.ends_with("# メモ"));
+
+        // 削除日時より前のカットオフでは消えない
+        let purged = store
+            .purge(base_dir, Some(Utc::now() - chrono::Duration::days(1)))
+            .unwrap();
+        assert_eq!(purged, 0);
+
+        assert_eq!(store.purge(base_dir, None).unwrap(), 1);
+        assert!(store.list(base_dir).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,35 @@
+use crate::domain::Note;
+use crate::infrastructure::HeadingFilenameStrategy;
+use crate::traits::FilenameStrategy;
+
+/// UID のみのファイル名生成戦略（見出しを変えてもファイル名が変わらない）
+pub struct UidFilenameStrategy;
+
+impl UidFilenameStrategy {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for UidFilenameStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FilenameStrategy for UidFilenameStrategy {
+    fn generate(&self, note: &Note, is_taken: &dyn Fn(&str) -> bool) -> String {
+        HeadingFilenameStrategy::finish(&note.metadata.uid, is_taken)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_uid() {
+        let note = Note::with_title("買い物リスト");
+        assert_eq!(UidFilenameStrategy::new().generate(&note, &|_| false), note.metadata.uid);
+    }
+}
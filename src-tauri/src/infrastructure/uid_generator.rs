@@ -0,0 +1,93 @@
+use crate::domain::{timestamp_uid, ulid, uuid7, UidFormat};
+use crate::services::SettingsService;
+use crate::traits::UidGenerator;
+use chrono::Utc;
+use std::sync::Arc;
+
+/// タイムスタンプ形式の UID（`20261017093000123456`）
+pub struct TimestampUidGenerator;
+
+impl UidGenerator for TimestampUidGenerator {
+    fn generate(&self) -> String {
+        timestamp_uid(Utc::now())
+    }
+}
+
+/// ULID（`01J1Z9P6V9WQ7H9QXGQ2K5J1ZC`）
+pub struct UlidGenerator;
+
+impl UidGenerator for UlidGenerator {
+    fn generate(&self) -> String {
+        ulid(Utc::now(), random_bytes())
+    }
+}
+
+/// UUID バージョン7（`01929a3b-7c4d-7e8f-9a0b-1c2d3e4f5a6b`）
+pub struct Uuid7Generator;
+
+impl UidGenerator for Uuid7Generator {
+    fn generate(&self) -> String {
+        uuid7(Utc::now(), random_bytes())
+    }
+}
+
+/// 設定で選択された UID 生成戦略（`UidSettings::format`）
+pub struct ConfiguredUidGenerator {
+    settings_service: Arc<SettingsService>,
+}
+
+impl ConfiguredUidGenerator {
+    pub fn new(settings_service: Arc<SettingsService>) -> Self {
+        Self { settings_service }
+    }
+}
+
+impl UidGenerator for ConfiguredUidGenerator {
+    fn generate(&self) -> String {
+        match self.settings_service.get_arc().uids.format {
+            UidFormat::Timestamp => TimestampUidGenerator.generate(),
+            UidFormat::Ulid => UlidGenerator.generate(),
+            UidFormat::Uuid7 => Uuid7Generator.generate(),
+        }
+    }
+}
+
+/// UID の乱数部分（OS の乱数が使えない場合は現在時刻のハッシュで代用する）
+fn random_bytes() -> [u8; 10] {
+    let mut bytes = [0u8; 10];
+    if let Err(e) = getrandom::getrandom(&mut bytes) {
+        eprintln!("[UidGenerator] Failed to get random bytes: {}", e);
+        let nanos = Utc::now().timestamp_nanos_opt().unwrap_or_default();
+        bytes.copy_from_slice(&blake3::hash(&nanos.to_le_bytes()).as_bytes()[..10]);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::is_valid_uid;
+    use crate::infrastructure::EventBusImpl;
+    use crate::services::test_support;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_configured_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings_service =
+            test_support::settings_service(temp_dir.path(), Arc::new(EventBusImpl::new()));
+        let generator = ConfiguredUidGenerator::new(settings_service.clone());
+        assert!(generator.generate().bytes().all(|b| b.is_ascii_digit()));
+
+        settings_service.update(|s| s.uids.format = UidFormat::Ulid).unwrap();
+        let uid = generator.generate();
+        assert_eq!(uid.len(), 26);
+        assert!(is_valid_uid(&uid));
+        assert_ne!(generator.generate(), uid);
+
+        settings_service.update(|s| s.uids.format = UidFormat::Uuid7).unwrap();
+        let uid = generator.generate();
+        assert_eq!(uid.len(), 36);
+        assert!(is_valid_uid(&uid));
+    }
+}
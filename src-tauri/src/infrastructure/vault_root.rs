@@ -0,0 +1,41 @@
+//! 保管庫の ID と保存ディレクトリ
+//!
+//! 保管庫ごとのサービスは構築時にこのハンドルを受け取り、グローバルな設定の
+//! `storage_directory`・`vaults.active` ではなくこちらを参照する。保管庫の切り替えと重なっても、
+//! 切り替え前のサービス（終了時のコミット・同期など）は自分の保管庫を使い続ける。
+//! 同じ保管庫内での保存ディレクトリの移転では `set` で更新する。
+
+use parking_lot::RwLock;
+use std::path::{Path, PathBuf};
+
+/// 保管庫の ID と保存ディレクトリ（保管庫のサービス間で共有）
+#[derive(Debug)]
+pub struct VaultRoot {
+    id: String,
+    dir: RwLock<PathBuf>,
+}
+
+impl VaultRoot {
+    /// 保管庫の ID と保存ディレクトリを指定して作成
+    pub fn new(id: impl Into<String>, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            id: id.into(),
+            dir: RwLock::new(dir.into()),
+        }
+    }
+
+    /// 保管庫の ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 保存ディレクトリを取得
+    pub fn get(&self) -> PathBuf {
+        self.dir.read().clone()
+    }
+
+    /// 保存ディレクトリを変更（保存ディレクトリの移転時）
+    pub fn set(&self, dir: &Path) {
+        *self.dir.write() = dir.to_path_buf();
+    }
+}
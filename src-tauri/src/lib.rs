@@ -9,9 +9,12 @@ pub mod platform;
 pub mod commands;
 pub mod app_state;
 
+use domain::DomainEvent;
 use parking_lot::Mutex;
 use platform::{setup_global_hotkey, setup_tray};
-use tauri::{AppHandle, Manager};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+use traits::EventBus;
 
 // AppState を re-export
 pub use app_state::AppState;
@@ -37,6 +40,13 @@ fn toggle_window_from_ipc() {
 
 // AppState は app_state.rs に移動済み
 
+/// トレイメニューのお気に入り一覧を更新
+fn refresh_favorites(app: &AppHandle) {
+    if let Err(e) = platform::refresh_tray_menu(app) {
+        eprintln!("[Tray] Failed to refresh favorites: {}", e);
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // NVIDIA + Wayland + webkitgtk の互換性問題を回避
@@ -50,9 +60,88 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .setup(|app| {
             // アプリケーション状態を初期化
-            let state = AppState::new();
+            let state = AppState::new(Arc::new(platform::TauriClipboard::new(app.handle().clone())));
+
+            // 保存結果・ファイルの書き直し・保存ディレクトリの移転の進捗・暗号化ノートのロック・git へのコミット・バックアップの進捗・ミラーとの同期をフロントエンドへ通知（自動保存はバックエンドで行うため）
+            // ノートの更新・削除・復元とインデックスの作り直しではトレイのお気に入り一覧も更新する
+            let handle = app.handle().clone();
+            state.event_bus.subscribe(
+                "*",
+                Arc::new(move |event| match event {
+                    DomainEvent::SaveCompleted { uid } => {
+                        let _ = handle.emit("save-completed", uid);
+                    }
+                    DomainEvent::NoteUpdated { uid } => {
+                        let _ = handle.emit("note-updated", uid);
+                        refresh_favorites(&handle);
+                    }
+                    DomainEvent::NoteDeleted { .. }
+                    | DomainEvent::NoteRestored { .. }
+                    | DomainEvent::IndexRebuilt => refresh_favorites(&handle),
+                    DomainEvent::SaveFailed { uid, error, conflict } => {
+                        // 保存コマンドのエラーと同じ形（競合時は両方の版）で通知する
+                        let error = match conflict {
+                            Some(conflict) => commands::SaveErrorDto::Conflict(Box::new(
+                                commands::NoteConflictDto::from((**conflict).clone()),
+                            )),
+                            None => commands::SaveErrorDto::from(error.clone()),
+                        };
+                        let _ = handle.emit(
+                            "save-failed",
+                            serde_json::json!({ "uid": uid, "error": error }),
+                        );
+                    }
+                    DomainEvent::RelocationProgress { done, total } => {
+                        let _ = handle.emit(
+                            "relocation-progress",
+                            serde_json::json!({ "done": done, "total": total }),
+                        );
+                    }
+                    DomainEvent::NotesLocked => {
+                        let _ = handle.emit("notes-locked", ());
+                    }
+                    DomainEvent::VaultCommitted { id, changed } => {
+                        let _ = handle.emit(
+                            "vault-committed",
+                            serde_json::json!({ "id": id, "changed": changed }),
+                        );
+                    }
+                    DomainEvent::BackupProgress { restoring, done, total } => {
+                        let _ = handle.emit(
+                            "backup-progress",
+                            serde_json::json!({ "restoring": restoring, "done": done, "total": total }),
+                        );
+                    }
+                    DomainEvent::BackupFailed { error } => {
+                        let _ = handle.emit("backup-failed", error);
+                    }
+                    DomainEvent::Conflict { path, conflict_copy } => {
+                        let _ = handle.emit(
+                            "sync-conflict",
+                            serde_json::json!({ "path": path, "conflict_copy": conflict_copy }),
+                        );
+                    }
+                    DomainEvent::MirrorSynced { pulled, pushed, deleted, conflicts } => {
+                        let _ = handle.emit(
+                            "mirror-synced",
+                            serde_json::json!({
+                                "pulled": pulled,
+                                "pushed": pushed,
+                                "deleted": deleted,
+                                "conflicts": conflicts,
+                            }),
+                        );
+                    }
+                    DomainEvent::SyncFailed { error } => {
+                        let _ = handle.emit("sync-failed", error);
+                    }
+                    _ => {}
+                }),
+            );
+
             app.manage(state);
 
             // システムトレイをセットアップ
@@ -143,17 +232,25 @@ pub fn run() {
             // Note commands
             commands::note::create_note,
             commands::note::save_note,
+            commands::note::request_autosave,
             commands::note::load_note,
             commands::note::delete_note,
             commands::note::list_notes,
+            commands::note::list_notes_page,
             commands::note::search_notes,
             commands::note::resolve_wiki_link,
+            commands::note::toggle_pinned,
+            commands::note::toggle_favorite,
             // Backlink commands
             commands::backlink::get_backlinks,
             commands::backlink::rebuild_backlink_index,
             // Settings commands
             commands::settings::get_settings,
             commands::settings::update_settings,
+            commands::settings::update_view_preference,
+            commands::settings::migrate_filenames,
+            commands::settings::normalize_timestamps,
+            commands::settings::relocate_storage,
             // Window commands
             commands::window::save_window_geometry,
             commands::window::prepare_hide,
@@ -166,10 +263,63 @@ pub fn run() {
             commands::hotkey::get_current_hotkey,
             // Gallery commands
             commands::gallery::list_notes_gallery,
+            commands::gallery::list_notes_gallery_page,
+            commands::gallery::set_manual_order,
             // Tag commands
             commands::tag::get_all_tags,
             commands::tag::get_note_tags,
             commands::tag::update_note_tags,
+            // Trash commands
+            commands::trash::list_trash,
+            commands::trash::restore_note,
+            commands::trash::empty_trash,
+            // History commands
+            commands::history::list_revisions,
+            commands::history::diff_revisions,
+            commands::history::restore_revision,
+            // Draft commands
+            commands::draft::write_draft,
+            commands::draft::list_drafts,
+            commands::draft::recover_draft,
+            commands::draft::discard_draft,
+            // Template commands
+            commands::template::list_templates,
+            commands::template::create_note_from_template,
+            // Daily note commands
+            commands::daily::open_daily_note,
+            commands::daily::adjacent_daily_note,
+            commands::daily::list_daily_notes,
+            // Folder commands
+            commands::folder::get_folder_tree,
+            commands::folder::move_note,
+            // Vault commands
+            commands::vault::list_vaults,
+            commands::vault::add_vault,
+            commands::vault::remove_vault,
+            commands::vault::set_vault_mirror,
+            commands::vault::switch_vault,
+            // Encryption commands
+            commands::encryption::unlock_notes,
+            commands::encryption::lock_notes,
+            commands::encryption::get_encryption_status,
+            commands::encryption::set_note_encrypted,
+            // Versioning commands
+            commands::versioning::get_versioning_status,
+            commands::versioning::commit_vault,
+            commands::versioning::list_note_commits,
+            commands::versioning::load_note_commit,
+            commands::versioning::restore_note_commit,
+            // Backup commands
+            commands::backup::backup_now,
+            commands::backup::list_backups,
+            commands::backup::restore_backup,
+            // Mirror sync commands
+            commands::sync::sync_mirror,
+            commands::sync::list_note_conflicts,
+            commands::sync::merge_note_conflict,
+            commands::sync::resolve_note_conflict,
+            commands::sync::list_duplicate_uids,
+            commands::sync::repair_duplicate_uids,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,28 @@
+// クリップボードの読み取り（tauri-plugin-clipboard-manager）
+
+use crate::traits::Clipboard;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// システムのクリップボード
+pub struct TauriClipboard<R: Runtime> {
+    app: AppHandle<R>,
+}
+
+impl<R: Runtime> TauriClipboard<R> {
+    pub fn new(app: AppHandle<R>) -> Self {
+        Self { app }
+    }
+}
+
+impl<R: Runtime> Clipboard for TauriClipboard<R> {
+    fn read_text(&self) -> Option<String> {
+        match self.app.clipboard().read_text() {
+            Ok(text) => Some(text),
+            Err(e) => {
+                log::debug!("[Clipboard] Failed to read text: {}", e);
+                None
+            }
+        }
+    }
+}
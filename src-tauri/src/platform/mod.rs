@@ -3,14 +3,16 @@ pub mod hotkey;
 pub mod window;
 pub mod ipc;
 pub mod manager;
+pub mod clipboard;
 #[cfg(target_os = "linux")]
 pub mod hyprland;
 
-pub use tray::setup_tray;
+pub use tray::{setup_tray, refresh_tray_menu};
 pub use hotkey::{setup_global_hotkey, mark_window_hidden, mark_window_visible, is_window_visible};
 pub use window::WindowManager;
 pub use ipc::{send_command, is_instance_running, start_ipc_server, cleanup as cleanup_ipc};
 pub use manager::PlatformManager;
+pub use clipboard::TauriClipboard;
 
 // ===== オフスクリーン座標定数 =====
 // Hyprlandでウィンドウを非表示にする際、画面外に移動する座標
@@ -1,11 +1,21 @@
 use super::hotkey::{mark_window_hidden, mark_window_visible, is_window_visible};
+use crate::AppState;
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem},
+    menu::{Menu, MenuItem, PredefinedMenuItem},
     tray::{TrayIcon, TrayIconBuilder},
     AppHandle, Emitter, Manager, Runtime,
 };
 
+/// トレイアイコンのID（メニュー更新時の参照用）
+const TRAY_ID: &str = "main";
+
+/// お気に入りメニュー項目のIDプレフィックス（後ろにUIDが続く）
+const FAVORITE_ID_PREFIX: &str = "favorite:";
+
+/// トレイメニューに表示するお気に入りの最大数
+const MAX_TRAY_FAVORITES: usize = 20;
+
 // トレイアイコン画像をコンパイル時に埋め込み
 static TRAY_ICON_PNG: &[u8] = include_bytes!("../../../kaku.png");
 
@@ -19,22 +29,69 @@ fn load_tray_icon() -> Image<'static> {
     Image::new_owned(rgba, width, height)
 }
 
-/// システムトレイをセットアップ
-pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> Result<TrayIcon<R>, tauri::Error> {
-    // メニューアイテム作成
+/// トレイメニューを作成（表示/非表示、お気に入り一覧、終了）
+fn build_menu<R: Runtime>(app: &AppHandle<R>) -> Result<Menu<R>, tauri::Error> {
     let show_item = MenuItem::with_id(app, "show", "表示", true, None::<&str>)?;
     let hide_item = MenuItem::with_id(app, "hide", "非表示", true, None::<&str>)?;
     let quit_item = MenuItem::with_id(app, "quit", "終了", true, None::<&str>)?;
 
+    let menu = Menu::with_items(app, &[&show_item, &hide_item])?;
+
+    // お気に入り（AppState 初期化前は空）
+    let favorites = app
+        .try_state::<AppState>()
+        .and_then(|state| state.note_service().list_favorites().ok())
+        .unwrap_or_default();
+
+    if !favorites.is_empty() {
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+        for item in favorites.iter().take(MAX_TRAY_FAVORITES) {
+            let favorite_item = MenuItem::with_id(
+                app,
+                format!("{}{}", FAVORITE_ID_PREFIX, item.uid),
+                format!("★ {}", item.title),
+                true,
+                None::<&str>,
+            )?;
+            menu.append(&favorite_item)?;
+        }
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    menu.append(&quit_item)?;
+    Ok(menu)
+}
+
+/// トレイメニューを再作成（お気に入りの変更を反映）
+pub fn refresh_tray_menu<R: Runtime>(app: &AppHandle<R>) -> Result<(), tauri::Error> {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        tray.set_menu(Some(build_menu(app)?))?;
+    }
+    Ok(())
+}
+
+/// システムトレイをセットアップ
+pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> Result<TrayIcon<R>, tauri::Error> {
     // メニュー作成
-    let menu = Menu::with_items(app, &[&show_item, &hide_item, &quit_item])?;
+    let menu = build_menu(app)?;
 
     // トレイアイコン作成
-    let tray = TrayIconBuilder::new()
+    let tray = TrayIconBuilder::with_id(TRAY_ID)
         .icon(load_tray_icon())
         .tooltip("kaku - クリックで表示/非表示")
         .menu(&menu)
         .on_menu_event(|app, event| {
+            if let Some(uid) = event.id.as_ref().strip_prefix(FAVORITE_ID_PREFIX) {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                    mark_window_visible();
+                    let _ = window.emit("open-note", uid);
+                    println!("[Tray Menu] Opened favorite: {}", uid);
+                }
+                return;
+            }
+
             match event.id.as_ref() {
                 "show" => {
                     if let Some(window) = app.get_webview_window("main") {
@@ -54,6 +111,10 @@ pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> Result<TrayIcon<R>, tauri::
                 }
                 "quit" => {
                     println!("[Tray Menu] Quitting...");
+                    // 保存待ちの自動保存を書き出してから終了
+                    if let Some(state) = app.try_state::<AppState>() {
+                        state.autosave_service().shutdown();
+                    }
                     app.exit(0);
                 }
                 _ => {}
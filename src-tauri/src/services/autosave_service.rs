@@ -0,0 +1,671 @@
+//! 自動保存サービス
+//!
+//! フロントエンドからの保存リクエストをメモごとにキューイングし、
+//! `AutosaveSettings::delay_ms` の間に届いたリクエストを1回の保存にまとめる。
+//! 保存はバックグラウンドスレッドで行うため、WebView の状態に依存しない。
+//!
+//! 一時的なI/Oエラーはバックオフ付きで再試行し、最終的に失敗した場合は
+//! `DomainEvent::SaveFailed` を発行する（競合時は両方の版を含める）。
+//! `AutosaveSettings::enabled` が `false` の間はリクエストを受け付けない。
+//!
+//! 同じメモの保存（バックグラウンドの保存・`save_now`・`flush`）は1つずつ行い、
+//! 新しいリクエストより古い内容の再試行は破棄する（手動保存を古い内容で上書きしない）。
+//!
+//! リクエストされた内容は保存前に下書きジャーナルへ記録し、保存が完了したら削除する。
+//! 暗号化ノートは平文を残さないよう下書きを記録しない。
+
+use crate::domain::{DomainEvent, ExpectedVersion, Note};
+use crate::services::{BacklinkService, DraftService, NoteService, SettingsService};
+use crate::traits::{EventBus, RepositoryError, StorageError};
+use parking_lot::{Condvar, Mutex, MutexGuard};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 一時的なエラーの最大再試行回数
+const MAX_RETRIES: u32 = 5;
+
+/// 再試行間隔の初期値（試行ごとに倍増）
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// 再試行間隔の上限
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// 保存待ちのリクエスト
+struct PendingSave {
+    content: String,
+    expected: ExpectedVersion,
+    due: Instant,
+    attempts: u32,
+    /// リクエストの順番（`AutosaveQueue::latest` と比べて古い再試行を破棄する）
+    seq: u64,
+}
+
+/// 自動保存で書き込んだ版の記録
+///
+/// フロントエンドは自動保存の結果を受け取らないため、読み込み時の版のまま次の保存を送ってくる。
+/// 自分で書き込んだ版を競合と誤判定しないよう、クライアントの版を最新の書き込みに読み替える。
+struct Baseline {
+    client_hash: String,
+    saved_hash: String,
+}
+
+#[derive(Default)]
+struct AutosaveQueue {
+    pending: HashMap<String, PendingSave>,
+    baselines: HashMap<String, Baseline>,
+    /// 保存中のメモ
+    in_flight: HashSet<String>,
+    /// メモごとの最新のリクエストの順番
+    latest: HashMap<String, u64>,
+    next_seq: u64,
+    shutdown: bool,
+}
+
+impl AutosaveQueue {
+    /// リクエストの順番を払い出し、メモの最新のリクエストとして記録
+    fn issue(&mut self, uid: &str) -> u64 {
+        self.next_seq += 1;
+        self.latest.insert(uid.to_string(), self.next_seq);
+        self.next_seq
+    }
+}
+
+/// 自動保存サービス
+pub struct AutosaveService {
+    note_service: Arc<NoteService>,
+    backlink_service: Arc<BacklinkService>,
+    draft_service: Arc<DraftService>,
+    settings_service: Arc<SettingsService>,
+    event_bus: Arc<dyn EventBus>,
+    queue: Mutex<AutosaveQueue>,
+    wakeup: Condvar,
+    /// 保存中のメモの保存が終わったことの通知
+    idle: Condvar,
+    /// 保存スレッド（`shutdown` で終了を待つ）
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl AutosaveService {
+    pub fn new(
+        note_service: Arc<NoteService>,
+        backlink_service: Arc<BacklinkService>,
+        draft_service: Arc<DraftService>,
+        settings_service: Arc<SettingsService>,
+        event_bus: Arc<dyn EventBus>,
+    ) -> Self {
+        Self {
+            note_service,
+            backlink_service,
+            draft_service,
+            settings_service,
+            event_bus,
+            queue: Mutex::new(AutosaveQueue::default()),
+            wakeup: Condvar::new(),
+            idle: Condvar::new(),
+            thread: Mutex::new(None),
+        }
+    }
+
+    /// バックグラウンドの保存スレッドを起動
+    pub fn start(self: &Arc<Self>) {
+        let service = Arc::clone(self);
+        let handle = thread::Builder::new()
+            .name("autosave".to_string())
+            .spawn(move || service.run())
+            .expect("Failed to spawn autosave thread");
+        *self.thread.lock() = Some(handle);
+    }
+
+    /// 保存をリクエスト（`delay_ms` 後に保存、それまでのリクエストは最新の内容にまとめる）
+    ///
+    /// 自動保存が無効なら何もしない（保存は `save_now` で行う）。
+    pub fn request(&self, uid: &str, content: String, expected: ExpectedVersion) {
+        let settings = self.settings_service.get_arc();
+        if !settings.autosave.enabled {
+            return;
+        }
+        let delay = Duration::from_millis(settings.autosave.delay_ms);
+
+        // クラッシュしても復旧できるよう、キューに入れる前に下書きを記録
+        if !self.note_service.is_encrypted(uid) {
+            if let Err(e) = self.draft_service.record(uid, &content) {
+                eprintln!("[AutosaveService] Failed to record draft: uid={}, {}", uid, e);
+            }
+        }
+
+        {
+            let mut queue = self.queue.lock();
+            let seq = queue.issue(uid);
+            queue.pending.insert(
+                uid.to_string(),
+                PendingSave {
+                    content,
+                    expected,
+                    due: Instant::now() + delay,
+                    attempts: 0,
+                    seq,
+                },
+            );
+        }
+        self.wakeup.notify_one();
+
+        self.event_bus.emit(DomainEvent::SaveRequested {
+            uid: uid.to_string(),
+        });
+    }
+
+    /// 即座に保存（保存待ちのリクエストは破棄して置き換える）
+    ///
+    /// バックグラウンドで同じメモを保存中なら、その完了を待ってから保存する。
+    pub fn save_now(
+        &self,
+        uid: &str,
+        content: String,
+        expected: ExpectedVersion,
+    ) -> Result<Note, RepositoryError> {
+        {
+            let mut queue = self.queue.lock();
+            queue.pending.remove(uid);
+            queue.issue(uid);
+            self.begin(&mut queue, uid);
+        }
+        let result = self.save(uid, content, expected);
+        self.finish(uid);
+        result
+    }
+
+    /// 保存待ちのリクエストをすべて即座に保存（終了時・非表示時用）
+    pub fn flush(&self) {
+        let pending: Vec<(String, PendingSave)> = self.queue.lock().pending.drain().collect();
+        for (uid, request) in pending {
+            self.begin(&mut self.queue.lock(), &uid);
+            let result = self.save(&uid, request.content, request.expected);
+            self.finish(&uid);
+            if let Err(e) = result {
+                self.emit_failed(&uid, &e);
+            }
+        }
+    }
+
+    /// メモの保存待ちのリクエストと下書きを破棄（暗号化したメモの平文を残さないため）
+    ///
+    /// 保存中の書き込みがあれば完了を待ち、その再試行も行わない。
+    pub fn discard(&self, uid: &str) -> Result<(), StorageError> {
+        {
+            let mut queue = self.queue.lock();
+            queue.issue(uid);
+            self.begin(&mut queue, uid);
+            queue.pending.remove(uid);
+        }
+        let result = self.draft_service.clear(uid);
+        self.finish(uid);
+        result
+    }
+
+    /// 保存スレッドを停止（保存中の書き込みの完了を待ち、保存待ちのリクエストは保存してから停止する）
+    pub fn shutdown(&self) {
+        self.queue.lock().shutdown = true;
+        self.wakeup.notify_all();
+        if let Some(handle) = self.thread.lock().take() {
+            if handle.join().is_err() {
+                eprintln!("[AutosaveService] Autosave thread panicked");
+            }
+        }
+        self.flush();
+    }
+
+    fn run(&self) {
+        loop {
+            let (uid, request) = {
+                let mut queue = self.queue.lock();
+                loop {
+                    if queue.shutdown {
+                        return;
+                    }
+                    // 保存中のメモは、その保存が終わってから取り出す
+                    let next = queue
+                        .pending
+                        .iter()
+                        .filter(|(uid, _)| !queue.in_flight.contains(*uid))
+                        .min_by_key(|(_, p)| p.due)
+                        .map(|(uid, p)| (uid.clone(), p.due));
+
+                    match next {
+                        Some((uid, due)) if due <= Instant::now() => {
+                            let request = queue.pending.remove(&uid).expect("pending entry");
+                            queue.in_flight.insert(uid.clone());
+                            break (uid, request);
+                        }
+                        Some((_, due)) => {
+                            self.wakeup.wait_until(&mut queue, due);
+                        }
+                        None => self.wakeup.wait(&mut queue),
+                    }
+                }
+            };
+
+            let attempts = request.attempts;
+            let retry = PendingSave {
+                content: request.content.clone(),
+                expected: request.expected.clone(),
+                due: Instant::now() + retry_delay(attempts),
+                attempts: attempts + 1,
+                seq: request.seq,
+            };
+
+            match self.save(&uid, request.content, request.expected) {
+                Err(e) if e.is_transient() && attempts < MAX_RETRIES => {
+                    let mut queue = self.queue.lock();
+                    // 保存中に新しいリクエストや手動保存が届いていれば、古い内容は再試行しない
+                    if queue.latest.get(&uid) == Some(&retry.seq) {
+                        log::warn!(
+                            "[AutosaveService] Retrying save: uid={}, attempt={}, {}",
+                            uid,
+                            attempts + 1,
+                            e
+                        );
+                        queue.pending.insert(uid.clone(), retry);
+                    }
+                }
+                Err(e) => self.emit_failed(&uid, &e),
+                Ok(_) => {}
+            }
+            self.finish(&uid);
+        }
+    }
+
+    /// 同じメモの保存が終わるのを待ち、保存中として記録
+    fn begin(&self, queue: &mut MutexGuard<'_, AutosaveQueue>, uid: &str) {
+        while queue.in_flight.contains(uid) {
+            self.idle.wait(queue);
+        }
+        queue.in_flight.insert(uid.to_string());
+    }
+
+    /// 保存中の記録を外し、待っている保存と保存スレッドに知らせる
+    fn finish(&self, uid: &str) {
+        self.queue.lock().in_flight.remove(uid);
+        self.idle.notify_all();
+        self.wakeup.notify_one();
+    }
+
+    /// 保存してバックリンクを更新し、書き込んだ版を記録
+    fn save(
+        &self,
+        uid: &str,
+        content: String,
+        expected: ExpectedVersion,
+    ) -> Result<Note, RepositoryError> {
+        let client_hash = expected.content_hash.clone();
+        let expected = self.resolve_expected(uid, expected);
+
+        let note = self.note_service.save_content(uid, content, &expected)?;
+
+        // 暗号化ノートのリンクはバックリンクに含めない（インデックスと揃える）
+        if !note.metadata.encrypted {
+            let title = note.metadata.title.clone().unwrap_or_default();
+            self.backlink_service
+                .update_note(note.uid(), &title, &note.content);
+        }
+
+        let has_pending = {
+            let mut queue = self.queue.lock();
+            if let Some(client_hash) = client_hash {
+                queue.baselines.insert(
+                    uid.to_string(),
+                    Baseline {
+                        client_hash,
+                        saved_hash: note.content_hash(),
+                    },
+                );
+            }
+            queue.pending.contains_key(uid)
+        };
+
+        // 新しいリクエストが保存待ちなら、その下書きは残しておく
+        if !has_pending {
+            if let Err(e) = self.draft_service.clear(uid) {
+                eprintln!("[AutosaveService] Failed to clear draft: uid={}, {}", uid, e);
+            }
+        }
+
+        Ok(note)
+    }
+
+    /// クライアントの版が自動保存前のものであれば、自動保存で書き込んだ版に読み替える
+    fn resolve_expected(&self, uid: &str, mut expected: ExpectedVersion) -> ExpectedVersion {
+        let queue = self.queue.lock();
+        if let (Some(hash), Some(baseline)) = (&expected.content_hash, queue.baselines.get(uid)) {
+            if *hash == baseline.client_hash {
+                expected.content_hash = Some(baseline.saved_hash.clone());
+                // updated_at も自動保存で変わっているため、ハッシュのみで判定する
+                expected.updated_at = None;
+            }
+        }
+        expected
+    }
+
+    fn emit_failed(&self, uid: &str, error: &RepositoryError) {
+        eprintln!("[AutosaveService] Save failed: uid={}, {}", uid, error);
+        let conflict = match error {
+            RepositoryError::Conflict(conflict) => Some(conflict.clone()),
+            _ => None,
+        };
+        self.event_bus.emit(DomainEvent::SaveFailed {
+            uid: uid.to_string(),
+            error: error.to_string(),
+            conflict,
+        });
+    }
+}
+
+/// 再試行までの待ち時間（指数バックオフ）
+fn retry_delay(attempts: u32) -> Duration {
+    RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempts))
+        .min(RETRY_MAX_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::{
+        EventBusImpl, FileNoteRepository, FileStorage, HeadingFilenameStrategy,
+    };
+    use crate::services::test_support;
+    use crate::domain::{NoteSortOrder, TrashItem};
+    use crate::infrastructure::GalleryNote;
+    use crate::traits::{NoteFilter, NoteListItem, NoteRepository, Page, PageCursor};
+    use std::path::PathBuf;
+    use std::sync::mpsc;
+    use tempfile::TempDir;
+
+    const UID: &str = "20260101000000";
+
+    fn create_service(temp_dir: &TempDir) -> (Arc<AutosaveService>, Arc<dyn NoteRepository>) {
+        let repository: Arc<dyn NoteRepository> = Arc::new(FileNoteRepository::with_fixed_path(
+            Arc::new(FileStorage::new()),
+            Arc::new(HeadingFilenameStrategy::new()),
+            temp_dir.path().join("notes"),
+        ));
+        create_service_with(temp_dir, repository)
+    }
+
+    fn create_service_with(
+        temp_dir: &TempDir,
+        repository: Arc<dyn NoteRepository>,
+    ) -> (Arc<AutosaveService>, Arc<dyn NoteRepository>) {
+        let event_bus = Arc::new(EventBusImpl::new());
+        let settings_service = test_support::settings_service(temp_dir.path(), event_bus.clone());
+        let service = test_support::autosave_fixture(
+            temp_dir.path(),
+            repository.clone(),
+            Arc::new(FileStorage::new()),
+            settings_service,
+            event_bus,
+        )
+        .autosave_service;
+        (service, repository)
+    }
+
+    #[test]
+    fn test_requests_are_coalesced_and_flushed() {
+        let temp_dir = TempDir::new().unwrap();
+        let (service, repository) = create_service(&temp_dir);
+
+        service.request(UID, "# v1".to_string(), ExpectedVersion::default());
+        service.request(UID, "# v2".to_string(), ExpectedVersion::default());
+        assert!(repository.load(UID).is_err());
+        // 保存前の内容は下書きとして残っている
+        assert_eq!(service.draft_service.get(UID).unwrap().unwrap().content, "# v2");
+
+        service.flush();
+        assert_eq!(repository.load(UID).unwrap().content, "# v2");
+        assert!(service.draft_service.get(UID).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_own_writes_are_not_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let (service, repository) = create_service(&temp_dir);
+
+        let loaded = service
+            .save_now(UID, "# 読み込み時".to_string(), ExpectedVersion::default())
+            .unwrap();
+        let expected = ExpectedVersion {
+            content_hash: Some(loaded.content_hash()),
+            updated_at: None,
+        };
+
+        // クライアントは自動保存の結果を知らないまま、読み込み時の版で保存を続ける
+        service.request(UID, "# 自動保存".to_string(), expected.clone());
+        service.flush();
+        service
+            .save_now(UID, "# 手動保存".to_string(), expected)
+            .unwrap();
+
+        assert_eq!(repository.load(UID).unwrap().content, "# 手動保存");
+    }
+
+    #[test]
+    fn test_request_is_ignored_when_disabled() {
+        let temp_dir = TempDir::new().unwrap();
+        let (service, repository) = create_service(&temp_dir);
+        service
+            .settings_service
+            .update(|s| s.autosave.enabled = false)
+            .unwrap();
+
+        service.request(UID, "# 無効".to_string(), ExpectedVersion::default());
+        service.flush();
+
+        assert!(repository.load(UID).is_err());
+        assert!(service.draft_service.get(UID).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_conflict_failure_carries_both_versions() {
+        let temp_dir = TempDir::new().unwrap();
+        let (service, repository) = create_service(&temp_dir);
+        let loaded = service
+            .save_now(UID, "# 読み込み時".to_string(), ExpectedVersion::default())
+            .unwrap();
+        let expected = ExpectedVersion {
+            content_hash: Some(loaded.content_hash()),
+            updated_at: None,
+        };
+
+        // 外部エディタでの変更
+        let mut external = repository.load(UID).unwrap();
+        external.content = "# 外部で編集".to_string();
+        repository.save(&external).unwrap();
+
+        let failures = Arc::new(Mutex::new(Vec::new()));
+        let recorded = failures.clone();
+        service.event_bus.subscribe(
+            "save:failed",
+            Arc::new(move |event| {
+                if let DomainEvent::SaveFailed { conflict, .. } = event {
+                    recorded.lock().push(conflict.clone());
+                }
+            }),
+        );
+
+        service.request(UID, "# 自動保存".to_string(), expected);
+        service.flush();
+
+        let failures = failures.lock();
+        let conflict = failures[0].as_ref().expect("conflict");
+        assert_eq!(conflict.local.content, "# 自動保存");
+        assert_eq!(conflict.remote.content, "# 外部で編集");
+    }
+
+    #[test]
+    fn test_shutdown_joins_the_thread_and_saves_pending() {
+        let temp_dir = TempDir::new().unwrap();
+        let (service, repository) = create_service(&temp_dir);
+        service.start();
+
+        service.request(UID, "# 終了前".to_string(), ExpectedVersion::default());
+        service.shutdown();
+
+        assert!(service.thread.lock().is_none());
+        assert_eq!(repository.load(UID).unwrap().content, "# 終了前");
+    }
+
+    /// 指定した内容の保存を一度だけ止め、再開後に一時的なエラーで失敗させるリポジトリ
+    struct FlakyRepository {
+        inner: FileNoteRepository,
+        fail_content: String,
+        entered: Mutex<Option<mpsc::Sender<()>>>,
+        release: Mutex<Option<mpsc::Receiver<()>>>,
+    }
+
+    impl NoteRepository for FlakyRepository {
+        fn save(&self, note: &Note) -> Result<PathBuf, RepositoryError> {
+            self.inner.save(note)
+        }
+
+        fn save_verified(
+            &self,
+            note: &Note,
+            verify: &dyn Fn() -> Result<(), RepositoryError>,
+        ) -> Result<PathBuf, RepositoryError> {
+            if note.content == self.fail_content {
+                if let Some(entered) = self.entered.lock().take() {
+                    entered.send(()).unwrap();
+                    self.release.lock().take().unwrap().recv().unwrap();
+                    let busy = std::io::Error::from(std::io::ErrorKind::ResourceBusy);
+                    return Err(RepositoryError::storage("save_note", StorageError::Io(busy)));
+                }
+            }
+            self.inner.save_verified(note, verify)
+        }
+
+        fn load(&self, uid: &str) -> Result<Note, RepositoryError> {
+            self.inner.load(uid)
+        }
+
+        fn delete(&self, uid: &str) -> Result<(), RepositoryError> {
+            self.inner.delete(uid)
+        }
+
+        fn list_all(&self) -> Result<Vec<NoteListItem>, RepositoryError> {
+            self.inner.list_all()
+        }
+
+        fn get_path(&self, uid: &str) -> Option<PathBuf> {
+            self.inner.get_path(uid)
+        }
+
+        fn list_gallery(
+            &self,
+            sort: NoteSortOrder,
+            filter: &NoteFilter,
+        ) -> Result<Vec<GalleryNote>, RepositoryError> {
+            self.inner.list_gallery(sort, filter)
+        }
+
+        fn list_page(
+            &self,
+            sort: NoteSortOrder,
+            filter: &NoteFilter,
+            cursor: Option<&PageCursor>,
+            limit: usize,
+        ) -> Result<Page<NoteListItem>, RepositoryError> {
+            self.inner.list_page(sort, filter, cursor, limit)
+        }
+
+        fn list_gallery_page(
+            &self,
+            sort: NoteSortOrder,
+            filter: &NoteFilter,
+            cursor: Option<&PageCursor>,
+            limit: usize,
+        ) -> Result<Page<GalleryNote>, RepositoryError> {
+            self.inner.list_gallery_page(sort, filter, cursor, limit)
+        }
+
+        fn set_manual_order(&self, uids: &[String]) -> Result<(), RepositoryError> {
+            self.inner.set_manual_order(uids)
+        }
+
+        fn list_trash(&self) -> Result<Vec<TrashItem>, RepositoryError> {
+            self.inner.list_trash()
+        }
+
+        fn restore(&self, uid: &str) -> Result<Note, RepositoryError> {
+            self.inner.restore(uid)
+        }
+
+        fn purge_trash(
+            &self,
+            deleted_before: Option<chrono::DateTime<chrono::Utc>>,
+        ) -> Result<usize, RepositoryError> {
+            self.inner.purge_trash(deleted_before)
+        }
+
+        fn relocate(&self, uid: &str) -> Result<Option<PathBuf>, RepositoryError> {
+            self.inner.relocate(uid)
+        }
+
+        fn move_to_folder(&self, uid: &str, folder: &str) -> Result<PathBuf, RepositoryError> {
+            self.inner.move_to_folder(uid, folder)
+        }
+    }
+
+    #[test]
+    fn test_failed_retry_does_not_overwrite_manual_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel();
+        let repository: Arc<dyn NoteRepository> = Arc::new(FlakyRepository {
+            inner: FileNoteRepository::with_fixed_path(
+                Arc::new(FileStorage::new()),
+                Arc::new(HeadingFilenameStrategy::new()),
+                temp_dir.path().join("notes"),
+            ),
+            fail_content: "# 自動保存".to_string(),
+            entered: Mutex::new(Some(entered_tx)),
+            release: Mutex::new(Some(release_rx)),
+        });
+        let (service, repository) = create_service_with(&temp_dir, repository);
+        service
+            .settings_service
+            .update(|s| s.autosave.delay_ms = 0)
+            .unwrap();
+        let loaded = service
+            .save_now(UID, "# 読み込み時".to_string(), ExpectedVersion::default())
+            .unwrap();
+        let expected = ExpectedVersion {
+            content_hash: Some(loaded.content_hash()),
+            updated_at: None,
+        };
+        service.start();
+
+        // 自動保存の書き込み中に手動保存する
+        service.request(UID, "# 自動保存".to_string(), expected.clone());
+        entered_rx.recv().unwrap();
+        let manual = {
+            let service = service.clone();
+            thread::spawn(move || service.save_now(UID, "# 手動保存".to_string(), expected))
+        };
+        thread::sleep(Duration::from_millis(50));
+
+        // 自動保存が一時的なエラーで失敗しても、古い内容は再試行されない
+        release_tx.send(()).unwrap();
+        manual.join().unwrap().unwrap();
+        service.shutdown();
+
+        assert_eq!(repository.load(UID).unwrap().content, "# 手動保存");
+    }
+
+    #[test]
+    fn test_retry_delay_backoff() {
+        assert_eq!(retry_delay(0), RETRY_BASE_DELAY);
+        assert_eq!(retry_delay(1), RETRY_BASE_DELAY * 2);
+        assert_eq!(retry_delay(20), RETRY_MAX_DELAY);
+    }
+}
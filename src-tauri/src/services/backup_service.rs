@@ -0,0 +1,494 @@
+//! バックアップサービス
+//!
+//! 保存ディレクトリと設定を圧縮アーカイブにまとめてバックアップ用のディレクトリに保存し、
+//! 世代管理（日ごと・週ごと）で古いアーカイブを削除する。`BackupSettings::enabled` の場合は
+//! バックグラウンドスレッドで `interval_hours` ごとにバックアップする。
+//!
+//! アーカイブの読み書きは `backup_archive` で行い（ファイルを1つずつ圧縮・展開する）、
+//! それ以外のファイル操作は `Storage` を通して行う。進捗は `DomainEvent::BackupProgress` で通知する。
+
+use crate::domain::{
+    backups_to_prune, BackupError, BackupInfo, BackupReport, DomainEvent, IgnoreRules,
+    RestoreReport, Settings, SettingsError, ARCHIVE_NOTES_DIR, ARCHIVE_SETTINGS_FILE,
+    IGNORE_FILE_NAME,
+};
+use crate::infrastructure::backup_archive::{self, ArchiveWriter};
+use crate::infrastructure::VaultRoot;
+use crate::services::{BacklinkService, SettingsService};
+use crate::traits::{EventBus, NoteRepository, Storage, StorageError, SubscriptionId};
+use chrono::Utc;
+use log::info;
+use parking_lot::{Condvar, Mutex};
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 定期バックアップが失敗したときに再試行するまでの時間（間隔の方が短ければ間隔）
+const RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+
+/// 定期バックアップの状態
+#[derive(Default)]
+struct Schedule {
+    /// 次にバックアップする時刻（`None` は設定から計算し直す）
+    next_run: Option<Instant>,
+    shutdown: bool,
+}
+
+/// バックアップサービス
+pub struct BackupService {
+    storage: Arc<dyn Storage>,
+    repository: Arc<dyn NoteRepository>,
+    backlink_service: Arc<BacklinkService>,
+    settings_service: Arc<SettingsService>,
+    event_bus: Arc<dyn EventBus>,
+    root: Arc<VaultRoot>,
+    schedule: Mutex<Schedule>,
+    wakeup: Condvar,
+    /// バックアップと復元を直列化
+    run_lock: Mutex<()>,
+    subscription: Mutex<Option<SubscriptionId>>,
+}
+
+impl BackupService {
+    pub fn new(
+        storage: Arc<dyn Storage>,
+        repository: Arc<dyn NoteRepository>,
+        backlink_service: Arc<BacklinkService>,
+        settings_service: Arc<SettingsService>,
+        event_bus: Arc<dyn EventBus>,
+        root: Arc<VaultRoot>,
+    ) -> Self {
+        Self {
+            storage,
+            repository,
+            backlink_service,
+            settings_service,
+            event_bus,
+            root,
+            schedule: Mutex::new(Schedule::default()),
+            wakeup: Condvar::new(),
+            run_lock: Mutex::new(()),
+            subscription: Mutex::new(None),
+        }
+    }
+
+    /// 定期バックアップのスレッドを起動（設定の変更で次回の時刻を計算し直す）
+    pub fn start(self: &Arc<Self>) -> JoinHandle<()> {
+        let service: Weak<Self> = Arc::downgrade(self);
+        *self.subscription.lock() = Some(self.event_bus.subscribe(
+            "settings:changed",
+            Arc::new(move |_| {
+                if let Some(service) = service.upgrade() {
+                    service.schedule.lock().next_run = None;
+                    service.wakeup.notify_one();
+                }
+            }),
+        ));
+
+        let service = Arc::clone(self);
+        thread::Builder::new()
+            .name("backup".to_string())
+            .spawn(move || service.run())
+            .expect("Failed to spawn backup thread")
+    }
+
+    /// 定期バックアップのスレッドを停止
+    pub fn shutdown(&self) {
+        if let Some(id) = self.subscription.lock().take() {
+            self.event_bus.unsubscribe(id);
+        }
+        self.schedule.lock().shutdown = true;
+        self.wakeup.notify_all();
+    }
+
+    /// 今すぐバックアップし、世代管理で古いアーカイブを削除する
+    pub fn backup_now(&self) -> Result<BackupReport, BackupError> {
+        let _guard = self.run_lock.lock();
+        self.backup(None)
+    }
+
+    /// この保管庫のバックアップ一覧（新しい順）
+    pub fn list_backups(&self) -> Result<Vec<BackupInfo>, BackupError> {
+        let vault = self.root.id();
+        let mut backups: Vec<BackupInfo> = self
+            .storage
+            .list_files(&self.backup_directory(), "gz")?
+            .iter()
+            .filter_map(|path| BackupInfo::from_path(path))
+            .filter(|backup| backup.vault == vault)
+            .collect();
+        backups.sort_by_key(|backup| Reverse(backup.created_at));
+        Ok(backups)
+    }
+
+    /// バックアップから保存ディレクトリのファイルを書き戻す
+    ///
+    /// アーカイブにあるファイルを上書きし、アーカイブにないファイルは残す。復元の前に現在の状態を
+    /// バックアップする。`include_settings` の場合は設定も戻す（保存ディレクトリ・保管庫・
+    /// ウィンドウ位置・バックアップ設定はこの環境のものを保つ）。書き戻した後はインデックスを作り直す。
+    pub fn restore(&self, name: &str, include_settings: bool) -> Result<RestoreReport, BackupError> {
+        let _guard = self.run_lock.lock();
+
+        let archive_path = self.archive_path(name)?;
+        let notes_prefix = format!("{}/", ARCHIVE_NOTES_DIR);
+        // 書き戻す前にアーカイブ全体を確認し、ファイル数と（小さい）設定だけを読み込む
+        let mut total = 0;
+        let mut archived_settings = None;
+        backup_archive::for_each_file(&archive_path, |path, content| {
+            if path.starts_with(&notes_prefix) {
+                total += 1;
+            } else if path == ARCHIVE_SETTINGS_FILE && include_settings {
+                let mut settings = Vec::new();
+                content.read_to_end(&mut settings).map_err(io_error)?;
+                archived_settings = Some(settings);
+            }
+            Ok(())
+        })?;
+
+        // 復元するアーカイブは世代管理で消さない
+        let safety_backup = self.backup(Some(name))?.backup;
+
+        let base_dir = self.root.get();
+        let mut done = 0;
+        backup_archive::for_each_file(&archive_path, |path, content| {
+            let Some(relative) = path.strip_prefix(&notes_prefix) else {
+                return Ok(());
+            };
+            backup_archive::extract_to(content, &base_dir.join(relative))?;
+            done += 1;
+            self.emit_progress(true, done, total);
+            Ok(())
+        })?;
+
+        let settings_restored = match archived_settings {
+            Some(content) => {
+                self.restore_settings(&content)?;
+                true
+            }
+            None => false,
+        };
+
+        self.repository
+            .reindex()
+            .map_err(|e| BackupError::Reindex(e.to_string()))?;
+        if let Err(e) = self.backlink_service.rebuild_index() {
+            eprintln!("[BackupService] Failed to rebuild backlink index: {}", e);
+        }
+        self.event_bus.emit(DomainEvent::IndexRebuilt);
+
+        info!("[BackupService] Restored {} files from {}", done, name);
+        Ok(RestoreReport {
+            restored: done,
+            settings_restored,
+            safety_backup: Some(safety_backup),
+        })
+    }
+
+    /// バックアップを作成して世代管理を行う（`run_lock` を取得して呼ぶ）
+    ///
+    /// `keep` のアーカイブ（復元中のもの）は世代管理の対象でも削除しない。
+    fn backup(&self, keep: Option<&str>) -> Result<BackupReport, BackupError> {
+        let settings = self.settings_service.get_arc();
+        let base_dir = self.root.get();
+
+        let mut files = self
+            .storage
+            .list_all_files_recursive(&base_dir, &IgnoreRules::default())?;
+        let ignore_file = base_dir.join(IGNORE_FILE_NAME);
+        if self.storage.exists(&ignore_file) {
+            files.push(ignore_file);
+        }
+
+        let backup = BackupInfo::new(self.root.id(), Utc::now());
+        let mut archive = ArchiveWriter::create(&self.backup_directory().join(&backup.name))?;
+        let total = files.len();
+        let mut file_count = 0;
+        for (done, path) in files.iter().enumerate() {
+            let Some(relative) = relative_archive_path(&base_dir, path) else {
+                continue;
+            };
+            archive.append_file(&format!("{}/{}", ARCHIVE_NOTES_DIR, relative), path)?;
+            file_count += 1;
+            self.emit_progress(false, done + 1, total);
+        }
+        let settings_toml = toml::to_string_pretty(settings.as_ref()).map_err(SettingsError::from)?;
+        archive.append_data(ARCHIVE_SETTINGS_FILE, settings_toml.as_bytes())?;
+        archive.finish()?;
+        info!("[BackupService] Wrote {} ({} files)", backup.name, file_count);
+
+        let pruned = self.prune(&backup, keep)?;
+        Ok(BackupReport {
+            backup,
+            files: file_count,
+            pruned,
+        })
+    }
+
+    /// 世代管理で残さないアーカイブを削除（作成したばかりのアーカイブと `keep` は必ず残す）
+    fn prune(&self, latest: &BackupInfo, keep: Option<&str>) -> Result<usize, BackupError> {
+        let backup_settings = &self.settings_service.get_arc().backup;
+        let backups = self.list_backups()?;
+        let dir = self.backup_directory();
+
+        let mut pruned = 0;
+        for backup in backups_to_prune(&backups, backup_settings.keep_daily, backup_settings.keep_weekly) {
+            if backup.name == latest.name || Some(backup.name.as_str()) == keep {
+                continue;
+            }
+            self.storage.delete(&dir.join(&backup.name))?;
+            pruned += 1;
+        }
+        Ok(pruned)
+    }
+
+    /// アーカイブの設定を現在の設定に適用
+    fn restore_settings(&self, content: &[u8]) -> Result<(), BackupError> {
+        let text = String::from_utf8_lossy(content);
+        let archived: Settings = toml::from_str(&text).map_err(SettingsError::from)?;
+        self.settings_service.update(|s| {
+            *s = Settings {
+                window: s.window.clone(),
+                storage_directory: s.storage_directory.clone(),
+                vaults: s.vaults.clone(),
+                last_note_uid: s.last_note_uid.clone(),
+                backup: s.backup.clone(),
+                ..archived
+            };
+        })?;
+        Ok(())
+    }
+
+    /// アーカイブの保存先
+    fn backup_directory(&self) -> PathBuf {
+        self.settings_service
+            .get_arc()
+            .backup
+            .directory
+            .clone()
+            .unwrap_or_else(|| self.settings_service.config_directory().join("backups"))
+    }
+
+    /// バックアップ名からアーカイブのパス（ファイル名以外の指定は受け付けない）
+    fn archive_path(&self, name: &str) -> Result<PathBuf, BackupError> {
+        let is_file_name = Path::new(name).file_name().and_then(|n| n.to_str()) == Some(name);
+        if !is_file_name || BackupInfo::parse(name).is_none() {
+            return Err(BackupError::NotFound(name.to_string()));
+        }
+        let path = self.backup_directory().join(name);
+        if !self.storage.exists(&path) {
+            return Err(BackupError::NotFound(name.to_string()));
+        }
+        Ok(path)
+    }
+
+    fn emit_progress(&self, restoring: bool, done: usize, total: usize) {
+        self.event_bus.emit(DomainEvent::BackupProgress {
+            restoring,
+            done,
+            total,
+        });
+    }
+
+    fn run(&self) {
+        loop {
+            {
+                let mut schedule = self.schedule.lock();
+                loop {
+                    if schedule.shutdown {
+                        return;
+                    }
+                    if schedule.next_run.is_none() {
+                        schedule.next_run = self.next_scheduled_run();
+                    }
+                    match schedule.next_run {
+                        Some(due) if due <= Instant::now() => break,
+                        Some(due) => {
+                            self.wakeup.wait_until(&mut schedule, due);
+                        }
+                        None => self.wakeup.wait(&mut schedule),
+                    }
+                }
+            }
+
+            let result = self.backup_now();
+            let mut schedule = self.schedule.lock();
+            schedule.next_run = match result {
+                Ok(_) => None,
+                Err(e) => {
+                    eprintln!("[BackupService] Scheduled backup failed: {}", e);
+                    self.event_bus.emit(DomainEvent::BackupFailed {
+                        error: e.to_string(),
+                    });
+                    Some(Instant::now() + RETRY_DELAY.min(self.interval()))
+                }
+            };
+        }
+    }
+
+    /// 次に定期バックアップする時刻（無効なら `None`）
+    ///
+    /// 最新のアーカイブの作成日時から間隔が経った時刻。アーカイブがなければすぐに行う。
+    fn next_scheduled_run(&self) -> Option<Instant> {
+        if !self.settings_service.get_arc().backup.enabled {
+            return None;
+        }
+        let latest = self.list_backups().ok()?.into_iter().next();
+        let elapsed = latest
+            .map(|backup| (Utc::now() - backup.created_at).to_std().unwrap_or_default())
+            .unwrap_or(Duration::MAX);
+        Some(Instant::now() + self.interval().saturating_sub(elapsed))
+    }
+
+    fn interval(&self) -> Duration {
+        let hours = self.settings_service.get_arc().backup.interval_hours.max(1);
+        Duration::from_secs(u64::from(hours) * 60 * 60)
+    }
+}
+
+fn io_error(e: std::io::Error) -> BackupError {
+    BackupError::Storage(StorageError::Io(e))
+}
+
+/// 保存ディレクトリからの相対パス（`/` 区切り）
+fn relative_archive_path(base_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(base_dir).ok()?;
+    let parts: Vec<&str> = relative.iter().map(|part| part.to_str()).collect::<Option<_>>()?;
+    Some(parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::DEFAULT_VAULT_ID;
+    use crate::infrastructure::{
+        EventBusImpl, FileNoteRepository, FileStorage,
+        HeadingFilenameStrategy,
+    };
+    use crate::services::test_support;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// 記録した進捗（復元中か、完了数、総数）
+    type Progress = Arc<Mutex<Vec<(bool, usize, usize)>>>;
+
+    fn create_service(temp_dir: &TempDir) -> (BackupService, Arc<SettingsService>, Progress) {
+        let event_bus = Arc::new(EventBusImpl::new());
+        let settings_service = test_support::settings_service(temp_dir.path(), event_bus.clone());
+        let notes_dir = temp_dir.path().join("notes");
+        settings_service
+            .update(|s| {
+                s.storage_directory = notes_dir.clone();
+                s.backup.directory = Some(temp_dir.path().join("backups"));
+            })
+            .unwrap();
+        let storage = Arc::new(FileStorage::new());
+        let repository: Arc<dyn NoteRepository> = Arc::new(FileNoteRepository::with_fixed_path(
+            storage.clone(),
+            Arc::new(HeadingFilenameStrategy::new()),
+            notes_dir.clone(),
+        ));
+
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let recorded = progress.clone();
+        event_bus.subscribe(
+            "backup:progress",
+            Arc::new(move |event| {
+                if let DomainEvent::BackupProgress { restoring, done, total } = event {
+                    recorded.lock().push((*restoring, *done, *total));
+                }
+            }),
+        );
+
+        let service = BackupService::new(
+            storage,
+            repository.clone(),
+            Arc::new(BacklinkService::new(repository)),
+            settings_service.clone(),
+            event_bus,
+            Arc::new(VaultRoot::new(DEFAULT_VAULT_ID, notes_dir)),
+        );
+        (service, settings_service, progress)
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let (service, settings_service, progress) = create_service(&temp_dir);
+        let notes_dir = temp_dir.path().join("notes");
+        fs::create_dir_all(notes_dir.join("attachments")).unwrap();
+        fs::create_dir_all(notes_dir.join(".trash")).unwrap();
+        fs::write(notes_dir.join("メモ.md"), "# メモ\n\n元の内容").unwrap();
+        fs::write(notes_dir.join("attachments/image.png"), [0x89, b'P', b'N', b'G']).unwrap();
+        fs::write(notes_dir.join(".trash/old.md"), "削除済み").unwrap();
+        settings_service.update(|s| s.editor.font_size = 20).unwrap();
+
+        let report = service.backup_now().unwrap();
+        assert_eq!(report.files, 2);
+        assert_eq!(service.list_backups().unwrap(), vec![report.backup.clone()]);
+        assert_eq!(progress.lock().last(), Some(&(false, 2, 2)));
+
+        fs::write(notes_dir.join("メモ.md"), "# メモ\n\n壊れた内容").unwrap();
+        fs::remove_file(notes_dir.join("attachments/image.png")).unwrap();
+        settings_service.update(|s| s.editor.font_size = 12).unwrap();
+
+        let restored = service.restore(&report.backup.name, true).unwrap();
+        assert_eq!(restored.restored, 2);
+        assert!(restored.settings_restored);
+        assert!(restored.safety_backup.is_some());
+        assert_eq!(
+            fs::read_to_string(notes_dir.join("メモ.md")).unwrap(),
+            "# メモ\n\n元の内容"
+        );
+        assert_eq!(
+            fs::read(notes_dir.join("attachments/image.png")).unwrap(),
+            [0x89, b'P', b'N', b'G']
+        );
+        assert_eq!(settings_service.get_arc().editor.font_size, 20);
+        assert_eq!(settings_service.storage_directory(), notes_dir);
+    }
+
+    #[test]
+    fn test_restore_keeps_restored_archive() {
+        let temp_dir = TempDir::new().unwrap();
+        let (service, settings_service, _) = create_service(&temp_dir);
+        let notes_dir = temp_dir.path().join("notes");
+        fs::create_dir_all(&notes_dir).unwrap();
+        fs::write(notes_dir.join("メモ.md"), "# メモ\n\n古い内容").unwrap();
+
+        // 世代管理で削除される古いアーカイブ
+        let report = service.backup_now().unwrap();
+        let old = BackupInfo::parse("kaku-default-20200101T000000.000Z.tar.gz").unwrap();
+        let backups = temp_dir.path().join("backups");
+        fs::rename(backups.join(&report.backup.name), backups.join(&old.name)).unwrap();
+        settings_service
+            .update(|s| {
+                s.backup.keep_daily = 1;
+                s.backup.keep_weekly = 0;
+            })
+            .unwrap();
+        fs::write(notes_dir.join("メモ.md"), "# メモ\n\n新しい内容").unwrap();
+
+        let restored = service.restore(&old.name, false).unwrap();
+        assert_eq!(restored.restored, 1);
+        assert!(backups.join(&old.name).exists());
+        assert_eq!(
+            fs::read_to_string(notes_dir.join("メモ.md")).unwrap(),
+            "# メモ\n\n古い内容"
+        );
+    }
+
+    #[test]
+    fn test_restore_rejects_paths_outside_backup_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let (service, _, _) = create_service(&temp_dir);
+
+        for name in ["../config.toml", "kaku-default-20260101T000000.000Z.tar.gz", "/etc/passwd"] {
+            assert!(matches!(
+                service.restore(name, false),
+                Err(BackupError::NotFound(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! 下書きサービス
+//!
+//! 未保存のバッファを下書きジャーナルに記録し、起動時に復旧可能な下書きを検出する。
+
+use crate::domain::Draft;
+use crate::infrastructure::DraftJournal;
+use crate::traits::{NoteRepository, RepositoryError, StorageError};
+use std::sync::Arc;
+
+/// 下書きサービス
+pub struct DraftService {
+    journal: DraftJournal,
+    repository: Arc<dyn NoteRepository>,
+}
+
+impl DraftService {
+    pub fn new(journal: DraftJournal, repository: Arc<dyn NoteRepository>) -> Self {
+        Self {
+            journal,
+            repository,
+        }
+    }
+
+    /// バッファのスナップショットを記録
+    pub fn record(&self, uid: &str, content: &str) -> Result<(), StorageError> {
+        self.journal.append(uid, content).map(|_| ())
+    }
+
+    /// 下書きを削除（保存完了時・破棄時）
+    pub fn clear(&self, uid: &str) -> Result<(), StorageError> {
+        self.journal.clear(uid)
+    }
+
+    /// 下書きを取得
+    pub fn get(&self, uid: &str) -> Result<Option<Draft>, StorageError> {
+        self.journal.latest(uid)
+    }
+
+    /// 復旧可能な下書き一覧（保存済みのファイルより新しく、内容が異なるもの）
+    ///
+    /// 保存済みの内容と同じ、または保存より古い下書きはここで削除する。
+    pub fn recoverable(&self) -> Result<Vec<Draft>, StorageError> {
+        let mut drafts = Vec::new();
+        for draft in self.journal.list()? {
+            let recoverable = match self.repository.load(&draft.uid) {
+                Ok(note) => {
+                    draft.saved_at > note.metadata.updated_at && draft.content != note.content
+                }
+                // 一度も保存されずに終了したメモ（空の下書きは復旧不要）
+                Err(RepositoryError::NotFound { .. }) => !draft.content.trim().is_empty(),
+                Err(_) => true,
+            };
+
+            if recoverable {
+                drafts.push(draft);
+            } else {
+                self.journal.clear(&draft.uid)?;
+            }
+        }
+
+        drafts.sort_by_key(|draft| std::cmp::Reverse(draft.saved_at));
+        Ok(drafts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Note;
+    use crate::infrastructure::{FileNoteRepository, FileStorage, HeadingFilenameStrategy};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_recoverable_drafts() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = Arc::new(FileStorage::new());
+        let repository: Arc<dyn NoteRepository> = Arc::new(FileNoteRepository::with_fixed_path(
+            storage.clone(),
+            Arc::new(HeadingFilenameStrategy::new()),
+            temp_dir.path().join("notes"),
+        ));
+        let service = DraftService::new(
+            DraftJournal::new(temp_dir.path().join("drafts"), storage),
+            repository.clone(),
+        );
+
+        let mut saved = Note::with_uid("20260101000000".to_string());
+        saved.update_content("# 保存済み".to_string());
+        repository.save(&saved).unwrap();
+
+        // 保存済みと同じ内容の下書きは削除される
+        service.record(saved.uid(), "# 保存済み").unwrap();
+        // 保存後に編集された下書き・未保存のメモの下書きは復旧対象
+        service.record("20260101000001", "# 未保存").unwrap();
+        assert_eq!(service.recoverable().unwrap().len(), 1);
+        assert!(service.get(saved.uid()).unwrap().is_none());
+
+        service.record(saved.uid(), "# 保存済み\n\n追記").unwrap();
+        assert_eq!(service.recoverable().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,441 @@
+//! 同じ UID を持つファイル（競合コピー）の解決サービス
+//!
+//! インデックスの同期で見つかった重複（`NoteRepository::list_duplicates`）を UID ごとにまとめ、
+//! 同期ツールの競合コピーを含む組を競合として一覧にする。競合コピーと元のノートの本文は
+//! 3方向マージでき、共通の祖先はリビジョン履歴から探す。
+//!
+//! 解決では残す本文を元のノートのパスに保存し、競合コピーを `.sync-trash` へ移す。
+//! 元のノートのパスを保つため、ノートへのリンクや固定表示はそのまま使える。
+//!
+//! 手作業でコピーしたファイル（競合コピーの名前でない複製）は別のノートとして扱い、
+//! 新しい UID を振って front matter を書き換える。元のノートは UID を保つため、UID に紐づく
+//! 履歴・並び順・最後に開いたノートは元のノートのまま残る。複製のファイル名と本文は変えないため、
+//! タイトルやファイルパスでのリンクもそのまま使える。
+
+use crate::domain::{
+    common_lines, merge_three_way, ConflictMerge, DomainEvent, DuplicateFile, DuplicateGroup,
+    Note, UidRepair, SYNC_TRASH_DIR_NAME, SYNC_TRASH_TIME_FORMAT,
+};
+use crate::infrastructure::VaultRoot;
+use crate::services::{BacklinkService, HistoryService, NoteService, SettingsService};
+use crate::traits::{EventBus, NoteRepository, RepositoryError, Storage, StorageError};
+use chrono::{DateTime, Local, Utc};
+use log::info;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// 同じ UID を持つファイルの解決サービス
+pub struct DuplicateService {
+    repository: Arc<dyn NoteRepository>,
+    note_service: Arc<NoteService>,
+    history_service: Arc<HistoryService>,
+    backlink_service: Arc<BacklinkService>,
+    storage: Arc<dyn Storage>,
+    settings_service: Arc<SettingsService>,
+    event_bus: Arc<dyn EventBus>,
+    root: Arc<VaultRoot>,
+}
+
+impl DuplicateService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        repository: Arc<dyn NoteRepository>,
+        note_service: Arc<NoteService>,
+        history_service: Arc<HistoryService>,
+        backlink_service: Arc<BacklinkService>,
+        storage: Arc<dyn Storage>,
+        settings_service: Arc<SettingsService>,
+        event_bus: Arc<dyn EventBus>,
+        root: Arc<VaultRoot>,
+    ) -> Self {
+        Self {
+            repository,
+            note_service,
+            history_service,
+            backlink_service,
+            storage,
+            settings_service,
+            event_bus,
+            root,
+        }
+    }
+
+    /// この保管庫の保存ディレクトリ（一覧のパスの基準）
+    pub fn base_dir(&self) -> PathBuf {
+        self.root.get()
+    }
+
+    /// 同じ UID を持つファイルの組（UID 順、元のノートがインデックスにない組は除く）
+    pub fn list_groups(&self) -> Result<Vec<DuplicateGroup>, RepositoryError> {
+        let mut copies: BTreeMap<String, Vec<DuplicateFile>> = BTreeMap::new();
+        for file in self.repository.list_duplicates()? {
+            copies.entry(file.uid.clone()).or_default().push(file);
+        }
+
+        Ok(copies
+            .into_iter()
+            .filter_map(|(uid, copies)| {
+                let primary = self.repository.get_path(&uid)?;
+                Some(DuplicateGroup { uid, primary, copies })
+            })
+            .collect())
+    }
+
+    /// 同期ツールの競合コピーを含む組
+    pub fn list_conflicts(&self) -> Result<Vec<DuplicateGroup>, RepositoryError> {
+        Ok(self
+            .list_groups()?
+            .into_iter()
+            .filter(DuplicateGroup::has_conflict_copy)
+            .collect())
+    }
+
+    /// 元のノートと複製（保存ディレクトリからの相対パス）の本文を3方向マージする（保存はしない）
+    ///
+    /// 共通の祖先には、両方の更新日時より前に取ったリビジョンのうち最新のものを使う。
+    /// 履歴にない場合は両方に共通する行を祖先とする。
+    pub fn merge_conflict(&self, uid: &str, copy_path: &str) -> Result<ConflictMerge, RepositoryError> {
+        let group = self.group(uid)?;
+        let copy = self.find_copy(&group, copy_path)?;
+
+        let ours = self.note_service.load_note(uid)?;
+        let theirs = self.load_copy(uid, &copy.path)?;
+        let base = self.find_base(uid, ours.metadata.updated_at.min(theirs.metadata.updated_at));
+        let base_found = base.is_some();
+        let base = base.unwrap_or_else(|| common_lines(&ours.content, &theirs.content));
+
+        let merged = merge_three_way(
+            &base,
+            &ours.content,
+            &theirs.content,
+            &file_label(&group.primary),
+            &file_label(&copy.path),
+        );
+        Ok(ConflictMerge {
+            ours: ours.content,
+            theirs: theirs.content,
+            base_found,
+            merged,
+        })
+    }
+
+    /// 1つのファイルを残して競合を解決する
+    ///
+    /// `keep_path` は残すファイル（元のノートか競合コピーの相対パス）。`content` を指定した場合は
+    /// その本文（マージ結果など）で元のノートを置き換える。指定がなく競合コピーを残す場合は
+    /// 競合コピーの本文で置き換える。置き換える前の本文は履歴に残し、競合コピーは `.sync-trash` へ移す。
+    /// 手作業でコピーした複製は別のノートとして扱うため移さない（`repair_duplicate_uids` で扱う）。
+    ///
+    /// 一覧を取得した後に競合コピーが消えていた場合は、何も変更せずにエラーを返す。
+    pub fn resolve_conflict(
+        &self,
+        uid: &str,
+        keep_path: &str,
+        content: Option<String>,
+    ) -> Result<Note, RepositoryError> {
+        let group = self.group(uid)?;
+        let base_dir = self.root.get();
+        let keep = base_dir.join(keep_path);
+        if keep != group.primary && !self.find_copy(&group, keep_path)?.kind().is_conflict_copy() {
+            return Err(RepositoryError::storage(
+                "resolve_conflict",
+                StorageError::NotFound(keep),
+            ));
+        }
+        let conflict_copies: Vec<&DuplicateFile> = group.conflict_copies().collect();
+        if let Some(missing) = conflict_copies.iter().find(|copy| !self.storage.exists(&copy.path)) {
+            return Err(RepositoryError::storage(
+                "resolve_conflict",
+                StorageError::NotFound(missing.path.clone()),
+            ));
+        }
+
+        let content = match content {
+            Some(content) => Some(content),
+            None if keep != group.primary => Some(self.load_copy(uid, &keep)?.content),
+            None => None,
+        };
+        let note = match content {
+            Some(content) => self.note_service.resolve_conflict(uid, content)?,
+            None => self.note_service.load_note(uid)?,
+        };
+
+        let stamp = Local::now().format(SYNC_TRASH_TIME_FORMAT).to_string();
+        for copy in &conflict_copies {
+            self.move_to_sync_trash(&base_dir, &copy.path, &stamp)?;
+        }
+        self.repository.reindex()?;
+        let title = note.extract_heading().unwrap_or_else(|| uid.to_string());
+        self.backlink_service.update_note(uid, &title, &note.content);
+        self.event_bus.emit(DomainEvent::IndexRebuilt);
+
+        info!(
+            "[DuplicateService] Resolved {} by keeping {} ({} conflict copies removed)",
+            uid,
+            keep_path,
+            conflict_copies.len()
+        );
+        Ok(note)
+    }
+
+    /// 手作業でコピーしたファイルなど、競合コピーの名前でない複製を含む組
+    pub fn list_duplicate_uids(&self) -> Result<Vec<DuplicateGroup>, RepositoryError> {
+        Ok(self
+            .list_groups()?
+            .into_iter()
+            .filter(|group| group.plain_copies().next().is_some())
+            .collect())
+    }
+
+    /// 競合コピーの名前でない複製すべてに新しい UID を振り、インデックスに登録し直す
+    ///
+    /// front matter の `uid` のみを書き換え、ファイル名・本文（暗号化ノートは暗号文のまま）は変えない。
+    pub fn repair_duplicate_uids(&self) -> Result<Vec<UidRepair>, RepositoryError> {
+        let groups = self.list_duplicate_uids()?;
+        let mut assigned = HashSet::new();
+        let mut repairs = Vec::new();
+        for copy in groups.iter().flat_map(DuplicateGroup::plain_copies) {
+            let content = self
+                .storage
+                .load(&copy.path)
+                .map_err(|e| RepositoryError::storage("load_duplicate", e))?;
+            let mut note = Note::from_file_content(&content).map_err(|e| {
+                RepositoryError::parse(e.to_string(), Some(copy.path.clone()))
+            })?;
+
+            let new_uid = self.fresh_uid(&assigned);
+            assigned.insert(new_uid.clone());
+            note.metadata.uid = new_uid.clone();
+            self.storage
+                .save_atomic(
+                    &copy.path,
+                    &note.to_file_content_in(self.settings_service.get_arc().timestamps.zone),
+                )
+                .map_err(|e| RepositoryError::storage("save_duplicate", e))?;
+            repairs.push(UidRepair {
+                path: copy.path.clone(),
+                old_uid: copy.uid.clone(),
+                new_uid,
+            });
+        }
+
+        if !repairs.is_empty() {
+            self.repository.reindex()?;
+            if let Err(e) = self.backlink_service.rebuild_index() {
+                eprintln!("[DuplicateService] Failed to rebuild backlink index: {}", e);
+            }
+            self.event_bus.emit(DomainEvent::IndexRebuilt);
+            info!("[DuplicateService] Assigned new uids to {} duplicate files", repairs.len());
+        }
+        Ok(repairs)
+    }
+
+    /// インデックスにも今回振った中にもない UID
+    fn fresh_uid(&self, assigned: &HashSet<String>) -> String {
+        loop {
+            let uid = self.note_service.new_uid();
+            if !assigned.contains(&uid) && self.repository.get_path(&uid).is_none() {
+                return uid;
+            }
+        }
+    }
+
+    fn group(&self, uid: &str) -> Result<DuplicateGroup, RepositoryError> {
+        self.list_groups()?
+            .into_iter()
+            .find(|group| group.uid == uid)
+            .ok_or_else(|| RepositoryError::not_found(uid))
+    }
+
+    /// 組の中の複製（組にないパスは扱わない）
+    fn find_copy<'a>(&self, group: &'a DuplicateGroup, path: &str) -> Result<&'a DuplicateFile, RepositoryError> {
+        let path = self.root.get().join(path);
+        group.copies.iter().find(|copy| copy.path == path).ok_or_else(|| {
+            RepositoryError::storage("find_duplicate", StorageError::NotFound(path))
+        })
+    }
+
+    fn load_copy(&self, uid: &str, path: &Path) -> Result<Note, RepositoryError> {
+        let content = self
+            .storage
+            .load(path)
+            .map_err(|e| RepositoryError::storage("load_duplicate", e))?;
+        self.note_service.decode_version(uid, &content)
+    }
+
+    /// `before` 以前に取ったリビジョンのうち最新のものの本文
+    ///
+    /// front matter の日時は秒単位のため、秒単位で比べる。
+    fn find_base(&self, uid: &str, before: DateTime<Utc>) -> Option<String> {
+        let revisions = self.history_service.list_revisions(uid).ok()?;
+        let revision = revisions
+            .iter()
+            .find(|revision| revision.saved_at.timestamp() <= before.timestamp())?;
+        self.history_service.load_revision(uid, &revision.id).ok()
+    }
+
+    fn move_to_sync_trash(&self, base_dir: &Path, path: &Path, stamp: &str) -> Result<(), RepositoryError> {
+        let relative = path.strip_prefix(base_dir).unwrap_or(path);
+        let to: PathBuf = base_dir.join(SYNC_TRASH_DIR_NAME).join(stamp).join(relative);
+        self.storage
+            .rename(path, &to)
+            .map_err(|e| RepositoryError::storage("move_to_sync_trash", e))
+    }
+}
+
+/// 競合マーカーに付けるファイル名
+fn file_label(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::DEFAULT_VAULT_ID;
+    use crate::infrastructure::{
+        EventBusImpl, FileStorage, HeadingFilenameStrategy, HistoryStore,
+        HybridRepository, SqliteIndex,
+    };
+    use crate::services::test_support;
+    use std::fs;
+    use tempfile::TempDir;
+
+    struct Fixture {
+        service: DuplicateService,
+        note_service: Arc<NoteService>,
+        notes_dir: PathBuf,
+    }
+
+    fn create_service(temp_dir: &TempDir) -> Fixture {
+        let event_bus = Arc::new(EventBusImpl::new());
+        let settings_service = test_support::settings_service(temp_dir.path(), event_bus.clone());
+        let notes_dir = temp_dir.path().join("notes");
+        fs::create_dir_all(&notes_dir).unwrap();
+        let root = Arc::new(VaultRoot::new(DEFAULT_VAULT_ID, notes_dir.clone()));
+
+        let storage = Arc::new(FileStorage::new());
+        let repository: Arc<dyn NoteRepository> = Arc::new(HybridRepository::new(
+            Arc::new(SqliteIndex::open_in_memory().unwrap()),
+            storage.clone(),
+            Arc::new(HeadingFilenameStrategy::new()),
+            settings_service.clone(),
+            root.clone(),
+        ));
+        let history_service = Arc::new(HistoryService::new(
+            HistoryStore::new(temp_dir.path().join("history"), storage.clone()),
+            settings_service.clone(),
+        ));
+        let note_service = Arc::new(
+            NoteService::new(repository.clone(), event_bus.clone())
+                .with_history(history_service.clone()),
+        );
+        let service = DuplicateService::new(
+            repository.clone(),
+            note_service.clone(),
+            history_service,
+            Arc::new(BacklinkService::new(repository)),
+            storage,
+            settings_service,
+            event_bus,
+            root,
+        );
+        Fixture {
+            service,
+            note_service,
+            notes_dir,
+        }
+    }
+
+    #[test]
+    fn test_merge_and_resolve_conflict_copy() {
+        let temp_dir = TempDir::new().unwrap();
+        let fixture = create_service(&temp_dir);
+
+        let mut note = fixture.note_service.create_note().unwrap();
+        note.update_content("# 買い物\n牛乳\nパン".to_string());
+        fixture.note_service.save_note(&note).unwrap();
+        let primary = fixture.notes_dir.join("買い物.md");
+
+        fixture
+            .note_service
+            .save_content(note.uid(), "# 買い物\n牛乳（低脂肪）\nパン".to_string(), &Default::default())
+            .unwrap();
+        // 別の端末で編集された版が Syncthing の競合コピーとして届く
+        let mut theirs = note.clone();
+        theirs.update_content("# 買い物\n牛乳\nパン\n卵".to_string());
+        let copy = "買い物.sync-conflict-20261017-120000-ABCDEFG.md";
+        fs::write(fixture.notes_dir.join(copy), theirs.to_file_content()).unwrap();
+        // 手作業でのコピーは別のノートとして扱い、競合の解決では移さない
+        let plain = "買い物のコピー.md";
+        fs::write(fixture.notes_dir.join(plain), theirs.to_file_content()).unwrap();
+        fixture.service.repository.reindex().unwrap();
+
+        let conflicts = fixture.service.list_conflicts().unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].primary, primary);
+        assert!(conflicts[0].copies.iter().any(|c| c.path == fixture.notes_dir.join(copy)));
+
+        let merge = fixture.service.merge_conflict(note.uid(), copy).unwrap();
+        assert!(merge.base_found);
+        assert_eq!(merge.merged.conflicts, 0);
+        assert_eq!(merge.merged.content, "# 買い物\n牛乳（低脂肪）\nパン\n卵");
+
+        // 組にないファイルは扱わない
+        assert!(fixture.service.merge_conflict(note.uid(), "../outside.md").is_err());
+        assert!(fixture.service.resolve_conflict(note.uid(), plain, None).is_err());
+
+        // 競合コピーが消えていたら何も変更しない
+        fs::rename(fixture.notes_dir.join(copy), temp_dir.path().join(copy)).unwrap();
+        assert!(fixture.service.resolve_conflict(note.uid(), "買い物.md", None).is_err());
+        assert!(fixture.notes_dir.join(plain).exists());
+        fs::rename(temp_dir.path().join(copy), fixture.notes_dir.join(copy)).unwrap();
+
+        let resolved = fixture
+            .service
+            .resolve_conflict(note.uid(), "買い物.md", Some(merge.merged.content.clone()))
+            .unwrap();
+        assert_eq!(resolved.content, merge.merged.content);
+        assert!(!fixture.notes_dir.join(copy).exists());
+        assert!(fixture.notes_dir.join(plain).exists());
+        assert!(fs::read_dir(fixture.notes_dir.join(SYNC_TRASH_DIR_NAME)).unwrap().next().is_some());
+        assert!(fixture.service.list_conflicts().unwrap().is_empty());
+        assert_eq!(fixture.service.list_duplicate_uids().unwrap().len(), 1);
+        assert_eq!(fixture.note_service.load_note(note.uid()).unwrap().content, merge.merged.content);
+    }
+
+    #[test]
+    fn test_repair_duplicate_uids() {
+        let temp_dir = TempDir::new().unwrap();
+        let fixture = create_service(&temp_dir);
+
+        let mut note = fixture.note_service.create_note().unwrap();
+        note.update_content("# 議事録\n[[買い物]] を参照".to_string());
+        fixture.note_service.save_note(&note).unwrap();
+        let original = fs::read_to_string(fixture.notes_dir.join("議事録.md")).unwrap();
+        // 手作業でコピーしたファイルと競合コピー
+        fs::write(fixture.notes_dir.join("議事録 のコピー.md"), &original).unwrap();
+        fs::write(fixture.notes_dir.join("議事録 (conflicted copy).md"), &original).unwrap();
+        fixture.service.repository.reindex().unwrap();
+
+        let duplicates = fixture.service.list_duplicate_uids().unwrap();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].primary, fixture.notes_dir.join("議事録.md"));
+
+        let repairs = fixture.service.repair_duplicate_uids().unwrap();
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(repairs[0].path, fixture.notes_dir.join("議事録 のコピー.md"));
+        assert_eq!(repairs[0].old_uid, note.uid());
+        assert_ne!(repairs[0].new_uid, note.uid());
+
+        // 元のノートは UID を保ち、複製は本文を変えずに別のノートとして登録される
+        assert_eq!(fixture.service.repository.get_path(note.uid()), Some(fixture.notes_dir.join("議事録.md")));
+        let copy = fixture.note_service.load_note(&repairs[0].new_uid).unwrap();
+        assert_eq!(copy.content, note.content);
+        assert!(fixture.service.list_duplicate_uids().unwrap().is_empty());
+        // 競合コピーは解決するまで残る
+        assert_eq!(fixture.service.list_conflicts().unwrap().len(), 1);
+    }
+}
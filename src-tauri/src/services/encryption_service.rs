@@ -0,0 +1,507 @@
+//! 暗号化ノートのセッション管理サービス
+//!
+//! ロック解除時に受け取ったパスフレーズと導出済みの鍵をメモリ上にのみ保持し、
+//! 暗号化ノートの本文を読み書きのたびに復号・暗号化する。
+//!
+//! 最後の操作から `EncryptionSettings::session_timeout_secs` が経つと、バックグラウンドスレッドが
+//! セッションを破棄してロックする（`DomainEvent::NotesLocked` を発行）。
+
+use crate::domain::{
+    DomainEvent, EncryptedBody, EncryptionError, EncryptionStatus, KdfParams, Note,
+};
+use crate::infrastructure::note_cipher::{self, NoteKey};
+use crate::services::SettingsService;
+use crate::traits::{EventBus, NoteRepository, RepositoryError, SubscriptionId};
+use log::info;
+use parking_lot::{Condvar, Mutex};
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
+
+/// ロック解除中のセッション
+struct Session {
+    passphrase: Zeroizing<String>,
+    /// 新しく暗号化するときのソルトと鍵導出パラメータ
+    salt: Vec<u8>,
+    params: KdfParams,
+    /// 導出済みの鍵（ソルトごと）
+    keys: HashMap<Vec<u8>, NoteKey>,
+    last_used: Instant,
+}
+
+impl Session {
+    /// ソルトに対応する鍵（未導出なら導出してキャッシュ）
+    fn key(&mut self, salt: &[u8], params: &KdfParams) -> Result<&NoteKey, EncryptionError> {
+        if !self.keys.contains_key(salt) {
+            let key = note_cipher::derive_key(&self.passphrase, salt, params)?;
+            self.keys.insert(salt.to_vec(), key);
+        }
+        Ok(&self.keys[salt])
+    }
+}
+
+/// 自動ロックのスレッドの状態
+#[derive(Default)]
+struct Schedule {
+    /// ロック解除・設定の変更で期限の計算し直しが必要
+    changed: bool,
+    shutdown: bool,
+}
+
+/// 暗号化ノートのセッション管理サービス
+pub struct EncryptionService {
+    repository: Arc<dyn NoteRepository>,
+    settings_service: Arc<SettingsService>,
+    event_bus: Arc<dyn EventBus>,
+    kdf_params: KdfParams,
+    session: Mutex<Option<Session>>,
+    schedule: Mutex<Schedule>,
+    wakeup: Condvar,
+    subscription: Mutex<Option<SubscriptionId>>,
+}
+
+impl EncryptionService {
+    pub fn new(
+        repository: Arc<dyn NoteRepository>,
+        settings_service: Arc<SettingsService>,
+        event_bus: Arc<dyn EventBus>,
+    ) -> Self {
+        Self {
+            repository,
+            settings_service,
+            event_bus,
+            kdf_params: KdfParams::default(),
+            session: Mutex::new(None),
+            schedule: Mutex::new(Schedule::default()),
+            wakeup: Condvar::new(),
+            subscription: Mutex::new(None),
+        }
+    }
+
+    /// 新しく暗号化するときの鍵導出パラメータを変更する
+    pub fn with_kdf_params(mut self, params: KdfParams) -> Self {
+        self.kdf_params = params;
+        self
+    }
+
+    /// 自動ロックのスレッドを起動（設定の変更で期限を計算し直す）
+    pub fn start(self: &Arc<Self>) -> JoinHandle<()> {
+        let service: Weak<Self> = Arc::downgrade(self);
+        *self.subscription.lock() = Some(self.event_bus.subscribe(
+            "settings:changed",
+            Arc::new(move |_| {
+                if let Some(service) = service.upgrade() {
+                    service.notify();
+                }
+            }),
+        ));
+
+        let service = Arc::clone(self);
+        thread::Builder::new()
+            .name("encryption-timeout".to_string())
+            .spawn(move || service.run())
+            .expect("Failed to spawn encryption timeout thread")
+    }
+
+    /// 自動ロックのスレッドを停止
+    pub fn shutdown(&self) {
+        if let Some(id) = self.subscription.lock().take() {
+            self.event_bus.unsubscribe(id);
+        }
+        self.schedule.lock().shutdown = true;
+        self.wakeup.notify_all();
+    }
+
+    /// パスフレーズでロックを解除
+    ///
+    /// 暗号化ノートがあれば1件を復号してパスフレーズを検証する。まだない場合は
+    /// このパスフレーズで以降のノートを暗号化する。
+    pub fn unlock(&self, passphrase: &str) -> Result<(), RepositoryError> {
+        if passphrase.is_empty() {
+            return Err(EncryptionError::EmptyPassphrase.into());
+        }
+
+        let mut session = Session {
+            passphrase: Zeroizing::new(passphrase.to_string()),
+            salt: Vec::new(),
+            params: self.kdf_params,
+            keys: HashMap::new(),
+            last_used: Instant::now(),
+        };
+
+        match self.sample_body()? {
+            Some(body) => {
+                note_cipher::open(session.key(&body.salt, &body.params)?, &body)?;
+                session.salt = body.salt;
+                session.params = body.params;
+            }
+            None => session.salt = note_cipher::generate_salt()?,
+        }
+
+        *self.session.lock() = Some(session);
+        self.notify();
+        info!("[Encryption] Unlocked");
+        Ok(())
+    }
+
+    /// ロックする（保持していたパスフレーズと鍵を破棄）
+    pub fn lock(&self) {
+        if self.session.lock().take().is_some() {
+            info!("[Encryption] Locked");
+            self.event_bus.emit(DomainEvent::NotesLocked);
+        }
+    }
+
+    /// セッションの状態
+    pub fn status(&self) -> EncryptionStatus {
+        let mut guard = self.session.lock();
+        self.expire(&mut guard);
+        let timeout = self.timeout();
+
+        EncryptionStatus {
+            unlocked: guard.is_some(),
+            expires_in_secs: guard.as_ref().zip(timeout).map(|(session, timeout)| {
+                timeout.saturating_sub(session.last_used.elapsed()).as_secs()
+            }),
+        }
+    }
+
+    /// 暗号化ノートの本文を復号する（暗号化ノート以外はそのまま）
+    ///
+    /// `encrypted: true` でも本文がまだ暗号化されていない場合（手で追記した場合など）は
+    /// 平文として扱い、次の保存時に暗号化する。
+    pub fn decrypt(&self, note: &mut Note) -> Result<(), EncryptionError> {
+        if !note.metadata.encrypted || !EncryptedBody::is_armored(&note.content) {
+            return Ok(());
+        }
+        let body = EncryptedBody::parse(&note.content)?;
+        note.content = self.with_session(|session| {
+            note_cipher::open(session.key(&body.salt, &body.params)?, &body)
+        })?;
+        Ok(())
+    }
+
+    /// 保存用に本文を暗号化したノートを返す
+    ///
+    /// 見出しから付けたタイトルは平文の front matter に残さない。
+    pub fn encrypt(&self, note: &Note) -> Result<Note, EncryptionError> {
+        let armor = self.with_session(|session| {
+            let (salt, params) = (session.salt.clone(), session.params);
+            let key = session.key(&salt, &params)?;
+            Ok(note_cipher::seal(key, &salt, params, &note.content)?.to_armor())
+        })?;
+
+        let mut sealed = note.clone();
+        sealed.content = armor;
+        sealed.metadata.title = None;
+        Ok(sealed)
+    }
+
+    /// ロック解除中のセッションで処理し、最終操作時刻を更新
+    fn with_session<T>(
+        &self,
+        f: impl FnOnce(&mut Session) -> Result<T, EncryptionError>,
+    ) -> Result<T, EncryptionError> {
+        let mut guard = self.session.lock();
+        self.expire(&mut guard);
+        let session = guard.as_mut().ok_or(EncryptionError::Locked)?;
+        session.last_used = Instant::now();
+        f(session)
+    }
+
+    /// タイムアウトしたセッションを破棄
+    fn expire(&self, session: &mut Option<Session>) {
+        let expired = session
+            .as_ref()
+            .zip(self.timeout())
+            .is_some_and(|(session, timeout)| session.last_used.elapsed() >= timeout);
+        if expired {
+            *session = None;
+            info!("[Encryption] Session timed out");
+            self.event_bus.emit(DomainEvent::NotesLocked);
+        }
+    }
+
+    /// 自動ロックのスレッドに期限を計算し直させる
+    fn notify(&self) {
+        self.schedule.lock().changed = true;
+        self.wakeup.notify_one();
+    }
+
+    /// 期限まで待ってセッションを破棄する（セッションの使用で期限が延びた場合は待ち直す）
+    fn run(&self) {
+        loop {
+            let expires_at = {
+                let mut session = self.session.lock();
+                self.expire(&mut session);
+                session
+                    .as_ref()
+                    .zip(self.timeout())
+                    .map(|(session, timeout)| session.last_used + timeout)
+            };
+
+            let mut schedule = self.schedule.lock();
+            if schedule.shutdown {
+                return;
+            }
+            if std::mem::take(&mut schedule.changed) {
+                continue;
+            }
+            match expires_at {
+                Some(due) => {
+                    self.wakeup.wait_until(&mut schedule, due);
+                }
+                None => self.wakeup.wait(&mut schedule),
+            }
+        }
+    }
+
+    /// 自動ロックまでの時間（0 は自動でロックしない）
+    fn timeout(&self) -> Option<Duration> {
+        let secs = self.settings_service.get_arc().encryption.session_timeout_secs;
+        (secs > 0).then(|| Duration::from_secs(secs))
+    }
+
+    /// パスフレーズの検証に使う暗号化ノートの本文
+    fn sample_body(&self) -> Result<Option<EncryptedBody>, RepositoryError> {
+        for item in self.repository.list_all()? {
+            if !item.encrypted {
+                continue;
+            }
+            let note = self.repository.load(&item.uid)?;
+            if EncryptedBody::is_armored(&note.content) {
+                return Ok(Some(EncryptedBody::parse(&note.content)?));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::DEFAULT_VAULT_ID;
+    use crate::infrastructure::{
+        EventBusImpl, FileStorage, HeadingFilenameStrategy,
+        HybridRepository, SqliteIndex, VaultRoot,
+    };
+    use crate::services::test_support;
+    use tempfile::TempDir;
+
+    /// テスト用の軽いパラメータ
+    const TEST_PARAMS: KdfParams = KdfParams {
+        m_cost: 64,
+        t_cost: 1,
+        p_cost: 1,
+    };
+
+    fn create_service(temp_dir: &TempDir) -> (EncryptionService, Arc<dyn NoteRepository>, Arc<SettingsService>) {
+        let event_bus = Arc::new(EventBusImpl::new());
+        let settings_service = test_support::settings_service(temp_dir.path(), event_bus.clone());
+        let repository: Arc<dyn NoteRepository> = Arc::new(HybridRepository::new(
+            Arc::new(SqliteIndex::open_in_memory().unwrap()),
+            Arc::new(FileStorage::new()),
+            Arc::new(HeadingFilenameStrategy::new()),
+            settings_service.clone(),
+            Arc::new(VaultRoot::new(DEFAULT_VAULT_ID, temp_dir.path().join("notes"))),
+        ));
+        let service = EncryptionService::new(repository.clone(), settings_service.clone(), event_bus)
+            .with_kdf_params(TEST_PARAMS);
+        (service, repository, settings_service)
+    }
+
+    #[test]
+    fn test_unlock_verifies_passphrase() {
+        let temp_dir = TempDir::new().unwrap();
+        let (service, repository, _) = create_service(&temp_dir);
+
+        let mut note = Note::new();
+        note.content = "# 暗証番号\n\n0000".to_string();
+        note.metadata.encrypted = true;
+        service.unlock("first").unwrap();
+        repository.save(&service.encrypt(&note).unwrap()).unwrap();
+        service.lock();
+        assert!(!service.status().unlocked);
+
+        let mut loaded = repository.load(note.uid()).unwrap();
+        assert!(matches!(service.decrypt(&mut loaded), Err(EncryptionError::Locked)));
+        assert!(matches!(
+            service.unlock("second"),
+            Err(RepositoryError::Encryption(EncryptionError::WrongPassphrase))
+        ));
+
+        service.unlock("first").unwrap();
+        service.decrypt(&mut loaded).unwrap();
+        assert_eq!(loaded.content, "# 暗証番号\n\n0000");
+    }
+
+    #[test]
+    fn test_encrypted_note_is_not_stored_in_plaintext() {
+        use crate::infrastructure::HistoryStore;
+        use crate::services::{HistoryService, NoteService};
+
+        let temp_dir = TempDir::new().unwrap();
+        let (service, repository, settings_service) = create_service(&temp_dir);
+        let service = Arc::new(service);
+        let history = Arc::new(HistoryService::new(
+            HistoryStore::new(temp_dir.path().join("history"), Arc::new(FileStorage::new())),
+            settings_service,
+        ));
+        let note_service = NoteService::new(repository.clone(), Arc::new(EventBusImpl::new()))
+            .with_history(history.clone())
+            .with_encryption(service.clone());
+
+        let note = note_service.create_titled_note("Bank").unwrap();
+        let uid = note.uid().to_string();
+        note_service
+            .save_content(&uid, "# Bank\n\nPIN 9876".to_string(), &Default::default())
+            .unwrap();
+        assert!(!history.list_revisions(&uid).unwrap().is_empty());
+
+        // ロック中は暗号化できない
+        assert!(note_service.set_encrypted(&uid, true).is_err());
+        assert!(!note_service.is_encrypted(&uid));
+        service.unlock("passphrase").unwrap();
+        note_service.set_encrypted(&uid, true).unwrap();
+        assert!(note_service.is_encrypted(&uid));
+        assert!(history.list_revisions(&uid).unwrap().is_empty());
+
+        // ファイル名・本文・一覧のタイトルに平文が残らない
+        let path = repository.get_path(&uid).unwrap();
+        assert!(!path.to_string_lossy().contains("Bank"));
+        let on_disk = std::fs::read_to_string(&path).unwrap();
+        assert!(!on_disk.contains("9876") && !on_disk.contains("Bank"));
+        assert_eq!(repository.list_all().unwrap()[0].title, uid);
+
+        // 保存・読み込みは平文で行える
+        let saved = note_service
+            .save_content(&uid, "# Bank\n\nPIN 5555".to_string(), &Default::default())
+            .unwrap();
+        assert_eq!(saved.content, "# Bank\n\nPIN 5555");
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("5555"));
+        assert_eq!(note_service.load_note(&uid).unwrap().content, "# Bank\n\nPIN 5555");
+
+        service.lock();
+        assert!(matches!(
+            note_service.load_note(&uid),
+            Err(RepositoryError::Encryption(EncryptionError::Locked))
+        ));
+
+        // 暗号化を解除するとファイル名も見出しに戻る
+        service.unlock("passphrase").unwrap();
+        note_service.set_encrypted(&uid, false).unwrap();
+        let path = repository.get_path(&uid).unwrap();
+        assert!(path.ends_with("Bank.md"));
+        assert!(std::fs::read_to_string(&path).unwrap().contains("PIN 5555"));
+    }
+
+    #[test]
+    fn test_no_plaintext_file_remains_after_encrypting() {
+        use crate::infrastructure::{DraftJournal, HistoryStore};
+        use crate::services::{AutosaveService, BacklinkService, DraftService, HistoryService, NoteService};
+
+        let temp_dir = TempDir::new().unwrap();
+        let (service, repository, settings_service) = create_service(&temp_dir);
+        let service = Arc::new(service);
+        let event_bus = Arc::new(EventBusImpl::new());
+        let history = Arc::new(HistoryService::new(
+            HistoryStore::new(temp_dir.path().join("history"), Arc::new(FileStorage::new())),
+            settings_service.clone(),
+        ));
+        let note_service = Arc::new(
+            NoteService::new(repository.clone(), event_bus.clone())
+                .with_history(history)
+                .with_encryption(service.clone()),
+        );
+        let autosave = AutosaveService::new(
+            note_service.clone(),
+            Arc::new(BacklinkService::new(repository.clone())),
+            Arc::new(DraftService::new(
+                DraftJournal::new(temp_dir.path().join("drafts"), Arc::new(FileStorage::new())),
+                repository.clone(),
+            )),
+            settings_service,
+            event_bus,
+        );
+
+        let uid = note_service.create_titled_note("Bank").unwrap().uid().to_string();
+        note_service
+            .save_content(&uid, "# Bank\n\nPIN 9876".to_string(), &Default::default())
+            .unwrap();
+        // 暗号化の直前に届いた自動保存（下書きが記録され、保存待ちになる）
+        autosave.request(&uid, "# Bank\n\nPIN 9876 1234".to_string(), Default::default());
+
+        service.unlock("passphrase").unwrap();
+        note_service.set_encrypted(&uid, true).unwrap();
+        autosave.discard(&uid).unwrap();
+
+        fn files(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+            std::fs::read_dir(dir)
+                .unwrap()
+                .flatten()
+                .flat_map(|entry| match entry.path() {
+                    path if path.is_dir() => files(&path),
+                    path => vec![path],
+                })
+                .collect()
+        }
+        for path in files(temp_dir.path()) {
+            let content = std::fs::read(&path).unwrap();
+            assert!(
+                !String::from_utf8_lossy(&content).contains("9876"),
+                "plaintext in {}",
+                path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn test_session_times_out() {
+        let temp_dir = TempDir::new().unwrap();
+        let (service, _, settings_service) = create_service(&temp_dir);
+
+        service.unlock("passphrase").unwrap();
+        let status = service.status();
+        assert!(status.unlocked);
+        assert!(status.expires_in_secs.is_some_and(|secs| secs <= 600));
+
+        // 0 は自動でロックしない
+        settings_service
+            .update(|s| s.encryption.session_timeout_secs = 0)
+            .unwrap();
+        assert_eq!(service.status().expires_in_secs, None);
+
+        // 期限切れのセッションは次の操作でロックされる
+        service.session.lock().as_mut().unwrap().last_used -= Duration::from_secs(2);
+        settings_service
+            .update(|s| s.encryption.session_timeout_secs = 1)
+            .unwrap();
+        assert!(matches!(service.encrypt(&Note::new()), Err(EncryptionError::Locked)));
+        assert!(!service.status().unlocked);
+    }
+
+    #[test]
+    fn test_session_is_locked_in_background() {
+        let temp_dir = TempDir::new().unwrap();
+        let (service, _, settings_service) = create_service(&temp_dir);
+        let service = Arc::new(service);
+        let handle = service.start();
+
+        service.unlock("passphrase").unwrap();
+        service.session.lock().as_mut().unwrap().last_used -= Duration::from_secs(2);
+        settings_service
+            .update(|s| s.encryption.session_timeout_secs = 1)
+            .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while service.session.lock().is_some() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(service.session.lock().is_none());
+
+        service.shutdown();
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,73 @@
+//! リビジョン履歴サービス
+//!
+//! 保存時に上書きされる直前の本文をスナップショットとして残す。
+//! 自動保存のたびに履歴が増えないよう、設定の最小間隔で間引く。
+
+use crate::domain::{diff_lines, DiffLine, Revision};
+use crate::infrastructure::HistoryStore;
+use crate::services::SettingsService;
+use crate::traits::StorageError;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+/// リビジョン履歴サービス
+pub struct HistoryService {
+    store: HistoryStore,
+    settings_service: Arc<SettingsService>,
+}
+
+impl HistoryService {
+    pub fn new(store: HistoryStore, settings_service: Arc<SettingsService>) -> Self {
+        Self {
+            store,
+            settings_service,
+        }
+    }
+
+    /// スナップショットを取る時期か（履歴が無効なら常に false）
+    pub fn is_due(&self, uid: &str) -> bool {
+        let settings = self.settings_service.get_arc();
+        if !settings.history.enabled {
+            return false;
+        }
+
+        match self.store.latest(uid) {
+            Ok(Some(latest)) => {
+                let interval = Duration::seconds(settings.history.min_interval_secs as i64);
+                Utc::now() - latest.saved_at >= interval
+            }
+            Ok(None) => true,
+            Err(_) => true,
+        }
+    }
+
+    /// スナップショットを保存（最新リビジョンと同じ内容なら `None`）
+    pub fn snapshot(&self, uid: &str, content: &str) -> Result<Option<Revision>, StorageError> {
+        let max_revisions = self.settings_service.get_arc().history.max_revisions;
+        self.store.append(uid, content, Utc::now(), max_revisions)
+    }
+
+    /// リビジョン一覧を取得（新しい順）
+    pub fn list_revisions(&self, uid: &str) -> Result<Vec<Revision>, StorageError> {
+        let mut revisions = self.store.revisions(uid)?;
+        revisions.reverse();
+        Ok(revisions)
+    }
+
+    /// リビジョンの本文を取得
+    pub fn load_revision(&self, uid: &str, id: &str) -> Result<String, StorageError> {
+        self.store.load(uid, id)
+    }
+
+    /// ノートの履歴をすべて削除（暗号化したノートの平文を残さないため）
+    pub fn clear(&self, uid: &str) -> Result<(), StorageError> {
+        self.store.clear(uid)
+    }
+
+    /// 2つのリビジョンの差分を計算
+    pub fn diff(&self, uid: &str, from: &str, to: &str) -> Result<Vec<DiffLine>, StorageError> {
+        let old = self.store.load(uid, from)?;
+        let new = self.store.load(uid, to)?;
+        Ok(diff_lines(&old, &new))
+    }
+}
@@ -3,9 +3,33 @@ pub mod settings_service;
 pub mod window_service;
 pub mod search_service;
 pub mod backlink_service;
+pub mod history_service;
+pub mod autosave_service;
+pub mod draft_service;
+pub mod template_service;
+pub mod relocation_service;
+pub mod encryption_service;
+pub mod versioning_service;
+pub mod backup_service;
+pub mod sync_service;
+pub mod duplicate_service;
+pub mod trash_service;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 pub use note_service::NoteService;
 pub use settings_service::SettingsService;
 pub use window_service::{WindowService, ToggleResult};
 pub use search_service::SearchService;
 pub use backlink_service::BacklinkService;
+pub use history_service::HistoryService;
+pub use autosave_service::AutosaveService;
+pub use draft_service::DraftService;
+pub use template_service::TemplateService;
+pub use relocation_service::{RelocationService, RelocationError};
+pub use encryption_service::EncryptionService;
+pub use versioning_service::VersioningService;
+pub use backup_service::BackupService;
+pub use sync_service::SyncService;
+pub use duplicate_service::DuplicateService;
+pub use trash_service::TrashService;
@@ -1,12 +1,29 @@
-use crate::domain::{DomainEvent, Note};
+use crate::domain::{
+    build_folder_tree, DailyDirection, DailyNoteEntry, DomainEvent, EncryptionError,
+    ExpectedVersion, FolderNode, NormalizeReport, Note, NoteSortOrder, RenameReport, TemplateVars,
+    TrashItem,
+};
+use chrono::{Duration, NaiveDate, Utc};
 use crate::infrastructure::GalleryNote;
-use crate::traits::{EventBus, NoteListItem, NoteRepository, RepositoryError};
+use crate::services::{EncryptionService, HistoryService, TemplateService};
+use crate::traits::{
+    EventBus, NoteFilter, NoteListItem, NoteRepository, Page, PageCursor, RepositoryError,
+    UidGenerator,
+};
+use parking_lot::Mutex;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 /// ノートサービス（ビジネスロジック層）
 pub struct NoteService {
     repository: Arc<dyn NoteRepository>,
     event_bus: Arc<dyn EventBus>,
+    history: Option<Arc<HistoryService>>,
+    templates: Option<Arc<TemplateService>>,
+    encryption: Option<Arc<EncryptionService>>,
+    uid_generator: Option<Arc<dyn UidGenerator>>,
+    /// デイリーノートの確認と作成を直列化（同じ日付のノートを二重に作らない）
+    daily_lock: Mutex<()>,
 }
 
 impl NoteService {
@@ -14,13 +31,107 @@ impl NoteService {
         Self {
             repository,
             event_bus,
+            history: None,
+            templates: None,
+            encryption: None,
+            uid_generator: None,
+            daily_lock: Mutex::new(()),
         }
     }
 
+    /// リビジョン履歴を有効にする
+    pub fn with_history(mut self, history: Arc<HistoryService>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// テンプレートを有効にする
+    pub fn with_templates(mut self, templates: Arc<TemplateService>) -> Self {
+        self.templates = Some(templates);
+        self
+    }
+
+    /// 暗号化ノートを有効にする
+    pub fn with_encryption(mut self, encryption: Arc<EncryptionService>) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
+    /// 新しいノートの UID の生成戦略を設定する（未設定ならタイムスタンプ形式）
+    pub fn with_uid_generator(mut self, uid_generator: Arc<dyn UidGenerator>) -> Self {
+        self.uid_generator = Some(uid_generator);
+        self
+    }
+
+    /// 新しいノートの UID を生成
+    pub fn new_uid(&self) -> String {
+        match &self.uid_generator {
+            Some(generator) => generator.generate(),
+            None => Note::new().metadata.uid,
+        }
+    }
+
+    /// 作成したノートに新しい UID を振る（テンプレートなどから作ったノートも同じ形式にする）
+    fn with_new_uid(&self, mut note: Note) -> Note {
+        if self.uid_generator.is_some() {
+            note.metadata.uid = self.new_uid();
+        }
+        note
+    }
+
     /// 新規メモを作成（ファイルは保存しない、メモリ上のみ）
+    ///
+    /// デフォルトテンプレートが設定されている場合はそれを適用する。
+    /// テンプレートの front matter（タグなど）を失わないよう、その場合は保存まで行う。
     pub fn create_note(&self) -> Result<Note, RepositoryError> {
-        let note = Note::new();
-        // ファイルは保存しない - 内容が入力されたときに初めて保存
+        let note = self.with_new_uid(match &self.templates {
+            Some(templates) => templates.new_note(None),
+            None => Note::new(),
+        });
+        // 空のメモは保存しない - 内容が入力されたときに初めて保存
+        if !note.content.is_empty() {
+            self.repository.save(&note)?;
+        }
+
+        self.event_bus.emit(DomainEvent::NoteCreated {
+            uid: note.metadata.uid.clone(),
+        });
+
+        Ok(note)
+    }
+
+    /// タイトル付きのメモを作成して保存（ウィキリンクのリンク先など）
+    pub fn create_titled_note(&self, title: &str) -> Result<Note, RepositoryError> {
+        let note = self.with_new_uid(match &self.templates {
+            Some(templates) => templates.new_note(Some(title)),
+            None => Note::with_title(title),
+        });
+        self.save_note(&note)?;
+
+        self.event_bus.emit(DomainEvent::NoteCreated {
+            uid: note.metadata.uid.clone(),
+        });
+
+        Ok(note)
+    }
+
+    /// テンプレートからメモを作成して保存
+    pub fn create_note_from_template(
+        &self,
+        name: &str,
+        vars: TemplateVars,
+    ) -> Result<Note, RepositoryError> {
+        let templates = self.templates.as_ref().ok_or_else(|| {
+            RepositoryError::storage(
+                "load_template",
+                crate::traits::StorageError::NotFound(name.into()),
+            )
+        })?;
+        let note = templates
+            .instantiate(name, vars)
+            .map(|note| self.with_new_uid(note))
+            .map_err(|e| RepositoryError::storage("load_template", e))?;
+        self.save_note(&note)?;
 
         self.event_bus.emit(DomainEvent::NoteCreated {
             uid: note.metadata.uid.clone(),
@@ -29,9 +140,109 @@ impl NoteService {
         Ok(note)
     }
 
+    /// 指定日のデイリーノートを開く（なければテンプレートから作成して保存）
+    ///
+    /// 作成した場合は `true` を返す。
+    pub fn open_daily_note(&self, date: NaiveDate) -> Result<(Note, bool), RepositoryError> {
+        let _guard = self.daily_lock.lock();
+        if let Some(entry) = self.repository.list_daily_notes(Some(date), Some(date))?.first() {
+            return Ok((self.load_plain(&entry.uid)?, false));
+        }
+
+        let note = self.with_new_uid(match &self.templates {
+            Some(templates) => templates.daily_note(date),
+            None => {
+                let mut note = Note::with_title(&date.format("%Y-%m-%d").to_string());
+                note.metadata.daily = Some(date);
+                note
+            }
+        });
+        self.save_note(&note)?;
+
+        self.event_bus.emit(DomainEvent::NoteCreated {
+            uid: note.metadata.uid.clone(),
+        });
+
+        Ok((note, true))
+    }
+
+    /// 指定日の前後で最も近い既存のデイリーノート（指定日自身は含まない）
+    pub fn adjacent_daily_note(
+        &self,
+        date: NaiveDate,
+        direction: DailyDirection,
+    ) -> Result<Option<DailyNoteEntry>, RepositoryError> {
+        self.repository.adjacent_daily_note(date, direction)
+    }
+
+    /// 期間内のデイリーノート一覧（カレンダー表示用）
+    pub fn list_daily_notes(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyNoteEntry>, RepositoryError> {
+        self.repository.list_daily_notes(Some(from), Some(to))
+    }
+
+    /// 全ノートのファイル名を現在のファイル名生成戦略で付け直す
+    ///
+    /// 1件の失敗で中断せず、失敗したノートは結果に記録する。
+    pub fn rename_all_files(&self) -> Result<RenameReport, RepositoryError> {
+        let mut report = RenameReport::default();
+
+        for item in self.repository.list_all()? {
+            match self.repository.relocate(&item.uid) {
+                Ok(Some(_)) => report.renamed += 1,
+                Ok(None) => report.unchanged += 1,
+                Err(e) => {
+                    eprintln!("[NoteService] Failed to rename: uid={}, {}", item.uid, e);
+                    report.failed.push(item.uid);
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 全ノートの front matter を現在の日時の形式で書き直す（本文と更新日時は変えない）
+    ///
+    /// 書き直したノートごとに `DomainEvent::NoteUpdated` を発行する（ファイルのハッシュが変わるため、
+    /// 開いているノートなどは読み込み直す）。
+    pub fn normalize_timestamps(&self) -> Result<NormalizeReport, RepositoryError> {
+        let mut report = NormalizeReport::default();
+
+        for item in self.repository.list_all()? {
+            match self.repository.rewrite_front_matter(&item.uid) {
+                Ok(true) => {
+                    report.updated += 1;
+                    self.event_bus.emit(DomainEvent::NoteUpdated { uid: item.uid });
+                }
+                Ok(false) => report.unchanged += 1,
+                Err(e) => {
+                    eprintln!("[NoteService] Failed to normalize timestamps: uid={}, {}", item.uid, e);
+                    report.failed.push(item.uid);
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
     /// メモを保存
     pub fn save_note(&self, note: &Note) -> Result<(), RepositoryError> {
-        self.repository.save(note)?;
+        self.save_note_checked(note, &ExpectedVersion::default())
+    }
+
+    /// 前提の版と一致する場合のみメモを保存
+    ///
+    /// ファイルが他の場所で変更されていた場合は `RepositoryError::Conflict` を返す。
+    pub fn save_note_checked(
+        &self,
+        note: &Note,
+        expected: &ExpectedVersion,
+    ) -> Result<(), RepositoryError> {
+        self.snapshot_previous(note);
+        self.store(note, expected)?;
 
         self.event_bus.emit(DomainEvent::SaveCompleted {
             uid: note.metadata.uid.clone(),
@@ -40,9 +251,160 @@ impl NoteService {
         Ok(())
     }
 
-    /// メモをロード
+    /// メモを書き込む（暗号化ノートは本文を暗号化してから書き込む）
+    ///
+    /// 暗号化ノートの版の検査は、ファイルの暗号文ではなく復号した本文で行う
+    /// （検査と書き込みはリポジトリの同じ書き込みロックの中で行う）。
+    fn store(&self, note: &Note, expected: &ExpectedVersion) -> Result<(), RepositoryError> {
+        if !note.metadata.encrypted {
+            self.repository.save_checked(note, expected)?;
+            return Ok(());
+        }
+
+        let encryption = self.encryption.as_ref().ok_or(EncryptionError::Locked)?;
+        let sealed = encryption.encrypt(note)?;
+        let verify = || {
+            if expected.is_unconditional() {
+                return Ok(());
+            }
+            match self.load_plain(note.uid()) {
+                Ok(current) if current.content != note.content && !expected.matches(&current) => {
+                    Err(RepositoryError::conflict(note.clone(), current))
+                }
+                Ok(_) | Err(RepositoryError::NotFound { .. }) => Ok(()),
+                Err(e) => Err(e),
+            }
+        };
+        self.repository.save_verified(&sealed, &verify)?;
+        Ok(())
+    }
+
+    /// メモを読み込み、暗号化ノートは本文を復号する
+    fn load_plain(&self, uid: &str) -> Result<Note, RepositoryError> {
+        let mut note = self.repository.load(uid)?;
+        if note.metadata.encrypted {
+            self.encryption
+                .as_ref()
+                .ok_or(EncryptionError::Locked)?
+                .decrypt(&mut note)?;
+        }
+        Ok(note)
+    }
+
+    /// 本文を置き換えて保存（メモがまだ保存されていなければ新規作成）
+    ///
+    /// 本文が変わっていない場合は保存しない。保存後のメモを返す。
+    pub fn save_content(
+        &self,
+        uid: &str,
+        content: String,
+        expected: &ExpectedVersion,
+    ) -> Result<Note, RepositoryError> {
+        let mut note = match self.load_plain(uid) {
+            Ok(note) => note,
+            // ノートが存在しない場合は新規作成（UIDを保持）
+            Err(RepositoryError::NotFound { .. }) => Note::with_uid(uid.to_string()),
+            Err(e) => return Err(e),
+        };
+
+        if note.content == content && self.repository.get_path(uid).is_some() {
+            return Ok(note);
+        }
+
+        note.update_content(content);
+        self.save_note_checked(&note, expected)?;
+        Ok(note)
+    }
+
+    /// 上書きされる直前の本文を履歴に残す（最小間隔で間引く）
+    ///
+    /// 履歴の保存に失敗してもメモの保存は妨げない。暗号化ノートの履歴は残さない。
+    fn snapshot_previous(&self, note: &Note) {
+        let Some(history) = &self.history else {
+            return;
+        };
+        if note.metadata.encrypted {
+            return;
+        }
+        let uid = note.uid();
+        if !history.is_due(uid) {
+            return;
+        }
+        // 未保存の新規メモは前の内容がない
+        let Ok(previous) = self.repository.load(uid) else {
+            return;
+        };
+        if previous.content == note.content {
+            return;
+        }
+        if let Err(e) = history.snapshot(uid, &previous.content) {
+            eprintln!("[NoteService] Failed to snapshot revision: uid={}, {}", uid, e);
+        }
+    }
+
+    /// リビジョンの本文でメモを置き換える
+    ///
+    /// 復元前の本文は間隔に関係なく履歴に残すため、復元自体も元に戻せる。
+    pub fn restore_revision(&self, uid: &str, revision_id: &str) -> Result<Note, RepositoryError> {
+        let history = self.history.as_ref().ok_or_else(|| RepositoryError::NotFound {
+            uid: uid.to_string(),
+        })?;
+
+        let content = history
+            .load_revision(uid, revision_id)
+            .map_err(|e| RepositoryError::storage("load_revision", e))?;
+
+        self.replace_content(uid, content)
+    }
+
+    /// 以前の版のファイル内容（front matter を含む）をメモとして読む
+    ///
+    /// 暗号化されていた版は復号する（ロック中はエラー）。保存はしない。
+    pub fn decode_version(&self, uid: &str, file_content: &str) -> Result<Note, RepositoryError> {
+        let mut note = Note::from_file_content(file_content)
+            .map_err(|e| RepositoryError::parse(format!("{}: {}", uid, e), None))?;
+        if note.metadata.encrypted {
+            self.encryption
+                .as_ref()
+                .ok_or(EncryptionError::Locked)?
+                .decrypt(&mut note)?;
+        }
+        Ok(note)
+    }
+
+    /// 以前の版のファイル内容の本文でメモを置き換える（front matter は現在のものを保つ）
+    pub fn restore_version(&self, uid: &str, file_content: &str) -> Result<Note, RepositoryError> {
+        let version = self.decode_version(uid, file_content)?;
+        self.replace_content(uid, version.content)
+    }
+
+    /// 競合を解決した本文でメモを置き換える（置き換える前の本文は履歴に残す）
+    pub fn resolve_conflict(&self, uid: &str, content: String) -> Result<Note, RepositoryError> {
+        self.replace_content(uid, content)
+    }
+
+    /// 本文を置き換えて保存（置き換える前の本文は間隔に関係なく履歴に残す）
+    fn replace_content(&self, uid: &str, content: String) -> Result<Note, RepositoryError> {
+        let mut note = self.load_plain(uid)?;
+        if let (Some(history), false) = (&self.history, note.metadata.encrypted) {
+            history
+                .snapshot(uid, &note.content)
+                .map_err(|e| RepositoryError::storage("save_revision", e))?;
+        }
+
+        note.update_content(content);
+        self.store(&note, &ExpectedVersion::default())?;
+
+        self.event_bus.emit(DomainEvent::SaveCompleted {
+            uid: uid.to_string(),
+        });
+
+        Ok(note)
+    }
+
+    /// メモをロード（暗号化ノートは復号する、ロック中はエラー）
     pub fn load_note(&self, uid: &str) -> Result<Note, RepositoryError> {
-        let note = self.repository.load(uid)?;
+        let note = self.load_plain(uid)?;
 
         self.event_bus.emit(DomainEvent::NoteLoaded {
             uid: uid.to_string(),
@@ -51,6 +413,43 @@ impl NoteService {
         Ok(note)
     }
 
+    /// 暗号化ノートか（存在しないメモは `false`、ファイルは読まずにインデックスで判定）
+    pub fn is_encrypted(&self, uid: &str) -> bool {
+        self.repository
+            .get_list_item(uid)
+            .is_ok_and(|item| item.is_some_and(|item| item.encrypted))
+    }
+
+    /// メモの本文を暗号化する、または暗号化を解除する（変更後のメモを返す）
+    ///
+    /// 暗号化するときは、平文が残らないよう履歴を削除し、見出しから付けたファイル名を付け直す。
+    /// 解除するときは見出しからファイル名を付け直す。いずれもロック解除中のみ行える。
+    pub fn set_encrypted(&self, uid: &str, encrypted: bool) -> Result<Note, RepositoryError> {
+        let mut note = self.load_plain(uid)?;
+        if note.metadata.encrypted == encrypted {
+            return Ok(note);
+        }
+
+        note.metadata.encrypted = encrypted;
+        note.metadata.title = note.extract_heading();
+        self.store(&note, &ExpectedVersion::default())?;
+        self.repository.relocate(uid)?;
+
+        if encrypted {
+            if let Some(history) = &self.history {
+                history
+                    .clear(uid)
+                    .map_err(|e| RepositoryError::storage("clear_revisions", e))?;
+            }
+        }
+
+        self.event_bus.emit(DomainEvent::NoteUpdated {
+            uid: uid.to_string(),
+        });
+
+        Ok(note)
+    }
+
     /// メモを削除
     pub fn delete_note(&self, uid: &str) -> Result<(), RepositoryError> {
         self.repository.delete(uid)?;
@@ -62,17 +461,193 @@ impl NoteService {
         Ok(())
     }
 
+    /// ゴミ箱内のメモ一覧を取得
+    pub fn list_trash(&self) -> Result<Vec<TrashItem>, RepositoryError> {
+        self.repository.list_trash()
+    }
+
+    /// ゴミ箱からメモを復元
+    pub fn restore_note(&self, uid: &str) -> Result<Note, RepositoryError> {
+        let note = self.repository.restore(uid)?;
+
+        self.event_bus.emit(DomainEvent::NoteRestored {
+            uid: uid.to_string(),
+        });
+
+        Ok(note)
+    }
+
+    /// ゴミ箱を空にする（削除した件数を返す）
+    pub fn empty_trash(&self) -> Result<usize, RepositoryError> {
+        self.repository.purge_trash(None)
+    }
+
+    /// 保持期間を過ぎたゴミ箱のメモを削除（`retention_days` が 0 の場合は何もしない）
+    pub fn purge_expired_trash(&self, retention_days: u32) -> Result<usize, RepositoryError> {
+        if retention_days == 0 {
+            return Ok(0);
+        }
+        let cutoff = Utc::now() - Duration::days(retention_days as i64);
+        self.repository.purge_trash(Some(cutoff))
+    }
+
     /// 全メモ一覧を取得
     pub fn list_notes(&self) -> Result<Vec<NoteListItem>, RepositoryError> {
         self.repository.list_all()
     }
 
+    /// お気に入りのメモ一覧を取得
+    pub fn list_favorites(&self) -> Result<Vec<NoteListItem>, RepositoryError> {
+        let mut items = self.repository.list_all()?;
+        items.retain(|item| item.favorite);
+        Ok(items)
+    }
+
+    /// ピン留めを切り替え（切り替え後の状態を返す）
+    pub fn toggle_pinned(&self, uid: &str) -> Result<bool, RepositoryError> {
+        self.update_flags(uid, |note| {
+            let pinned = !note.metadata.pinned;
+            note.set_pinned(pinned);
+            pinned
+        })
+    }
+
+    /// お気に入りを切り替え（切り替え後の状態を返す）
+    pub fn toggle_favorite(&self, uid: &str) -> Result<bool, RepositoryError> {
+        self.update_flags(uid, |note| {
+            let favorite = !note.metadata.favorite;
+            note.set_favorite(favorite);
+            favorite
+        })
+    }
+
+    /// メモをロードしてフラグを変更し、保存する
+    fn update_flags<F>(&self, uid: &str, f: F) -> Result<bool, RepositoryError>
+    where
+        F: FnOnce(&mut Note) -> bool,
+    {
+        let mut note = self.repository.load(uid)?;
+        let value = f(&mut note);
+        self.repository.save(&note)?;
+
+        self.event_bus.emit(DomainEvent::NoteUpdated {
+            uid: uid.to_string(),
+        });
+
+        Ok(value)
+    }
+
     /// ギャラリー用ノート一覧を取得（高速キャッシュ版）
     pub fn list_gallery_notes(
         &self,
-        sort_by_created: bool,
-        tag_filter: Option<&str>,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
     ) -> Result<Vec<GalleryNote>, RepositoryError> {
-        self.repository.list_gallery(sort_by_created, tag_filter)
+        self.repository.list_gallery(sort, filter)
+    }
+
+    /// メモ一覧を1ページ分取得（キーセットページネーション）
+    pub fn list_notes_page(
+        &self,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
+        cursor: Option<&PageCursor>,
+        limit: usize,
+    ) -> Result<Page<NoteListItem>, RepositoryError> {
+        self.repository.list_page(sort, filter, cursor, limit)
+    }
+
+    /// ギャラリー用ノート一覧を1ページ分取得（キーセットページネーション）
+    pub fn list_gallery_page(
+        &self,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
+        cursor: Option<&PageCursor>,
+        limit: usize,
+    ) -> Result<Page<GalleryNote>, RepositoryError> {
+        self.repository
+            .list_gallery_page(sort, filter, cursor, limit)
+    }
+
+    /// フォルダツリーを取得（ルートは保存ディレクトリ）
+    pub fn folder_tree(&self) -> Result<FolderNode, RepositoryError> {
+        let counts = self.repository.list_folders()?;
+        Ok(build_folder_tree(&counts))
+    }
+
+    /// メモを別のフォルダへ移動（`folder` は正規化済みの相対パス）
+    pub fn move_note(&self, uid: &str, folder: &str) -> Result<PathBuf, RepositoryError> {
+        let path = self.repository.move_to_folder(uid, folder)?;
+
+        self.event_bus.emit(DomainEvent::NoteUpdated {
+            uid: uid.to_string(),
+        });
+
+        Ok(path)
+    }
+
+    /// 手動ソート順を設定（`uids` の並び順がそのまま表示順になる）
+    pub fn set_manual_order(&self, uids: &[String]) -> Result<(), RepositoryError> {
+        self.repository.set_manual_order(uids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::{EventBusImpl, FileNoteRepository, FileStorage, HeadingFilenameStrategy};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_concurrent_open_daily_note_creates_one_note() {
+        let temp_dir = TempDir::new().unwrap();
+        let repository: Arc<dyn NoteRepository> = Arc::new(FileNoteRepository::with_fixed_path(
+            Arc::new(FileStorage::new()),
+            Arc::new(HeadingFilenameStrategy::new()),
+            temp_dir.path().to_path_buf(),
+        ));
+        let service = Arc::new(NoteService::new(repository.clone(), Arc::new(EventBusImpl::new())));
+        let date = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let service = service.clone();
+                std::thread::spawn(move || service.open_daily_note(date).unwrap())
+            })
+            .collect();
+        let opened: Vec<(Note, bool)> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+        assert_eq!(opened.iter().filter(|(_, created)| *created).count(), 1);
+        assert!(opened.iter().all(|(note, _)| note.uid() == opened[0].0.uid()));
+        assert_eq!(repository.list_daily_notes(Some(date), Some(date)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_normalize_timestamps_notifies_rewritten_notes() {
+        let temp_dir = TempDir::new().unwrap();
+        let event_bus = Arc::new(EventBusImpl::new());
+        let repository: Arc<dyn NoteRepository> = Arc::new(FileNoteRepository::with_fixed_path(
+            Arc::new(FileStorage::new()),
+            Arc::new(HeadingFilenameStrategy::new()),
+            temp_dir.path().to_path_buf(),
+        ));
+        let service = NoteService::new(repository.clone(), event_bus.clone());
+        let note = Note::with_title("古い形式");
+        repository.save(&note).unwrap();
+
+        let updated = Arc::new(Mutex::new(Vec::new()));
+        let recorded = updated.clone();
+        event_bus.subscribe(
+            "note:updated",
+            Arc::new(move |event| {
+                if let DomainEvent::NoteUpdated { uid } = event {
+                    recorded.lock().push(uid.clone());
+                }
+            }),
+        );
+
+        let report = service.normalize_timestamps().unwrap();
+        assert_eq!(report.updated, 1);
+        assert_eq!(*updated.lock(), vec![note.metadata.uid.clone()]);
     }
 }
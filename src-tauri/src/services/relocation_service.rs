@@ -0,0 +1,477 @@
+//! 保存ディレクトリの移転サービス
+//!
+//! 保存ディレクトリを変更し、既存のノートを新しいディレクトリへ移動・コピーする。
+//! 移転後はインデックスとバックリンクを新しいディレクトリで作り直すため、
+//! 変更前のディレクトリを指すエントリは一覧に残らない。
+//!
+//! # 対象
+//!
+//! - ノート（サブフォルダを含む、`.kakuignore` で除外したものは対象外）
+//! - `.kakuignore`
+//! - ゴミ箱（`.trash`）
+//!
+//! 移転先に同じ相対パスのファイルがある場合は上書きせず、内容が同じなら書き込みを省き、
+//! 異なれば失敗として報告する。
+//!
+//! # 失敗時
+//!
+//! すべてのファイルを先にコピーし、1件でも失敗したら移転先に書き込んだファイルを削除して
+//! 保存ディレクトリを変更しない（`RelocationReport::switched` が `false`）。
+//! 移動の場合も元のファイルを削除するのは、すべてのコピーが成功して切り替えた後に限る。
+//! どのノートも一覧から外れないようにするため。
+
+use crate::domain::{
+    is_nested_directory, DomainEvent, RelocationFailure, RelocationMode, RelocationReport,
+    SettingsError, IGNORE_FILE_NAME, TRASH_DIR_NAME,
+};
+use crate::infrastructure::{list_note_files, VaultRoot};
+use crate::services::{AutosaveService, BacklinkService, SettingsService};
+use crate::traits::{EventBus, NoteRepository, RepositoryError, Storage, StorageError};
+use log::{info, warn};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// 移転エラー（個々のファイルの失敗は `RelocationReport::failed` で報告する）
+#[derive(Debug, Error)]
+pub enum RelocationError {
+    #[error("移転先が現在の保存ディレクトリと入れ子になっています: {0}")]
+    NestedDirectory(PathBuf),
+    #[error("移転するファイルの一覧を取得できません: {0}")]
+    Scan(#[from] StorageError),
+    #[error(transparent)]
+    Settings(#[from] SettingsError),
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
+
+/// ファイル1件の移転結果
+enum Transfer {
+    Written,
+    Skipped,
+}
+
+/// 保存ディレクトリの移転サービス
+pub struct RelocationService {
+    repository: Arc<dyn NoteRepository>,
+    storage: Arc<dyn Storage>,
+    backlink_service: Arc<BacklinkService>,
+    autosave_service: Arc<AutosaveService>,
+    settings_service: Arc<SettingsService>,
+    event_bus: Arc<dyn EventBus>,
+    root: Arc<VaultRoot>,
+}
+
+impl RelocationService {
+    pub fn new(
+        repository: Arc<dyn NoteRepository>,
+        storage: Arc<dyn Storage>,
+        backlink_service: Arc<BacklinkService>,
+        autosave_service: Arc<AutosaveService>,
+        settings_service: Arc<SettingsService>,
+        event_bus: Arc<dyn EventBus>,
+        root: Arc<VaultRoot>,
+    ) -> Self {
+        Self {
+            repository,
+            storage,
+            backlink_service,
+            autosave_service,
+            settings_service,
+            event_bus,
+            root,
+        }
+    }
+
+    /// 保存ディレクトリを `new_dir` に変更し、既存のノートを `mode` に従って移転する
+    ///
+    /// 保存待ちの変更は移転前に書き出す。進捗は `DomainEvent::RelocationProgress` で通知する。
+    pub fn relocate(
+        &self,
+        new_dir: &Path,
+        mode: RelocationMode,
+    ) -> Result<RelocationReport, RelocationError> {
+        let old_dir = self.root.get();
+        let (old_real, new_real) = (canonical_path(&old_dir), canonical_path(new_dir));
+        if mode != RelocationMode::Keep && is_nested_directory(&old_real, &new_real) {
+            return Err(RelocationError::NestedDirectory(new_dir.to_path_buf()));
+        }
+
+        self.autosave_service.flush();
+
+        let mut report = RelocationReport::default();
+        let mut transferred = Vec::new();
+        let mut written = Vec::new();
+        if mode != RelocationMode::Keep && old_real != new_real {
+            let files = self.collect_files(&old_dir)?;
+            let total = files.len();
+            self.emit_progress(0, total);
+
+            for (done, relative) in files.into_iter().enumerate() {
+                let to = new_dir.join(&relative);
+                match self.transfer(&old_dir.join(&relative), &to) {
+                    Ok(Transfer::Written) => {
+                        report.transferred += 1;
+                        written.push(to);
+                    }
+                    Ok(Transfer::Skipped) => report.skipped += 1,
+                    Err(error) => {
+                        report.failed.push(RelocationFailure {
+                            path: relative,
+                            error,
+                        });
+                        self.emit_progress(done + 1, total);
+                        continue;
+                    }
+                }
+                transferred.push(relative);
+                self.emit_progress(done + 1, total);
+            }
+        }
+
+        if !report.failed.is_empty() {
+            // 書き込んだファイルを取り除き、元の保存ディレクトリのまま続ける
+            for path in &written {
+                if let Err(e) = self.storage.delete(path) {
+                    warn!("[Relocation] Failed to remove {:?}: {}", path, e);
+                }
+            }
+            info!(
+                "[Relocation] {:?} -> {:?} ({:?}) aborted: {} failed",
+                old_dir,
+                new_dir,
+                mode,
+                report.failed.len()
+            );
+            return Ok(report);
+        }
+
+        self.settings_service.update(|s| {
+            s.vaults.sync_active(new_dir);
+            s.storage_directory = new_dir.to_path_buf();
+        })?;
+        self.root.set(new_dir);
+        report.switched = true;
+
+        if mode == RelocationMode::Move {
+            for relative in &transferred {
+                let path = old_dir.join(relative);
+                if let Err(e) = self.storage.delete(&path) {
+                    warn!("[Relocation] Failed to remove {:?}: {}", path, e);
+                }
+            }
+        }
+        self.reindex()?;
+
+        info!(
+            "[Relocation] {:?} -> {:?} ({:?}): {} transferred, {} skipped, {} failed",
+            old_dir,
+            new_dir,
+            mode,
+            report.transferred,
+            report.skipped,
+            report.failed.len()
+        );
+        Ok(report)
+    }
+
+    /// 現在の保存ディレクトリでインデックスとバックリンクを作り直す
+    pub fn reindex(&self) -> Result<(), RepositoryError> {
+        self.repository.reindex()?;
+        if let Err(e) = self.backlink_service.rebuild_index() {
+            eprintln!("[Relocation] Failed to rebuild backlink index: {}", e);
+        }
+        self.event_bus.emit(DomainEvent::IndexRebuilt);
+        Ok(())
+    }
+
+    /// 移転するファイル（保存ディレクトリからの相対パス）
+    fn collect_files(&self, base_dir: &Path) -> Result<Vec<PathBuf>, StorageError> {
+        let mut files = list_note_files(self.storage.as_ref(), base_dir)?;
+
+        let ignore_file = base_dir.join(IGNORE_FILE_NAME);
+        if self.storage.exists(&ignore_file) {
+            files.push(ignore_file);
+        }
+
+        let trash_dir = base_dir.join(TRASH_DIR_NAME);
+        for extension in ["md", "json"] {
+            files.extend(self.storage.list_files(&trash_dir, extension)?);
+        }
+
+        Ok(files
+            .into_iter()
+            .filter_map(|path| path.strip_prefix(base_dir).ok().map(Path::to_path_buf))
+            .collect())
+    }
+
+    /// ファイルを1件コピー（エラーは報告用のメッセージ、元のファイルは削除しない）
+    fn transfer(&self, from: &Path, to: &Path) -> Result<Transfer, String> {
+        let content = self.storage.load(from).map_err(|e| e.to_string())?;
+
+        if self.storage.exists(to) {
+            if self.storage.load(to).map_err(|e| e.to_string())? != content {
+                return Err("A different file already exists at the destination".to_string());
+            }
+            return Ok(Transfer::Skipped);
+        }
+        self.storage
+            .save_atomic(to, &content)
+            .map_err(|e| e.to_string())?;
+        Ok(Transfer::Written)
+    }
+
+    fn emit_progress(&self, done: usize, total: usize) {
+        self.event_bus
+            .emit(DomainEvent::RelocationProgress { done, total });
+    }
+}
+
+/// シンボリックリンクと `..` を解決したパス
+///
+/// 移転先はまだ存在しないことがあるため、存在する最も近い祖先を `canonicalize` し、
+/// 残りのコンポーネントをつなげる。
+fn canonical_path(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    let mut resolved = loop {
+        if let Ok(real) = std::fs::canonicalize(existing) {
+            break real;
+        }
+        match (existing.parent(), existing.components().next_back()) {
+            (Some(parent), Some(last)) => {
+                rest.push(last);
+                existing = parent;
+            }
+            _ => break PathBuf::new(),
+        }
+    };
+
+    for component in rest.into_iter().rev() {
+        match component {
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::CurDir => {}
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Note, DEFAULT_VAULT_ID};
+    use crate::infrastructure::{
+        EventBusImpl, FileStorage, HeadingFilenameStrategy, HybridRepository, SqliteIndex,
+    };
+    use crate::services::test_support;
+    use crate::traits::NoteFilter;
+    use parking_lot::Mutex;
+    use tempfile::TempDir;
+
+    struct Fixture {
+        service: RelocationService,
+        repository: Arc<dyn NoteRepository>,
+        backlink_service: Arc<BacklinkService>,
+        progress: Arc<Mutex<Vec<(usize, usize)>>>,
+    }
+
+    fn create_service(temp_dir: &TempDir) -> Fixture {
+        let event_bus = Arc::new(EventBusImpl::new());
+        let settings_service = test_support::settings_service(temp_dir.path(), event_bus.clone());
+        settings_service
+            .update(|s| s.storage_directory = temp_dir.path().join("old"))
+            .unwrap();
+
+        let storage = Arc::new(FileStorage::new());
+        let root = Arc::new(VaultRoot::new(DEFAULT_VAULT_ID, temp_dir.path().join("old")));
+        let repository: Arc<dyn NoteRepository> = Arc::new(HybridRepository::new(
+            Arc::new(SqliteIndex::open_in_memory().unwrap()),
+            storage.clone(),
+            Arc::new(HeadingFilenameStrategy::new()),
+            settings_service.clone(),
+            root.clone(),
+        ));
+        let fixture = test_support::autosave_fixture(
+            temp_dir.path(),
+            repository.clone(),
+            storage.clone(),
+            settings_service.clone(),
+            event_bus.clone(),
+        );
+
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let recorded = progress.clone();
+        event_bus.subscribe(
+            "relocation:progress",
+            Arc::new(move |event| {
+                if let DomainEvent::RelocationProgress { done, total } = event {
+                    recorded.lock().push((*done, *total));
+                }
+            }),
+        );
+
+        Fixture {
+            service: RelocationService::new(
+                repository.clone(),
+                storage,
+                fixture.backlink_service.clone(),
+                fixture.autosave_service,
+                settings_service,
+                event_bus,
+                root,
+            ),
+            repository,
+            backlink_service: fixture.backlink_service,
+            progress,
+        }
+    }
+
+    fn save(repository: &Arc<dyn NoteRepository>, content: &str) -> Note {
+        let mut note = Note::new();
+        note.content = content.to_string();
+        repository.save(&note).unwrap();
+        note
+    }
+
+    #[test]
+    fn test_move_rewrites_index_and_backlinks() {
+        let temp_dir = TempDir::new().unwrap();
+        let fixture = create_service(&temp_dir);
+        let repository = &fixture.repository;
+        let target = save(repository, "# Target");
+        let source = save(repository, "# Source\n\n[[Target]]");
+        repository.move_to_folder(&source.metadata.uid, "work").unwrap();
+
+        let new_dir = temp_dir.path().join("new");
+        let report = fixture
+            .service
+            .relocate(&new_dir, RelocationMode::Move)
+            .unwrap();
+
+        assert_eq!((report.transferred, report.skipped), (2, 0));
+        assert!(report.failed.is_empty());
+        assert!(new_dir.join("work").join("Source.md").exists());
+        assert!(!temp_dir.path().join("old").join("Target.md").exists());
+        assert_eq!(fixture.progress.lock().last(), Some(&(2, 2)));
+
+        // インデックスは新しいディレクトリを指し、フォルダも保たれる
+        let items = repository.list_all().unwrap();
+        assert_eq!(items.len(), 2);
+        let filter = NoteFilter { folder: Some("work"), ..Default::default() };
+        let page = repository
+            .list_page(Default::default(), &filter, None, 10)
+            .unwrap();
+        assert_eq!(page.items[0].uid, source.metadata.uid);
+        assert_eq!(repository.load(&target.metadata.uid).unwrap().content, "# Target");
+        assert_eq!(fixture.backlink_service.get_backlinks("Target").len(), 1);
+    }
+
+    #[test]
+    fn test_failed_transfer_aborts_the_switch() {
+        let temp_dir = TempDir::new().unwrap();
+        let fixture = create_service(&temp_dir);
+        let old_dir = temp_dir.path().join("old");
+        let note = save(&fixture.repository, "# Plan");
+        save(&fixture.repository, "# Other");
+
+        // 移転先に同名で内容の異なるファイルがある
+        let new_dir = temp_dir.path().join("new");
+        std::fs::create_dir_all(&new_dir).unwrap();
+        std::fs::write(new_dir.join("Plan.md"), "# Someone else's plan").unwrap();
+
+        let report = fixture
+            .service
+            .relocate(&new_dir, RelocationMode::Move)
+            .unwrap();
+
+        assert!(!report.switched);
+        assert_eq!(report.transferred, 1);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].path, PathBuf::from("Plan.md"));
+
+        // 元のファイルは残り、移転先に書き込んだファイルは取り除く
+        assert!(old_dir.join("Plan.md").exists());
+        assert!(old_dir.join("Other.md").exists());
+        assert!(!new_dir.join("Other.md").exists());
+        assert_eq!(
+            std::fs::read_to_string(new_dir.join("Plan.md")).unwrap(),
+            "# Someone else's plan"
+        );
+
+        // 保存ディレクトリは変わらず、すべてのノートが一覧に残る
+        assert_eq!(fixture.service.root.get(), old_dir);
+        assert_eq!(
+            fixture.service.settings_service.get().storage_directory,
+            old_dir
+        );
+        let items = fixture.repository.list_all().unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().any(|item| item.uid == note.metadata.uid));
+    }
+
+    #[test]
+    fn test_copy_keeps_originals() {
+        let temp_dir = TempDir::new().unwrap();
+        let fixture = create_service(&temp_dir);
+        save(&fixture.repository, "# Plan");
+
+        // 同じ内容のファイルは書き込まない
+        let new_dir = temp_dir.path().join("new");
+        std::fs::create_dir_all(&new_dir).unwrap();
+        std::fs::copy(temp_dir.path().join("old").join("Plan.md"), new_dir.join("Plan.md")).unwrap();
+
+        let report = fixture
+            .service
+            .relocate(&new_dir, RelocationMode::Copy)
+            .unwrap();
+
+        assert!(report.switched);
+        assert_eq!((report.transferred, report.skipped), (0, 1));
+        assert!(temp_dir.path().join("old").join("Plan.md").exists());
+        assert_eq!(fixture.service.root.get(), new_dir);
+        assert_eq!(fixture.repository.list_all().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_nested_directory_is_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let fixture = create_service(&temp_dir);
+        let nested = temp_dir.path().join("old").join("sub");
+
+        assert!(matches!(
+            fixture.service.relocate(&nested, RelocationMode::Move),
+            Err(RelocationError::NestedDirectory(_))
+        ));
+        // Keep はファイルを動かさないため入れ子でもよい
+        assert!(fixture.service.relocate(&nested, RelocationMode::Keep).is_ok());
+    }
+
+    #[test]
+    fn test_nested_directory_is_detected_through_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let fixture = create_service(&temp_dir);
+        std::fs::create_dir_all(temp_dir.path().join("old")).unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("other")).unwrap();
+
+        // `..` を経由して現在の保存ディレクトリの配下を指す
+        let dotted = temp_dir.path().join("other").join("..").join("old").join("sub");
+        assert!(matches!(
+            fixture.service.relocate(&dotted, RelocationMode::Move),
+            Err(RelocationError::NestedDirectory(_))
+        ));
+
+        // シンボリックリンクを経由して現在の保存ディレクトリの配下を指す
+        #[cfg(unix)]
+        {
+            let link = temp_dir.path().join("link");
+            std::os::unix::fs::symlink(temp_dir.path().join("old"), &link).unwrap();
+            assert!(matches!(
+                fixture.service.relocate(&link.join("sub"), RelocationMode::Copy),
+                Err(RelocationError::NestedDirectory(_))
+            ));
+        }
+    }
+}
@@ -94,9 +94,12 @@ impl SearchService {
         // タイトルマッチング
         let title_matched = title_lower.contains(query_lower);
 
-        // 本文マッチング（memmap + 先頭のみ）
-        let (content_matched, content_preview) =
-            Self::match_content(query_lower, &note.path).unwrap_or((false, None));
+        // 本文マッチング（memmap + 先頭のみ、暗号化ノートの本文は検索しない）
+        let (content_matched, content_preview) = if note.encrypted {
+            (false, None)
+        } else {
+            Self::match_content(query_lower, &note.path).unwrap_or((false, None))
+        };
 
         // マッチなしならスキップ
         if !title_matched && !content_matched {
@@ -35,7 +35,9 @@ impl SettingsService {
         event_bus: Arc<dyn EventBus>,
     ) -> Self {
         // 設定をロード（存在しなければデフォルト）
-        let settings = repository.load().unwrap_or_default();
+        let mut settings = repository.load().unwrap_or_default();
+        // 旧バージョンの設定では保存ディレクトリを既定の保管庫として登録
+        settings.vaults.sync_active(&settings.storage_directory);
 
         Self {
             repository,
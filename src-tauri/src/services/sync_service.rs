@@ -0,0 +1,481 @@
+//! フォルダミラーとの同期サービス
+//!
+//! サービスを構築した保管庫の保存ディレクトリと `Vault::mirror_directory` を双方向に同期する。
+//! 判定は `plan_sync` で行い、前回の同期時点のハッシュは `SyncStateStore` に保存する。
+//! `SyncSettings::interval_minutes` が 0 でなければ、起動時と一定間隔ごとにバックグラウンドで同期する。
+//!
+//! ファイルの読み書きはすべて `Storage` を通して行う。同期の前に自動保存の待ちを書き出し、
+//! 保存ディレクトリを変更した場合はインデックスとバックリンクを作り直す。
+
+use crate::domain::{
+    conflict_copy_path, file_hash, is_nested_directory, plan_sync, DomainEvent, Note,
+    SyncAction, SyncConflict, SyncError, SyncReport, SYNC_TRASH_DIR_NAME, SYNC_TRASH_TIME_FORMAT,
+};
+use crate::infrastructure::{load_ignore_rules, SyncStateStore, VaultRoot};
+use crate::services::{AutosaveService, BacklinkService, NoteService, SettingsService};
+use crate::traits::{EventBus, NoteRepository, Storage, SubscriptionId};
+use chrono::Local;
+use log::info;
+use parking_lot::{Condvar, Mutex};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 定期同期の状態
+#[derive(Default)]
+struct Schedule {
+    /// 最後に同期した時刻（`None` はまだ同期していない）
+    last_run: Option<Instant>,
+    shutdown: bool,
+}
+
+/// 同期する片側のディレクトリ
+struct Side<'a> {
+    root: &'a Path,
+    /// 相対パス → 現在の `content_hash`
+    hashes: HashMap<String, String>,
+}
+
+/// フォルダミラーとの同期サービス
+pub struct SyncService {
+    state_store: SyncStateStore,
+    storage: Arc<dyn Storage>,
+    note_service: Arc<NoteService>,
+    repository: Arc<dyn NoteRepository>,
+    backlink_service: Arc<BacklinkService>,
+    autosave_service: Arc<AutosaveService>,
+    settings_service: Arc<SettingsService>,
+    event_bus: Arc<dyn EventBus>,
+    root: Arc<VaultRoot>,
+    schedule: Mutex<Schedule>,
+    wakeup: Condvar,
+    /// 同期を直列化
+    run_lock: Mutex<()>,
+    subscription: Mutex<Option<SubscriptionId>>,
+}
+
+impl SyncService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        state_store: SyncStateStore,
+        storage: Arc<dyn Storage>,
+        note_service: Arc<NoteService>,
+        repository: Arc<dyn NoteRepository>,
+        backlink_service: Arc<BacklinkService>,
+        autosave_service: Arc<AutosaveService>,
+        settings_service: Arc<SettingsService>,
+        event_bus: Arc<dyn EventBus>,
+        root: Arc<VaultRoot>,
+    ) -> Self {
+        Self {
+            state_store,
+            storage,
+            note_service,
+            repository,
+            backlink_service,
+            autosave_service,
+            settings_service,
+            event_bus,
+            root,
+            schedule: Mutex::new(Schedule::default()),
+            wakeup: Condvar::new(),
+            run_lock: Mutex::new(()),
+            subscription: Mutex::new(None),
+        }
+    }
+
+    /// 定期同期のスレッドを起動（設定の変更で次回の時刻を計算し直す）
+    pub fn start(self: &Arc<Self>) -> JoinHandle<()> {
+        let service: Weak<Self> = Arc::downgrade(self);
+        *self.subscription.lock() = Some(self.event_bus.subscribe(
+            "settings:changed",
+            Arc::new(move |_| {
+                if let Some(service) = service.upgrade() {
+                    service.wakeup.notify_one();
+                }
+            }),
+        ));
+
+        let service = Arc::clone(self);
+        thread::Builder::new()
+            .name("mirror-sync".to_string())
+            .spawn(move || service.run())
+            .expect("Failed to spawn mirror sync thread")
+    }
+
+    /// 定期同期のスレッドを停止
+    pub fn shutdown(&self) {
+        if let Some(id) = self.subscription.lock().take() {
+            self.event_bus.unsubscribe(id);
+        }
+        self.schedule.lock().shutdown = true;
+        self.wakeup.notify_all();
+    }
+
+    /// この保管庫のミラーのディレクトリ
+    pub fn mirror_directory(&self) -> Option<PathBuf> {
+        self.settings_service
+            .get_arc()
+            .vaults
+            .get(self.root.id())
+            .and_then(|vault| vault.mirror_directory.clone())
+    }
+
+    /// 今すぐミラーと同期する
+    pub fn sync_now(&self) -> Result<SyncReport, SyncError> {
+        let _guard = self.run_lock.lock();
+        let result = self.sync();
+        self.schedule.lock().last_run = Some(Instant::now());
+        result
+    }
+
+    /// ミラーと同期する（`run_lock` を取得して呼ぶ）
+    fn sync(&self) -> Result<SyncReport, SyncError> {
+        let mirror_dir = self.mirror_directory().ok_or(SyncError::MirrorNotConfigured)?;
+        let vault_dir = self.root.get();
+        if !mirror_dir.is_dir() {
+            return Err(SyncError::MirrorUnavailable(mirror_dir));
+        }
+        if vault_dir == mirror_dir || is_nested_directory(&vault_dir, &mirror_dir) {
+            return Err(SyncError::InvalidMirror(mirror_dir.display().to_string()));
+        }
+
+        // 保存待ちの変更を書き出してから比較する
+        self.autosave_service.flush();
+
+        let mut state = self.state_store.load()?.for_mirror(&mirror_dir);
+        let vault = self.scan(&vault_dir)?;
+        let mirror = self.scan(&mirror_dir)?;
+
+        // ミラーが空のまま（マウントされていないなど）で全ファイルを削除しないよう中止する
+        if !state.files.is_empty() {
+            if vault.hashes.is_empty() && !mirror.hashes.is_empty() {
+                return Err(SyncError::EmptySide("保存ディレクトリ"));
+            }
+            if mirror.hashes.is_empty() && !vault.hashes.is_empty() {
+                return Err(SyncError::EmptySide("ミラー"));
+            }
+        }
+
+        let trash_stamp = Local::now().format(SYNC_TRASH_TIME_FORMAT).to_string();
+        let mut report = SyncReport::default();
+        let mut vault_changed = false;
+        for action in plan_sync(&vault.hashes, &mirror.hashes, &state.files) {
+            match action {
+                SyncAction::Push(path) => {
+                    self.copy(&vault, &mirror, &path)?;
+                    state.files.insert(path.clone(), vault.hashes[&path].clone());
+                    report.pushed += 1;
+                }
+                SyncAction::Pull(path) => {
+                    self.copy(&mirror, &vault, &path)?;
+                    state.files.insert(path.clone(), mirror.hashes[&path].clone());
+                    report.pulled += 1;
+                    vault_changed = true;
+                }
+                SyncAction::DeleteInVault(path) => {
+                    self.delete_in_vault(&vault, &path, &trash_stamp)?;
+                    state.files.remove(&path);
+                    report.deleted += 1;
+                    vault_changed = true;
+                }
+                SyncAction::DeleteInMirror(path) => {
+                    move_to_sync_trash(self.storage.as_ref(), mirror.root, &path, &trash_stamp)?;
+                    state.files.remove(&path);
+                    report.deleted += 1;
+                }
+                SyncAction::Conflict(path) => {
+                    let conflict = self.resolve_conflict(&vault, &mirror, &path)?;
+                    state.files.insert(path.clone(), vault.hashes[&path].clone());
+                    state
+                        .files
+                        .insert(conflict.conflict_copy.clone(), mirror.hashes[&path].clone());
+                    self.event_bus.emit(DomainEvent::Conflict {
+                        path: conflict.path.clone(),
+                        conflict_copy: conflict.conflict_copy.clone(),
+                    });
+                    report.conflicts.push(conflict);
+                    vault_changed = true;
+                }
+                SyncAction::Record(path) => {
+                    state.files.insert(path.clone(), vault.hashes[&path].clone());
+                }
+                SyncAction::Forget(path) => {
+                    state.files.remove(&path);
+                }
+            }
+        }
+        self.state_store.save(&state)?;
+
+        if vault_changed {
+            self.repository
+                .reindex()
+                .map_err(|e| SyncError::Reindex(e.to_string()))?;
+            if let Err(e) = self.backlink_service.rebuild_index() {
+                eprintln!("[SyncService] Failed to rebuild backlink index: {}", e);
+            }
+            self.event_bus.emit(DomainEvent::IndexRebuilt);
+        }
+
+        info!(
+            "[SyncService] Synced with {:?} (pulled: {}, pushed: {}, deleted: {}, conflicts: {})",
+            mirror_dir,
+            report.pulled,
+            report.pushed,
+            report.deleted,
+            report.conflicts.len()
+        );
+        self.event_bus.emit(DomainEvent::MirrorSynced {
+            pulled: report.pulled,
+            pushed: report.pushed,
+            deleted: report.deleted,
+            conflicts: report.conflicts.len(),
+        });
+        Ok(report)
+    }
+
+    /// ディレクトリ以下のファイルのハッシュ（保存ディレクトリの除外ルールを両側に適用する）
+    fn scan<'a>(&self, root: &'a Path) -> Result<Side<'a>, SyncError> {
+        let ignore = load_ignore_rules(self.storage.as_ref(), &self.root.get());
+        let mut hashes = HashMap::new();
+        for path in self.storage.list_all_files_recursive(root, &ignore)? {
+            if let Some(relative) = relative_key(root, &path) {
+                hashes.insert(relative, file_hash(&self.storage.load_bytes(&path)?));
+            }
+        }
+        Ok(Side { root, hashes })
+    }
+
+    fn copy(&self, from: &Side, to: &Side, path: &str) -> Result<(), SyncError> {
+        let content = self.storage.load_bytes(&from.root.join(path))?;
+        self.storage.save_atomic_bytes(&to.root.join(path), &content)?;
+        Ok(())
+    }
+
+    /// 保存ディレクトリの版を残し、ミラーの版を両側に競合コピーとして保存する
+    ///
+    /// ノートの競合コピーには新しい UID を振り、元のノートとは別のノートとしてインデックスに載せる。
+    fn resolve_conflict(&self, vault: &Side, mirror: &Side, path: &str) -> Result<SyncConflict, SyncError> {
+        let conflict_copy = conflict_copy_path(path, Local::now());
+        let theirs = self.with_new_uid(self.storage.load_bytes(&mirror.root.join(path))?);
+        self.storage.save_atomic_bytes(&vault.root.join(&conflict_copy), &theirs)?;
+        self.storage.save_atomic_bytes(&mirror.root.join(&conflict_copy), &theirs)?;
+        self.copy(vault, mirror, path)?;
+        Ok(SyncConflict {
+            path: path.to_string(),
+            conflict_copy,
+        })
+    }
+
+    /// front matter を持つノートなら UID を新しいものに書き換える（それ以外はそのまま）
+    fn with_new_uid(&self, content: Vec<u8>) -> Vec<u8> {
+        let note = std::str::from_utf8(&content)
+            .ok()
+            .and_then(|text| Note::from_file_content(text).ok());
+        match note {
+            Some(mut note) => {
+                note.metadata.uid = self.note_service.new_uid();
+                note.to_file_content_in(self.settings_service.get_arc().timestamps.zone)
+                    .into_bytes()
+            }
+            None => content,
+        }
+    }
+
+    /// 保存ディレクトリのファイルを削除（インデックスにあるノートはゴミ箱へ）
+    fn delete_in_vault(&self, vault: &Side, path: &str, trash_stamp: &str) -> Result<(), SyncError> {
+        let file_path = vault.root.join(path);
+        let uid = self
+            .storage
+            .load(&file_path)
+            .ok()
+            .and_then(|content| Note::from_file_content(&content).ok())
+            .map(|note| note.metadata.uid)
+            .filter(|uid| self.repository.get_path(uid).as_deref() == Some(file_path.as_path()));
+
+        match uid {
+            Some(uid) => self.note_service.delete_note(&uid).or_else(|e| {
+                eprintln!("[SyncService] Failed to move {} to trash: {}", path, e);
+                move_to_sync_trash(self.storage.as_ref(), vault.root, path, trash_stamp)
+            }),
+            None => move_to_sync_trash(self.storage.as_ref(), vault.root, path, trash_stamp),
+        }
+    }
+
+    fn run(&self) {
+        loop {
+            {
+                let mut schedule = self.schedule.lock();
+                loop {
+                    if schedule.shutdown {
+                        return;
+                    }
+                    match self.next_scheduled_run(schedule.last_run) {
+                        Some(due) if due <= Instant::now() => break,
+                        Some(due) => {
+                            self.wakeup.wait_until(&mut schedule, due);
+                        }
+                        None => self.wakeup.wait(&mut schedule),
+                    }
+                }
+            }
+
+            // 失敗しても次の間隔で再試行する
+            if let Err(e) = self.sync_now() {
+                eprintln!("[SyncService] Scheduled sync failed: {}", e);
+                self.event_bus.emit(DomainEvent::SyncFailed {
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    /// 次に定期同期する時刻（無効またはミラーが未設定なら `None`、まだ同期していなければすぐ）
+    fn next_scheduled_run(&self, last_run: Option<Instant>) -> Option<Instant> {
+        let minutes = self.settings_service.get_arc().sync.interval_minutes;
+        if minutes == 0 || self.mirror_directory().is_none() {
+            return None;
+        }
+        let interval = Duration::from_secs(u64::from(minutes) * 60);
+        Some(last_run.map_or_else(Instant::now, |last| last + interval))
+    }
+}
+
+/// 同期で削除するファイルをその側の `.sync-trash/{日時}/` へ移動
+fn move_to_sync_trash(
+    storage: &dyn Storage,
+    root: &Path,
+    path: &str,
+    trash_stamp: &str,
+) -> Result<(), SyncError> {
+    let to = root.join(SYNC_TRASH_DIR_NAME).join(trash_stamp).join(path);
+    storage.rename(&root.join(path), &to)?;
+    Ok(())
+}
+
+/// ディレクトリからの `/` 区切りの相対パス
+fn relative_key(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Vec<&str> = relative.iter().map(|part| part.to_str()).collect::<Option<_>>()?;
+    Some(parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::DEFAULT_VAULT_ID;
+    use crate::infrastructure::{
+        EventBusImpl, FileNoteRepository, FileStorage, HeadingFilenameStrategy,
+    };
+    use crate::services::test_support;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_service(temp_dir: &TempDir) -> SyncService {
+        let event_bus = Arc::new(EventBusImpl::new());
+        let settings_service = test_support::settings_service(temp_dir.path(), event_bus.clone());
+        let notes_dir = temp_dir.path().join("notes");
+        let mirror_dir = temp_dir.path().join("mirror");
+        fs::create_dir_all(&notes_dir).unwrap();
+        fs::create_dir_all(&mirror_dir).unwrap();
+        settings_service
+            .update(|s| {
+                s.vaults.sync_active(&notes_dir);
+                s.vaults.list[0].mirror_directory = Some(mirror_dir.clone());
+            })
+            .unwrap();
+
+        let storage = Arc::new(FileStorage::new());
+        let repository: Arc<dyn NoteRepository> = Arc::new(FileNoteRepository::with_fixed_path(
+            storage.clone(),
+            Arc::new(HeadingFilenameStrategy::new()),
+            notes_dir.clone(),
+        ));
+        let fixture = test_support::autosave_fixture(
+            temp_dir.path(),
+            repository.clone(),
+            storage.clone(),
+            settings_service.clone(),
+            event_bus.clone(),
+        );
+
+        SyncService::new(
+            SyncStateStore::new(temp_dir.path().to_path_buf(), storage.clone()),
+            storage,
+            fixture.note_service,
+            repository,
+            fixture.backlink_service,
+            fixture.autosave_service,
+            settings_service,
+            event_bus,
+            Arc::new(VaultRoot::new(DEFAULT_VAULT_ID, notes_dir)),
+        )
+    }
+
+    #[test]
+    fn test_sync_changes_deletions_and_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = create_service(&temp_dir);
+        let notes_dir = temp_dir.path().join("notes");
+        let mirror_dir = temp_dir.path().join("mirror");
+        fs::create_dir_all(notes_dir.join("attachments")).unwrap();
+        fs::write(notes_dir.join("メモ.md"), "# メモ\n\nv1").unwrap();
+        fs::write(notes_dir.join("attachments/image.png"), [0x89, b'P', b'N', b'G']).unwrap();
+
+        let report = service.sync_now().unwrap();
+        assert_eq!((report.pushed, report.pulled), (2, 0));
+        assert_eq!(fs::read_to_string(mirror_dir.join("メモ.md")).unwrap(), "# メモ\n\nv1");
+
+        // ミラーでの変更は取り込み、保存ディレクトリでの削除はミラーの .sync-trash へ移す
+        fs::write(mirror_dir.join("メモ.md"), "# メモ\n\nv2").unwrap();
+        fs::remove_file(notes_dir.join("attachments/image.png")).unwrap();
+        let report = service.sync_now().unwrap();
+        assert_eq!((report.pulled, report.deleted), (1, 1));
+        assert_eq!(fs::read_to_string(notes_dir.join("メモ.md")).unwrap(), "# メモ\n\nv2");
+        assert!(!mirror_dir.join("attachments/image.png").exists());
+        assert!(mirror_dir.join(SYNC_TRASH_DIR_NAME).is_dir());
+        assert_eq!(service.sync_now().unwrap(), SyncReport::default());
+
+        // 両側で変更すると保存ディレクトリの版を残し、ミラーの版を競合コピーにする
+        fs::write(notes_dir.join("メモ.md"), "# メモ\n\nこちら").unwrap();
+        fs::write(mirror_dir.join("メモ.md"), "# メモ\n\nあちら").unwrap();
+        let report = service.sync_now().unwrap();
+        assert_eq!(report.conflicts.len(), 1);
+        let copy = &report.conflicts[0].conflict_copy;
+        assert_eq!(fs::read_to_string(mirror_dir.join("メモ.md")).unwrap(), "# メモ\n\nこちら");
+        assert_eq!(fs::read_to_string(notes_dir.join(copy)).unwrap(), "# メモ\n\nあちら");
+        assert_eq!(fs::read_to_string(mirror_dir.join(copy)).unwrap(), "# メモ\n\nあちら");
+
+        // ミラーが空になっている場合は削除を反映しない
+        fs::remove_dir_all(&mirror_dir).unwrap();
+        fs::create_dir_all(&mirror_dir).unwrap();
+        assert!(matches!(service.sync_now(), Err(SyncError::EmptySide(_))));
+        assert!(notes_dir.join("メモ.md").exists());
+    }
+
+    #[test]
+    fn test_conflict_copy_of_note_gets_new_uid() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = create_service(&temp_dir);
+        let notes_dir = temp_dir.path().join("notes");
+        let mirror_dir = temp_dir.path().join("mirror");
+        fs::create_dir_all(&notes_dir).unwrap();
+        let mut note = Note::new();
+        note.content = "# メモ\n\nv1".to_string();
+        fs::write(notes_dir.join("メモ.md"), note.to_file_content()).unwrap();
+        service.sync_now().unwrap();
+
+        note.content = "# メモ\n\nこちら".to_string();
+        fs::write(notes_dir.join("メモ.md"), note.to_file_content()).unwrap();
+        note.content = "# メモ\n\nあちら".to_string();
+        fs::write(mirror_dir.join("メモ.md"), note.to_file_content()).unwrap();
+        let report = service.sync_now().unwrap();
+
+        let copy = fs::read_to_string(notes_dir.join(&report.conflicts[0].conflict_copy)).unwrap();
+        let copy = Note::from_file_content(&copy).unwrap();
+        assert_eq!(copy.content, "# メモ\n\nあちら");
+        assert_ne!(copy.metadata.uid, note.metadata.uid);
+    }
+}
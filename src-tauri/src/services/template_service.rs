@@ -0,0 +1,174 @@
+//! テンプレートサービス
+//!
+//! テンプレートの一覧・展開と、新規ノート用のデフォルトテンプレートの適用を行う。
+//! テンプレートが `{{clipboard}}` を使っている場合のみクリップボードを読み取る。
+
+use crate::domain::{builtin_vars, Note, NoteTemplate, TemplateVars, BUILTIN_TITLE_TEMPLATE};
+use crate::infrastructure::TemplateStore;
+use crate::services::SettingsService;
+use crate::traits::{Clipboard, StorageError};
+use chrono::{Local, NaiveDate};
+use std::sync::Arc;
+
+/// テンプレートサービス
+pub struct TemplateService {
+    store: TemplateStore,
+    settings_service: Arc<SettingsService>,
+    clipboard: Option<Arc<dyn Clipboard>>,
+}
+
+impl TemplateService {
+    pub fn new(store: TemplateStore, settings_service: Arc<SettingsService>) -> Self {
+        Self {
+            store,
+            settings_service,
+            clipboard: None,
+        }
+    }
+
+    /// `{{clipboard}}` 変数にクリップボードの内容を使う（未設定なら空になる）
+    pub fn with_clipboard(mut self, clipboard: Arc<dyn Clipboard>) -> Self {
+        self.clipboard = Some(clipboard);
+        self
+    }
+
+    /// テンプレート名の一覧
+    pub fn list_templates(&self) -> Result<Vec<String>, StorageError> {
+        self.store.list()
+    }
+
+    /// 指定テンプレートからノートを作成（保存はしない）
+    ///
+    /// `vars` の `title` がタイトル変数になる。
+    pub fn instantiate(&self, name: &str, vars: TemplateVars) -> Result<Note, StorageError> {
+        let template = self.store.load(name)?;
+        let title = vars.get("title").cloned();
+        let vars = self.with_clipboard_var(&template, vars);
+        Ok(template.instantiate(&builtin_vars(
+            Local::now(),
+            title.as_deref(),
+            vars,
+        )))
+    }
+
+    /// デフォルトテンプレートでノートを作成（保存はしない）
+    ///
+    /// デフォルトテンプレートが未設定・読み込めない場合は、
+    /// タイトルがあれば見出しだけのノート、なければ空のノートを返す。
+    pub fn new_note(&self, title: Option<&str>) -> Note {
+        let default_template = self.settings_service.get_arc().templates.default_template.clone();
+
+        let template = default_template.and_then(|name| match self.store.load(&name) {
+            Ok(template) => Some(template),
+            Err(e) => {
+                eprintln!("[TemplateService] Failed to load default template '{}': {}", name, e);
+                None
+            }
+        });
+
+        let template = match (template, title) {
+            (Some(template), _) => template,
+            (None, Some(_)) => NoteTemplate::new("", BUILTIN_TITLE_TEMPLATE),
+            (None, None) => return Note::new(),
+        };
+
+        let vars = self.with_clipboard_var(&template, TemplateVars::new());
+        template.instantiate(&builtin_vars(Local::now(), title, vars))
+    }
+
+    /// 指定日のデイリーノートを作成（保存はしない）
+    ///
+    /// デイリーノート用テンプレートが未設定・読み込めない場合は、日付を見出しにしたノートを返す。
+    /// `date` / `title` 変数は今日ではなくデイリーノートの日付になる。
+    pub fn daily_note(&self, date: NaiveDate) -> Note {
+        let template_name = self.settings_service.get_arc().daily_notes.template.clone();
+
+        let template = template_name
+            .and_then(|name| match self.store.load(&name) {
+                Ok(template) => Some(template),
+                Err(e) => {
+                    eprintln!("[TemplateService] Failed to load daily template '{}': {}", name, e);
+                    None
+                }
+            })
+            .unwrap_or_else(|| NoteTemplate::new("", BUILTIN_TITLE_TEMPLATE));
+
+        let date_str = date.format("%Y-%m-%d").to_string();
+        let mut extra = TemplateVars::new();
+        extra.insert("date".to_string(), date_str.clone());
+        let extra = self.with_clipboard_var(&template, extra);
+
+        let mut note = template.instantiate(&builtin_vars(Local::now(), Some(&date_str), extra));
+        note.metadata.daily = Some(date);
+        note
+    }
+
+    /// テンプレートが使っていれば `clipboard` 変数を追加（呼び出し側の値が優先、読み取れなければ空）
+    fn with_clipboard_var(&self, template: &NoteTemplate, mut vars: TemplateVars) -> TemplateVars {
+        if !vars.contains_key("clipboard") && template.uses_var("clipboard") {
+            let text = self
+                .clipboard
+                .as_ref()
+                .and_then(|clipboard| clipboard.read_text())
+                .unwrap_or_default();
+            vars.insert("clipboard".to_string(), text);
+        }
+        vars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::{EventBusImpl, FileStorage};
+    use crate::services::test_support;
+    use tempfile::TempDir;
+
+    struct FixedClipboard(Option<String>);
+
+    impl Clipboard for FixedClipboard {
+        fn read_text(&self) -> Option<String> {
+            self.0.clone()
+        }
+    }
+
+    fn create_service(temp_dir: &TempDir, clipboard: Option<&str>) -> TemplateService {
+        let templates = temp_dir.path().join("templates");
+        std::fs::create_dir_all(&templates).unwrap();
+        std::fs::write(templates.join("引用.md"), "# {{title}}\n\n> {{ clipboard }}\n").unwrap();
+        let settings_service =
+            test_support::settings_service(temp_dir.path(), Arc::new(EventBusImpl::new()));
+        settings_service
+            .update(|s| s.templates.default_template = Some("引用".to_string()))
+            .unwrap();
+        TemplateService::new(
+            TemplateStore::new(templates, Arc::new(FileStorage::new())),
+            settings_service,
+        )
+        .with_clipboard(Arc::new(FixedClipboard(clipboard.map(str::to_string))))
+    }
+
+    #[test]
+    fn test_clipboard_variable() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = create_service(&temp_dir, Some("貼り付け"));
+
+        let note = service.new_note(Some("メモ"));
+        assert_eq!(note.content, "# メモ\n\n> 貼り付け\n");
+
+        // 呼び出し側の値が優先
+        let mut vars = TemplateVars::new();
+        vars.insert("clipboard".to_string(), "指定".to_string());
+        let note = service.instantiate("引用", vars).unwrap();
+        assert!(note.content.contains("> 指定"));
+    }
+
+    #[test]
+    fn test_unreadable_clipboard_is_empty() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = create_service(&temp_dir, None);
+
+        let note = service.new_note(Some("メモ"));
+        assert_eq!(note.content, "# メモ\n\n> \n");
+    }
+}
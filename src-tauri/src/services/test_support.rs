@@ -0,0 +1,50 @@
+//! テスト用の共通フィクスチャ
+
+use crate::infrastructure::{DraftJournal, FileSettingsRepository};
+use crate::services::{AutosaveService, BacklinkService, DraftService, NoteService, SettingsService};
+use crate::traits::{EventBus, NoteRepository, Storage};
+use std::path::Path;
+use std::sync::Arc;
+
+/// `dir/config.toml` に保存する設定サービス
+pub fn settings_service(dir: &Path, event_bus: Arc<dyn EventBus>) -> Arc<SettingsService> {
+    Arc::new(SettingsService::new(
+        Arc::new(FileSettingsRepository::with_path(dir.join("config.toml"))),
+        event_bus,
+    ))
+}
+
+/// 自動保存サービスとその依存
+pub struct AutosaveFixture {
+    pub note_service: Arc<NoteService>,
+    pub backlink_service: Arc<BacklinkService>,
+    pub autosave_service: Arc<AutosaveService>,
+}
+
+/// `repository` に保存する自動保存サービスを作成（下書きは `dir/drafts` に書き出す）
+pub fn autosave_fixture(
+    dir: &Path,
+    repository: Arc<dyn NoteRepository>,
+    storage: Arc<dyn Storage>,
+    settings_service: Arc<SettingsService>,
+    event_bus: Arc<dyn EventBus>,
+) -> AutosaveFixture {
+    let note_service = Arc::new(NoteService::new(repository.clone(), event_bus.clone()));
+    let backlink_service = Arc::new(BacklinkService::new(repository.clone()));
+    let draft_service = Arc::new(DraftService::new(
+        DraftJournal::new(dir.join("drafts"), storage),
+        repository,
+    ));
+    let autosave_service = Arc::new(AutosaveService::new(
+        note_service.clone(),
+        backlink_service.clone(),
+        draft_service,
+        settings_service,
+        event_bus,
+    ));
+    AutosaveFixture {
+        note_service,
+        backlink_service,
+        autosave_service,
+    }
+}
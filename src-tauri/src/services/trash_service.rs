@@ -0,0 +1,178 @@
+//! ゴミ箱の定期削除サービス
+//!
+//! 保持期間（`TrashSettings::retention_days`）を過ぎたゴミ箱のメモを、起動時と
+//! `PURGE_INTERVAL` ごとにバックグラウンドスレッドで完全に削除する。
+//! 設定が変更された場合はすぐに削除し直す（保持期間を短くした場合など）。
+
+use crate::services::{NoteService, SettingsService};
+use crate::traits::{EventBus, SubscriptionId};
+use log::info;
+use parking_lot::{Condvar, Mutex};
+use std::sync::{Arc, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 定期削除の間隔
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 定期削除の状態
+#[derive(Default)]
+struct Schedule {
+    /// 設定が変更された（すぐに削除し直す）
+    changed: bool,
+    shutdown: bool,
+}
+
+/// ゴミ箱の定期削除サービス
+pub struct TrashService {
+    note_service: Arc<NoteService>,
+    settings_service: Arc<SettingsService>,
+    event_bus: Arc<dyn EventBus>,
+    schedule: Mutex<Schedule>,
+    wakeup: Condvar,
+    subscription: Mutex<Option<SubscriptionId>>,
+}
+
+impl TrashService {
+    pub fn new(
+        note_service: Arc<NoteService>,
+        settings_service: Arc<SettingsService>,
+        event_bus: Arc<dyn EventBus>,
+    ) -> Self {
+        Self {
+            note_service,
+            settings_service,
+            event_bus,
+            schedule: Mutex::new(Schedule::default()),
+            wakeup: Condvar::new(),
+            subscription: Mutex::new(None),
+        }
+    }
+
+    /// 定期削除のスレッドを起動（起動直後に1回削除する）
+    pub fn start(self: &Arc<Self>) -> JoinHandle<()> {
+        let service: Weak<Self> = Arc::downgrade(self);
+        *self.subscription.lock() = Some(self.event_bus.subscribe(
+            "settings:changed",
+            Arc::new(move |_| {
+                if let Some(service) = service.upgrade() {
+                    service.schedule.lock().changed = true;
+                    service.wakeup.notify_one();
+                }
+            }),
+        ));
+
+        let service = Arc::clone(self);
+        thread::Builder::new()
+            .name("trash-purge".to_string())
+            .spawn(move || service.run())
+            .expect("Failed to spawn trash purge thread")
+    }
+
+    /// 定期削除のスレッドを停止
+    pub fn shutdown(&self) {
+        if let Some(id) = self.subscription.lock().take() {
+            self.event_bus.unsubscribe(id);
+        }
+        self.schedule.lock().shutdown = true;
+        self.wakeup.notify_all();
+    }
+
+    /// 保持期間を過ぎたゴミ箱のメモを削除（削除した件数を返す）
+    pub fn purge_expired(&self) -> usize {
+        let retention_days = self.settings_service.get_arc().trash.retention_days;
+        match self.note_service.purge_expired_trash(retention_days) {
+            Ok(0) => 0,
+            Ok(purged) => {
+                info!("[TrashService] Purged {} expired notes from trash", purged);
+                purged
+            }
+            Err(e) => {
+                eprintln!("[TrashService] Failed to purge trash: {}", e);
+                0
+            }
+        }
+    }
+
+    fn run(&self) {
+        loop {
+            self.purge_expired();
+
+            let due = Instant::now() + PURGE_INTERVAL;
+            let mut schedule = self.schedule.lock();
+            loop {
+                if schedule.shutdown {
+                    return;
+                }
+                if std::mem::take(&mut schedule.changed) || Instant::now() >= due {
+                    break;
+                }
+                self.wakeup.wait_until(&mut schedule, due);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::TrashItem;
+    use crate::infrastructure::{
+        EventBusImpl, FileNoteRepository, FileStorage,
+        HeadingFilenameStrategy,
+    };
+    use crate::services::test_support;
+    use crate::traits::NoteRepository;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_purge_runs_in_background_and_on_settings_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let notes_dir = temp_dir.path().join("notes");
+        let event_bus = Arc::new(EventBusImpl::new());
+        let settings_service = test_support::settings_service(temp_dir.path(), event_bus.clone());
+        settings_service
+            .update(|s| s.trash.retention_days = 0)
+            .unwrap();
+        let repository: Arc<dyn NoteRepository> = Arc::new(FileNoteRepository::with_fixed_path(
+            Arc::new(FileStorage::new()),
+            Arc::new(HeadingFilenameStrategy::new()),
+            notes_dir.clone(),
+        ));
+        let note_service = Arc::new(NoteService::new(repository.clone(), event_bus.clone()));
+
+        // 10日前に削除したメモ
+        let note = crate::domain::Note::with_title("古いメモ");
+        repository.save(&note).unwrap();
+        repository.delete(note.uid()).unwrap();
+        let meta_path = notes_dir.join(".trash").join(format!("{}.json", note.uid()));
+        let mut item: TrashItem =
+            serde_json::from_str(&std::fs::read_to_string(&meta_path).unwrap()).unwrap();
+        item.deleted_at = Utc::now() - chrono::Duration::days(10);
+        std::fs::write(&meta_path, serde_json::to_string(&item).unwrap()).unwrap();
+
+        let service = Arc::new(TrashService::new(
+            note_service,
+            settings_service.clone(),
+            event_bus,
+        ));
+        let handle = service.start();
+
+        // 保持期間 0 は削除しない
+        assert_eq!(repository.list_trash().unwrap().len(), 1);
+
+        // 保持期間を短くするとスレッドが削除し直す
+        settings_service
+            .update(|s| s.trash.retention_days = 7)
+            .unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !repository.list_trash().unwrap().is_empty() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(repository.list_trash().unwrap().is_empty());
+
+        service.shutdown();
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,411 @@
+//! Git によるバージョン管理サービス
+//!
+//! ノートの保存・作成・削除などのイベントを受けて、保存ディレクトリを git にコミットする。
+//! 最後の変更から `VersioningSettings::quiet_period_secs` が経つか、最初の未コミットの変更から
+//! `max_interval_secs` が経つとバックグラウンドスレッドでコミットする。
+//!
+//! 暗号化ノートはファイルに暗号文しか書かないため、コミットにも平文は含まれない。
+//! 暗号化する前にコミットした平文は git の履歴に残る（履歴の書き換えは行わない）ため、
+//! コミット済みのノートは暗号化できない（`keeps_plaintext`）。未コミットのノートは
+//! `encrypt_uncommitted` でコミットを止めたまま暗号化し、すぐに暗号文をコミットする。
+
+use crate::domain::{
+    commit_message, DomainEvent, EncryptionError, VaultCommit, VersioningError, VersioningStatus,
+};
+use crate::infrastructure::{GitRepository, VaultRoot};
+use crate::services::SettingsService;
+use crate::traits::{EventBus, NoteRepository, SubscriptionId};
+use log::info;
+use parking_lot::{Condvar, Mutex};
+use std::sync::{Arc, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// コミットの契機になるイベント
+const CHANGE_EVENTS: [&str; 6] = [
+    "note:created",
+    "note:updated",
+    "note:deleted",
+    "note:restored",
+    "save:completed",
+    "sync:completed",
+];
+
+/// リポジトリを作成したときの最初のコミットの件名
+const INITIAL_COMMIT_SUMMARY: &str = "Initial commit of notes";
+
+/// 未コミットの変更の記録
+#[derive(Default)]
+struct Schedule {
+    first_change: Option<Instant>,
+    last_change: Option<Instant>,
+    shutdown: bool,
+}
+
+/// Git によるバージョン管理サービス
+pub struct VersioningService {
+    repository: Arc<dyn NoteRepository>,
+    settings_service: Arc<SettingsService>,
+    event_bus: Arc<dyn EventBus>,
+    root: Arc<VaultRoot>,
+    schedule: Mutex<Schedule>,
+    wakeup: Condvar,
+    /// git コマンドの実行を直列化
+    git_lock: Mutex<()>,
+    subscriptions: Mutex<Vec<SubscriptionId>>,
+}
+
+impl VersioningService {
+    pub fn new(
+        repository: Arc<dyn NoteRepository>,
+        settings_service: Arc<SettingsService>,
+        event_bus: Arc<dyn EventBus>,
+        root: Arc<VaultRoot>,
+    ) -> Self {
+        Self {
+            repository,
+            settings_service,
+            event_bus,
+            root,
+            schedule: Mutex::new(Schedule::default()),
+            wakeup: Condvar::new(),
+            git_lock: Mutex::new(()),
+            subscriptions: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 変更イベントを購読し、コミットスレッドを起動
+    ///
+    /// 前回の終了後に外部で編集された分もコミットされるよう、起動時に変更ありとして扱う。
+    pub fn start(self: &Arc<Self>) -> JoinHandle<()> {
+        let mut subscriptions = self.subscriptions.lock();
+        for event_name in CHANGE_EVENTS {
+            let service: Weak<Self> = Arc::downgrade(self);
+            subscriptions.push(self.event_bus.subscribe(
+                event_name,
+                Arc::new(move |_| {
+                    if let Some(service) = service.upgrade() {
+                        service.mark_changed();
+                    }
+                }),
+            ));
+        }
+        self.mark_changed();
+
+        let service = Arc::clone(self);
+        thread::Builder::new()
+            .name("versioning".to_string())
+            .spawn(move || service.run())
+            .expect("Failed to spawn versioning thread")
+    }
+
+    /// 変更があったことを記録（バージョン管理が無効なら何もしない）
+    pub fn mark_changed(&self) {
+        if !self.settings_service.get_arc().versioning.enabled {
+            return;
+        }
+        let now = Instant::now();
+        {
+            let mut schedule = self.schedule.lock();
+            schedule.first_change.get_or_insert(now);
+            schedule.last_change = Some(now);
+        }
+        self.wakeup.notify_one();
+    }
+
+    /// 未コミットの変更を即座にコミット（変更がなければ `None`）
+    ///
+    /// `message` を省略した場合は変更したノートからメッセージを作る
+    /// （リポジトリを作成した直後は既存のノートをまとめて最初のコミットにする）。
+    pub fn commit_now(&self, message: Option<&str>) -> Result<Option<VaultCommit>, VersioningError> {
+        let _guard = self.git_lock.lock();
+        self.commit_locked(message)
+    }
+
+    /// `commit_now` の本体（`git_lock` を取得して呼ぶ）
+    fn commit_locked(&self, message: Option<&str>) -> Result<Option<VaultCommit>, VersioningError> {
+        self.clear_pending();
+
+        let git = self.git();
+        let created = git.ensure_initialized()?;
+        let changes = git.stage_changes()?;
+        if changes.is_empty() {
+            return Ok(None);
+        }
+
+        let message = match message.map(str::trim) {
+            Some(message) if !message.is_empty() => message.to_string(),
+            _ if created => format!("{}\n\n{}", INITIAL_COMMIT_SUMMARY, commit_message(&changes)),
+            _ => commit_message(&changes),
+        };
+        let commit = git.commit(&message)?;
+        info!(
+            "[VersioningService] Committed {} changes: {}",
+            changes.len(),
+            commit.summary
+        );
+
+        self.event_bus.emit(DomainEvent::VaultCommitted {
+            id: commit.id.clone(),
+            changed: changes.len(),
+        });
+        Ok(Some(commit))
+    }
+
+    /// バージョン管理の状態
+    pub fn status(&self) -> VersioningStatus {
+        let enabled = self.settings_service.get_arc().versioning.enabled;
+        let has_pending_changes = self.schedule.lock().first_change.is_some();
+
+        let _guard = self.git_lock.lock();
+        let git_available = GitRepository::is_git_available();
+        let git = self.git();
+        let initialized = git_available && git.is_repository();
+        let last_commit = if initialized {
+            git.last_commit().ok().flatten()
+        } else {
+            None
+        };
+
+        VersioningStatus {
+            enabled,
+            git_available,
+            initialized,
+            has_pending_changes,
+            last_commit,
+        }
+    }
+
+    /// ノートのコミット履歴（新しい順、ファイル名の変更を追跡）
+    pub fn note_history(&self, uid: &str) -> Result<Vec<VaultCommit>, VersioningError> {
+        let _guard = self.git_lock.lock();
+        self.note_history_locked(uid)
+    }
+
+    /// `note_history` の本体（`git_lock` を取得して呼ぶ）
+    fn note_history_locked(&self, uid: &str) -> Result<Vec<VaultCommit>, VersioningError> {
+        let git = self.git();
+        let path = self.note_path(&git, uid)?;
+        if !git.is_repository() {
+            return Err(VersioningError::NotARepository);
+        }
+        git.file_history(&path)
+    }
+
+    /// ノートを暗号化しても平文が git の履歴に残るか（コミット済みか）
+    pub fn keeps_plaintext(&self, uid: &str) -> bool {
+        self.note_history(uid).is_ok_and(|history| !history.is_empty())
+    }
+
+    /// 平文をコミットさせずにノートを暗号化する
+    ///
+    /// コミット済みのノートは `EncryptionError::PlaintextInHistory` で拒否する。`encrypt` の間は
+    /// コミットを止め、バージョン管理が有効なら暗号化した直後にコミットする
+    /// （未コミットの平文が後のコミットに含まれないようにする）。
+    pub fn encrypt_uncommitted<T, E: From<EncryptionError>>(
+        &self,
+        uid: &str,
+        encrypt: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E> {
+        let _guard = self.git_lock.lock();
+        if self.note_history_locked(uid).is_ok_and(|history| !history.is_empty()) {
+            return Err(EncryptionError::PlaintextInHistory.into());
+        }
+
+        let result = encrypt()?;
+        if self.settings_service.get_arc().versioning.enabled {
+            // 暗号化は済んでいるため、コミットに失敗しても次のコミットで暗号文が入る
+            if let Err(e) = self.commit_locked(None) {
+                eprintln!("[VersioningService] Failed to commit encrypted note: {}", e);
+            }
+        }
+        Ok(result)
+    }
+
+    /// コミット時点のノートのファイル内容（front matter を含む）
+    ///
+    /// コミットはノートの履歴に含まれるもののみ受け付ける。
+    pub fn note_file_at(&self, uid: &str, commit_id: &str) -> Result<String, VersioningError> {
+        let history = self.note_history(uid)?;
+        let commit = history
+            .into_iter()
+            .find(|commit| commit.id == commit_id)
+            .ok_or_else(|| VersioningError::CommitNotFound(commit_id.to_string()))?;
+        let path = commit
+            .path
+            .ok_or_else(|| VersioningError::CommitNotFound(commit_id.to_string()))?;
+
+        let _guard = self.git_lock.lock();
+        self.git().file_at(&commit.id, &path)
+    }
+
+    /// コミットスレッドを停止（未コミットの変更はコミットしてから停止する）
+    pub fn shutdown(&self) {
+        for id in self.subscriptions.lock().drain(..) {
+            self.event_bus.unsubscribe(id);
+        }
+        let pending = {
+            let mut schedule = self.schedule.lock();
+            schedule.shutdown = true;
+            schedule.first_change.is_some()
+        };
+        self.wakeup.notify_all();
+
+        if pending && self.settings_service.get_arc().versioning.enabled {
+            if let Err(e) = self.commit_now(None) {
+                eprintln!("[VersioningService] Failed to commit on shutdown: {}", e);
+            }
+        }
+    }
+
+    fn run(&self) {
+        loop {
+            {
+                let mut schedule = self.schedule.lock();
+                loop {
+                    if schedule.shutdown {
+                        return;
+                    }
+                    match self.due(&schedule) {
+                        Some(due) if due <= Instant::now() => break,
+                        Some(due) => {
+                            self.wakeup.wait_until(&mut schedule, due);
+                        }
+                        None => self.wakeup.wait(&mut schedule),
+                    }
+                }
+            }
+
+            if !self.settings_service.get_arc().versioning.enabled {
+                self.clear_pending();
+                continue;
+            }
+            // 失敗しても次の変更で再試行する
+            if let Err(e) = self.commit_now(None) {
+                eprintln!("[VersioningService] Failed to commit: {}", e);
+            }
+        }
+    }
+
+    /// 未コミットの変更の記録を消す（コミットする直前に呼ぶ）
+    fn clear_pending(&self) {
+        let mut schedule = self.schedule.lock();
+        schedule.first_change = None;
+        schedule.last_change = None;
+    }
+
+    /// 次にコミットする時刻（未コミットの変更がなければ `None`）
+    fn due(&self, schedule: &Schedule) -> Option<Instant> {
+        let (first, last) = (schedule.first_change?, schedule.last_change?);
+        let settings = self.settings_service.get_arc();
+        let quiet = last + Duration::from_secs(settings.versioning.quiet_period_secs);
+        match settings.versioning.max_interval_secs {
+            0 => Some(quiet),
+            max => Some(quiet.min(first + Duration::from_secs(max))),
+        }
+    }
+
+    /// この保管庫の保存ディレクトリ（保管庫の切り替え後も終了時のコミットは元の保管庫に入る）
+    fn git(&self) -> GitRepository {
+        GitRepository::new(self.root.get())
+    }
+
+    /// ノートのファイルの保存ディレクトリからの相対パス
+    fn note_path(&self, git: &GitRepository, uid: &str) -> Result<String, VersioningError> {
+        self.repository
+            .get_path(uid)
+            .and_then(|path| git.relative_path(&path))
+            .ok_or_else(|| VersioningError::NoteNotFound(uid.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Note, DEFAULT_VAULT_ID};
+    use crate::infrastructure::{
+        EventBusImpl, FileNoteRepository, FileStorage,
+        HeadingFilenameStrategy,
+    };
+    use crate::services::test_support;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_commit_and_load_note_history() {
+        if !GitRepository::is_git_available() {
+            return;
+        }
+        let temp_dir = TempDir::new().unwrap();
+        let notes_dir = temp_dir.path().join("notes");
+        let event_bus = Arc::new(EventBusImpl::new());
+        let settings_service = test_support::settings_service(temp_dir.path(), event_bus.clone());
+        settings_service
+            .update(|s| s.versioning.enabled = true)
+            .unwrap();
+        let repository: Arc<dyn NoteRepository> = Arc::new(FileNoteRepository::with_fixed_path(
+            Arc::new(FileStorage::new()),
+            Arc::new(HeadingFilenameStrategy::new()),
+            notes_dir.clone(),
+        ));
+        let service = VersioningService::new(
+            repository.clone(),
+            settings_service.clone(),
+            event_bus,
+            Arc::new(VaultRoot::new(DEFAULT_VAULT_ID, notes_dir)),
+        );
+
+        let mut note = Note::new();
+        note.content = "# 買い物\n\n牛乳".to_string();
+        repository.save(&note).unwrap();
+        let first = service.commit_now(None).unwrap().unwrap();
+        assert_eq!(first.summary, INITIAL_COMMIT_SUMMARY);
+        assert!(service.commit_now(None).unwrap().is_none());
+
+        note.content = "# 買い物\n\n牛乳\n卵".to_string();
+        repository.save(&note).unwrap();
+        assert_eq!(service.commit_now(None).unwrap().unwrap().summary, "Edit 買い物");
+        note.content = "# 買い物\n\n牛乳\n卵\nパン".to_string();
+        repository.save(&note).unwrap();
+        service.commit_now(Some("パンを追加")).unwrap().unwrap();
+
+        let history = service.note_history(note.uid()).unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].summary, "パンを追加");
+
+        let file = service.note_file_at(note.uid(), &first.id).unwrap();
+        assert_eq!(Note::from_file_content(&file).unwrap().content, "# 買い物\n\n牛乳");
+        assert!(matches!(
+            service.note_file_at(note.uid(), "HEAD"),
+            Err(VersioningError::CommitNotFound(_))
+        ));
+        assert!(service.status().initialized);
+
+        // コミット済みのノートは平文が履歴に残る
+        assert!(service.keeps_plaintext(note.uid()));
+        let result: Result<(), EncryptionError> = service.encrypt_uncommitted(note.uid(), || Ok(()));
+        assert!(matches!(result, Err(EncryptionError::PlaintextInHistory)));
+
+        // 未コミットのノートは暗号化でき、暗号化した内容がすぐにコミットされる
+        let mut uncommitted = Note::new();
+        uncommitted.content = "# 秘密\n\n平文".to_string();
+        repository.save(&uncommitted).unwrap();
+        let uid = uncommitted.uid().to_string();
+        assert!(!service.keeps_plaintext(&uid));
+        service
+            .encrypt_uncommitted(&uid, || {
+                uncommitted.content = "# 秘密\n\n暗号文".to_string();
+                repository.save(&uncommitted).map(|_| ())
+            })
+            .unwrap();
+        let history = service.note_history(&uid).unwrap();
+        assert_eq!(history[0].summary, "Add 秘密");
+        let file = service.note_file_at(&uid, &history[0].id).unwrap();
+        assert!(!file.contains("平文"));
+
+        // 無効にしてもコミット済みのノートは平文が履歴に残る
+        settings_service
+            .update(|s| s.versioning.enabled = false)
+            .unwrap();
+        assert!(service.keeps_plaintext(note.uid()));
+    }
+}
@@ -0,0 +1,5 @@
+/// クリップボードの読み取り（テンプレートの `{{clipboard}}` 変数用）
+pub trait Clipboard: Send + Sync {
+    /// テキストを読み取る（テキストがない・読み取れない場合は `None`）
+    fn read_text(&self) -> Option<String>;
+}
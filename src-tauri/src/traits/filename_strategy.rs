@@ -1,8 +1,10 @@
 use crate::domain::Note;
-use std::path::Path;
 
 /// ファイル名生成戦略（Strategyパターン）
 pub trait FilenameStrategy: Send + Sync {
     /// メモからファイル名を生成（拡張子なし）
-    fn generate(&self, note: &Note, existing_files: &[&Path]) -> String;
+    ///
+    /// `is_taken` はファイル名（拡張子なし）が使用済みかを返す。
+    /// 呼び出し側はディレクトリを一覧せず、存在確認やインデックスの参照で O(1) に判定する。
+    fn generate(&self, note: &Note, is_taken: &dyn Fn(&str) -> bool) -> String;
 }
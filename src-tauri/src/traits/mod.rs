@@ -3,9 +3,13 @@ pub mod repository;
 pub mod filename_strategy;
 pub mod event_bus;
 pub mod settings_repository;
+pub mod uid_generator;
+pub mod clipboard;
 
 pub use storage::{Storage, StorageError};
-pub use repository::{NoteRepository, NoteListItem, RepositoryError};
+pub use repository::{NoteRepository, NoteListItem, NoteFilter, Page, PageCursor, RepositoryError};
 pub use filename_strategy::FilenameStrategy;
 pub use event_bus::{EventBus, EventHandler, SubscriptionId};
 pub use settings_repository::SettingsRepository;
+pub use uid_generator::UidGenerator;
+pub use clipboard::Clipboard;
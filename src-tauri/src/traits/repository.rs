@@ -1,5 +1,10 @@
-use crate::domain::Note;
+use crate::domain::{
+    DailyDirection, DailyNoteEntry, DuplicateFile, ExpectedVersion, Note, NoteConflict, NoteSortOrder, TrashItem,
+};
+use chrono::NaiveDate;
 use crate::infrastructure::GalleryNote;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -8,10 +13,48 @@ pub trait NoteRepository: Send + Sync {
     /// メモを保存
     fn save(&self, note: &Note) -> Result<PathBuf, RepositoryError>;
 
+    /// 前提の版と一致する場合のみメモを保存（楽観的排他制御）
+    ///
+    /// ファイルが読み込み後に変更されていた場合は `RepositoryError::Conflict` を返す。
+    fn save_checked(
+        &self,
+        note: &Note,
+        expected: &ExpectedVersion,
+    ) -> Result<PathBuf, RepositoryError> {
+        self.save_verified(note, &|| self.check_version(note, expected))
+    }
+
+    /// `verify` が成功した場合のみメモを保存
+    ///
+    /// 実装は `verify` と保存を同じ書き込みロックの中で行い、確認から保存までの間に
+    /// 別の保存が割り込まないようにする。`verify` の中でメモを読み込んでもよい
+    /// （暗号化ノートを復号して版を比べる場合など）。
+    fn save_verified(
+        &self,
+        note: &Note,
+        verify: &dyn Fn() -> Result<(), RepositoryError>,
+    ) -> Result<PathBuf, RepositoryError>;
+
+    /// 保存しようとしたメモが前提の版から変更されていないか確認（`save_checked` の実装用）
+    ///
+    /// ファイルの内容が保存しようとした本文と同じ場合（再送など）は競合としない。
+    fn check_version(&self, note: &Note, expected: &ExpectedVersion) -> Result<(), RepositoryError> {
+        if expected.is_unconditional() {
+            return Ok(());
+        }
+        match self.load(note.uid()) {
+            Ok(current) if current.content != note.content && !expected.matches(&current) => {
+                Err(RepositoryError::conflict(note.clone(), current))
+            }
+            Ok(_) | Err(RepositoryError::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
     /// メモをロード
     fn load(&self, uid: &str) -> Result<Note, RepositoryError>;
 
-    /// メモを削除
+    /// メモを削除（ゴミ箱へ移動。本文が空のメモはそのまま削除）
     fn delete(&self, uid: &str) -> Result<(), RepositoryError>;
 
     /// 全メモの一覧を取得（メタデータのみ）
@@ -20,12 +63,143 @@ pub trait NoteRepository: Send + Sync {
     /// UIDからファイルパスを取得
     fn get_path(&self, uid: &str) -> Option<PathBuf>;
 
+    /// UIDから一覧の項目（メタデータのみ）を取得
+    ///
+    /// 既定の実装は全メモの一覧から探すため、インデックスを持つ実装では上書きする。
+    fn get_list_item(&self, uid: &str) -> Result<Option<NoteListItem>, RepositoryError> {
+        Ok(self.list_all()?.into_iter().find(|item| item.uid == uid))
+    }
+
     /// ギャラリー用ノート一覧を取得（高速キャッシュ版）
     fn list_gallery(
         &self,
-        sort_by_created: bool,
-        tag_filter: Option<&str>,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
     ) -> Result<Vec<GalleryNote>, RepositoryError>;
+
+    /// メモ一覧をカーソル位置から1ページ分取得
+    fn list_page(
+        &self,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
+        cursor: Option<&PageCursor>,
+        limit: usize,
+    ) -> Result<Page<NoteListItem>, RepositoryError>;
+
+    /// ギャラリー用ノート一覧をカーソル位置から1ページ分取得
+    fn list_gallery_page(
+        &self,
+        sort: NoteSortOrder,
+        filter: &NoteFilter,
+        cursor: Option<&PageCursor>,
+        limit: usize,
+    ) -> Result<Page<GalleryNote>, RepositoryError>;
+
+    /// 手動ソート順を設定（`uids` の並び順が表示順になる）
+    fn set_manual_order(&self, uids: &[String]) -> Result<(), RepositoryError>;
+
+    /// ゴミ箱内のメモ一覧を取得（削除日時の新しい順）
+    fn list_trash(&self) -> Result<Vec<TrashItem>, RepositoryError>;
+
+    /// ゴミ箱からメモを復元
+    ///
+    /// 元のパスが使用中の場合は新しいファイル名で復元する。
+    fn restore(&self, uid: &str) -> Result<Note, RepositoryError>;
+
+    /// ゴミ箱のメモを完全に削除（`deleted_before` が `None` の場合は全件）
+    ///
+    /// 削除した件数を返す。
+    fn purge_trash(
+        &self,
+        deleted_before: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<usize, RepositoryError>;
+
+    /// 現在のファイル名生成戦略でファイル名を付け直す
+    ///
+    /// ファイル名が変わった場合は新しいパスを返す（変わらない場合は `None`）。
+    fn relocate(&self, uid: &str) -> Result<Option<PathBuf>, RepositoryError>;
+
+    /// メモを別のフォルダへ移動（`folder` は正規化済みの相対パス、`""` は直下）
+    ///
+    /// ファイル名は変えず、移動先で使用中の場合のみ連番を付ける。移動後のパスを返す。
+    fn move_to_folder(&self, uid: &str, folder: &str) -> Result<PathBuf, RepositoryError>;
+
+    /// ノートのあるフォルダとフォルダ直下のノート数（フォルダ名順）
+    fn list_folders(&self) -> Result<Vec<(String, usize)>, RepositoryError> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for item in self.list_all()? {
+            *counts.entry(item.folder).or_default() += 1;
+        }
+        Ok(counts.into_iter().collect())
+    }
+
+    /// 保存ディレクトリの内容でインデックスを作り直す（保存ディレクトリの変更後に呼ぶ）
+    ///
+    /// インデックスを持たない実装では何もしない。
+    fn reindex(&self) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+
+    /// 現在の設定の形式で front matter を書き直す（書き直した場合は `true`）
+    ///
+    /// 本文と更新日時は変えない。既定の実装は内容を比べずに保存し直す。
+    fn rewrite_front_matter(&self, uid: &str) -> Result<bool, RepositoryError> {
+        let note = self.load(uid)?;
+        self.save(&note)?;
+        Ok(true)
+    }
+
+    /// 前回の同期で見つかった、インデックスのノートと同じ UID を持つ別のファイル
+    ///
+    /// インデックスを持たない実装では検出しない。
+    fn list_duplicates(&self) -> Result<Vec<DuplicateFile>, RepositoryError> {
+        Ok(Vec::new())
+    }
+
+    /// 期間内のデイリーノート一覧を取得（日付の昇順、`None` は期間の指定なし）
+    ///
+    /// 既定の実装は全メモを読み込むため、インデックスを持つ実装では上書きする。
+    fn list_daily_notes(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<DailyNoteEntry>, RepositoryError> {
+        let mut entries: Vec<DailyNoteEntry> = self
+            .list_all()?
+            .iter()
+            .filter_map(|item| self.load(&item.uid).ok())
+            .filter_map(|note| {
+                let date = note.metadata.daily?;
+                let in_range = from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to);
+                in_range.then_some(DailyNoteEntry {
+                    date,
+                    uid: note.metadata.uid,
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.uid.cmp(&b.uid)));
+        Ok(entries)
+    }
+
+    /// 指定日の前後で最も近いデイリーノート（指定日自身は含まない）
+    ///
+    /// 既定の実装は期間内の一覧から探すため、インデックスを持つ実装では上書きする。
+    fn adjacent_daily_note(
+        &self,
+        date: NaiveDate,
+        direction: DailyDirection,
+    ) -> Result<Option<DailyNoteEntry>, RepositoryError> {
+        match direction {
+            DailyDirection::Previous => match date.pred_opt() {
+                Some(to) => Ok(self.list_daily_notes(None, Some(to))?.pop()),
+                None => Ok(None),
+            },
+            DailyDirection::Next => match date.succ_opt() {
+                Some(from) => Ok(self.list_daily_notes(Some(from), None)?.into_iter().next()),
+                None => Ok(None),
+            },
+        }
+    }
 }
 
 /// メモ一覧アイテム
@@ -35,6 +209,44 @@ pub struct NoteListItem {
     pub title: String,
     pub path: PathBuf,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub pinned: bool,
+    pub favorite: bool,
+    /// フォルダ（保存ディレクトリからの相対パス、直下は `""`）
+    pub folder: String,
+    /// 暗号化ノートか
+    pub encrypted: bool,
+}
+
+/// 一覧の絞り込み条件
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoteFilter<'a> {
+    /// タグ（フロントマター + ハッシュタグ）
+    pub tag: Option<&'a str>,
+    /// フォルダ（サブフォルダのノートも含む）
+    pub folder: Option<&'a str>,
+}
+
+/// キーセットページネーション用カーソル
+///
+/// 直前のページの最終アイテムの `(pinned, sort_key, uid)` を保持する。
+/// ピン留めされたノートが常に先頭に並び、その中で `sort_key`（ソート列の値、
+/// インデックスに保存された文字列表現）の順、同一値は `uid` の降順で順序付けられる。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageCursor {
+    #[serde(default)]
+    pub pinned: bool,
+    pub sort_key: String,
+    pub uid: String,
+}
+
+/// ページ単位の取得結果
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// 次ページのカーソル（最終ページの場合は None）
+    pub next_cursor: Option<PageCursor>,
+    /// フィルタ適用後の総件数
+    pub total: usize,
 }
 
 /// リポジトリエラー
@@ -68,6 +280,12 @@ pub enum RepositoryError {
     FilenameGeneration {
         reason: String,
     },
+    /// 保存時の競合（ファイルが読み込み後に変更された）
+    #[error("メモが他の場所で変更されています: uid={}", .0.uid())]
+    Conflict(Box<NoteConflict>),
+    /// 暗号化ノートの暗号化・復号の失敗（ロック中を含む）
+    #[error(transparent)]
+    Encryption(#[from] crate::domain::EncryptionError),
 }
 
 impl RepositoryError {
@@ -84,6 +302,32 @@ impl RepositoryError {
         }
     }
 
+    /// 再試行で回復しうる一時的なI/Oエラーか
+    ///
+    /// 同期ツールやウイルス対策ソフトによるファイルロックなどを想定する。
+    pub fn is_transient(&self) -> bool {
+        use std::io::ErrorKind;
+        match self {
+            Self::Storage {
+                source: crate::traits::StorageError::Io(e),
+                ..
+            } => matches!(
+                e.kind(),
+                ErrorKind::Interrupted
+                    | ErrorKind::WouldBlock
+                    | ErrorKind::TimedOut
+                    | ErrorKind::PermissionDenied
+                    | ErrorKind::ResourceBusy
+            ),
+            _ => false,
+        }
+    }
+
+    /// 競合エラーを作成
+    pub fn conflict(local: Note, remote: Note) -> Self {
+        Self::Conflict(Box::new(NoteConflict { local, remote }))
+    }
+
     /// パースエラーを作成
     pub fn parse(context: impl Into<String>, path: Option<PathBuf>) -> Self {
         Self::Parse {
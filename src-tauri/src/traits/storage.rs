@@ -1,22 +1,56 @@
+use crate::domain::IgnoreRules;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// ストレージ抽象化（依存性逆転原則）
 pub trait Storage: Send + Sync {
-    /// アトミック保存（tmp → rename パターン）
+    /// アトミック保存（一意な tmp に書き込み → fsync → rename パターン）
     fn save_atomic(&self, path: &Path, content: &str) -> Result<(), StorageError>;
 
+    /// バイト列のアトミック保存（添付ファイルやバックアップなどテキスト以外のファイル用）
+    fn save_atomic_bytes(&self, path: &Path, content: &[u8]) -> Result<(), StorageError>;
+
     /// ファイル読み込み
     fn load(&self, path: &Path) -> Result<String, StorageError>;
 
+    /// バイト列として読み込み
+    fn load_bytes(&self, path: &Path) -> Result<Vec<u8>, StorageError>;
+
+    /// ファイル末尾に追記（ファイルがなければ作成）
+    ///
+    /// 追記後のファイルサイズ（バイト）を返す。
+    fn append(&self, path: &Path, content: &str) -> Result<u64, StorageError>;
+
     /// ファイル削除
     fn delete(&self, path: &Path) -> Result<(), StorageError>;
 
+    /// ファイル移動（移動先の親ディレクトリは必要に応じて作成）
+    fn rename(&self, from: &Path, to: &Path) -> Result<(), StorageError>;
+
     /// ファイル存在確認
     fn exists(&self, path: &Path) -> bool;
 
     /// 指定拡張子のファイル一覧を取得
     fn list_files(&self, dir: &Path, extension: &str) -> Result<Vec<PathBuf>, StorageError>;
+
+    /// 指定拡張子のファイル一覧をサブディレクトリも含めて取得
+    ///
+    /// `ignore` に一致するファイル・ディレクトリ（`dir` からの相対パスで判定）は除外する。
+    fn list_files_recursive(
+        &self,
+        dir: &Path,
+        extension: &str,
+        ignore: &IgnoreRules,
+    ) -> Result<Vec<PathBuf>, StorageError>;
+
+    /// 拡張子に関係なくすべてのファイル一覧をサブディレクトリも含めて取得
+    ///
+    /// 除外の扱いは `list_files_recursive` と同じ。
+    fn list_all_files_recursive(
+        &self,
+        dir: &Path,
+        ignore: &IgnoreRules,
+    ) -> Result<Vec<PathBuf>, StorageError>;
 }
 
 /// ストレージエラー
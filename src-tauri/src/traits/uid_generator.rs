@@ -0,0 +1,5 @@
+/// UID 生成戦略（Strategyパターン）
+pub trait UidGenerator: Send + Sync {
+    /// 新しいノートの UID を生成（`is_valid_uid` を満たすこと）
+    fn generate(&self) -> String;
+}
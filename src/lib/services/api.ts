@@ -14,7 +14,37 @@ import type {
   AppError,
   NoteGalleryItemDto,
   GallerySortOrder,
+  NoteSortOrder,
+  ViewKind,
   NoteTagsDto,
+  PageCursor,
+  PageDto,
+  TrashItemDto,
+  RevisionDto,
+  DiffLineDto,
+  SaveResultDto,
+  DraftDto,
+  DailyDirection,
+  DailyNoteEntryDto,
+  FilenameStyle,
+  RenameReport,
+  FolderNode,
+  VaultDto,
+  RelocationMode,
+  RelocationReport,
+  EncryptionStatus,
+  VaultCommitDto,
+  VersioningStatusDto,
+  BackupInfoDto,
+  BackupReportDto,
+  RestoreReportDto,
+  SyncReportDto,
+  DuplicateGroupDto,
+  ConflictMergeDto,
+  UidRepairDto,
+  UidFormat,
+  TimestampZone,
+  NormalizeReport,
 } from '$lib/types';
 import { ok, err, parseAppError } from '$lib/types';
 
@@ -45,14 +75,47 @@ export async function createNoteSafe(): Promise<Result<NoteDto, AppError>> {
   return safeInvoke('create_note');
 }
 
+/** Version the client last saw; the save is rejected with a CONFLICT error if the file changed since */
+export interface ExpectedVersion {
+  contentHash?: string;
+  updatedAt?: string;
+}
+
+function expectedArgs(expected?: ExpectedVersion) {
+  return {
+    expectedHash: expected?.contentHash ?? null,
+    expectedUpdatedAt: expected?.updatedAt ?? null,
+  };
+}
+
 /** Save note content to disk */
-export async function saveNote(uid: string, content: string): Promise<void> {
-  return await invoke('save_note', { uid, content });
+export async function saveNote(
+  uid: string,
+  content: string,
+  expected?: ExpectedVersion
+): Promise<SaveResultDto> {
+  return await invoke('save_note', { uid, content, ...expectedArgs(expected) });
 }
 
 /** Save note with Result-based error handling */
-export async function saveNoteSafe(uid: string, content: string): Promise<Result<void, AppError>> {
-  return safeInvoke('save_note', { uid, content });
+export async function saveNoteSafe(
+  uid: string,
+  content: string,
+  expected?: ExpectedVersion
+): Promise<Result<SaveResultDto, AppError>> {
+  return safeInvoke('save_note', { uid, content, ...expectedArgs(expected) });
+}
+
+/**
+ * Queue a background save; requests within settings.autosave.delay_ms are coalesced.
+ * Completion and failure are reported through the `save-completed` / `save-failed` events.
+ */
+export async function requestAutosave(
+  uid: string,
+  content: string,
+  expected?: ExpectedVersion
+): Promise<void> {
+  return await invoke('request_autosave', { uid, content, ...expectedArgs(expected) });
 }
 
 /** Load a note by UID */
@@ -65,7 +128,7 @@ export async function loadNoteSafe(uid: string): Promise<Result<NoteDto, AppErro
   return safeInvoke('load_note', { uid });
 }
 
-/** Delete a note by UID */
+/** Delete a note by UID (moves it to the trash; empty notes are removed) */
 export async function deleteNote(uid: string): Promise<void> {
   return await invoke('delete_note', { uid });
 }
@@ -75,7 +138,27 @@ export async function deleteNoteSafe(uid: string): Promise<Result<void, AppError
   return safeInvoke('delete_note', { uid });
 }
 
-/** List all notes (sorted by updated_at descending) */
+/** Toggle the pinned flag; returns the new state */
+export async function togglePinned(uid: string): Promise<boolean> {
+  return await invoke('toggle_pinned', { uid });
+}
+
+/** Toggle pinned with Result-based error handling */
+export async function togglePinnedSafe(uid: string): Promise<Result<boolean, AppError>> {
+  return safeInvoke('toggle_pinned', { uid });
+}
+
+/** Toggle the favorite flag (also refreshes the tray menu); returns the new state */
+export async function toggleFavorite(uid: string): Promise<boolean> {
+  return await invoke('toggle_favorite', { uid });
+}
+
+/** Toggle favorite with Result-based error handling */
+export async function toggleFavoriteSafe(uid: string): Promise<Result<boolean, AppError>> {
+  return safeInvoke('toggle_favorite', { uid });
+}
+
+/** List all notes (pinned first, then updated_at descending) */
 export async function listNotes(): Promise<NoteListItemDto[]> {
   return await invoke('list_notes');
 }
@@ -85,6 +168,32 @@ export async function listNotesSafe(): Promise<Result<NoteListItemDto[], AppErro
   return safeInvoke('list_notes');
 }
 
+/**
+ * List one page of notes (keyset pagination).
+ * Omitted sortOrder / tagFilter fall back to the saved sidebar view preference.
+ * `folder` limits the list to a folder and its subfolders.
+ */
+export async function listNotesPage(
+  cursor?: PageCursor | null,
+  limit?: number,
+  sortOrder?: NoteSortOrder,
+  tagFilter?: string,
+  folder?: string
+): Promise<PageDto<NoteListItemDto>> {
+  return await invoke('list_notes_page', { sortOrder, tagFilter, folder, cursor, limit });
+}
+
+/** List one page of notes with Result-based error handling */
+export async function listNotesPageSafe(
+  cursor?: PageCursor | null,
+  limit?: number,
+  sortOrder?: NoteSortOrder,
+  tagFilter?: string,
+  folder?: string
+): Promise<Result<PageDto<NoteListItemDto>, AppError>> {
+  return safeInvoke('list_notes_page', { sortOrder, tagFilter, folder, cursor, limit });
+}
+
 // ===== Search =====
 
 /** Search notes with fuzzy matching */
@@ -157,6 +266,38 @@ export interface SettingsUpdate {
   shortcut_save_note?: string;
   shortcut_find_in_note?: string;
   shortcut_backlink_panel?: string;
+  // Trash
+  trash_retention_days?: number;
+  // History
+  history_enabled?: boolean;
+  history_min_interval_secs?: number;
+  history_max_revisions?: number;
+  // Templates (empty string restores the built-in template)
+  default_template?: string;
+  // Filenames (applies to new notes; use migrateFilenames to rename existing files)
+  filename_style?: FilenameStyle;
+  filename_pattern?: string;
+  // Daily notes (empty template restores the built-in template)
+  daily_filename_pattern?: string;
+  daily_template?: string;
+  // Encryption (0 never locks automatically)
+  encryption_session_timeout_secs?: number;
+  // Versioning (git commits of the storage directory; 0 = no upper limit)
+  versioning_enabled?: boolean;
+  versioning_quiet_period_secs?: number;
+  versioning_max_interval_secs?: number;
+  // Backup (empty directory = default location)
+  backup_enabled?: boolean;
+  backup_interval_hours?: number;
+  backup_directory?: string;
+  backup_keep_daily?: number;
+  backup_keep_weekly?: number;
+  // Mirror sync (0 = manual only)
+  sync_interval_minutes?: number;
+  // UIDs (new notes only)
+  uid_format?: UidFormat;
+  // Timestamps (use normalizeTimestamps to rewrite existing notes)
+  timestamp_zone?: TimestampZone;
 }
 
 /** Update settings */
@@ -169,6 +310,75 @@ export async function updateSettingsSafe(settings: SettingsUpdate): Promise<Resu
   return safeInvoke('update_settings', { settings });
 }
 
+/** Persist the sort order and tag filter of a view (null tagFilter clears the filter) */
+export async function updateViewPreference(
+  view: ViewKind,
+  sortOrder: NoteSortOrder,
+  tagFilter: string | null
+): Promise<void> {
+  return await invoke('update_view_preference', { view, sortOrder, tagFilter });
+}
+
+/** Update view preference with Result-based error handling */
+export async function updateViewPreferenceSafe(
+  view: ViewKind,
+  sortOrder: NoteSortOrder,
+  tagFilter: string | null
+): Promise<Result<void, AppError>> {
+  return safeInvoke('update_view_preference', { view, sortOrder, tagFilter });
+}
+
+/**
+ * Switch the filename style and rename existing files to match.
+ * Notes with `filename:` in front matter keep their own style.
+ */
+export async function migrateFilenames(
+  style: FilenameStyle,
+  pattern?: string
+): Promise<RenameReport> {
+  return await invoke('migrate_filenames', { style, pattern: pattern ?? null });
+}
+
+/** Migrate filenames with Result-based error handling */
+export async function migrateFilenamesSafe(
+  style: FilenameStyle,
+  pattern?: string
+): Promise<Result<RenameReport, AppError>> {
+  return safeInvoke('migrate_filenames', { style, pattern: pattern ?? null });
+}
+
+/**
+ * Switch the front-matter time zone and rewrite created_at/updated_at of existing notes.
+ * Old notes without an offset are converted too; bodies and update times are kept.
+ */
+export async function normalizeTimestamps(zone: TimestampZone): Promise<NormalizeReport> {
+  return await invoke('normalize_timestamps', { zone });
+}
+
+/** Normalize timestamps with Result-based error handling */
+export async function normalizeTimestampsSafe(zone: TimestampZone): Promise<Result<NormalizeReport, AppError>> {
+  return safeInvoke('normalize_timestamps', { zone });
+}
+
+/**
+ * Change the storage directory, moving or copying existing notes ('keep' leaves them in place).
+ * Progress is reported through the `relocation-progress` event; the index is rebuilt afterwards.
+ */
+export async function relocateStorage(
+  storageDirectory: string,
+  mode: RelocationMode
+): Promise<RelocationReport> {
+  return await invoke('relocate_storage', { storageDirectory, mode });
+}
+
+/** Relocate storage with Result-based error handling */
+export async function relocateStorageSafe(
+  storageDirectory: string,
+  mode: RelocationMode
+): Promise<Result<RelocationReport, AppError>> {
+  return safeInvoke('relocate_storage', { storageDirectory, mode });
+}
+
 // ===== Window Management =====
 
 /** Save current window geometry to settings */
@@ -177,8 +387,12 @@ export async function saveWindowGeometry(): Promise<void> {
 }
 
 /** Prepare for window hide (save note, delete if empty) */
-export async function prepareHide(uid?: string, content?: string): Promise<void> {
-  return await invoke('prepare_hide', { uid, content });
+export async function prepareHide(
+  uid?: string,
+  content?: string,
+  expected?: ExpectedVersion
+): Promise<void> {
+  return await invoke('prepare_hide', { uid, content, ...expectedArgs(expected) });
 }
 
 /** Set the last opened note UID for restoration */
@@ -208,25 +422,73 @@ export async function getCurrentHotkey(): Promise<string> {
 /** List notes for gallery view */
 export async function listNotesGallery(
   sortOrder?: GallerySortOrder,
-  tagFilter?: string
+  tagFilter?: string,
+  folder?: string
 ): Promise<NoteGalleryItemDto[]> {
   return await invoke('list_notes_gallery', {
-    sortOrder: sortOrder ?? 'updated_at',
+    sortOrder,
     tagFilter,
+    folder,
   });
 }
 
 /** List notes for gallery with Result-based error handling */
 export async function listNotesGallerySafe(
   sortOrder?: GallerySortOrder,
-  tagFilter?: string
+  tagFilter?: string,
+  folder?: string
 ): Promise<Result<NoteGalleryItemDto[], AppError>> {
   return safeInvoke('list_notes_gallery', {
-    sortOrder: sortOrder ?? 'updated_at',
+    sortOrder,
+    tagFilter,
+    folder,
+  });
+}
+
+/** List one page of notes for gallery view (keyset pagination) */
+export async function listNotesGalleryPage(
+  sortOrder?: GallerySortOrder,
+  tagFilter?: string,
+  cursor?: PageCursor | null,
+  limit?: number,
+  folder?: string
+): Promise<PageDto<NoteGalleryItemDto>> {
+  return await invoke('list_notes_gallery_page', {
+    sortOrder,
     tagFilter,
+    folder,
+    cursor,
+    limit,
   });
 }
 
+/** List one gallery page with Result-based error handling */
+export async function listNotesGalleryPageSafe(
+  sortOrder?: GallerySortOrder,
+  tagFilter?: string,
+  cursor?: PageCursor | null,
+  limit?: number,
+  folder?: string
+): Promise<Result<PageDto<NoteGalleryItemDto>, AppError>> {
+  return safeInvoke('list_notes_gallery_page', {
+    sortOrder,
+    tagFilter,
+    folder,
+    cursor,
+    limit,
+  });
+}
+
+/** Save the manual sort order (uids in display order) */
+export async function setManualOrder(uids: string[]): Promise<void> {
+  return await invoke('set_manual_order', { uids });
+}
+
+/** Save manual sort order with Result-based error handling */
+export async function setManualOrderSafe(uids: string[]): Promise<Result<void, AppError>> {
+  return safeInvoke('set_manual_order', { uids });
+}
+
 // ===== Tags =====
 
 /** Get all tags across all notes */
@@ -258,3 +520,475 @@ export async function updateNoteTags(uid: string, tags: string[]): Promise<void>
 export async function updateNoteTagsSafe(uid: string, tags: string[]): Promise<Result<void, AppError>> {
   return safeInvoke('update_note_tags', { uid, tags });
 }
+
+// ===== Trash =====
+
+/** List notes in the trash (most recently deleted first) */
+export async function listTrash(): Promise<TrashItemDto[]> {
+  return await invoke('list_trash');
+}
+
+/** List trash with Result-based error handling */
+export async function listTrashSafe(): Promise<Result<TrashItemDto[], AppError>> {
+  return safeInvoke('list_trash');
+}
+
+/** Restore a note from the trash */
+export async function restoreNote(uid: string): Promise<NoteDto> {
+  return await invoke('restore_note', { uid });
+}
+
+/** Restore note with Result-based error handling */
+export async function restoreNoteSafe(uid: string): Promise<Result<NoteDto, AppError>> {
+  return safeInvoke('restore_note', { uid });
+}
+
+/** Permanently delete everything in the trash; returns the number of notes removed */
+export async function emptyTrash(): Promise<number> {
+  return await invoke('empty_trash');
+}
+
+/** Empty trash with Result-based error handling */
+export async function emptyTrashSafe(): Promise<Result<number, AppError>> {
+  return safeInvoke('empty_trash');
+}
+
+// ===== History =====
+
+/** List revisions of a note (newest first) */
+export async function listRevisions(uid: string): Promise<RevisionDto[]> {
+  return await invoke('list_revisions', { uid });
+}
+
+/** List revisions with Result-based error handling */
+export async function listRevisionsSafe(uid: string): Promise<Result<RevisionDto[], AppError>> {
+  return safeInvoke('list_revisions', { uid });
+}
+
+/** Line diff between two revisions (omit `to` to compare with the current content) */
+export async function diffRevisions(uid: string, from: string, to?: string): Promise<DiffLineDto[]> {
+  return await invoke('diff_revisions', { uid, from, to: to ?? null });
+}
+
+/** Diff revisions with Result-based error handling */
+export async function diffRevisionsSafe(
+  uid: string,
+  from: string,
+  to?: string
+): Promise<Result<DiffLineDto[], AppError>> {
+  return safeInvoke('diff_revisions', { uid, from, to: to ?? null });
+}
+
+/** Replace a note's content with a revision (the current content is kept as a revision) */
+export async function restoreRevision(uid: string, id: string): Promise<NoteDto> {
+  return await invoke('restore_revision', { uid, id });
+}
+
+/** Restore revision with Result-based error handling */
+export async function restoreRevisionSafe(uid: string, id: string): Promise<Result<NoteDto, AppError>> {
+  return safeInvoke('restore_revision', { uid, id });
+}
+
+// ===== Drafts =====
+
+/** Journal the editor buffer so it survives a crash (request_autosave already does this) */
+export async function writeDraft(uid: string, content: string): Promise<void> {
+  return await invoke('write_draft', { uid, content });
+}
+
+/** List drafts newer than their saved notes (call on startup) */
+export async function listDrafts(): Promise<DraftDto[]> {
+  return await invoke('list_drafts');
+}
+
+/** List drafts with Result-based error handling */
+export async function listDraftsSafe(): Promise<Result<DraftDto[], AppError>> {
+  return safeInvoke('list_drafts');
+}
+
+/** Save a draft's content over its note */
+export async function recoverDraft(uid: string): Promise<NoteDto> {
+  return await invoke('recover_draft', { uid });
+}
+
+/** Recover draft with Result-based error handling */
+export async function recoverDraftSafe(uid: string): Promise<Result<NoteDto, AppError>> {
+  return safeInvoke('recover_draft', { uid });
+}
+
+/** Discard a draft */
+export async function discardDraft(uid: string): Promise<void> {
+  return await invoke('discard_draft', { uid });
+}
+
+/** Discard draft with Result-based error handling */
+export async function discardDraftSafe(uid: string): Promise<Result<void, AppError>> {
+  return safeInvoke('discard_draft', { uid });
+}
+
+// ===== Templates =====
+
+/** List template names (markdown files in the templates folder of the config directory) */
+export async function listTemplates(): Promise<string[]> {
+  return await invoke('list_templates');
+}
+
+/** List templates with Result-based error handling */
+export async function listTemplatesSafe(): Promise<Result<string[], AppError>> {
+  return safeInvoke('list_templates');
+}
+
+/**
+ * Create and save a note from a template.
+ * `vars` fills {{name}} placeholders such as title and clipboard; date/time default to now
+ * and clipboard defaults to the current clipboard text.
+ */
+export async function createNoteFromTemplate(
+  name: string,
+  vars?: Record<string, string>
+): Promise<NoteDto> {
+  return await invoke('create_note_from_template', { name, vars: vars ?? null });
+}
+
+/** Create note from template with Result-based error handling */
+export async function createNoteFromTemplateSafe(
+  name: string,
+  vars?: Record<string, string>
+): Promise<Result<NoteDto, AppError>> {
+  return safeInvoke('create_note_from_template', { name, vars: vars ?? null });
+}
+
+// ===== Daily notes =====
+
+/** Open the daily note for a date (YYYY-MM-DD, default today), creating it from the template if missing */
+export async function openDailyNote(date?: string): Promise<NoteDto> {
+  return await invoke('open_daily_note', { date: date ?? null });
+}
+
+/** Open daily note with Result-based error handling */
+export async function openDailyNoteSafe(date?: string): Promise<Result<NoteDto, AppError>> {
+  return safeInvoke('open_daily_note', { date: date ?? null });
+}
+
+/** Get the nearest existing daily note before or after a date (null if there is none) */
+export async function adjacentDailyNote(
+  date: string,
+  direction: DailyDirection
+): Promise<NoteDto | null> {
+  return await invoke('adjacent_daily_note', { date, direction });
+}
+
+/** Get adjacent daily note with Result-based error handling */
+export async function adjacentDailyNoteSafe(
+  date: string,
+  direction: DailyDirection
+): Promise<Result<NoteDto | null, AppError>> {
+  return safeInvoke('adjacent_daily_note', { date, direction });
+}
+
+/** List the dates that have daily notes between `from` and `to` (inclusive, at most one year) */
+export async function listDailyNotes(from: string, to: string): Promise<DailyNoteEntryDto[]> {
+  return await invoke('list_daily_notes', { from, to });
+}
+
+/** List daily notes with Result-based error handling */
+export async function listDailyNotesSafe(
+  from: string,
+  to: string
+): Promise<Result<DailyNoteEntryDto[], AppError>> {
+  return safeInvoke('list_daily_notes', { from, to });
+}
+
+// ===== Folders =====
+
+/** Get the folder tree of the storage directory with note counts */
+export async function getFolderTree(): Promise<FolderNode> {
+  return await invoke('get_folder_tree');
+}
+
+/** Get folder tree with Result-based error handling */
+export async function getFolderTreeSafe(): Promise<Result<FolderNode, AppError>> {
+  return safeInvoke('get_folder_tree');
+}
+
+/**
+ * Move a note to a folder relative to the storage directory ('' for the top level).
+ * The folder is created if needed; returns the normalised folder.
+ */
+export async function moveNote(uid: string, folder: string): Promise<string> {
+  return await invoke('move_note', { uid, folder });
+}
+
+/** Move note with Result-based error handling */
+export async function moveNoteSafe(uid: string, folder: string): Promise<Result<string, AppError>> {
+  return safeInvoke('move_note', { uid, folder });
+}
+
+// ===== Vault API =====
+
+/** List registered vaults */
+export async function listVaults(): Promise<VaultDto[]> {
+  return await invoke('list_vaults');
+}
+
+/** List vaults with Result-based error handling */
+export async function listVaultsSafe(): Promise<Result<VaultDto[], AppError>> {
+  return safeInvoke('list_vaults');
+}
+
+/** Register a vault; each storage directory can belong to only one vault */
+export async function addVault(name: string, storageDirectory: string): Promise<VaultDto> {
+  return await invoke('add_vault', { name, storageDirectory });
+}
+
+/** Add vault with Result-based error handling */
+export async function addVaultSafe(name: string, storageDirectory: string): Promise<Result<VaultDto, AppError>> {
+  return safeInvoke('add_vault', { name, storageDirectory });
+}
+
+/** Unregister a vault (its notes are kept); the active vault cannot be removed */
+export async function removeVault(id: string): Promise<void> {
+  await invoke('remove_vault', { id });
+}
+
+/** Remove vault with Result-based error handling */
+export async function removeVaultSafe(id: string): Promise<Result<void, AppError>> {
+  return safeInvoke('remove_vault', { id });
+}
+
+/**
+ * Set the directory a vault is synced with (null stops syncing).
+ * It must not be the storage directory or nested with it.
+ */
+export async function setVaultMirror(id: string, mirrorDirectory: string | null): Promise<VaultDto> {
+  return await invoke('set_vault_mirror', { id, mirrorDirectory });
+}
+
+/** Set vault mirror with Result-based error handling */
+export async function setVaultMirrorSafe(id: string, mirrorDirectory: string | null): Promise<Result<VaultDto, AppError>> {
+  return safeInvoke('set_vault_mirror', { id, mirrorDirectory });
+}
+
+/**
+ * Switch to another vault without restarting.
+ * Pending autosaves are flushed first; reload notes and settings afterwards.
+ */
+export async function switchVault(id: string): Promise<VaultDto> {
+  return await invoke('switch_vault', { id });
+}
+
+/** Switch vault with Result-based error handling */
+export async function switchVaultSafe(id: string): Promise<Result<VaultDto, AppError>> {
+  return safeInvoke('switch_vault', { id });
+}
+
+// ===== Encrypted notes =====
+
+/**
+ * Unlock encrypted notes with the passphrase.
+ * Without any encrypted note yet, the passphrase is used for notes encrypted from now on.
+ */
+export async function unlockNotes(passphrase: string): Promise<EncryptionStatus> {
+  return await invoke('unlock_notes', { passphrase });
+}
+
+/** Unlock notes with Result-based error handling */
+export async function unlockNotesSafe(passphrase: string): Promise<Result<EncryptionStatus, AppError>> {
+  return safeInvoke('unlock_notes', { passphrase });
+}
+
+/** Lock encrypted notes (the `notes-locked` event also fires on idle timeout) */
+export async function lockNotes(): Promise<void> {
+  await invoke('lock_notes');
+}
+
+/** Lock notes with Result-based error handling */
+export async function lockNotesSafe(): Promise<Result<void, AppError>> {
+  return safeInvoke('lock_notes');
+}
+
+/** Get the lock state and seconds until the session locks itself */
+export async function getEncryptionStatus(): Promise<EncryptionStatus> {
+  return await invoke('get_encryption_status');
+}
+
+/** Get encryption status with Result-based error handling */
+export async function getEncryptionStatusSafe(): Promise<Result<EncryptionStatus, AppError>> {
+  return safeInvoke('get_encryption_status');
+}
+
+/**
+ * Encrypt a note or remove its encryption (requires unlocked notes).
+ * Encrypting also deletes the note's revision history and drafts.
+ * Encrypting is refused while git versioning is enabled or once the note has been committed,
+ * because the plaintext would remain in the git history.
+ */
+export async function setNoteEncrypted(uid: string, encrypted: boolean): Promise<NoteDto> {
+  return await invoke('set_note_encrypted', { uid, encrypted });
+}
+
+/** Set note encryption with Result-based error handling */
+export async function setNoteEncryptedSafe(uid: string, encrypted: boolean): Promise<Result<NoteDto, AppError>> {
+  return safeInvoke('set_note_encrypted', { uid, encrypted });
+}
+
+// ===== Git versioning =====
+
+/** Get whether git versioning is enabled and the last commit */
+export async function getVersioningStatus(): Promise<VersioningStatusDto> {
+  return await invoke('get_versioning_status');
+}
+
+/** Get versioning status with Result-based error handling */
+export async function getVersioningStatusSafe(): Promise<Result<VersioningStatusDto, AppError>> {
+  return safeInvoke('get_versioning_status');
+}
+
+/**
+ * Commit uncommitted changes now (null when nothing changed).
+ * The message is generated from the changed notes when omitted.
+ */
+export async function commitVault(message?: string): Promise<VaultCommitDto | null> {
+  return await invoke('commit_vault', { message });
+}
+
+/** Commit the vault with Result-based error handling */
+export async function commitVaultSafe(message?: string): Promise<Result<VaultCommitDto | null, AppError>> {
+  return safeInvoke('commit_vault', { message });
+}
+
+/** List commits that changed a note (newest first, follows renames) */
+export async function listNoteCommits(uid: string): Promise<VaultCommitDto[]> {
+  return await invoke('list_note_commits', { uid });
+}
+
+/** List note commits with Result-based error handling */
+export async function listNoteCommitsSafe(uid: string): Promise<Result<VaultCommitDto[], AppError>> {
+  return safeInvoke('list_note_commits', { uid });
+}
+
+/** Load a note as it was at a commit (not saved) */
+export async function loadNoteCommit(uid: string, commitId: string): Promise<NoteDto> {
+  return await invoke('load_note_commit', { uid, commitId });
+}
+
+/** Load a note at a commit with Result-based error handling */
+export async function loadNoteCommitSafe(uid: string, commitId: string): Promise<Result<NoteDto, AppError>> {
+  return safeInvoke('load_note_commit', { uid, commitId });
+}
+
+/** Replace a note's content with its content at a commit */
+export async function restoreNoteCommit(uid: string, commitId: string): Promise<NoteDto> {
+  return await invoke('restore_note_commit', { uid, commitId });
+}
+
+/** Restore a note from a commit with Result-based error handling */
+export async function restoreNoteCommitSafe(uid: string, commitId: string): Promise<Result<NoteDto, AppError>> {
+  return safeInvoke('restore_note_commit', { uid, commitId });
+}
+
+// ===== Backups =====
+
+/** Create a backup of the current vault now (old archives are rotated) */
+export async function backupNow(): Promise<BackupReportDto> {
+  return await invoke('backup_now');
+}
+
+/** Create a backup with Result-based error handling */
+export async function backupNowSafe(): Promise<Result<BackupReportDto, AppError>> {
+  return safeInvoke('backup_now');
+}
+
+/** List backups of the current vault (newest first) */
+export async function listBackups(): Promise<BackupInfoDto[]> {
+  return await invoke('list_backups');
+}
+
+/** List backups with Result-based error handling */
+export async function listBackupsSafe(): Promise<Result<BackupInfoDto[], AppError>> {
+  return safeInvoke('list_backups');
+}
+
+/**
+ * Restore the vault from a backup.
+ * The current state is backed up first; settings are restored only when requested.
+ */
+export async function restoreBackup(name: string, includeSettings: boolean): Promise<RestoreReportDto> {
+  return await invoke('restore_backup', { name, includeSettings });
+}
+
+/** Restore a backup with Result-based error handling */
+export async function restoreBackupSafe(name: string, includeSettings: boolean): Promise<Result<RestoreReportDto, AppError>> {
+  return safeInvoke('restore_backup', { name, includeSettings });
+}
+
+// ===== Mirror sync =====
+
+/**
+ * Sync the current vault with its mirror directory now.
+ * Files changed on both sides keep the vault's version; the mirror's is saved as a conflict copy.
+ */
+export async function syncMirror(): Promise<SyncReportDto> {
+  return await invoke('sync_mirror');
+}
+
+/** Sync with the mirror with Result-based error handling */
+export async function syncMirrorSafe(): Promise<Result<SyncReportDto, AppError>> {
+  return safeInvoke('sync_mirror');
+}
+
+/** Notes that have sync-tool conflict copies (Syncthing, Dropbox, mirror sync) */
+export async function listNoteConflicts(): Promise<DuplicateGroupDto[]> {
+  return await invoke('list_note_conflicts');
+}
+
+/** List note conflicts with Result-based error handling */
+export async function listNoteConflictsSafe(): Promise<Result<DuplicateGroupDto[], AppError>> {
+  return safeInvoke('list_note_conflicts');
+}
+
+/** Three-way merge a note with one of its conflict copies (nothing is saved) */
+export async function mergeNoteConflict(uid: string, copyPath: string): Promise<ConflictMergeDto> {
+  return await invoke('merge_note_conflict', { uid, copyPath });
+}
+
+/** Merge a note conflict with Result-based error handling */
+export async function mergeNoteConflictSafe(uid: string, copyPath: string): Promise<Result<ConflictMergeDto, AppError>> {
+  return safeInvoke('merge_note_conflict', { uid, copyPath });
+}
+
+/**
+ * Resolve a conflict by keeping one file; `content` (e.g. an edited merge) replaces the note's body.
+ * The other files are moved to the vault's .sync-trash folder.
+ */
+export async function resolveNoteConflict(uid: string, keepPath: string, content?: string): Promise<NoteDto> {
+  return await invoke('resolve_note_conflict', { uid, keepPath, content: content ?? null });
+}
+
+/** Resolve a note conflict with Result-based error handling */
+export async function resolveNoteConflictSafe(uid: string, keepPath: string, content?: string): Promise<Result<NoteDto, AppError>> {
+  return safeInvoke('resolve_note_conflict', { uid, keepPath, content: content ?? null });
+}
+
+/** Notes that share their uid with a plain copy (e.g. a file duplicated by hand) */
+export async function listDuplicateUids(): Promise<DuplicateGroupDto[]> {
+  return await invoke('list_duplicate_uids');
+}
+
+/** List duplicate uids with Result-based error handling */
+export async function listDuplicateUidsSafe(): Promise<Result<DuplicateGroupDto[], AppError>> {
+  return safeInvoke('list_duplicate_uids');
+}
+
+/**
+ * Give every plain copy a new uid so it becomes a separate note.
+ * Only the front-matter uid changes; file names and bodies are kept, so links still work.
+ */
+export async function repairDuplicateUids(): Promise<UidRepairDto[]> {
+  return await invoke('repair_duplicate_uids');
+}
+
+/** Repair duplicate uids with Result-based error handling */
+export async function repairDuplicateUidsSafe(): Promise<Result<UidRepairDto[], AppError>> {
+  return safeInvoke('repair_duplicate_uids');
+}
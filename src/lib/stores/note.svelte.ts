@@ -4,9 +4,10 @@
 // - データ層（内部状態）とアクション層（公開API）を分離
 // - 外部インターフェースは変更なし（後方互換性維持）
 
-import { createNote, saveNote, loadNote, listNotes, deleteNote } from '$lib/services/api';
+import { createNote, saveNote, requestAutosave, writeDraft, loadNote, listNotes, deleteNote } from '$lib/services/api';
 import { historyStore } from '$lib/stores/history.svelte';
-import type { NoteDto, NoteListItemDto, AppError } from '$lib/types';
+import { settingsStore } from '$lib/stores/settings.svelte';
+import type { NoteDto, NoteListItemDto, AppError, SaveErrorDto } from '$lib/types';
 import { parseAppError } from '$lib/types';
 
 interface LoadOptions {
@@ -24,7 +25,8 @@ let saveError = $state<AppError | null>(null);
 // 自動保存タイマー
 let autosaveTimer: ReturnType<typeof setTimeout> | null = null;
 
-// 即時保存のデバウンス時間（ミリ秒）
+// バックエンドへ自動保存をリクエストするまでのデバウンス時間（ミリ秒）
+// 実際の保存タイミングはバックエンドが settings.autosave.delay_ms でまとめる
 const AUTOSAVE_DELAY_MS = 50;
 
 // ===== 内部データ操作（テスト用にエクスポート）=====
@@ -61,7 +63,8 @@ export function useNoteStore() {
         isDirty = false;
         saveError = null;
         // 新規ノートを即座にファイルとして保存
-        await saveNote(currentNote.uid, currentNote.content);
+        const saved = await saveNote(currentNote.uid, currentNote.content);
+        currentNote = { ...currentNote, ...saved };
         await this.refreshList();
         // 履歴に追加
         historyStore.push(currentNote.uid);
@@ -94,8 +97,14 @@ export function useNoteStore() {
 
       try {
         // 空でも保存する（削除は非表示時に行う）
-        await saveNote(currentNote.uid, currentNote.content);
-        isDirty = false;
+        // 読み込み時の版を渡し、他の場所での変更を上書きしないようにする
+        const content = currentNote.content;
+        const saved = await saveNote(currentNote.uid, content, {
+          contentHash: currentNote.content_hash,
+        });
+        // 保存中に入力が続いた場合は dirty のまま（基準の版のみ更新）
+        currentNote = { ...currentNote, ...saved };
+        isDirty = currentNote.content !== content;
         await this.refreshList();
       } catch (e) {
         saveError = parseAppError(e);
@@ -105,6 +114,20 @@ export function useNoteStore() {
       }
     },
 
+    /**
+     * 競合を解決して保存する
+     *
+     * ファイル上の最新版を基準にして、マージ済みの内容で上書きする。
+     */
+    async resolveConflict(mergedContent: string) {
+      const conflict = saveError?.conflict;
+      if (!conflict || conflict.uid !== currentNote?.uid) return;
+
+      currentNote = { ...conflict.remote, content: mergedContent };
+      saveError = null;
+      await this.save();
+    },
+
     // 空のノートを削除（非表示時に呼び出す）
     async deleteIfEmpty() {
       if (!currentNote) return false;
@@ -136,10 +159,52 @@ export function useNoteStore() {
         clearTimeout(autosaveTimer);
       }
       autosaveTimer = setTimeout(() => {
-        this.save().catch(console.error);
+        autosaveTimer = null;
+        if (!currentNote) return;
+        // 自動保存が無効でも、クラッシュに備えて下書きだけは記録する
+        if (!settingsStore.settings.autosave.enabled) {
+          writeDraft(currentNote.uid, currentNote.content).catch(console.error);
+          return;
+        }
+        // 保存はバックエンドのキューで行う（WebView が閉じても保存される）
+        requestAutosave(currentNote.uid, currentNote.content, {
+          contentHash: currentNote.content_hash,
+        }).catch((e) => {
+          saveError = parseAppError(e);
+        });
       }, AUTOSAVE_DELAY_MS);
     },
 
+    /** バックエンドでの保存完了通知（save-completed イベント） */
+    handleSaveCompleted(uid: string) {
+      if (currentNote?.uid !== uid) return;
+      if (autosaveTimer === null) {
+        isDirty = false;
+      }
+      saveError = null;
+      this.refreshList();
+    },
+
+    /** バックエンドでの保存失敗通知（save-failed イベント、競合時は両方の版）。次の保存成功まで警告を残す */
+    handleSaveFailed(uid: string, error: SaveErrorDto) {
+      if (currentNote?.uid !== uid) return;
+      saveError = parseAppError(error);
+    },
+
+    /**
+     * バックエンドでノートのファイルが書き直された通知（note-updated イベント）
+     *
+     * 開いているノートに未保存の変更がなければ読み込み直し、保存の基準の版を最新にする。
+     */
+    async handleNoteUpdated(uid: string) {
+      if (currentNote?.uid !== uid || isDirty || isSaving || autosaveTimer !== null) return;
+      try {
+        currentNote = await loadNote(uid);
+      } catch (e) {
+        console.error('Failed to reload updated note:', e);
+      }
+    },
+
     cancelAutosave() {
       if (autosaveTimer) {
         clearTimeout(autosaveTimer);
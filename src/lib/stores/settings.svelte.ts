@@ -29,6 +29,22 @@ const defaultSettings: Settings = {
   autosave: { enabled: true, delay_ms: 2000 },
   restore_last_note: false,
   last_note_uid: null,
+  views: {
+    gallery: { sort: 'updated_at', tag_filter: null },
+    sidebar: { sort: 'updated_at', tag_filter: null },
+  },
+  trash: { retention_days: 30 },
+  history: { enabled: true, min_interval_secs: 300, max_revisions: 100 },
+  templates: { default_template: null },
+  daily_notes: { filename_pattern: '%Y-%m-%d', template: null },
+  filenames: { style: 'heading', pattern: '{{date}} {{title}}' },
+  vaults: { active: 'default', list: [] },
+  encryption: { session_timeout_secs: 600 },
+  versioning: { enabled: false, quiet_period_secs: 120, max_interval_secs: 1800 },
+  backup: { enabled: false, interval_hours: 24, directory: null, keep_daily: 7, keep_weekly: 4 },
+  sync: { interval_minutes: 0 },
+  uids: { format: 'timestamp' },
+  timestamps: { zone: 'local' },
 };
 
 // ===== 内部データ層（外部非公開）=====
@@ -13,6 +13,7 @@ export type AppErrorCode =
   | 'IO_ERROR'
   | 'SETTINGS_ERROR'
   | 'SEARCH_ERROR'
+  | 'CONFLICT'
   | 'UNKNOWN';
 
 /** Structured error type for consistent error handling */
@@ -20,6 +21,8 @@ export interface AppError {
   code: AppErrorCode;
   message: string;
   details?: string;
+  /** Both versions of the note when a save was rejected (code === 'CONFLICT') */
+  conflict?: NoteConflictDto;
 }
 
 /** Result type for operations that can fail */
@@ -39,6 +42,18 @@ export function err<E>(error: E): Result<never, E> {
 
 /** Parse error from Tauri invoke into AppError */
 export function parseAppError(e: unknown): AppError {
+  // Structured save errors ({ kind: 'conflict' | 'failed', ... })
+  if (isSaveError(e)) {
+    if (e.kind === 'conflict') {
+      return {
+        code: 'CONFLICT',
+        message: 'The note was changed elsewhere since it was loaded',
+        conflict: e,
+      };
+    }
+    return parseAppError(e.message);
+  }
+
   const message = e instanceof Error ? e.message : String(e);
 
   // Match common error patterns from Rust backend
@@ -73,6 +88,10 @@ export function parseAppError(e: unknown): AppError {
   return { code: 'UNKNOWN', message };
 }
 
+function isSaveError(e: unknown): e is SaveErrorDto {
+  return typeof e === 'object' && e !== null && 'kind' in e;
+}
+
 // ===== Data Transfer Objects =====
 
 export interface NoteDto {
@@ -81,12 +100,60 @@ export interface NoteDto {
   created_at: string;
   updated_at: string;
   is_dirty: boolean;
+  pinned: boolean;
+  favorite: boolean;
+  /** Hash of the content as last loaded or saved (sent back on save to detect conflicts) */
+  content_hash: string;
+  /** Date of a daily note (YYYY-MM-DD), null for other notes */
+  daily: string | null;
+  /** Whether the note is encrypted at rest (content is decrypted) */
+  encrypted: boolean;
 }
 
+/** Version of a note after a successful save */
+export interface SaveResultDto {
+  content_hash: string;
+  updated_at: string;
+}
+
+/** Both sides of a rejected save */
+export interface NoteConflictDto {
+  uid: string;
+  /** The version that was being saved */
+  local: NoteDto;
+  /** The version currently on disk */
+  remote: NoteDto;
+}
+
+export type SaveErrorDto =
+  | ({ kind: 'conflict' } & NoteConflictDto)
+  | { kind: 'failed'; message: string };
+
 export interface NoteListItemDto {
   uid: string;
   title: string;
   updated_at: string;
+  pinned: boolean;
+  favorite: boolean;
+  /** Folder relative to the storage directory ('' for the top level) */
+  folder: string;
+  /** Encrypted notes are listed by UID since their heading is encrypted */
+  encrypted: boolean;
+}
+
+// ページネーション関連
+/** Keyset cursor: (pinned, sort_key, uid) of the last item of the previous page */
+export interface PageCursor {
+  pinned: boolean;
+  sort_key: string;
+  uid: string;
+}
+
+export interface PageDto<T> {
+  items: T[];
+  /** Cursor for the next page, null on the last page */
+  next_cursor: PageCursor | null;
+  total: number;
 }
 
 // 検索関連
@@ -143,6 +210,171 @@ export interface ShortcutSettings {
   backlink_panel: string;
 }
 
+/** Sort order shared by the gallery and the sidebar */
+export type NoteSortOrder =
+  | 'updated_at'
+  | 'created_at'
+  | 'title'
+  | 'backlink_count'
+  | 'word_count'
+  | 'file_size'
+  | 'manual';
+
+export type ViewKind = 'gallery' | 'sidebar';
+
+export interface ViewPreference {
+  sort: NoteSortOrder;
+  tag_filter: string | null;
+}
+
+export interface ViewSettings {
+  gallery: ViewPreference;
+  sidebar: ViewPreference;
+}
+
+export interface TrashSettings {
+  /** Days to keep deleted notes (0 keeps them forever) */
+  retention_days: number;
+}
+
+export interface HistorySettings {
+  enabled: boolean;
+  /** Minimum seconds between revision snapshots of the same note */
+  min_interval_secs: number;
+  /** Maximum revisions kept per note */
+  max_revisions: number;
+}
+
+export interface TemplateSettings {
+  /** Template applied to new notes and wiki-link notes (null uses the built-in "# {{title}}") */
+  default_template: string | null;
+}
+
+/** How note filenames are generated (a note can override it with `filename:` in front matter) */
+export type FilenameStyle = 'heading' | 'uid' | 'date_prefixed' | 'slug' | 'pattern';
+
+export interface FilenameSettings {
+  style: FilenameStyle;
+  /** Pattern for the 'pattern' style ({{title}}, {{slug}}, {{uid}}, {{date}}, {{time}}, {{year}}, {{month}}, {{day}}) */
+  pattern: string;
+}
+
+/** Result of renaming existing files to the current filename style */
+export interface RenameReport {
+  renamed: number;
+  unchanged: number;
+  /** UIDs of notes that could not be renamed */
+  failed: string[];
+}
+
+export interface NormalizeReport {
+  updated: number;
+  unchanged: number;
+  /** UIDs of notes that could not be rewritten */
+  failed: string[];
+}
+
+/** How existing notes are handled when the storage directory changes */
+export type RelocationMode = 'move' | 'copy' | 'keep';
+
+export interface RelocationFailure {
+  /** Path relative to the previous storage directory */
+  path: string;
+  error: string;
+}
+
+/** Result of relocating the storage directory */
+export interface RelocationReport {
+  /** Files moved or copied */
+  transferred: number;
+  /** Files already present at the destination with identical content */
+  skipped: number;
+  /** Files that could not be transferred; if any, the storage directory is left unchanged */
+  failed: RelocationFailure[];
+  /** Whether the storage directory was changed */
+  switched: boolean;
+}
+
+/** Payload of the `relocation-progress` event */
+export interface RelocationProgress {
+  done: number;
+  total: number;
+}
+
+export interface DailyNoteSettings {
+  /** strftime pattern used to name daily note files (e.g. "%Y-%m-%d") */
+  filename_pattern: string;
+  /** Template for new daily notes (null uses the built-in "# {{title}}" with the date as title) */
+  template: string | null;
+}
+
+/** A named note collection with its own storage directory and index */
+export interface Vault {
+  id: string;
+  name: string;
+  storage_directory: string;
+  /** Directory synced both ways with the storage directory (null = no sync) */
+  mirror_directory: string | null;
+}
+
+export interface VaultSettings {
+  /** ID of the vault in use (its directory is mirrored in storage_directory) */
+  active: string;
+  list: Vault[];
+}
+
+export interface EncryptionSettings {
+  /** Lock encrypted notes after this many idle seconds (0 never locks automatically) */
+  session_timeout_secs: number;
+}
+
+export interface VersioningSettings {
+  /** Commit the storage directory to a local git repository */
+  enabled: boolean;
+  /** Commit after this many seconds without changes */
+  quiet_period_secs: number;
+  /** Commit at the latest this many seconds after the first uncommitted change (0 = no limit) */
+  max_interval_secs: number;
+}
+
+export interface BackupSettings {
+  /** Create compressed backups of the vault on a schedule */
+  enabled: boolean;
+  interval_hours: number;
+  /** Backup directory (null = "backups" in the config directory) */
+  directory: string | null;
+  /** Keep the newest backup of this many days */
+  keep_daily: number;
+  /** Keep the newest backup of this many ISO weeks */
+  keep_weekly: number;
+}
+
+export interface SyncSettings {
+  /** Sync with the vault's mirror directory every this many minutes (0 = manual only) */
+  interval_minutes: number;
+}
+
+/** UID format for new notes; existing notes keep their uid */
+export type UidFormat = 'timestamp' | 'ulid' | 'uuid7';
+
+export interface UidSettings {
+  format: UidFormat;
+}
+
+/** Time zone of created_at/updated_at in front matter (RFC 3339 with an offset either way) */
+export type TimestampZone = 'local' | 'utc';
+
+export interface TimestampSettings {
+  zone: TimestampZone;
+}
+
+/** Lock state of encrypted notes */
+export interface EncryptionStatus {
+  unlocked: boolean;
+  /** Seconds until the session locks itself (null when locked or never locking) */
+  expires_in_secs: number | null;
+}
+
 export type ThemeName = 'tokyo-night' | 'kanagawa' | 'monokai' | 'gruvbox' | 'dracula' | 'catppuccin' | 'synthwave';
 export type ThemeMode = 'light' | 'dark';
 
@@ -157,6 +389,19 @@ export interface Settings {
   autosave: AutosaveSettings;
   restore_last_note: boolean;
   last_note_uid: string | null;
+  views: ViewSettings;
+  trash: TrashSettings;
+  history: HistorySettings;
+  templates: TemplateSettings;
+  daily_notes: DailyNoteSettings;
+  filenames: FilenameSettings;
+  vaults: VaultSettings;
+  encryption: EncryptionSettings;
+  versioning: VersioningSettings;
+  backup: BackupSettings;
+  sync: SyncSettings;
+  uids: UidSettings;
+  timestamps: TimestampSettings;
 }
 
 // バックリンク関連
@@ -174,9 +419,167 @@ export interface NoteGalleryItemDto {
   tags: string[];
   created_at: string;
   updated_at: string;
+  pinned: boolean;
+  favorite: boolean;
+  /** Folder relative to the storage directory ('' for the top level) */
+  folder: string;
+  /** Encrypted notes have an empty preview */
+  encrypted: boolean;
+}
+
+export type GallerySortOrder = NoteSortOrder;
+
+// フォルダ関連
+/** Folder tree node; the root is the storage directory itself */
+export interface FolderNode {
+  name: string;
+  /** Path relative to the storage directory, '/'-separated ('' for the root) */
+  path: string;
+  /** Notes directly in this folder */
+  note_count: number;
+  /** Notes in this folder and all subfolders */
+  total_count: number;
+  children: FolderNode[];
+}
+
+// 保管庫関連
+export interface VaultDto extends Vault {
+  /** Whether this is the vault in use */
+  active: boolean;
+}
+
+// ゴミ箱関連
+export interface TrashItemDto {
+  uid: string;
+  title: string;
+  /** Path before deletion, relative to the storage directory */
+  original_path: string;
+  deleted_at: string;
+}
+
+// リビジョン履歴関連
+export interface RevisionDto {
+  /** blake3 hash of the revision content */
+  id: string;
+  saved_at: string;
+  size: number;
+}
+
+/** A git commit of the storage directory */
+export interface VaultCommitDto {
+  id: string;
+  short_id: string;
+  summary: string;
+  committed_at: string;
+}
+
+export interface VersioningStatusDto {
+  enabled: boolean;
+  /** Whether the git command could be run */
+  git_available: boolean;
+  /** Whether the storage directory is inside a git repository */
+  initialized: boolean;
+  has_pending_changes: boolean;
+  last_commit: VaultCommitDto | null;
+}
+
+/** A backup archive of a vault */
+export interface BackupInfoDto {
+  /** File name, used to pick the backup to restore */
+  name: string;
+  vault: string;
+  created_at: string;
+}
+
+export interface BackupReportDto {
+  backup: BackupInfoDto;
+  /** Number of files in the archive (excluding settings) */
+  files: number;
+  /** Number of old archives removed by rotation */
+  pruned: number;
+}
+
+export interface RestoreReportDto {
+  restored: number;
+  settings_restored: boolean;
+  /** Backup of the state just before restoring */
+  safety_backup: BackupInfoDto | null;
+}
+
+/** A file changed on both sides; the mirror's version was saved as a conflict copy */
+export interface SyncConflictDto {
+  path: string;
+  conflict_copy: string;
+}
+
+export interface SyncReportDto {
+  /** Files copied from the mirror into the vault */
+  pulled: number;
+  /** Files copied from the vault to the mirror */
+  pushed: number;
+  /** Deletions propagated to either side */
+  deleted: number;
+  conflicts: SyncConflictDto[];
+}
+
+/** How a file sharing a note's uid was created */
+export type CopyKind = 'syncthing' | 'dropbox' | 'mirror' | 'duplicate';
+
+export interface DuplicateCopyDto {
+  /** Path relative to the storage directory */
+  path: string;
+  kind: CopyKind;
+}
+
+/** A note and the other files with the same uid */
+export interface DuplicateGroupDto {
+  uid: string;
+  /** Indexed note, relative to the storage directory */
+  path: string;
+  copies: DuplicateCopyDto[];
+}
+
+/** A duplicate file that was given a new uid */
+export interface UidRepairDto {
+  /** Path relative to the storage directory */
+  path: string;
+  old_uid: string;
+  new_uid: string;
 }
 
-export type GallerySortOrder = 'updated_at' | 'created_at';
+export interface ConflictMergeDto {
+  ours: string;
+  theirs: string;
+  /** Whether the common ancestor was found in the revision history */
+  base_found: boolean;
+  /** Merged body; conflicting hunks are wrapped in <<<<<<< / ======= / >>>>>>> markers */
+  merged: string;
+  conflicts: number;
+}
+
+export type DiffOp = 'equal' | 'insert' | 'delete';
+
+export interface DiffLineDto {
+  op: DiffOp;
+  text: string;
+}
+
+// 下書き（クラッシュ復旧）関連
+export interface DraftDto {
+  uid: string;
+  title: string;
+  content: string;
+  saved_at: string;
+}
+
+// デイリーノート関連
+export type DailyDirection = 'previous' | 'next';
+
+export interface DailyNoteEntryDto {
+  /** YYYY-MM-DD */
+  date: string;
+  uid: string;
+}
 
 // タグ関連
 export interface NoteTagsDto {